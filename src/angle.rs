@@ -57,6 +57,38 @@ impl<T> Angle<T> {
         Angle(radians)
     }
 
+    /// Create an angle of zero radians.
+    pub fn zero() -> Self
+    where
+        T: Zero,
+    {
+        Angle(T::zero())
+    }
+
+    /// Create an angle of a quarter turn, or `pi/2` radians.
+    pub fn quarter_turn() -> Self
+    where
+        T: FloatConst,
+    {
+        Angle(T::FRAC_PI_2())
+    }
+
+    /// Create an angle of a half turn, or `pi` radians.
+    pub fn half_turn() -> Self
+    where
+        T: FloatConst,
+    {
+        Angle(T::PI())
+    }
+
+    /// Create an angle of a full turn, or `2*pi` radians.
+    pub fn full_turn() -> Self
+    where
+        T: FloatConst + ops::Add<Output = T>,
+    {
+        Angle(T::PI() + T::PI())
+    }
+
     /// Create a new `Angle` from the number of degrees.
     pub fn from_degrees(degrees: T) -> Self
     where
@@ -93,6 +125,14 @@ impl<T> Angle<T> {
     {
         self.0.cos()
     }
+
+    /// Get the sine and cosine of the angle, in that order.
+    pub fn sin_cos(self) -> (T, T)
+    where
+        T: Real,
+    {
+        self.0.sin_cos()
+    }
 }
 
 impl<T> Angle<T>
@@ -120,7 +160,30 @@ where
 
     /// Get the angle in the `[-pi..pi]` range.
     pub fn signed(self) -> Self {
-        todo!()
+        let two_pi = T::PI() + T::PI();
+        let angle = self.positive().0;
+
+        // Wrap angles past pi around to the negative side.
+        if angle > T::PI() {
+            Angle::from_radians(angle - two_pi)
+        } else {
+            Angle::from_radians(angle)
+        }
+    }
+
+    /// Get the shortest signed rotation from this angle to `other`.
+    ///
+    /// The result is in the `[-pi..pi]` range.
+    pub fn normalized_delta(self, other: Self) -> Self {
+        Angle::from_radians(other.0 - self.0).signed()
+    }
+
+    /// Interpolate between this angle and `other`, taking the shortest way around.
+    pub fn lerp_shortest(self, other: Self, t: T) -> Self
+    where
+        T: ops::Mul<Output = T>,
+    {
+        Angle::from_radians(self.0 + self.normalized_delta(other).0 * t)
     }
 }
 
@@ -215,3 +278,32 @@ impl<T: ops::Neg<Output = T>> ops::Neg for Angle<T> {
         Angle(-self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f32::consts::PI;
+
+    #[test]
+    fn test_signed() {
+        let angle = Angle::from_radians(PI * 1.5).signed();
+        assert!((angle.radians() + PI * 0.5).abs() < 1e-6);
+
+        let angle = Angle::from_radians(-PI * 2.5).signed();
+        assert!((angle.radians() + PI * 0.5).abs() < 1e-6);
+
+        let angle = Angle::from_radians(PI * 0.25).signed();
+        assert!((angle.radians() - PI * 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_lerp_shortest() {
+        let from = Angle::from_degrees(350.0f32);
+        let to = Angle::from_degrees(10.0);
+
+        assert!((from.normalized_delta(to).degrees() - 20.0).abs() < 1e-3);
+
+        let halfway = from.lerp_shortest(to, 0.5).positive();
+        assert!(halfway.degrees().abs() < 1e-3 || (halfway.degrees() - 360.0).abs() < 1e-3);
+    }
+}
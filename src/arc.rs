@@ -20,8 +20,11 @@
 use num_traits::real::Real;
 
 use crate::angle::Angle;
+use crate::curve::Curve;
 use crate::path::{Path, PathEvent};
-use crate::point::Point;
+use crate::point::{Point, Vector};
+
+use core::ops;
 
 /// A geometric arc.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
@@ -101,24 +104,268 @@ impl<T: Copy> Arc<T> {
     }
 }
 
+impl<T: Real> Arc<T> {
+    /// Get the signed angle swept out by the arc.
+    #[inline]
+    pub fn sweep(self) -> T {
+        self.end_angle.radians() - self.start_angle.radians()
+    }
+
+    /// Get the angle at the given parameter along the arc.
+    #[inline]
+    fn angle_at(self, t: T) -> Angle<T> {
+        Angle::from_radians(self.start_angle.radians() + self.sweep() * t)
+    }
+}
+
+/// The arc is parameterized by its angle, going from the start angle at `t = 0` to the end
+/// angle at `t = 1`.
+impl<T: Real> Curve<T> for Arc<T> {
+    type FlattenIterator = FlattenedArc<T>;
+    type Subsection = Self;
+    type Derivative = Self;
+
+    #[inline]
+    fn eval(&self, t: T) -> Point<T> {
+        self.center + Vector::from_angle(self.angle_at(t)) * self.radius
+    }
+
+    fn flatten(&self, tolerance: T) -> Self::FlattenIterator {
+        // The largest angle whose chord stays within the tolerance of the arc.
+        let two = T::one() + T::one();
+        let ratio = (T::one() - tolerance / self.radius.abs()).max(-T::one());
+        let step = two * ratio.acos();
+
+        let count = (self.sweep().abs() / step)
+            .ceil()
+            .to_usize()
+            .unwrap_or(1)
+            .max(1);
+
+        FlattenedArc {
+            arc: *self,
+            index: 0,
+            count,
+        }
+    }
+
+    #[inline]
+    fn split(self, index: T) -> (Self::Subsection, Self::Subsection) {
+        let mid = self.angle_at(index);
+
+        (
+            Arc::new(self.center, self.radius, self.start_angle, mid),
+            Arc::new(self.center, self.radius, mid, self.end_angle),
+        )
+    }
+
+    #[inline]
+    fn subsection(self, range: ops::Range<T>) -> Self::Subsection {
+        Arc::new(
+            self.center,
+            self.radius,
+            self.angle_at(range.start),
+            self.angle_at(range.end),
+        )
+    }
+
+    #[inline]
+    fn length(&self, _accuracy: T) -> T {
+        (self.radius * self.sweep()).abs()
+    }
+
+    /// The derivative of an arc is another arc around the origin, turned a quarter turn
+    /// in the direction of the sweep.
+    fn derivative(&self) -> Self::Derivative {
+        let sweep = self.sweep();
+        let quarter_turn = T::from(core::f32::consts::FRAC_PI_2).unwrap();
+        let quarter_turn = if sweep < T::zero() {
+            -quarter_turn
+        } else {
+            quarter_turn
+        };
+        let turn = |angle: Angle<T>| Angle::from_radians(angle.radians() + quarter_turn);
+
+        Arc::new(
+            Point::zero(),
+            (self.radius * sweep).abs(),
+            turn(self.start_angle),
+            turn(self.end_angle),
+        )
+    }
+}
+
 impl<T: Real> Path<T> for Arc<T> {
     type Iter = ArcPathIter<T>;
 
     fn path_iter(self) -> Self::Iter {
-        ArcPathIter { arc: self }
+        let pieces = (self.sweep().abs() / T::from(core::f32::consts::FRAC_PI_2).unwrap()).ceil();
+
+        ArcPathIter {
+            arc: self,
+            index: 0,
+            count: pieces.to_usize().unwrap_or(1).max(1),
+        }
     }
 }
 
+/// The path of an arc, made of cubic curves that each cover at most a quarter turn.
 #[doc(hidden)]
 pub struct ArcPathIter<T: Copy> {
     /// The inner arc.
     arc: Arc<T>,
+
+    /// The index of the next event.
+    index: usize,
+
+    /// The number of cubic curves to split the arc into.
+    count: usize,
 }
 
 impl<T: Real> Iterator for ArcPathIter<T> {
     type Item = PathEvent<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        todo!()
+        let index = self.index;
+        if index > self.count + 1 {
+            return None;
+        }
+        self.index += 1;
+
+        let count = T::from(self.count).unwrap();
+        let event = if index == 0 {
+            PathEvent::Begin {
+                at: self.arc.eval(T::zero()),
+            }
+        } else if index <= self.count {
+            // The control points lie along the tangents at either end of the piece.
+            let piece = self
+                .arc
+                .subsection(T::from(index - 1).unwrap() / count..T::from(index).unwrap() / count);
+            let four = T::from(4.0).unwrap();
+            let handle = (piece.sweep() / four).tan() * four / T::from(3.0).unwrap();
+            let tangent = |angle: Angle<T>| {
+                let quarter_turn = T::from(core::f32::consts::FRAC_PI_2).unwrap();
+                Vector::from_angle(Angle::from_radians(angle.radians() + quarter_turn))
+                    * (piece.radius * handle)
+            };
+
+            let (from, to) = (piece.eval(T::zero()), piece.eval(T::one()));
+            PathEvent::Cubic {
+                from,
+                control1: from + tangent(piece.start_angle),
+                control2: to - tangent(piece.end_angle),
+                to,
+            }
+        } else {
+            PathEvent::End {
+                first: self.arc.eval(T::zero()),
+                last: self.arc.eval(T::one()),
+                close: false,
+            }
+        };
+
+        Some(event)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = (self.count + 2).saturating_sub(self.index);
+        (size, Some(size))
+    }
+}
+
+impl<T: Real> ExactSizeIterator for ArcPathIter<T> {}
+
+/// The iterator returned by flattening an `Arc`.
+#[derive(Debug, Clone)]
+#[doc(hidden)]
+pub struct FlattenedArc<T: Copy> {
+    /// The arc being flattened.
+    arc: Arc<T>,
+
+    /// The index of the next point.
+    index: usize,
+
+    /// The number of line segments to split the arc into.
+    count: usize,
+}
+
+impl<T: Real> Iterator for FlattenedArc<T> {
+    type Item = Point<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index > self.count {
+            return None;
+        }
+
+        let t = T::from(self.index).unwrap() / T::from(self.count).unwrap();
+        self.index += 1;
+        Some(self.arc.eval(t))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = (self.count + 1).saturating_sub(self.index);
+        (size, Some(size))
+    }
+}
+
+impl<T: Real> ExactSizeIterator for FlattenedArc<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arc_curve() {
+        let arc = Arc::new(
+            Point::new(1.0f32, 1.0),
+            2.0,
+            Angle::zero(),
+            Angle::half_turn(),
+        );
+
+        assert!(arc.eval(0.5).distance(Point::new(1.0, 3.0)) < 1e-5);
+        assert!((arc.length(0.1) - 2.0 * core::f32::consts::PI).abs() < 1e-5);
+
+        let points = Curve::flatten(&arc, 0.01);
+        let len = points.len();
+        assert!(len > 2);
+        assert!(points.last().unwrap().distance(Point::new(-1.0, 1.0)) < 1e-5);
+
+        // The derivative at the top of the arc points to the left.
+        let derivative = arc.derivative().eval(0.5);
+        assert!(derivative.distance(Point::new(-2.0 * core::f32::consts::PI, 0.0)) < 1e-5);
+    }
+
+    #[test]
+    fn test_arc_path() {
+        let arc = Arc::new(
+            Point::new(1.0f64, 1.0),
+            2.0,
+            Angle::zero(),
+            Angle::from_radians(-3.0 * core::f64::consts::FRAC_PI_4),
+        );
+
+        let mut events = arc.path_iter();
+        assert_eq!(events.len(), 4);
+        assert_eq!(
+            events.next(),
+            Some(PathEvent::Begin {
+                at: Point::new(3.0, 1.0)
+            })
+        );
+        assert!(matches!(
+            events.nth(2),
+            Some(PathEvent::End { close: false, .. })
+        ));
+
+        // Every point along the curves stays close to the circle.
+        let mut last = Point::new(3.0, 1.0);
+        for segment in arc.segments(0.001) {
+            assert!((segment.to().distance(Point::new(1.0, 1.0)) - 2.0).abs() < 0.01);
+            last = segment.to();
+        }
+        assert!(last.distance(arc.eval(1.0)) < 1e-9);
+        assert!(last.y() < 1.0);
     }
 }
@@ -47,6 +47,12 @@ pub(crate) struct BoEdge<Num: Copy> {
     /// niching optimizations in the algorithm.
     id: NonZeroUsize,
 
+    /// The tag of the input that this edge came from.
+    ///
+    /// This is attached to every trapezoid that uses this edge
+    /// as its left edge.
+    tag: usize,
+
     /// The previous edge in the sweep line.
     prev: Cell<Option<NonZeroUsize>>,
 
@@ -85,11 +91,30 @@ impl<Num: Copy> BoEdge<Num> {
         self.id
     }
 
+    /// Get the tag of the input that this edge came from.
+    pub(super) fn tag(&self) -> usize {
+        self.tag
+    }
+
     /// Get the segment associated with this edge.
     pub(super) fn edge(&self) -> NhLineSegment<Num> {
         self.edge
     }
 
+    /// Get the change in winding number when crossing this edge from left to right.
+    ///
+    /// Edges pointing down add one and edges pointing up subtract one.
+    pub(super) fn winding(&self) -> i32
+    where
+        Num: PartialOrd + num_traits::Zero,
+    {
+        if self.edge.line().direction().y() > Num::zero() {
+            1
+        } else {
+            -1
+        }
+    }
+
     /// Get the point in this edge with the lowest Y value.
     pub(super) fn lowest_y(&self) -> Point<Num> {
         self.lowest_y
@@ -153,15 +178,18 @@ impl<Num: Real + ApproxEq> BoEdge<Num> {
     }
 
     /// Complete the trapezoid for this edge at a given Y value.
+    ///
+    /// The trapezoid is returned alongside this edge's tag.
     pub(super) fn complete_trapezoid(
         &self,
         bottom: Num,
         all: &Edges<Num>,
-    ) -> Option<Trapezoid<Num>> {
+    ) -> Option<(usize, Trapezoid<Num>)> {
         self.trapezoid
             .borrow_mut()
             .take()
             .and_then(|trap| trap.complete(self.id(), bottom, all))
+            .map(|trap| (self.tag, trap))
     }
 
     /// Create a `BoEdge` from two points.
@@ -175,7 +203,7 @@ impl<Num: Real + ApproxEq> BoEdge<Num> {
         use crate::LineSegment;
         use core::convert::TryInto;
         let edge = LineSegment::new(point1, point2);
-        Self::from_edge(edge.try_into().unwrap(), id)
+        Self::from_edge(edge.try_into().unwrap(), id, 0)
     }
 
     /// Is this edge colinear with another edge?
@@ -199,13 +227,13 @@ impl<Num: Real + ApproxEq> BoEdge<Num> {
     /// Either start a new trapezoid or continue an existing one.
     ///
     /// If a trapezoid was finished using this function, this returns
-    /// that trapezoid.
+    /// that trapezoid alongside this edge's tag.
     pub(super) fn start_trapezoid(
         &self,
         right: &BoEdge<Num>,
         top: Num,
         all: &Edges<Num>,
-    ) -> Option<Trapezoid<Num>> {
+    ) -> Option<(usize, Trapezoid<Num>)> {
         let mut trap = self.trapezoid.borrow_mut();
 
         // if the current trapezoid is not empty, we may need to either
@@ -240,7 +268,7 @@ impl<Num: Real + ApproxEq> BoEdge<Num> {
         };
         *trap = Some(trapezoid);
 
-        completed_trap
+        completed_trap.map(|trap| (self.tag, trap))
     }
 
     /// Get the intersection event between this edge and another edge.
@@ -258,8 +286,8 @@ impl<Num: Real + ApproxEq> BoEdge<Num> {
         })
     }
 
-    /// Create a new `BoEdge` from an `Edge` and its ID number.
-    pub(super) fn from_edge(edge: NhLineSegment<Num>, id: NonZeroUsize) -> Self {
+    /// Create a new `BoEdge` from an `Edge`, its ID number and its tag.
+    pub(super) fn from_edge(edge: NhLineSegment<Num>, id: NonZeroUsize, tag: usize) -> Self {
         // get the points of the edge
         debug_assert!(edge.top() <= edge.bottom());
         let lowest_y = Point::new(x_for_y(&edge.line(), edge.top()), edge.top());
@@ -270,6 +298,7 @@ impl<Num: Real + ApproxEq> BoEdge<Num> {
             lowest_y,
             highest_y,
             id,
+            tag,
             prev: Cell::new(None),
             next: Cell::new(None),
             trapezoid: RefCell::new(None),
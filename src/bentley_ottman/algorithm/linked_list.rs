@@ -34,9 +34,6 @@ pub(super) struct LinkedListIter<'all, Num: Copy> {
     edges: &'all Edges<Num>,
 }
 
-/// An iterator over pairs of elements in a `LinkedList`.
-pub(super) struct LinkedListPairIter<'all, Num: Copy>(LinkedListIter<'all, Num>);
-
 impl LinkedList {
     /// Get an iterator over this list.
     pub(super) fn iter<'all, Num: Copy>(
@@ -49,16 +46,8 @@ impl LinkedList {
         }
     }
 
-    /// Iterate over this list in pairs.
-    pub(super) fn pairs<'all, Num: Copy>(
-        &self,
-        edges: &'all Edges<Num>,
-    ) -> LinkedListPairIter<'all, Num> {
-        LinkedListPairIter(self.iter(edges))
-    }
-
     /// Push a `BoEdge` to the end of the linked list.
-    pub(super) fn push<'all, Num: Copy>(&mut self, edge: &BoEdge<Num>, all: &'all Edges<Num>) {
+    pub(super) fn push<Num: Copy>(&mut self, edge: &BoEdge<Num>, all: &Edges<Num>) {
         match &mut self.root {
             root @ None => {
                 *root = Some(edge.id());
@@ -80,10 +69,10 @@ impl LinkedList {
     ///
     /// The closure should return `true` if the edge should be inserted
     /// before the given element.
-    pub(super) fn insert<'all, Num: Copy>(
+    pub(super) fn insert<Num: Copy>(
         &mut self,
         edge: &BoEdge<Num>,
-        all: &'all Edges<Num>,
+        all: &Edges<Num>,
         mut before: impl FnMut(&BoEdge<Num>, &BoEdge<Num>) -> bool,
     ) {
         // find the node to insert this edge before
@@ -109,7 +98,7 @@ impl LinkedList {
     }
 
     /// Remove an edge from this linked list.
-    pub(super) fn remove<'all, Num: Copy>(&mut self, edge: &BoEdge<Num>, all: &'all Edges<Num>) {
+    pub(super) fn remove<Num: Copy>(&mut self, edge: &BoEdge<Num>, all: &Edges<Num>) {
         let prev = edge.prev();
         let next = edge.next();
 
@@ -132,11 +121,11 @@ impl LinkedList {
     /// # Panics
     ///
     /// Panics if the edge is the last element in the linked list.
-    pub(super) fn swap<'all, Num: Copy>(&mut self, edge: &BoEdge<Num>, all: &'all Edges<Num>) {
+    pub(super) fn swap<Num: Copy>(&mut self, edge: &BoEdge<Num>, all: &Edges<Num>) {
         let next = all.get(match edge.next() {
             Some(next) => next,
             None => {
-                tracing::error!("edge should never be the removed from the list");
+                trace_event!(error, "edge should never be the removed from the list");
                 return;
             }
         });
@@ -179,18 +168,6 @@ impl<'all, Num: Copy> Iterator for LinkedListIter<'all, Num> {
 
 impl<'all, Num: Copy> FusedIterator for LinkedListIter<'all, Num> {}
 
-impl<'all, Num: Copy> Iterator for LinkedListPairIter<'all, Num> {
-    type Item = (&'all BoEdge<Num>, &'all BoEdge<Num>);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let e1 = self.0.next()?;
-        let e2 = self.0.next()?;
-        Some((e1, e2))
-    }
-}
-
-impl<'all, Num: Copy> FusedIterator for LinkedListPairIter<'all, Num> {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,11 +20,11 @@ mod linked_list;
 mod priority_queue;
 mod sweep_line;
 
-use super::{Event, EventType, FillRule};
+use super::{BooleanOp, Event, EventType, FillRule, TessellationLimits, TessellationStats};
 use crate::trapezoid::Trapezoid;
 use crate::{
     line::{LineSegment, NhLineSegment},
-    ApproxEq,
+    ApproxEq, GeometryError,
 };
 
 use core::convert::TryInto;
@@ -91,6 +91,17 @@ pub(crate) struct Algorithm<Num: Copy, Variant> {
     /// This is either a ZST if we're looking for intersections, or
     /// contains a queue of trapezoids that we're looking for.
     variant: Variant,
+
+    /// Counters for what the algorithm has done so far.
+    stats: TessellationStats,
+
+    /// The limits on how much work the algorithm may do.
+    limits: TessellationLimits,
+
+    /// The limit that was exceeded, if any.
+    ///
+    /// Once this is set, no more events are processed.
+    exceeded: Option<GeometryError<Num>>,
 }
 
 /// The variant of the algorithm we are using.
@@ -112,6 +123,7 @@ pub(crate) trait Variant<Num: Copy>: Sized {
 
 /// We are not concerned about trapezoids in this algorithm.
 #[derive(Debug)]
+#[allow(dead_code)]
 pub(crate) struct NoTrapezoids;
 
 /// We are concerned about trapezoids in this algorithm.
@@ -126,29 +138,51 @@ pub(crate) struct Trapezoids<Num: Copy> {
     /// but that's too complicated for now, and it's not like this
     /// array is the bottleneck compared to the linked lists and
     /// priority queues above
-    trapezoids: Vec<Trapezoid<Num>>,
+    trapezoids: Vec<(usize, Trapezoid<Num>)>,
 
     /// Have we fused together the leftovers yet?
     fused_leftovers: bool,
 
-    /// The fill rule we use to create traps.
-    fill_rule: FillRule,
+    /// How we pair up edges to create traps.
+    pairing: Pairing,
+}
+
+/// How the active edges are paired up into the sides of trapezoids.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Pairing {
+    /// Every edge counts towards the same winding number.
+    Combined(FillRule),
+
+    /// Edges only count towards the winding number of edges with the same tag.
+    ///
+    /// This tessellates every tag as its own shape.
+    PerTag(FillRule),
+
+    /// Edges tagged zero belong to the first operand and all other edges belong to
+    /// the second, and the operands are combined using a boolean operation.
+    Boolean(BooleanOp, FillRule),
 }
 
 impl<Num: Real + ApproxEq, Var: Variant<Num>> Algorithm<Num, Var> {
     /// Create a new algorithm.
-    pub(crate) fn new(segments: impl Iterator<Item = LineSegment<Num>>, input: Var::Input) -> Self {
+    ///
+    /// Every segment comes with a tag that is attached to the trapezoids it creates.
+    pub(crate) fn new(
+        segments: impl Iterator<Item = (LineSegment<Num>, usize)>,
+        input: Var::Input,
+    ) -> Self {
         // collect the edges into a vector
         let edges: Edges<Num> = segments
-            .filter_map(|edge| {
+            .filter_map(|(edge, tag)| {
                 let nh_segment: Result<NhLineSegment<_>, _> = edge.try_into();
-                nh_segment.ok()
+                nh_segment.ok().map(|segment| (segment, tag))
             })
             .enumerate()
-            .map(|(i, segment)| {
+            .map(|(i, (segment, tag))| {
                 BoEdge::from_edge(
                     segment,
                     NonZeroUsize::new(i + 1).expect("cannot have more than usize::MAX - 1 edges"),
+                    tag,
                 )
             })
             .collect::<Vec<_>>()
@@ -165,9 +199,27 @@ impl<Num: Real + ApproxEq, Var: Variant<Num>> Algorithm<Num, Var> {
             event_queue: pqueue,
             sweep_line: SweepLine::default(),
             variant: Var::new(input),
+            stats: TessellationStats::default(),
+            limits: TessellationLimits::default(),
+            exceeded: None,
         }
     }
 
+    /// Set the limits on how much work the algorithm may do.
+    pub(crate) fn set_limits(&mut self, limits: TessellationLimits) {
+        self.limits = limits;
+    }
+
+    /// Get the limit that was exceeded, if any.
+    pub(crate) fn exceeded(&self) -> Option<GeometryError<Num>> {
+        self.exceeded
+    }
+
+    /// Get the counters for what the algorithm has done so far.
+    pub(crate) fn stats(&self) -> TessellationStats {
+        self.stats
+    }
+
     /// Get the length of the queue of events.
     pub(crate) fn queue_len(&self) -> usize {
         self.event_queue.len()
@@ -175,6 +227,17 @@ impl<Num: Real + ApproxEq, Var: Variant<Num>> Algorithm<Num, Var> {
 
     /// Get the next event in the algorithm.
     pub(crate) fn next_event(&mut self) -> Option<Event<Num>> {
+        // stop for good if we've done too much work
+        if self.exceeded.is_some() {
+            return None;
+        }
+        if self.stats.events >= self.limits.max_events {
+            self.exceeded = Some(GeometryError::TooManyEvents {
+                limit: self.limits.max_events,
+            });
+            return None;
+        }
+
         // pop an event from the event queue
         let event = loop {
             let event = self.event_queue.pop()?;
@@ -207,9 +270,22 @@ impl<Num: Real + ApproxEq, Var: Variant<Num>> Algorithm<Num, Var> {
             }
             EventType::Intersection { .. } => {
                 self.handle_intersection_event(&event);
+                self.stats.intersections += 1;
             }
         }
 
+        if self.event_queue.len() > self.limits.max_queue_len {
+            self.exceeded = Some(GeometryError::QueueTooLarge {
+                limit: self.limits.max_queue_len,
+            });
+        }
+
+        self.stats.events += 1;
+        self.stats.max_active_edges = self
+            .stats
+            .max_active_edges
+            .max(self.sweep_line.active_len());
+
         Some(event)
     }
 
@@ -291,11 +367,14 @@ impl<Num: Real + ApproxEq, Var: Variant<Num>> Algorithm<Num, Var> {
 }
 
 impl<Num: Real + ApproxEq> Algorithm<Num, Trapezoids<Num>> {
-    /// Get the next trapezoid in the algorithm.
-    pub(crate) fn next_trapezoid(&mut self) -> Option<Trapezoid<Num>> {
+    /// Get the next trapezoid in the algorithm, alongside the tag of its left edge.
+    pub(crate) fn next_trapezoid(&mut self) -> Option<(usize, Trapezoid<Num>)> {
         loop {
             match self.variant.trapezoids.pop() {
-                Some(trap) => return Some(trap),
+                Some(trap) => {
+                    self.stats.trapezoids += 1;
+                    return Some(trap);
+                }
                 None => {
                     // try to repopulate the trapezoid list
                     // by fetching the next event
@@ -304,7 +383,7 @@ impl<Num: Real + ApproxEq> Algorithm<Num, Trapezoids<Num>> {
                     // the last leftovers and squeeze trapezoids
                     // out of there
                     self.next_event().map(|_| ()).or_else(|| {
-                        if self.variant.fused_leftovers {
+                        if self.variant.fused_leftovers || self.exceeded.is_some() {
                             None
                         } else {
                             self.variant.fused_leftovers = true;
@@ -312,7 +391,8 @@ impl<Num: Real + ApproxEq> Algorithm<Num, Trapezoids<Num>> {
                             let edges = &self.edges;
                             self.variant.trapezoids.extend(
                                 self.sweep_line.take_leftovers(edges).filter_map(|edge| {
-                                    tracing::debug!(
+                                    trace_event!(
+                                        debug,
                                         "Completing leftover trapezoid for: {}",
                                         edge.id()
                                     );
@@ -380,18 +460,20 @@ impl<Num: Real> Variant<Num> for NoTrapezoids {
 }
 
 impl<Num: Real + ApproxEq> Variant<Num> for Trapezoids<Num> {
-    type Input = FillRule;
+    type Input = Pairing;
 
     fn new(input: Self::Input) -> Self {
         Self {
-            fill_rule: input,
+            pairing: input,
             fused_leftovers: false,
             trapezoids: Vec::new(),
         }
     }
 
     fn increment_y(alg: &mut Algorithm<Num, Self>, new_y: Num) {
-        if alg.sweep_line.current_y().approx_eq(&new_y) {
+        // trapezoids end where the sweep line moves down, not while it stays
+        // at the same height
+        if !alg.sweep_line.current_y().approx_eq(&new_y) {
             // we may need to iterate over the stopped lines to
             // see if there are any trapezoids we can use
             let edges = &alg.edges;
@@ -403,7 +485,7 @@ impl<Num: Real + ApproxEq> Variant<Num> for Trapezoids<Num> {
             // combine that with the traps that the sweep line may be
             // generating for us
             alg.variant.trapezoids.extend(
-                leftover_edges.chain(alg.sweep_line.trapezoids(alg.variant.fill_rule, edges)),
+                leftover_edges.chain(alg.sweep_line.trapezoids(alg.variant.pairing, edges)),
             );
         }
     }
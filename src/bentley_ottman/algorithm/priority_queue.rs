@@ -86,8 +86,16 @@ impl<Num: PartialEq + Copy> PartialEq for EventOrder<Num> {
 // we assert Eq because the algorithm fails fast on NaN anyhow
 impl<Num: PartialEq + Copy> Eq for EventOrder<Num> {}
 
+// the order lives in `Ord`, so that the two can never disagree
 impl<Num: Copy + PartialOrd> PartialOrd for EventOrder<Num> {
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// we assert Ord for the same reasons as above
+impl<Num: PartialEq + PartialOrd + Copy> Ord for EventOrder<Num> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         // cmp by point
         self.0
             .point
@@ -101,12 +109,6 @@ impl<Num: Copy + PartialOrd> PartialOrd for EventOrder<Num> {
                     Some(cmp)
                 }
             })
-    }
-}
-
-// we assert Ord for the same reasons as above
-impl<Num: PartialEq + PartialOrd + Copy> Ord for EventOrder<Num> {
-    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-        self.partial_cmp(other).expect("Unexpected NaN value")
+            .expect("Unexpected NaN value")
     }
 }
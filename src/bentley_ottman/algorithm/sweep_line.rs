@@ -15,12 +15,11 @@
 // You should have received a copy of the GNU Affero General Public License 
 // along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
 
-use crate::bentley_ottman::FillRule;
 use crate::trapezoid::Trapezoid;
 use crate::ApproxEq;
 
-use super::{edge::Edges, BoEdge, LinkedList};
-use alloc::vec::Vec;
+use super::{edge::Edges, BoEdge, LinkedList, Pairing};
+use alloc::{collections::BTreeMap, vec::Vec};
 use core::{cmp, iter::FusedIterator, mem};
 use num_traits::real::Real;
 
@@ -42,6 +41,9 @@ pub(crate) struct SweepLine<Num> {
     /// The list of edges that are no longer active, but still may
     /// have partial trapezoids.
     leftovers: LinkedList,
+
+    /// The number of edges in the active list.
+    active_len: usize,
 }
 
 impl<Num: Real> Default for SweepLine<Num> {
@@ -50,6 +52,7 @@ impl<Num: Real> Default for SweepLine<Num> {
             current_y: Num::min_value(),
             active: LinkedList::default(),
             leftovers: LinkedList::default(),
+            active_len: 0,
         }
     }
 }
@@ -65,6 +68,11 @@ impl<Num: Real + ApproxEq> SweepLine<Num> {
         self.current_y = y;
     }
 
+    /// Get the number of edges in the active set.
+    pub(super) fn active_len(&self) -> usize {
+        self.active_len
+    }
+
     /// Compare two edges along the sweep line.
     pub(super) fn compare_edges(&self, a: &BoEdge<Num>, b: &BoEdge<Num>) -> Option<cmp::Ordering> {
         // compare by their X values at the current Y
@@ -89,7 +97,7 @@ impl<Num: Real + ApproxEq> SweepLine<Num> {
 
     /// Add an edge to the active sweep line.
     pub(super) fn add_edge(&mut self, edge: &BoEdge<Num>, all: &Edges<Num>) {
-        tracing::trace!("Adding edge {} to active set", edge.id());
+        trace_event!(trace, "Adding edge {} to active set", edge.id());
 
         let mut active = mem::take(&mut self.active);
         active.insert(edge, all, |edge, next| {
@@ -97,13 +105,15 @@ impl<Num: Real + ApproxEq> SweepLine<Num> {
             matches!(c, Some(cmp::Ordering::Less | cmp::Ordering::Equal))
         });
         self.active = active;
+        self.active_len += 1;
     }
 
     /// Remove an edge from the active sweep line.
     pub(super) fn remove_edge(&mut self, edge: &BoEdge<Num>, all: &Edges<Num>) {
-        tracing::trace!("Removing edge {} from active set", edge.id());
+        trace_event!(trace, "Removing edge {} from active set", edge.id());
 
         self.active.remove(edge, all);
+        self.active_len -= 1;
 
         // if the edge has a pending trapezoid, add it to the leftovers
         if edge.pending_trapezoid() {
@@ -139,29 +149,127 @@ impl<Num: Real + ApproxEq> SweepLine<Num> {
 
     /// Try to complete trapezoids belonging to the active set
     /// of edges.
+    ///
+    /// The active edges are paired up into the left and right sides
+    /// of the spans that are inside of the shape under `pairing`.
+    /// Edges that were on the left side of a span but no longer are
+    /// have their trapezoids completed.
     pub(super) fn trapezoids<'all>(
         &self,
-        _fill_rule: FillRule,
+        pairing: Pairing,
         all: &'all Edges<Num>,
-    ) -> impl FusedIterator<Item = Trapezoid<Num>> + 'all {
+    ) -> impl FusedIterator<Item = (usize, Trapezoid<Num>)> + 'all {
         let current_y = self.current_y;
 
-        if cfg!(debug_assertions) {
-            tracing::debug!(
-                "Edges in active set: {:?}",
-                self.active.iter(all).map(|e| e.id()).collect::<Vec<_>>()
-            );
+        trace_event!(
+            debug,
+            "Edges in active set: {:?}",
+            ActiveIds(&self.active, all)
+        );
+
+        // find the edges that start and end each span
+        let mut spans = Vec::new();
+        match pairing {
+            Pairing::Combined(fill_rule) => {
+                let mut span = SpanState::default();
+                for edge in self.active.iter(all) {
+                    spans.extend(span.cross(edge, 0, |[w, _]| fill_rule.is_inside(w)));
+                }
+            }
+            Pairing::PerTag(fill_rule) => {
+                let mut tags = BTreeMap::new();
+                for edge in self.active.iter(all) {
+                    let span = tags.entry(edge.tag()).or_insert_with(SpanState::default);
+                    spans.extend(span.cross(edge, 0, |[w, _]| fill_rule.is_inside(w)));
+                }
+            }
+            Pairing::Boolean(op, fill_rule) => {
+                let mut span = SpanState::default();
+                for edge in self.active.iter(all) {
+                    let operand = if edge.tag() == 0 { 0 } else { 1 };
+                    spans.extend(span.cross(edge, operand, |[a, b]| {
+                        op.combine(fill_rule.is_inside(a), fill_rule.is_inside(b))
+                    }));
+                }
+            }
+        }
+
+        // edges that no longer start a span are finished
+        let mut trapezoids = Vec::new();
+        let mut lefts = spans.iter().map(|(left, _)| left.id()).collect::<Vec<_>>();
+        lefts.sort_unstable();
+        for edge in self.active.iter(all) {
+            if lefts.binary_search(&edge.id()).is_err() && edge.pending_trapezoid() {
+                trapezoids.extend(edge.complete_trapezoid(current_y, all));
+            }
         }
 
-        self.active.pairs(all).filter_map(move |current| {
-            let (left, right) = current;
-            tracing::debug!(
+        trapezoids.extend(spans.into_iter().filter_map(|(left, right)| {
+            trace_event!(
+                debug,
                 "Creating trapezoid between {} and {}",
                 left.id(),
                 right.id()
             );
             left.start_trapezoid(right, current_y, all)
-        })
+        }));
+
+        trapezoids.into_iter()
+    }
+}
+
+/// The winding numbers to the left of an edge, and the edge that opened the current span.
+struct SpanState<'all, Num: Copy> {
+    winding: [i32; 2],
+    left: Option<&'all BoEdge<Num>>,
+}
+
+impl<Num: Copy> Default for SpanState<'_, Num> {
+    fn default() -> Self {
+        Self {
+            winding: [0; 2],
+            left: None,
+        }
+    }
+}
+
+impl<'all, Num: Real> SpanState<'all, Num> {
+    /// Cross over an edge that belongs to `operand`, returning the span that it closes,
+    /// if any.
+    fn cross(
+        &mut self,
+        edge: &'all BoEdge<Num>,
+        operand: usize,
+        inside: impl Fn([i32; 2]) -> bool,
+    ) -> Option<(&'all BoEdge<Num>, &'all BoEdge<Num>)> {
+        let was_inside = inside(self.winding);
+        self.winding[operand] += edge.winding();
+
+        match (was_inside, inside(self.winding), self.left) {
+            (false, true, _) => {
+                self.left = Some(edge);
+                None
+            }
+            (true, false, Some(start)) => {
+                self.left = None;
+                Some((start, edge))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Lazily formats the IDs of the edges in a list, so that they aren't collected unless
+/// the event is actually logged.
+#[cfg(feature = "tracing")]
+struct ActiveIds<'a, Num: Copy>(&'a LinkedList, &'a Edges<Num>);
+
+#[cfg(feature = "tracing")]
+impl<Num: Copy> core::fmt::Debug for ActiveIds<'_, Num> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter(self.1).map(|e| e.id()))
+            .finish()
     }
 }
 
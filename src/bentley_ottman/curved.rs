@@ -0,0 +1,628 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Tessellation of shapes with curved edges.
+//!
+//! Rather than flattening the whole path up front, curves are split into pieces that are
+//! monotone in Y and carried through the sweep as they are. The sweep stops at the ends
+//! of every edge and at every point where two neighboring edges cross, which is found by
+//! subdividing the pair only where their bounding boxes overlap. Between two stops the
+//! order of the edges doesn't change, so each region between a pair of edges becomes a
+//! trapezoid whose left and right sides are pieces of the original curves.
+
+use crate::curve::{CubicBezier, Curve, QuadraticBezier};
+use crate::line::LineSegment;
+use crate::path::{Path, PathEvent, Shape};
+use crate::point::Point;
+use crate::{ApproxEq, FillRule};
+
+use alloc::collections::BTreeMap;
+use alloc::vec::{IntoIter, Vec};
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+use core::ops::Range;
+use num_traits::real::Real;
+
+/// The deepest that a pair of edges will be subdivided while looking for a crossing.
+const MAX_DEPTH: usize = 16;
+
+/// The number of bisection steps used to find where an edge crosses a horizontal line.
+const BISECTION_STEPS: usize = 48;
+
+/// An edge of a shape that is monotone in Y, and may be curved.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MonotoneEdge<T: Copy> {
+    /// A straight line.
+    Line(LineSegment<T>),
+
+    /// A quadratic Bezier curve.
+    Quadratic(QuadraticBezier<T>),
+
+    /// A cubic Bezier curve.
+    Cubic(CubicBezier<T>),
+}
+
+impl<T: Real + ApproxEq> MonotoneEdge<T> {
+    /// Get the point that this edge starts at.
+    pub fn from(&self) -> Point<T> {
+        match self {
+            MonotoneEdge::Line(line) => line.from(),
+            MonotoneEdge::Quadratic(quad) => quad.from(),
+            MonotoneEdge::Cubic(cubic) => cubic.from(),
+        }
+    }
+
+    /// Get the point that this edge ends at.
+    pub fn to(&self) -> Point<T> {
+        match self {
+            MonotoneEdge::Line(line) => line.to(),
+            MonotoneEdge::Quadratic(quad) => quad.to(),
+            MonotoneEdge::Cubic(cubic) => cubic.to(),
+        }
+    }
+
+    /// Get this edge going in the opposite direction.
+    pub fn reverse(self) -> Self {
+        match self {
+            MonotoneEdge::Line(line) => {
+                MonotoneEdge::Line(LineSegment::new(line.to(), line.from()))
+            }
+            MonotoneEdge::Quadratic(quad) => MonotoneEdge::Quadratic(QuadraticBezier::new(
+                quad.to(),
+                quad.control(),
+                quad.from(),
+            )),
+            MonotoneEdge::Cubic(cubic) => MonotoneEdge::Cubic(CubicBezier::new(
+                cubic.to(),
+                cubic.control2(),
+                cubic.control1(),
+                cubic.from(),
+            )),
+        }
+    }
+
+    /// Get the X coordinate where this edge crosses the horizontal line at `y`.
+    ///
+    /// `y` is clamped to the vertical extent of the edge.
+    pub fn x_at_y(&self, y: T) -> T {
+        let t = self.t_at_y(y);
+        self.eval(t).x()
+    }
+
+    /// Get the part of this edge between the horizontal lines at `top` and `bottom`,
+    /// going downwards.
+    pub fn between(self, top: T, bottom: T) -> Self {
+        let edge = if self.from().y() > self.to().y() {
+            self.reverse()
+        } else {
+            self
+        };
+
+        let (start, end) = (edge.t_at_y(top), edge.t_at_y(bottom));
+        edge.subsection(start..end)
+    }
+
+    /// Get the path event that draws this edge.
+    pub fn event(self) -> PathEvent<T> {
+        match self {
+            MonotoneEdge::Line(line) => PathEvent::Line {
+                from: line.from(),
+                to: line.to(),
+            },
+            MonotoneEdge::Quadratic(quad) => PathEvent::Quadratic {
+                from: quad.from(),
+                control: quad.control(),
+                to: quad.to(),
+            },
+            MonotoneEdge::Cubic(cubic) => PathEvent::Cubic {
+                from: cubic.from(),
+                control1: cubic.control1(),
+                control2: cubic.control2(),
+                to: cubic.to(),
+            },
+        }
+    }
+
+    /// Get the point at the given parameter along this edge.
+    fn eval(&self, t: T) -> Point<T> {
+        match self {
+            MonotoneEdge::Line(line) => line.eval(t),
+            MonotoneEdge::Quadratic(quad) => quad.eval(t),
+            MonotoneEdge::Cubic(cubic) => cubic.eval(t),
+        }
+    }
+
+    /// Get the part of this edge between two parameters.
+    fn subsection(self, range: Range<T>) -> Self {
+        match self {
+            MonotoneEdge::Line(line) => MonotoneEdge::Line(line.subsection(range)),
+            MonotoneEdge::Quadratic(quad) => MonotoneEdge::Quadratic(quad.subsection(range)),
+            MonotoneEdge::Cubic(cubic) => MonotoneEdge::Cubic(cubic.subsection(range)),
+        }
+    }
+
+    /// Get the parameter where this edge crosses the horizontal line at `y`.
+    fn t_at_y(&self, y: T) -> T {
+        let (from, to) = (self.from().y(), self.to().y());
+        if from.approx_eq(&to) {
+            return T::zero();
+        }
+
+        // the edge is monotone, so bisection always finds the crossing
+        let descending = from < to;
+        let (mut low, mut high) = (T::zero(), T::one());
+        for _ in 0..BISECTION_STEPS {
+            let mid = (low + high) / (T::one() + T::one());
+            if (self.eval(mid).y() < y) == descending {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        (low + high) / (T::one() + T::one())
+    }
+
+    /// Get the range of X coordinates covered by the control points of this edge.
+    fn x_extent(&self) -> (T, T) {
+        let extent = |points: &[Point<T>]| {
+            points
+                .iter()
+                .fold((points[0].x(), points[0].x()), |(min, max), point| {
+                    (min.min(point.x()), max.max(point.x()))
+                })
+        };
+
+        match self {
+            MonotoneEdge::Line(line) => extent(&[line.from(), line.to()]),
+            MonotoneEdge::Quadratic(quad) => extent(&quad.points()),
+            MonotoneEdge::Cubic(cubic) => extent(&cubic.points()),
+        }
+    }
+
+    /// Tell if every point of this edge is within `tolerance` of its chord.
+    fn is_flat(&self, tolerance: T) -> bool {
+        let chord = LineSegment::new(self.from(), self.to());
+        let within = |point: Point<T>| {
+            if chord.from().approx_eq(&chord.to()) {
+                point.distance(chord.from()) <= tolerance
+            } else {
+                chord.line().distance(point) <= tolerance
+            }
+        };
+
+        // The curve lies within the convex hull of its control points.
+        match self {
+            MonotoneEdge::Line(_) => true,
+            MonotoneEdge::Quadratic(quad) => within(quad.control()),
+            MonotoneEdge::Cubic(cubic) => within(cubic.control1()) && within(cubic.control2()),
+        }
+    }
+}
+
+/// Split the events of a path into edges that are monotone in Y.
+///
+/// Open subpaths are not closed, in the same way as `Path::segments`.
+pub(crate) fn monotone_edges<T: Real + ApproxEq>(
+    events: impl IntoIterator<Item = PathEvent<T>>,
+) -> impl Iterator<Item = MonotoneEdge<T>> {
+    events.into_iter().flat_map(|event| {
+        let (line, quad, cubic) = match event {
+            PathEvent::Begin { .. } | PathEvent::End { close: false, .. } => (None, None, None),
+            PathEvent::Line { from, to }
+            | PathEvent::End {
+                first: to,
+                last: from,
+                close: true,
+            } => (Some(LineSegment::new(from, to)), None, None),
+            PathEvent::Quadratic { from, control, to } => {
+                (None, Some(QuadraticBezier::new(from, control, to)), None)
+            }
+            PathEvent::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => (
+                None,
+                None,
+                Some(CubicBezier::new(from, control1, control2, to)),
+            ),
+            _ => unreachable!(),
+        };
+
+        line.into_iter()
+            .map(MonotoneEdge::Line)
+            .chain(
+                quad.into_iter()
+                    .flat_map(QuadraticBezier::split_monotone)
+                    .map(MonotoneEdge::Quadratic),
+            )
+            .chain(
+                cubic
+                    .into_iter()
+                    .flat_map(CubicBezier::split_monotone)
+                    .map(MonotoneEdge::Cubic),
+            )
+    })
+}
+
+/// A trapezoid with horizontal top and bottom edges, whose left and right sides may be
+/// curved.
+///
+/// This is what [`Shape::curved_trapezoids`] produces.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CurvedTrapezoid<T: Copy> {
+    /// The top edge of the trapezoid.
+    top: T,
+
+    /// The bottom edge of the trapezoid.
+    bottom: T,
+
+    /// The left side, going from the top to the bottom.
+    left: MonotoneEdge<T>,
+
+    /// The right side, going from the top to the bottom.
+    right: MonotoneEdge<T>,
+}
+
+impl<T: Copy> CurvedTrapezoid<T> {
+    /// Get the top edge of the trapezoid.
+    pub fn top(&self) -> T {
+        self.top
+    }
+
+    /// Get the bottom edge of the trapezoid.
+    pub fn bottom(&self) -> T {
+        self.bottom
+    }
+
+    /// Get the left side of the trapezoid, going from the top to the bottom.
+    pub fn left(&self) -> MonotoneEdge<T> {
+        self.left
+    }
+
+    /// Get the right side of the trapezoid, going from the top to the bottom.
+    pub fn right(&self) -> MonotoneEdge<T> {
+        self.right
+    }
+}
+
+impl<T: Real + ApproxEq> Path<T> for CurvedTrapezoid<T> {
+    type Iter = crate::iter::Five<PathEvent<T>>;
+
+    fn path_iter(self) -> Self::Iter {
+        let (left, right) = (self.left, self.right.reverse());
+
+        crate::iter::Five::from([
+            PathEvent::Begin { at: left.from() },
+            left.event(),
+            PathEvent::Line {
+                from: left.to(),
+                to: right.from(),
+            },
+            right.event(),
+            PathEvent::End {
+                first: left.from(),
+                last: right.to(),
+                close: true,
+            },
+        ])
+    }
+}
+
+impl<T: Real + ApproxEq> Shape<T> for CurvedTrapezoid<T> {}
+
+/// An iterator over the trapezoids of a shape with curved edges.
+///
+/// This is returned by [`Shape::curved_trapezoids`].
+#[derive(Debug, Clone)]
+pub struct CurvedTrapezoids<T: Copy> {
+    /// The trapezoids, which are all found up front.
+    inner: IntoIter<CurvedTrapezoid<T>>,
+}
+
+impl<T: Copy> Iterator for CurvedTrapezoids<T> {
+    type Item = CurvedTrapezoid<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T: Copy> ExactSizeIterator for CurvedTrapezoids<T> {}
+
+impl<T: Copy> FusedIterator for CurvedTrapezoids<T> {}
+
+/// An edge in the sweep, going downwards.
+struct SweepEdge<T: Copy> {
+    /// The edge itself.
+    edge: MonotoneEdge<T>,
+
+    /// The change in winding number when crossing this edge from left to right.
+    winding: i32,
+}
+
+/// Tessellate a shape made up of monotone edges into trapezoids with curved sides.
+pub(crate) fn curved_trapezoids<T: Real + ApproxEq>(
+    edges: impl IntoIterator<Item = MonotoneEdge<T>>,
+    tolerance: T,
+    fill_rule: FillRule,
+) -> CurvedTrapezoids<T> {
+    let mut edges = edges
+        .into_iter()
+        .filter_map(|edge| {
+            let (from, to) = (edge.from().y(), edge.to().y());
+            match from.partial_cmp(&to) {
+                Some(Ordering::Less) => Some(SweepEdge { edge, winding: 1 }),
+                Some(Ordering::Greater) => Some(SweepEdge {
+                    edge: edge.reverse(),
+                    winding: -1,
+                }),
+                // horizontal edges don't bound any area
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    edges.sort_by(|a, b| compare(a.edge.from().y(), b.edge.from().y()));
+
+    // the heights to stop at, with the next one at the end
+    let mut stops = edges
+        .iter()
+        .flat_map(|edge| [edge.edge.from().y(), edge.edge.to().y()])
+        .collect::<Vec<_>>();
+    stops.sort_by(|a, b| compare(*b, *a));
+    stops.dedup();
+
+    let mut trapezoids = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    let mut open: BTreeMap<(usize, usize), T> = BTreeMap::new();
+    let mut next_edge = 0;
+    let mut top = match stops.pop() {
+        Some(top) => top,
+        None => {
+            return CurvedTrapezoids {
+                inner: trapezoids.into_iter(),
+            }
+        }
+    };
+
+    while let Some(&next) = stops.last() {
+        while next_edge < edges.len() && edges[next_edge].edge.from().y() <= top {
+            active.push(next_edge);
+            next_edge += 1;
+        }
+        active.retain(|&i| edges[i].edge.to().y() > top);
+
+        // sort the edges in this band, stopping early at the first crossing
+        let mut bottom = next;
+        loop {
+            let mid = (top + bottom) / (T::one() + T::one());
+            active.sort_by(|&a, &b| compare(edges[a].edge.x_at_y(mid), edges[b].edge.x_at_y(mid)));
+
+            let first_crossing = active
+                .windows(2)
+                .filter_map(|pair| {
+                    crossing(
+                        edges[pair[0]].edge,
+                        edges[pair[1]].edge,
+                        top..bottom,
+                        tolerance,
+                        0,
+                    )
+                })
+                .filter(|&y| y - top > tolerance && bottom - y > tolerance)
+                .min_by(|a, b| compare(*a, *b));
+
+            match first_crossing {
+                Some(y) => {
+                    stops.push(y);
+                    bottom = y;
+                }
+                None => break,
+            }
+        }
+
+        // find the spans that are inside of the shape
+        let mut spans = BTreeMap::new();
+        let mut winding = 0;
+        let mut left = None;
+        for &i in &active {
+            let was_inside = fill_rule.is_inside(winding);
+            winding += edges[i].winding;
+
+            match (was_inside, fill_rule.is_inside(winding)) {
+                (false, true) => left = Some(i),
+                (true, false) => {
+                    if let Some(left) = left.take() {
+                        let start = open.remove(&(left, i)).unwrap_or(top);
+                        spans.insert((left, i), start);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // spans that didn't carry on into this band are finished
+        for ((left, right), start) in core::mem::replace(&mut open, spans) {
+            trapezoids.push(trapezoid(&edges, left, right, start, top));
+        }
+
+        stops.pop();
+        top = bottom;
+    }
+
+    for ((left, right), start) in open {
+        trapezoids.push(trapezoid(&edges, left, right, start, top));
+    }
+
+    CurvedTrapezoids {
+        inner: trapezoids.into_iter(),
+    }
+}
+
+/// Build the trapezoid between two edges.
+fn trapezoid<T: Real + ApproxEq>(
+    edges: &[SweepEdge<T>],
+    left: usize,
+    right: usize,
+    top: T,
+    bottom: T,
+) -> CurvedTrapezoid<T> {
+    CurvedTrapezoid {
+        top,
+        bottom,
+        left: edges[left].edge.between(top, bottom),
+        right: edges[right].edge.between(top, bottom),
+    }
+}
+
+/// Find the highest point in a band where two downward edges cross.
+///
+/// The pair is subdivided wherever their bounding boxes overlap, until both pieces are
+/// flat enough to treat as lines.
+fn crossing<T: Real + ApproxEq>(
+    a: MonotoneEdge<T>,
+    b: MonotoneEdge<T>,
+    band: Range<T>,
+    tolerance: T,
+    depth: usize,
+) -> Option<T> {
+    let (a, b) = (
+        a.between(band.start, band.end),
+        b.between(band.start, band.end),
+    );
+    let ((a_min, a_max), (b_min, b_max)) = (a.x_extent(), b.x_extent());
+    if a_max < b_min || b_max < a_min {
+        return None;
+    }
+
+    let flat = a.is_flat(tolerance) && b.is_flat(tolerance);
+    if flat || depth >= MAX_DEPTH || band.end - band.start <= tolerance {
+        // the pieces cross if their order at the top and bottom is different
+        let before = a.from().x() - b.from().x();
+        let after = a.to().x() - b.to().x();
+        let zero = T::zero();
+        return if (before < zero && after > zero) || (before > zero && after < zero) {
+            let t = before / (before - after);
+            Some(band.start + (band.end - band.start) * t)
+        } else {
+            None
+        };
+    }
+
+    let mid = (band.start + band.end) / (T::one() + T::one());
+    crossing(a, b, band.start..mid, tolerance, depth + 1)
+        .or_else(|| crossing(a, b, mid..band.end, tolerance, depth + 1))
+}
+
+/// Compare two numbers, treating `NaN` as equal to everything.
+fn compare<T: PartialOrd>(a: T, b: T) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{OwnedPathBuffer, PathBuffer};
+    use crate::Verb;
+    use alloc::vec;
+
+    /// A circle made out of four cubic curves.
+    fn circle(center: Point<f64>, radius: f64) -> OwnedPathBuffer<f64> {
+        let k = radius * 0.552_284_749_830_793_6;
+        let (x, y) = (center.x(), center.y());
+        let quarter = |to: Point<f64>, control1: Point<f64>, control2: Point<f64>| {
+            (to, Verb::Cubic { control1, control2 })
+        };
+
+        PathBuffer::new(
+            Point::new(x + radius, y),
+            vec![
+                quarter(
+                    Point::new(x, y + radius),
+                    Point::new(x + radius, y + k),
+                    Point::new(x + k, y + radius),
+                ),
+                quarter(
+                    Point::new(x - radius, y),
+                    Point::new(x - k, y + radius),
+                    Point::new(x - radius, y + k),
+                ),
+                quarter(
+                    Point::new(x, y - radius),
+                    Point::new(x - radius, y - k),
+                    Point::new(x - k, y - radius),
+                ),
+                quarter(
+                    Point::new(x + radius, y),
+                    Point::new(x + k, y - radius),
+                    Point::new(x + radius, y - k),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_curved_sides() {
+        let circle = circle(Point::new(0.0, 0.0), 10.0);
+        let trapezoids = (&circle).curved_trapezoids(0.01).collect::<Vec<_>>();
+
+        // The top and bottom halves, each bounded by two of the original curves.
+        assert_eq!(trapezoids.len(), 2);
+        for trapezoid in &trapezoids {
+            assert!(matches!(trapezoid.left(), MonotoneEdge::Cubic(_)));
+            assert!(matches!(trapezoid.right(), MonotoneEdge::Cubic(_)));
+        }
+
+        let area = trapezoids
+            .iter()
+            .fold(0.0, |area, trapezoid| area + trapezoid.area(0.01));
+        assert!((area - core::f64::consts::PI * 100.0).abs() < 0.5);
+
+        // Flattening first needs many more trapezoids for the same shape.
+        assert!((&circle).trapezoids(0.01).count() > 20);
+    }
+
+    #[test]
+    fn test_crossing_curves() {
+        // Two overlapping circles, which cross each other twice.
+        let a = circle(Point::new(0.0, 0.0), 10.0);
+        let b = circle(Point::new(10.0, 0.0), 10.0);
+        let edges = monotone_edges((&a).path_iter().chain((&b).path_iter()));
+        let trapezoids = curved_trapezoids(edges, 0.01, FillRule::Winding).collect::<Vec<_>>();
+
+        assert!(trapezoids.len() < 10);
+        for trapezoid in &trapezoids {
+            let (top, bottom) = (trapezoid.top(), trapezoid.bottom());
+            assert!(trapezoid.left().x_at_y(top) <= trapezoid.right().x_at_y(top) + 0.01);
+            assert!(trapezoid.left().x_at_y(bottom) <= trapezoid.right().x_at_y(bottom) + 0.01);
+        }
+
+        // The union of two unit circles a radius apart.
+        let lens = 2.0 * core::f64::consts::PI / 3.0 - 3.0f64.sqrt() / 2.0;
+        let expected = 100.0 * (2.0 * core::f64::consts::PI - lens);
+        let area = trapezoids
+            .iter()
+            .fold(0.0, |area, trapezoid| area + trapezoid.area(0.01));
+        assert!((area - expected).abs() < 1.0, "{} != {}", area, expected);
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Merging trapezoids that continue one another.
+
+use crate::line::Line;
+use crate::trapezoid::Trapezoid;
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num_traits::real::Real;
+
+/// Merge trapezoids that are stacked directly on top of one another along the same
+/// left and right edges.
+///
+/// The sweep splits trapezoids at the Y coordinate of every event, even when the event
+/// doesn't touch them. Merging them back together leaves less work for whatever consumes
+/// the trapezoids, such as a rasterizer.
+///
+/// The result is sorted by the top of each trapezoid.
+pub fn merge_trapezoids<T: Real + ApproxEq>(
+    trapezoids: impl IntoIterator<Item = Trapezoid<T>>,
+) -> Vec<Trapezoid<T>> {
+    let mut trapezoids: Vec<_> = trapezoids.into_iter().collect();
+    trapezoids.sort_by(|a, b| a.top().partial_cmp(&b.top()).unwrap_or(Ordering::Equal));
+
+    let mut merged: Vec<Trapezoid<T>> = Vec::with_capacity(trapezoids.len());
+
+    // Indices into `merged` of the trapezoids that could still be continued.
+    let mut open: Vec<usize> = Vec::new();
+
+    for trapezoid in trapezoids {
+        // Trapezoids that end above this one can't be continued anymore.
+        open.retain(|&index| {
+            let bottom = merged[index].bottom();
+            bottom >= trapezoid.top() || bottom.approx_eq(&trapezoid.top())
+        });
+
+        let continued = open.iter().copied().find(|&index| {
+            let above = &merged[index];
+            above.bottom().approx_eq(&trapezoid.top())
+                && same_line(
+                    above.left(),
+                    trapezoid.left(),
+                    above.top(),
+                    trapezoid.bottom(),
+                )
+                && same_line(
+                    above.right(),
+                    trapezoid.right(),
+                    above.top(),
+                    trapezoid.bottom(),
+                )
+        });
+
+        match continued {
+            Some(index) => {
+                let above = merged[index];
+                merged[index] =
+                    Trapezoid::new(above.top(), trapezoid.bottom(), above.left(), above.right());
+            }
+            None => {
+                open.push(merged.len());
+                merged.push(trapezoid);
+            }
+        }
+    }
+
+    merged.sort_by(|a, b| a.top().partial_cmp(&b.top()).unwrap_or(Ordering::Equal));
+    merged
+}
+
+/// Tell whether two lines run through the same points at two Y coordinates.
+fn same_line<T: Real + ApproxEq>(a: Line<T>, b: Line<T>, top: T, bottom: T) -> bool {
+    [top, bottom]
+        .iter()
+        .all(|&y| match (a.point_at_y(y), b.point_at_y(y)) {
+            (Some(a), Some(b)) => a.x().approx_eq(&b.x()),
+            _ => false,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Shape;
+    use crate::point::Point;
+    use crate::polygon::Polygon;
+
+    #[test]
+    fn test_merge() {
+        // The extra point on the left side splits the sweep in half.
+        let shape = Polygon::new(
+            alloc::vec![
+                Point::new(0.0f32, 0.0),
+                Point::new(4.0, 0.0),
+                Point::new(4.0, 4.0),
+                Point::new(0.0, 4.0),
+                Point::new(0.0, 2.0),
+            ],
+            Vec::new(),
+        );
+        let trapezoids: Vec<_> = (&shape).trapezoids(0.1).collect();
+        assert!(trapezoids.len() > 1);
+
+        let merged = merge_trapezoids(trapezoids);
+        assert_eq!(merged.len(), 1);
+        assert_eq!((merged[0].top(), merged[0].bottom()), (0.0, 4.0));
+        assert_eq!(Trapezoid::area(&merged[0]), 16.0);
+    }
+
+    #[test]
+    fn test_merge_distinct() {
+        let line = |x0: f32, x1: f32| Line::between(Point::new(x0, 0.0), Point::new(x1, 1.0));
+
+        // The second trapezoid doesn't touch the first, and the third changes direction.
+        let first = Trapezoid::new(0.0, 1.0, line(0.0, 0.0), line(1.0, 1.0));
+        let gap = Trapezoid::new(2.0, 3.0, line(0.0, 0.0), line(1.0, 1.0));
+        let bent = Trapezoid::new(1.0, 2.0, line(0.0, 1.0), line(1.0, 1.0));
+
+        assert_eq!(merge_trapezoids([first, gap, bent]).len(), 3);
+    }
+}
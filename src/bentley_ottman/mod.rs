@@ -20,15 +20,26 @@
 #![cfg(feature = "alloc")]
 
 use crate::line::LineSegment;
+use crate::path::{Flattened, LineSegments, PathEvent, Shape, StraightPathEvent};
 use crate::point::Point;
 use crate::trapezoid::Trapezoid;
-use crate::{ApproxEq, FillRule};
+use crate::{ApproxEq, FillRule, GeometryError};
 
+use alloc::vec::Vec;
 use core::{iter::FusedIterator, num::NonZeroUsize};
 use num_traits::real::Real;
 
 mod algorithm;
 mod compare;
+mod curved;
+mod merge;
+mod tessellator;
+
+pub(crate) use compare::AbsoluteEq;
+pub(crate) use curved::{curved_trapezoids, monotone_edges};
+pub use curved::{CurvedTrapezoid, CurvedTrapezoids, MonotoneEdge};
+pub use merge::merge_trapezoids;
+pub use tessellator::Tessellator;
 
 /// The whole point.
 ///
@@ -37,6 +48,7 @@ mod compare;
 ///
 /// The iterator does not yield intersections lazily; the entire
 /// `segments` iterator is consumed before the iterator is created.
+#[allow(dead_code)]
 pub(crate) fn bentley_ottmann<T: Real + ApproxEq>(
     segments: impl IntoIterator<Item = LineSegment<T>>,
 ) -> impl FusedIterator<Item = Point<T>> {
@@ -56,11 +68,12 @@ pub(crate) fn bentley_ottmann<T: Real + ApproxEq>(
 ///
 /// The iterator does not yield intersections lazily; the entire
 /// `segments` iterator is consumed before the iterator is created.
+#[allow(dead_code)]
 pub(crate) fn bentley_ottmann_events<T: Real + ApproxEq>(
     segments: impl IntoIterator<Item = LineSegment<T>>,
 ) -> BentleyOttmann<T> {
     BentleyOttmann {
-        inner: algorithm::Algorithm::new(segments.into_iter(), ()),
+        inner: algorithm::Algorithm::new(segments.into_iter().map(|segment| (segment, 0)), ()),
     }
 }
 
@@ -70,12 +83,265 @@ pub(crate) fn trapezoids<T: Real + ApproxEq>(
     fill_rule: FillRule,
 ) -> Trapezoids<T> {
     Trapezoids {
-        inner: algorithm::Algorithm::new(segments.into_iter(), fill_rule),
+        inner: algorithm::Algorithm::new(
+            segments.into_iter().map(|segment| (segment, 0)),
+            algorithm::Pairing::Combined(fill_rule),
+        ),
+    }
+}
+
+/// Tessellate the result of a boolean operation between two shapes into trapezoids.
+///
+/// Both shapes are filled using `fill_rule` before they are combined. This only takes
+/// one sweep, which is much faster than building the combined outline and then
+/// tessellating it.
+pub fn boolean_trapezoids<T: Real + ApproxEq, A: Shape<T>, B: Shape<T>>(
+    a: A,
+    b: B,
+    op: BooleanOp,
+    tolerance: T,
+    fill_rule: FillRule,
+) -> Trapezoids<T> {
+    let a = a
+        .segments(tolerance)
+        .skip_degenerate(true)
+        .map(|segment| (segment, 0));
+    let b = b
+        .segments(tolerance)
+        .skip_degenerate(true)
+        .map(|segment| (segment, 1));
+
+    Trapezoids {
+        inner: algorithm::Algorithm::new(a.chain(b), algorithm::Pairing::Boolean(op, fill_rule)),
+    }
+}
+
+/// Rasterizes the polygon defined by the tagged edges into trapezoids.
+///
+/// All of the edges are filled together as one shape. Each trapezoid is yielded
+/// alongside the tag of its left edge, which makes it possible to tell which input
+/// each part of the shape came from.
+pub fn trapezoids_tagged<T: Real + ApproxEq>(
+    segments: impl IntoIterator<Item = (usize, LineSegment<T>)>,
+    fill_rule: FillRule,
+) -> TaggedTrapezoids<T> {
+    TaggedTrapezoids {
+        inner: Trapezoids {
+            inner: algorithm::Algorithm::new(
+                segments.into_iter().map(|(tag, segment)| (segment, tag)),
+                algorithm::Pairing::Combined(fill_rule),
+            ),
+        },
+    }
+}
+
+/// Tessellate several shapes into trapezoids in a single sweep.
+///
+/// Every shape is filled on its own using `fill_rule`, so overlapping shapes produce
+/// overlapping trapezoids. Each trapezoid is yielded alongside the index of the shape
+/// that it came from.
+pub fn trapezoids_multi<T: Real + ApproxEq, S: Shape<T>>(
+    shapes: impl IntoIterator<Item = S>,
+    tolerance: T,
+    fill_rule: FillRule,
+) -> TaggedTrapezoids<T> {
+    let segments = shapes.into_iter().enumerate().flat_map(move |(id, shape)| {
+        shape
+            .segments(tolerance)
+            .skip_degenerate(true)
+            .map(move |segment| (segment, id))
+    });
+
+    TaggedTrapezoids {
+        inner: Trapezoids {
+            inner: algorithm::Algorithm::new(segments, algorithm::Pairing::PerTag(fill_rule)),
+        },
+    }
+}
+
+/// Rasterizes the polygon defined by the path into trapezoids, checking that its
+/// coordinates are finite first.
+pub(crate) fn try_trapezoids<T: Real + ApproxEq>(
+    path: impl IntoIterator<Item = PathEvent<T>>,
+    tolerance: T,
+    fill_rule: FillRule,
+) -> Result<Trapezoids<T>, GeometryError<T>> {
+    if !crate::is_finite(tolerance) || tolerance <= T::zero() {
+        return Err(GeometryError::InvalidTolerance { tolerance });
+    }
+
+    // Check the path before flattening it, since flattening doesn't expect NaN.
+    let events = path.into_iter().collect::<Vec<_>>();
+    for event in &events {
+        let (from, to, controls) = match *event {
+            PathEvent::Begin { at } => (at, at, [at, at]),
+            PathEvent::Line { from, to } => (from, to, [from, to]),
+            PathEvent::Quadratic { from, control, to } => (from, to, [control, control]),
+            PathEvent::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => (from, to, [control1, control2]),
+            PathEvent::End { first, last, .. } => (last, first, [last, first]),
+            PathEvent::__NonExhaustive => continue,
+        };
+
+        check_segment(LineSegment::new(from, to), &controls)?;
+    }
+
+    // Flattening very large curves can still overflow.
+    let segments = LineSegments::new(Flattened::new(events.into_iter(), tolerance))
+        .skip_degenerate(true)
+        .map(|segment| check_segment(segment, &[]).map(|()| segment))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(trapezoids(segments, fill_rule))
+}
+
+/// Rasterizes the polygon defined by the path into trapezoids, failing if the
+/// tessellator does more work than `limits` allow.
+pub(crate) fn try_trapezoids_with_limits<T: Real + ApproxEq>(
+    path: impl IntoIterator<Item = PathEvent<T>>,
+    tolerance: T,
+    fill_rule: FillRule,
+    limits: TessellationLimits,
+) -> Result<Vec<Trapezoid<T>>, GeometryError<T>> {
+    let mut trapezoids = try_trapezoids(path, tolerance, fill_rule)?.with_limits(limits);
+    let collected = trapezoids.by_ref().collect::<Vec<_>>();
+
+    match trapezoids.exceeded() {
+        Some(err) => Err(err),
+        None => Ok(collected),
+    }
+}
+
+/// Rasterizes the polygon defined by the path into trapezoids, handling subpaths that
+/// were left open according to `policy`.
+pub(crate) fn trapezoids_with_open_policy<T: Real + ApproxEq>(
+    path: impl IntoIterator<Item = PathEvent<T>>,
+    tolerance: T,
+    fill_rule: FillRule,
+    policy: OpenPathPolicy,
+) -> Result<Trapezoids<T>, GeometryError<T>> {
+    let mut segments = Vec::new();
+    let mut subpath = Vec::new();
+
+    for event in Flattened::new(path.into_iter(), tolerance) {
+        match event {
+            StraightPathEvent::Begin { .. } => subpath.clear(),
+            StraightPathEvent::Line { from, to } => subpath.push(LineSegment::new(from, to)),
+            StraightPathEvent::End { first, last, close } => {
+                // subpaths that end where they started don't need to be closed
+                let open = !close && !first.approx_eq(&last);
+
+                match (open, policy) {
+                    (true, OpenPathPolicy::SkipOpen) => subpath.clear(),
+                    (true, OpenPathPolicy::Error) => {
+                        return Err(GeometryError::OpenSubpath { first, last })
+                    }
+                    _ => {
+                        segments.append(&mut subpath);
+                        segments.push(LineSegment::new(last, first));
+                    }
+                }
+            }
+            StraightPathEvent::__NonExhaustive => {}
+        }
+    }
+
+    Ok(trapezoids(
+        segments
+            .into_iter()
+            .filter(|segment| !segment.from().approx_eq(&segment.to())),
+        fill_rule,
+    ))
+}
+
+/// Clip a segment to a box, keeping the winding of the area inside of the box intact.
+///
+/// Parts of the segment above or below the box are removed, and parts to the left or right
+/// of it are flattened onto its nearest side. Any segment that would cross the box
+/// horizontally still does so as a vertical edge, so shapes that cover the box keep
+/// covering it.
+pub(crate) fn clip_segment<T: Real>(
+    segment: LineSegment<T>,
+    clip: &crate::Box<T>,
+) -> crate::iter::Three<LineSegment<T>> {
+    let (min, max) = clip.min_max();
+    let (a, b) = segment.points();
+
+    // Drop segments that are entirely above or below the box.
+    if (a.y() <= min.y() && b.y() <= min.y()) || (a.y() >= max.y() && b.y() >= max.y()) {
+        return crate::iter::Three::empty();
+    }
+
+    // Trim the segment to the rows of the box. The segment can't be horizontal here,
+    // since it would have been dropped above.
+    let trim = |p: Point<T>, q: Point<T>| {
+        let y = if p.y() < min.y() {
+            min.y()
+        } else if p.y() > max.y() {
+            max.y()
+        } else {
+            return p;
+        };
+
+        let t = (y - p.y()) / (q.y() - p.y());
+        Point::new(p.x() + (q.x() - p.x()) * t, y)
+    };
+    let (a, b) = (trim(a, b), trim(b, a));
+
+    // Split the segment where it crosses the sides of the box.
+    let crossing = |x: T| {
+        if (a.x() - x) * (b.x() - x) < T::zero() {
+            Some((x - a.x()) / (b.x() - a.x()))
+        } else {
+            None
+        }
+    };
+    let mut cuts = [crossing(min.x()), crossing(max.x())];
+    if a.x() > b.x() {
+        cuts.swap(0, 1);
+    }
+
+    let clamp = |p: Point<T>| Point::new(p.x().max(min.x()).min(max.x()), p.y());
+    let mut points = [clamp(a); 4];
+    let mut len = 1;
+    for &t in cuts.iter().flatten() {
+        points[len] = clamp(a.lerp(b, t));
+        len += 1;
+    }
+    points[len] = clamp(b);
+
+    let piece = |i: usize| LineSegment::new(points[i], points[i + 1]);
+    match len {
+        1 => crate::iter::Three::from([piece(0)]),
+        2 => crate::iter::Three::from([piece(0), piece(1)]),
+        _ => crate::iter::Three::from([piece(0), piece(1), piece(2)]),
+    }
+}
+
+/// Make sure that a segment and any extra points that go along with it are finite.
+fn check_segment<T: Real>(
+    segment: LineSegment<T>,
+    extra: &[Point<T>],
+) -> Result<(), GeometryError<T>> {
+    let finite = [segment.from(), segment.to()]
+        .iter()
+        .chain(extra)
+        .all(|point| crate::is_finite(point.x()) && crate::is_finite(point.y()));
+
+    if finite {
+        Ok(())
+    } else {
+        Err(GeometryError::NonFinite { segment })
     }
 }
 
 /// An event that may occur in the Bentley-Ottmann algorithm.
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
 pub(crate) struct Event<Num: Copy> {
     /// The edge that this event is associated with.
     pub edge: LineSegment<Num>,
@@ -92,6 +358,7 @@ pub(crate) struct Event<Num: Copy> {
 
 /// The type of event that may occur in the Bentley-Ottmann algorithm.
 #[derive(Debug, Clone)]
+#[allow(dead_code)]
 pub enum EventType<Num: Copy> {
     /// A start event, or the beginning of a segment.
     Start,
@@ -106,6 +373,7 @@ pub enum EventType<Num: Copy> {
     },
 }
 
+#[allow(dead_code)]
 pub(crate) struct BentleyOttmann<Num: Copy> {
     inner: algorithm::Algorithm<Num, algorithm::NoTrapezoids>,
 }
@@ -131,11 +399,128 @@ pub struct Trapezoids<Num: Copy> {
     inner: algorithm::Algorithm<Num, algorithm::Trapezoids<Num>>,
 }
 
+impl<Num: Real + ApproxEq> Trapezoids<Num> {
+    /// Limit how much work the tessellator may do.
+    ///
+    /// Once a limit is exceeded, the iterator stops yielding trapezoids and
+    /// [`Trapezoids::exceeded`] returns the limit that was hit. This keeps untrusted
+    /// shapes from using unbounded time or memory. The trapezoids yielded before then only
+    /// cover part of the shape; use [`Shape::try_trapezoids_with_limits`] to get an error
+    /// instead.
+    pub fn with_limits(mut self, limits: TessellationLimits) -> Self {
+        self.inner.set_limits(limits);
+        self
+    }
+
+    /// Get the limit that stopped the tessellator early, if any.
+    ///
+    /// When this is `Some`, the trapezoids that were yielded only cover part of the shape.
+    pub fn exceeded(&self) -> Option<GeometryError<Num>> {
+        self.inner.exceeded()
+    }
+
+    /// Get counters for the work the tessellator has done so far.
+    ///
+    /// The counters are updated as the iterator is advanced, so they describe the whole
+    /// tessellation once the iterator has been exhausted.
+    pub fn stats(&self) -> TessellationStats {
+        self.inner.stats()
+    }
+}
+
+/// What to do with subpaths that were left open while tessellating a shape.
+///
+/// This is passed to [`Shape::trapezoids_with_open_policy`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OpenPathPolicy {
+    /// Close the subpath with a straight line from its last point to its first point.
+    AutoClose,
+
+    /// Leave the subpath out of the shape.
+    SkipOpen,
+
+    /// Fail with [`GeometryError::OpenSubpath`].
+    Error,
+}
+
+/// A boolean operation used to combine two shapes.
+///
+/// This is passed to [`boolean_trapezoids`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BooleanOp {
+    /// Everything inside of either shape.
+    Union,
+
+    /// Everything inside of both shapes.
+    Intersection,
+
+    /// Everything inside of the first shape but not the second.
+    Difference,
+
+    /// Everything inside of exactly one of the shapes.
+    Xor,
+}
+
+impl BooleanOp {
+    /// Tell whether a point is kept, given whether it is inside of either shape.
+    pub(crate) fn combine(self, a: bool, b: bool) -> bool {
+        match self {
+            BooleanOp::Union => a || b,
+            BooleanOp::Intersection => a && b,
+            BooleanOp::Difference => a && !b,
+            BooleanOp::Xor => a != b,
+        }
+    }
+}
+
+/// Limits on the work done while tessellating a shape.
+///
+/// This is passed to [`Trapezoids::with_limits`]. By default, there are no limits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TessellationLimits {
+    /// The largest number of events that may be processed.
+    pub max_events: usize,
+
+    /// The largest number of events that may be waiting in the queue at once.
+    pub max_queue_len: usize,
+}
+
+impl Default for TessellationLimits {
+    #[inline]
+    fn default() -> Self {
+        TessellationLimits {
+            max_events: usize::MAX,
+            max_queue_len: usize::MAX,
+        }
+    }
+}
+
+/// Counters for the work done while tessellating a shape.
+///
+/// This is returned by [`Trapezoids::stats`], and is useful for profiling shapes that are
+/// slow to tessellate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TessellationStats {
+    /// The number of start, stop and intersection events processed.
+    pub events: usize,
+
+    /// The number of intersections found between edges.
+    pub intersections: usize,
+
+    /// The largest number of edges that crossed the sweep line at once.
+    pub max_active_edges: usize,
+
+    /// The number of trapezoids emitted.
+    pub trapezoids: usize,
+}
+
 impl<Num: Real + ApproxEq> Iterator for Trapezoids<Num> {
     type Item = Trapezoid<Num>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next_trapezoid()
+        self.inner.next_trapezoid().map(|(_, trap)| trap)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -148,3 +533,377 @@ impl<Num: Real + ApproxEq> Iterator for Trapezoids<Num> {
 }
 
 impl<Num: Real + ApproxEq> FusedIterator for Trapezoids<Num> {}
+
+/// The return type of [`trapezoids_multi`] and [`trapezoids_tagged`].
+///
+/// This yields every trapezoid alongside the ID of the input it came from.
+pub struct TaggedTrapezoids<Num: Copy> {
+    inner: Trapezoids<Num>,
+}
+
+impl<Num: Real + ApproxEq> TaggedTrapezoids<Num> {
+    /// Limit how much work the tessellator may do.
+    ///
+    /// See [`Trapezoids::with_limits`] for more information.
+    pub fn with_limits(self, limits: TessellationLimits) -> Self {
+        TaggedTrapezoids {
+            inner: self.inner.with_limits(limits),
+        }
+    }
+
+    /// Get the limit that stopped the tessellator early, if any.
+    pub fn exceeded(&self) -> Option<GeometryError<Num>> {
+        self.inner.exceeded()
+    }
+
+    /// Get counters for the work the tessellator has done so far.
+    pub fn stats(&self) -> TessellationStats {
+        self.inner.stats()
+    }
+}
+
+impl<Num: Real + ApproxEq> Iterator for TaggedTrapezoids<Num> {
+    type Item = (usize, Trapezoid<Num>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.inner.next_trapezoid()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<Num: Real + ApproxEq> FusedIterator for TaggedTrapezoids<Num> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{BooleanOp, TessellationLimits, TessellationStats};
+    use crate::path::{Compound, Path, Shape};
+    use crate::polygon::Polygon;
+    use crate::{Box, FillRule, GeometryError, Point, Trapezoid};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_square() {
+        // The sweep only emits trapezoids once the sweep line moves down, so a square
+        // needs the sweep to step from its top edge to its bottom edge.
+        let square = Box::new(Point::new(0.0f32, 0.0), Point::new(2.0, 2.0));
+        let trapezoids = square.trapezoids(0.1).collect::<Vec<_>>();
+
+        assert_eq!(trapezoids.len(), 1);
+        let trapezoid = trapezoids[0];
+        assert_eq!((trapezoid.top(), trapezoid.bottom()), (0.0, 2.0));
+        assert_eq!(
+            trapezoid.left_segment().points(),
+            (Point::new(0.0, 0.0), Point::new(0.0, 2.0))
+        );
+        assert_eq!(
+            trapezoid.right_segment().points(),
+            (Point::new(2.0, 0.0), Point::new(2.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn test_trapezoids_clipped() {
+        let area = |trapezoids: super::Trapezoids<f32>| {
+            trapezoids.fold(0.0, |area, trap| area + trap.area(0.1))
+        };
+
+        // A huge shape covers the whole clip.
+        let huge = Box::new(Point::new(-1000.0f32, -1000.0), Point::new(1000.0, 1000.0));
+        let clip = Box::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+        let trapezoids: Vec<_> = huge.trapezoids_clipped(0.1, clip).collect();
+        assert!(trapezoids
+            .iter()
+            .all(|trap| clip.union(&crate::BoundingBox::bounding_box(trap)) == clip));
+        assert_eq!(area(huge.trapezoids_clipped(0.1, clip)), 100.0);
+
+        // The hypotenuse of a triangle cuts the clip in half.
+        let triangle = Polygon::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(20.0, 0.0),
+                Point::new(0.0, 20.0),
+            ],
+            vec![],
+        );
+        let clip = Box::new(Point::new(5.0, 5.0), Point::new(15.0, 15.0));
+        assert!((area((&triangle).trapezoids_clipped(0.1, clip)) - 50.0).abs() < 0.01);
+
+        // Shapes outside of the clip produce nothing.
+        let clip = Box::new(Point::new(2000.0, 0.0), Point::new(2010.0, 10.0));
+        assert_eq!(area(huge.trapezoids_clipped(0.1, clip)), 0.0);
+    }
+
+    #[test]
+    fn test_try_trapezoids() {
+        let shape = Box::new(Point::new(0.0f32, 0.0), Point::new(2.0, 2.0));
+        let area = shape
+            .try_trapezoids(0.1)
+            .unwrap()
+            .fold(0.0, |area, trap| area + trap.area(0.1));
+        assert_eq!(area, 4.0);
+
+        assert!(matches!(
+            shape.try_trapezoids(f32::NAN),
+            Err(GeometryError::InvalidTolerance { .. })
+        ));
+        assert!(matches!(
+            shape.try_trapezoids(0.0),
+            Err(GeometryError::InvalidTolerance { .. })
+        ));
+    }
+
+    #[test]
+    fn test_try_trapezoids_nan() {
+        let shape = Polygon::new(
+            vec![
+                Point::new(0.0f32, 0.0),
+                Point::new(1.0, 0.0),
+                Point::new(f32::NAN, 1.0),
+            ],
+            vec![],
+        );
+
+        match (&shape).try_trapezoids(0.1) {
+            Err(GeometryError::NonFinite { segment }) => {
+                assert_eq!(segment.from(), Point::new(1.0, 0.0));
+                assert!(segment.to().x().is_nan());
+            }
+            _ => panic!("expected a non-finite segment"),
+        }
+
+        let infinite = Box::new(Point::new(0.0f32, 0.0), Point::new(f32::INFINITY, 1.0));
+        assert!(infinite.try_trapezoids(0.1).is_err());
+    }
+
+    #[test]
+    fn test_stats() {
+        // An arrow pointing down has a notch where four edges cross the sweep line.
+        let arrow = Polygon::new(
+            vec![
+                Point::new(0.0f32, 0.0),
+                Point::new(2.0, 1.0),
+                Point::new(4.0, 0.0),
+                Point::new(2.0, 4.0),
+            ],
+            Vec::new(),
+        );
+
+        let mut trapezoids = (&arrow).trapezoids(0.1);
+        assert_eq!(trapezoids.stats(), TessellationStats::default());
+
+        let count = trapezoids.by_ref().count();
+        let stats = trapezoids.stats();
+        assert_eq!(stats.trapezoids, count);
+        assert_eq!(stats.intersections, 0);
+        assert_eq!(stats.max_active_edges, 4);
+        assert_eq!(stats.events, 8);
+    }
+
+    #[test]
+    fn test_limits() {
+        let shape = Polygon::new(
+            (0..32)
+                .map(|i| {
+                    let angle = i as f32 * core::f32::consts::PI / 16.0;
+                    Point::new(angle.cos(), angle.sin())
+                })
+                .collect(),
+            Vec::new(),
+        );
+
+        let mut trapezoids = (&shape).trapezoids(0.1);
+        trapezoids.by_ref().for_each(drop);
+        assert_eq!(trapezoids.exceeded(), None);
+        let events = trapezoids.stats().events;
+
+        let limits = TessellationLimits {
+            max_events: events / 2,
+            ..Default::default()
+        };
+        let mut trapezoids = (&shape).trapezoids(0.1).with_limits(limits);
+        trapezoids.by_ref().for_each(drop);
+        assert_eq!(
+            trapezoids.exceeded(),
+            Some(GeometryError::TooManyEvents { limit: events / 2 })
+        );
+        assert_eq!(trapezoids.stats().events, events / 2);
+
+        let limits = TessellationLimits {
+            max_queue_len: 4,
+            ..Default::default()
+        };
+        let mut trapezoids = (&shape).trapezoids(0.1).with_limits(limits);
+        assert_eq!(trapezoids.next(), None);
+        assert_eq!(
+            trapezoids.exceeded(),
+            Some(GeometryError::QueueTooLarge { limit: 4 })
+        );
+    }
+
+    #[test]
+    fn test_try_trapezoids_with_limits() {
+        let shape = Polygon::new(
+            (0..32)
+                .map(|i| {
+                    let angle = i as f32 * core::f32::consts::PI / 16.0;
+                    Point::new(angle.cos(), angle.sin())
+                })
+                .collect(),
+            Vec::new(),
+        );
+
+        let all = (&shape)
+            .try_trapezoids_with_limits(0.1, TessellationLimits::default())
+            .unwrap();
+        assert_eq!(all, (&shape).trapezoids(0.1).collect::<Vec<_>>());
+
+        let limits = TessellationLimits {
+            max_events: 8,
+            ..Default::default()
+        };
+        assert_eq!(
+            (&shape).try_trapezoids_with_limits(0.1, limits),
+            Err(GeometryError::TooManyEvents { limit: 8 })
+        );
+
+        let limits = TessellationLimits {
+            max_queue_len: 4,
+            ..Default::default()
+        };
+        assert_eq!(
+            (&shape).try_trapezoids_with_limits(0.1, limits),
+            Err(GeometryError::QueueTooLarge { limit: 4 })
+        );
+        assert!(matches!(
+            (&shape).try_trapezoids_with_limits(f32::NAN, limits),
+            Err(GeometryError::InvalidTolerance { .. })
+        ));
+    }
+
+    fn square(min: f32, max: f32) -> Vec<Point<f32>> {
+        vec![
+            Point::new(min, min),
+            Point::new(max, min),
+            Point::new(max, max),
+            Point::new(min, max),
+        ]
+    }
+
+    #[test]
+    fn test_fill_rule_overlap() {
+        // Two overlapping squares, wound in the same direction.
+        let shape = Polygon::new(square(0.0, 4.0), vec![square(2.0, 6.0)]);
+
+        assert_eq!((&shape).area_with_fill_rule(0.1, FillRule::Winding), 28.0);
+        assert_eq!((&shape).area_with_fill_rule(0.1, FillRule::EvenOdd), 24.0);
+    }
+
+    #[test]
+    fn test_trapezoids_multi() {
+        let shapes = vec![
+            Polygon::new(square(0.0, 4.0), vec![]),
+            Polygon::new(square(2.0, 6.0), vec![]),
+            Polygon::new(square(10.0, 12.0), vec![]),
+        ];
+
+        // Overlapping shapes are still filled separately.
+        let mut areas = [0.0; 3];
+        for (id, trap) in super::trapezoids_multi(&shapes, 0.1, FillRule::Winding) {
+            areas[id] += Trapezoid::area(&trap);
+        }
+        assert_eq!(areas, [16.0, 16.0, 4.0]);
+    }
+
+    #[test]
+    fn test_boolean_trapezoids() {
+        let a = Polygon::new(square(0.0, 4.0), vec![]);
+        let b = Polygon::new(square(2.0, 6.0), vec![]);
+        let area = |op| {
+            super::boolean_trapezoids(&a, &b, op, 0.1, FillRule::Winding)
+                .map(|trap| Trapezoid::area(&trap))
+                .sum::<f32>()
+        };
+
+        assert_eq!(area(BooleanOp::Union), 28.0);
+        assert_eq!(area(BooleanOp::Intersection), 4.0);
+        assert_eq!(area(BooleanOp::Difference), 12.0);
+        assert_eq!(area(BooleanOp::Xor), 24.0);
+
+        // A shape with a hole cut out of it.
+        let hole = Polygon::new(square(2.0, 4.0), vec![]);
+        let big = Polygon::new(square(0.0, 10.0), vec![]);
+        let area =
+            super::boolean_trapezoids(&big, &hole, BooleanOp::Difference, 0.1, FillRule::Winding)
+                .map(|trap| Trapezoid::area(&trap))
+                .sum::<f32>();
+        assert_eq!(area, 96.0);
+    }
+
+    #[test]
+    fn test_trapezoids_tagged() {
+        let a = Polygon::new(square(0.0, 4.0), vec![]);
+        let b = Polygon::new(square(2.0, 6.0), vec![]);
+        let segments = (&a)
+            .segments(0.1)
+            .map(|segment| (1, segment))
+            .chain((&b).segments(0.1).map(|segment| (2, segment)));
+
+        // The shapes are filled together, and the left edge decides the tag.
+        let mut areas = [0.0; 3];
+        for (tag, trap) in super::trapezoids_tagged(segments, FillRule::Winding) {
+            areas[tag] += Trapezoid::area(&trap);
+        }
+        assert_eq!(areas, [0.0, 20.0, 8.0]);
+    }
+
+    #[test]
+    fn test_fill_rule_holes() {
+        // A hole wound the same way as its outline only counts under even-odd.
+        let shape = Polygon::new(square(0.0, 10.0), vec![square(2.0, 4.0)]);
+        assert_eq!((&shape).area_with_fill_rule(0.1, FillRule::Winding), 100.0);
+        assert_eq!((&shape).area_with_fill_rule(0.1, FillRule::EvenOdd), 96.0);
+
+        // Polygons use the non-zero rule for their area, like every other shape.
+        assert_eq!((&shape).area(0.1), 100.0);
+
+        // A hole wound the other way is subtracted under both rules.
+        let mut hole = square(2.0, 4.0);
+        hole.reverse();
+        let shape = Polygon::new(square(0.0, 10.0), vec![hole]);
+        assert_eq!((&shape).area_with_fill_rule(0.1, FillRule::Winding), 96.0);
+        assert_eq!((&shape).area_with_fill_rule(0.1, FillRule::EvenOdd), 96.0);
+
+        // The perimeter includes the outline of the hole.
+        assert_eq!((&shape).perimeter(0.1), 48.0);
+
+        // Compound shapes fix up the winding of their holes.
+        let compound = Compound::new(Box::new(Point::new(0.0f32, 0.0), Point::new(10.0, 10.0)))
+            .with_hole(Box::new(Point::new(2.0, 2.0), Point::new(4.0, 4.0)));
+        assert_eq!((&compound).area(0.1), 96.0);
+        assert_eq!((&compound).perimeter(0.1), 48.0);
+    }
+
+    #[test]
+    fn test_bowtie() {
+        let bowtie = Polygon::new(
+            vec![
+                Point::new(0.0f32, 0.0),
+                Point::new(2.0, 2.0),
+                Point::new(2.0, 0.0),
+                Point::new(0.0, 2.0),
+            ],
+            Vec::new(),
+        );
+
+        let mut trapezoids = (&bowtie).trapezoids(0.1);
+        let area = trapezoids
+            .by_ref()
+            .fold(0.0, |area, trap| area + trap.area(0.1));
+        assert!((area - 2.0).abs() < 0.01);
+        assert_eq!(trapezoids.stats().intersections, 1);
+    }
+}
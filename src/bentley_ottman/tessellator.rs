@@ -0,0 +1,370 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Tessellation that reuses the results of previous calls.
+
+use super::trapezoids;
+use crate::box2d::Box;
+use crate::path::{Flattened, LineSegments, Path, PathEvent};
+use crate::polygon::sweep::overlapping_pairs;
+use crate::trapezoid::Trapezoid;
+use crate::{ApproxEq, FillRule};
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::hash::Hasher;
+use num_traits::real::Real;
+
+/// A tessellator that caches its output, so that paths which only change slightly
+/// between frames can be re-tessellated quickly.
+///
+/// Subpaths whose bounding boxes overlap can affect each other's winding, so they are
+/// tessellated together as a group. Groups that are unchanged since the last call to
+/// [`Tessellator::tessellate`] reuse their old trapezoids.
+#[derive(Debug, Clone)]
+pub struct Tessellator<T: Copy> {
+    /// The tolerance used to flatten curves.
+    tolerance: T,
+
+    /// The groups of subpaths that were tessellated, keyed by the hash of the group.
+    ///
+    /// Different groups can have the same hash, so every group with a hash is kept.
+    cache: BTreeMap<u64, Vec<CachedGroup<T>>>,
+
+    /// The number of groups that were reused during the last call to `tessellate`.
+    reused: usize,
+
+    /// The number of groups that were tessellated during the last call to `tessellate`.
+    tessellated: usize,
+}
+
+/// A group of subpaths and the trapezoids that it was tessellated into.
+#[derive(Debug, Clone)]
+struct CachedGroup<T: Copy> {
+    /// The events of every subpath in the group.
+    events: Vec<PathEvent<T>>,
+
+    /// The trapezoids of the group.
+    trapezoids: Vec<Trapezoid<T>>,
+}
+
+/// A subpath that has been read out of a path.
+struct Subpath<T: Copy> {
+    /// The events that make up the subpath.
+    events: Vec<PathEvent<T>>,
+
+    /// The bounding box of every point in the subpath, including control points.
+    bounds: Box<T>,
+
+    /// The hash of the events.
+    hash: u64,
+}
+
+impl<T: Real + ApproxEq> Tessellator<T> {
+    /// Create a new, empty `Tessellator` that flattens curves with the given tolerance.
+    pub fn new(tolerance: T) -> Self {
+        Tessellator {
+            tolerance,
+            cache: BTreeMap::new(),
+            reused: 0,
+            tessellated: 0,
+        }
+    }
+
+    /// Get the tolerance used to flatten curves.
+    pub fn tolerance(&self) -> T {
+        self.tolerance
+    }
+
+    /// Get the number of groups of subpaths that were reused by the last call to
+    /// `tessellate`.
+    pub fn reused(&self) -> usize {
+        self.reused
+    }
+
+    /// Get the number of groups of subpaths that had to be tessellated by the last call
+    /// to `tessellate`.
+    pub fn tessellated(&self) -> usize {
+        self.tessellated
+    }
+
+    /// Forget every cached result.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Tessellate a path into trapezoids, using the non-zero fill rule.
+    ///
+    /// Only results used by this call are kept in the cache afterwards.
+    pub fn tessellate<P: Path<T>>(&mut self, path: P) -> Vec<Trapezoid<T>> {
+        let subpaths = split_subpaths(path);
+        let groups = group_overlapping(&subpaths);
+
+        let mut cache: BTreeMap<u64, Vec<CachedGroup<T>>> = BTreeMap::new();
+        let mut output = Vec::new();
+        self.reused = 0;
+        self.tessellated = 0;
+
+        for group in groups {
+            let mut hasher = Fnv::default();
+            for &index in &group {
+                hasher.write_u64(subpaths[index].hash);
+            }
+            let key = hasher.finish();
+            let events: Vec<_> = group
+                .iter()
+                .flat_map(|&index| subpaths[index].events.iter().copied())
+                .collect();
+
+            // A group is only reused if its events are the same, not just its hash.
+            let cached = take_group(&mut self.cache, key, &events)
+                .or_else(|| take_group(&mut cache, key, &events));
+            let trapezoids = match cached {
+                Some(cached) => {
+                    self.reused += 1;
+                    cached.trapezoids
+                }
+                None => {
+                    self.tessellated += 1;
+                    let segments =
+                        LineSegments::new(Flattened::new(events.iter().copied(), self.tolerance))
+                            .skip_degenerate(true);
+
+                    trapezoids(segments, FillRule::Winding).collect()
+                }
+            };
+
+            output.extend_from_slice(&trapezoids);
+            cache
+                .entry(key)
+                .or_default()
+                .push(CachedGroup { events, trapezoids });
+        }
+
+        self.cache = cache;
+        output
+    }
+}
+
+/// Remove a group with the given hash and events from the cache.
+fn take_group<T: Copy + PartialEq>(
+    cache: &mut BTreeMap<u64, Vec<CachedGroup<T>>>,
+    key: u64,
+    events: &[PathEvent<T>],
+) -> Option<CachedGroup<T>> {
+    let groups = cache.get_mut(&key)?;
+    let index = groups.iter().position(|group| group.events == events)?;
+    Some(groups.swap_remove(index))
+}
+
+/// Read the subpaths out of a path, hashing each of them.
+fn split_subpaths<T: Real, P: Path<T>>(path: P) -> Vec<Subpath<T>> {
+    let mut subpaths: Vec<Subpath<T>> = Vec::new();
+
+    for event in path.path_iter() {
+        let (tag, points) = match event {
+            PathEvent::Begin { at } => (0, [at, at, at, at]),
+            PathEvent::Line { from, to } => (1, [from, to, to, to]),
+            PathEvent::Quadratic { from, control, to } => (2, [from, control, to, to]),
+            PathEvent::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => (3, [from, control1, control2, to]),
+            PathEvent::End { first, last, close } => (4 + close as u8, [first, last, last, last]),
+            PathEvent::__NonExhaustive => continue,
+        };
+
+        if let PathEvent::Begin { .. } = event {
+            subpaths.push(Subpath {
+                events: Vec::new(),
+                bounds: Box::unbounded_real(),
+                hash: 0,
+            });
+        }
+
+        let subpath = match subpaths.last_mut() {
+            Some(subpath) => subpath,
+            None => continue,
+        };
+
+        let mut hasher = Fnv(subpath.hash);
+        hasher.write_u8(tag);
+        for coordinate in points.iter().flat_map(|point| [point.x(), point.y()]) {
+            // Coordinates that can't be converted still hash differently from zero.
+            match coordinate.to_f64() {
+                Some(coordinate) => hasher.write_u64(coordinate.to_bits()),
+                None => hasher.write_u8(0xff),
+            }
+        }
+
+        subpath.hash = hasher.finish();
+        subpath.bounds = subpath.bounds.with_points(points.iter().copied());
+        subpath.events.push(event);
+    }
+
+    subpaths
+}
+
+/// Group together subpaths whose bounding boxes overlap, directly or through another
+/// subpath.
+fn group_overlapping<T: Real>(subpaths: &[Subpath<T>]) -> Vec<Vec<usize>> {
+    // A union-find over the subpaths.
+    let mut parents: Vec<usize> = (0..subpaths.len()).collect();
+    let find = |parents: &mut Vec<usize>, mut index: usize| {
+        while parents[index] != index {
+            parents[index] = parents[parents[index]];
+            index = parents[index];
+        }
+        index
+    };
+
+    // Sweep over the diagonals of the bounding boxes, rather than comparing every pair.
+    let diagonals: Vec<_> = subpaths
+        .iter()
+        .map(|subpath| subpath.bounds.min_max())
+        .collect();
+    for (i, j) in overlapping_pairs(&diagonals) {
+        let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+        parents[a.max(b)] = a.min(b);
+    }
+
+    // Collect the groups in the order of their first subpath.
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of = BTreeMap::new();
+    for index in 0..subpaths.len() {
+        let root = find(&mut parents, index);
+        let group = *group_of.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(index);
+    }
+
+    groups
+}
+
+/// The 64-bit FNV-1a hash function.
+///
+/// `core` doesn't have a hasher that isn't deprecated, and this doesn't need to resist
+/// collision attacks.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+    use crate::polygon::Polygon;
+    use alloc::vec;
+
+    fn area(trapezoids: &[Trapezoid<f32>]) -> f32 {
+        trapezoids.iter().map(|trap| trap.area()).sum()
+    }
+
+    fn square(x: f32, y: f32, size: f32) -> Polygon<f32> {
+        Polygon::new(
+            vec![
+                Point::new(x, y),
+                Point::new(x + size, y),
+                Point::new(x + size, y + size),
+                Point::new(x, y + size),
+            ],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_reuse() {
+        let mut tessellator = Tessellator::new(0.1);
+        let mut squares = [
+            square(0.0, 0.0, 2.0),
+            square(10.0, 0.0, 2.0),
+            square(20.0, 0.0, 2.0),
+        ];
+
+        let trapezoids = tessellator.tessellate(&squares[..]);
+        assert_eq!(area(&trapezoids), 12.0);
+        assert_eq!((tessellator.reused(), tessellator.tessellated()), (0, 3));
+
+        // Moving one square only re-tessellates that square.
+        squares[1] = square(11.0, 0.0, 2.0);
+        let trapezoids = tessellator.tessellate(&squares[..]);
+        assert_eq!(area(&trapezoids), 12.0);
+        assert_eq!((tessellator.reused(), tessellator.tessellated()), (2, 1));
+
+        let trapezoids = tessellator.tessellate(&squares[..]);
+        assert_eq!(area(&trapezoids), 12.0);
+        assert_eq!((tessellator.reused(), tessellator.tessellated()), (3, 0));
+    }
+
+    #[test]
+    fn test_overlapping_grouped() {
+        let mut tessellator = Tessellator::new(0.1);
+        let squares = [square(0.0, 0.0, 4.0), square(2.0, 2.0, 4.0)];
+
+        // The squares overlap, so they're tessellated together, just like they would be
+        // without the cache.
+        let trapezoids = tessellator.tessellate(&squares[..]);
+        assert_eq!(tessellator.tessellated(), 1);
+
+        let segments = (&squares[..]).segments(0.1).skip_degenerate(true);
+        let expected: Vec<_> = super::trapezoids(segments, FillRule::Winding).collect();
+        assert_eq!(trapezoids, expected);
+    }
+
+    #[test]
+    fn test_hash_collision() {
+        let events = |size| {
+            (&[square(0.0, 0.0, size)][..])
+                .path_iter()
+                .collect::<Vec<_>>()
+        };
+        let mut cache = BTreeMap::new();
+        cache.insert(
+            1,
+            vec![CachedGroup {
+                events: events(2.0),
+                trapezoids: Vec::new(),
+            }],
+        );
+
+        // A different group with the same hash doesn't get the cached trapezoids.
+        assert!(take_group(&mut cache, 1, &events(3.0)).is_none());
+        assert!(take_group(&mut cache, 1, &events(2.0)).is_some());
+        assert!(take_group(&mut cache, 1, &events(2.0)).is_none());
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Bounding hulls and extents of point sets.
+
+#![cfg(feature = "alloc")]
+
+use crate::point::Point;
+
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// Compute the convex hull of a set of points.
+///
+/// The hull is returned in counter-clockwise order, starting from the point with the
+/// lowest X coordinate. Collinear points along the hull's edges are left out.
+pub fn convex_hull<T, I>(points: I) -> Vec<Point<T>>
+where
+    T: Real,
+    I: IntoIterator<Item = Point<T>>,
+{
+    let mut points = points.into_iter().collect::<Vec<_>>();
+    points.sort_by(|a, b| {
+        a.x()
+            .partial_cmp(&b.x())
+            .and_then(|order| Some(order.then(a.y().partial_cmp(&b.y())?)))
+            .unwrap_or(core::cmp::Ordering::Equal)
+    });
+    points.dedup();
+
+    if points.len() < 3 {
+        return points;
+    }
+
+    // Build the lower and then the upper half with Andrew's monotone chain.
+    let mut hull: Vec<Point<T>> = Vec::with_capacity(points.len() + 1);
+    let mut lower = 0;
+    for (pass, &point) in points
+        .iter()
+        .map(|p| (0, p))
+        .chain(points.iter().rev().skip(1).map(|p| (1, p)))
+    {
+        // Don't pop points off of the lower half while building the upper half.
+        let floor = if pass == 0 { 2 } else { lower + 1 };
+
+        while hull.len() >= floor {
+            let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+            if (b - a).cross(point - a) > T::zero() {
+                break;
+            }
+            hull.pop();
+        }
+
+        hull.push(point);
+        if pass == 0 {
+            lower = hull.len();
+        }
+    }
+
+    // The last point is the same as the first.
+    hull.pop();
+    hull
+}
+
+/// The farthest pair of points in a point set.
+pub type Diameter<T> = (Point<T>, Point<T>, T);
+
+/// Find the two points in a set that are farthest apart, and the distance between them.
+///
+/// This runs rotating calipers over the convex hull of the points, so it takes
+/// `O(n log n)` time. Returns `None` if the set is empty.
+pub fn diameter<T, I>(points: I) -> Option<Diameter<T>>
+where
+    T: Real,
+    I: IntoIterator<Item = Point<T>>,
+{
+    let hull = convex_hull(points);
+    let n = hull.len();
+
+    match n {
+        0 => return None,
+        1 => return Some((hull[0], hull[0], T::zero())),
+        2 => return Some((hull[0], hull[1], hull[0].distance(hull[1]))),
+        _ => {}
+    }
+
+    let area = |i: usize, j: usize, k: usize| (hull[j] - hull[i]).cross(hull[k] - hull[i]).abs();
+    let mut best = (hull[0], hull[1], hull[0].distance_squared(hull[1]));
+    let mut farthest = 1;
+
+    for i in 0..n {
+        let next = (i + 1) % n;
+
+        // Advance the opposite caliper while it moves away from this edge.
+        while area(i, next, (farthest + 1) % n) > area(i, next, farthest) {
+            farthest = (farthest + 1) % n;
+        }
+
+        for &from in [i, next].iter() {
+            let distance = hull[from].distance_squared(hull[farthest]);
+            if distance > best.2 {
+                best = (hull[from], hull[farthest], distance);
+            }
+        }
+    }
+
+    Some((best.0, best.1, best.2.sqrt()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            Point::new(0.0f32, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 2.0),
+            Point::new(0.0, 2.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 0.0),
+        ];
+
+        assert_eq!(
+            convex_hull(points.iter().copied()),
+            [
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.0),
+                Point::new(2.0, 2.0),
+                Point::new(0.0, 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diameter() {
+        let points = (0..100).map(|i| {
+            let angle = i as f64 * 0.37;
+            Point::new(angle.cos() * 3.0, angle.sin()) + crate::Vector::new(5.0, 5.0)
+        });
+        let (from, to, distance) = diameter(points.clone()).unwrap();
+        assert_eq!(from.distance(to), distance);
+
+        // Compare against checking every pair.
+        let all = points.collect::<Vec<_>>();
+        let brute = all
+            .iter()
+            .flat_map(|a| all.iter().map(move |b| a.distance(*b)))
+            .fold(0.0, f64::max);
+        assert!((distance - brute).abs() < 1e-9);
+        assert!(distance > 5.9 && distance <= 6.0);
+    }
+
+    #[test]
+    fn test_diameter_degenerate() {
+        assert_eq!(diameter(core::iter::empty::<Point<f32>>()), None);
+
+        let single = diameter(Some(Point::new(1.0f32, 2.0))).unwrap();
+        assert_eq!(single.2, 0.0);
+
+        let line = [
+            Point::new(0.0f32, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(3.0, 3.0),
+        ];
+        let (.., distance) = diameter(line.iter().copied()).unwrap();
+        assert_eq!(distance, 18.0f32.sqrt());
+    }
+}
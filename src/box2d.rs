@@ -17,12 +17,13 @@
 
 use crate::pair::{Double, Quad};
 use crate::path::{Path, PathEvent, Shape};
-use crate::{Point, Size, Vector};
+use crate::{Direction, LineSegment, Point, Size, Vector};
 use num_traits::real::Real;
 use num_traits::{Bounded, One, Zero};
 
 use core::borrow::Borrow;
 use core::fmt;
+use core::iter::FusedIterator;
 use core::ops::{self, Range};
 
 /// A rectangular space consisting of its minimum and maximum points.
@@ -97,6 +98,29 @@ impl<T: Copy> Box<T> {
         Point::new(x, y)
     }
 
+    /// Get the four corners of the box.
+    ///
+    /// The corners are yielded in the same order as the box's path: starting at the
+    /// minimum point and ending at `top_left()`.
+    pub fn corners(&self) -> crate::iter::Four<Point<T>> {
+        crate::iter::Four::from([self.min(), self.bottom_right(), self.max(), self.top_left()])
+    }
+
+    /// Get the four edges of the box as line segments.
+    ///
+    /// Each edge starts at the corner of the same index in `corners()`.
+    pub fn edges(&self) -> crate::iter::Four<LineSegment<T>> {
+        let (min, bottom_right, max, top_left) =
+            (self.min(), self.bottom_right(), self.max(), self.top_left());
+
+        crate::iter::Four::from([
+            LineSegment::new(min, bottom_right),
+            LineSegment::new(bottom_right, max),
+            LineSegment::new(max, top_left),
+            LineSegment::new(top_left, min),
+        ])
+    }
+
     /// Get the minimum and maximum points of the box.
     pub fn min_max(&self) -> (Point<T>, Point<T>) {
         let (min, max) = self.0.split();
@@ -166,12 +190,16 @@ impl<T: Copy + PartialOrd> Box<T> {
     }
 
     /// Tell if this box contains a point.
+    ///
+    /// The box is half-open, so points on its minimum edges are inside of it and points
+    /// on its maximum edges are not.
     pub fn contains(&self, point: &Point<T>) -> bool
     where
         T: PartialOrd,
     {
+        // Compare the point against the box, and not the other way around.
         let point_repeated = Quad::from_double(point.0, point.0);
-        let packed_lt = self.0.packed_lt(point_repeated);
+        let packed_lt = point_repeated.packed_lt(self.0);
         let (min_cmp, max_cmp) = packed_lt.split();
 
         // The point should be greater than or equal to the minimum point (i.e lt is false)
@@ -368,8 +396,376 @@ impl<T: Copy> Box<T> {
         let (min, max) = self.0.split();
         Self(Quad::from_double(min.floor(), max.ceil()))
     }
+
+    /// Get the point inside of this box that is closest to `point`.
+    pub fn clamp_point(&self, point: Point<T>) -> Point<T>
+    where
+        T: PartialOrd,
+    {
+        let (min, max) = self.0.split();
+        Point(point.0.clamp(min, max))
+    }
+
+    /// Get the distance from this box to a point, or zero if the point is inside.
+    pub fn distance_to_point(&self, point: Point<T>) -> T
+    where
+        T: Real,
+    {
+        self.clamp_point(point).distance(point)
+    }
+
+    /// Get the squared distance between the closest points of two boxes, or zero if
+    /// they overlap.
+    pub fn distance_squared_to_box(&self, other: &Self) -> T
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + ops::Mul<Output = T> + Zero + PartialOrd,
+    {
+        let (self_min, self_max) = self.0.split();
+        let (other_min, other_max) = other.0.split();
+
+        // The gap along each axis, in either direction.
+        let gaps = Quad::from_double(other_min, self_min) - Quad::from_double(self_max, other_max);
+        let (forward, backward) = gaps.max(Quad::splat(T::zero())).split();
+        Vector(forward.max(backward)).length_squared()
+    }
+
+    /// Split the box into a left and right half along the vertical line at `x`.
+    ///
+    /// `x` is clamped to the horizontal extent of the box.
+    pub fn split_x(&self, x: T) -> (Self, Self)
+    where
+        T: PartialOrd,
+    {
+        let (min, max) = self.min_max();
+        let x = clamp(x, min.x(), max.x());
+
+        (
+            Box::new(min, Point::new(x, max.y())),
+            Box::new(Point::new(x, min.y()), max),
+        )
+    }
+
+    /// Split the box into a top and bottom half along the horizontal line at `y`.
+    ///
+    /// `y` is clamped to the vertical extent of the box.
+    pub fn split_y(&self, y: T) -> (Self, Self)
+    where
+        T: PartialOrd,
+    {
+        let (min, max) = self.min_max();
+        let y = clamp(y, min.y(), max.y());
+
+        (
+            Box::new(min, Point::new(max.x(), y)),
+            Box::new(Point::new(min.x(), y), max),
+        )
+    }
+
+    /// Split the box into four equal quadrants.
+    ///
+    /// The quadrants are returned in the order top-left, top-right, bottom-left and
+    /// bottom-right.
+    pub fn quadrants(&self) -> crate::iter::Four<Self>
+    where
+        T: ops::Add<Output = T> + ops::Div<Output = T> + One + PartialOrd,
+    {
+        let center = self.center();
+        let (top, bottom) = self.split_y(center.y());
+        let (top_left, top_right) = top.split_x(center.x());
+        let (bottom_left, bottom_right) = bottom.split_x(center.x());
+
+        crate::iter::Four::from([top_left, top_right, bottom_left, bottom_right])
+    }
+
+    /// Divide the box into a grid of equally sized cells.
+    ///
+    /// The cells are yielded row by row.
+    pub fn grid(&self, rows: usize, columns: usize) -> BoxGrid<T> {
+        BoxGrid {
+            bounds: *self,
+            rows,
+            columns,
+            index: 0,
+        }
+    }
+
+    /// Grow the box by `dx` on the left and right and by `dy` on the top and bottom.
+    ///
+    /// Negative values shrink the box. If the box would be inverted along an axis, it
+    /// collapses to its center along that axis instead.
+    pub fn inflate(self, dx: T, dy: T) -> Self
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + ops::Div<Output = T> + One + PartialOrd,
+    {
+        let delta = Double::new([dx, dy]);
+        let (min, max) = self.0.split();
+        let (new_min, new_max) = (min - delta, max + delta);
+
+        // The new edges are symmetric around the center, so clamping them to it
+        // collapses any inverted axes.
+        let center = (min + max) / Double::splat(T::one() + T::one());
+        Self(Quad::from_double(new_min.min(center), new_max.max(center)))
+    }
+
+    /// Shrink the box by `dx` on the left and right and by `dy` on the top and bottom.
+    ///
+    /// If the box would be inverted along an axis, it collapses to its center along
+    /// that axis instead.
+    pub fn deflate(self, dx: T, dy: T) -> Self
+    where
+        T: ops::Add<Output = T>
+            + ops::Sub<Output = T>
+            + ops::Div<Output = T>
+            + ops::Neg<Output = T>
+            + One
+            + PartialOrd,
+    {
+        self.inflate(-dx, -dy)
+    }
+
+    /// Grow the box by the width and height of `size` on each side.
+    pub fn expand(self, size: Size<T>) -> Self
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + ops::Div<Output = T> + One + PartialOrd,
+    {
+        self.inflate(size.width(), size.height())
+    }
+
+    /// Expand the box outwards so that its edges lie on a grid with the given cell size.
+    pub fn snap_out(self, cell: Size<T>) -> Self
+    where
+        T: Real,
+    {
+        let (min, max) = self.0.split();
+        let min = (min / cell.0).floor() * cell.0;
+        let max = (max / cell.0).ceil() * cell.0;
+        Self(Quad::from_double(min, max))
+    }
+}
+
+impl<T: Copy> Box<T> {
+    /// Get the path events that make up this box, going around it in the given
+    /// direction.
+    ///
+    /// `Direction::Forwards` gives the same order as `path_iter`, and
+    /// `Direction::Backwards` reverses it. Emitting a reversed box inside of another shape
+    /// punches a hole in it under the non-zero fill rule.
+    pub fn path_iter_with_direction(self, direction: Direction) -> crate::iter::Five<PathEvent<T>> {
+        crate::path::closed_quad(
+            [self.min(), self.bottom_right(), self.max(), self.top_left()],
+            direction,
+        )
+    }
+}
+
+impl Box<i32> {
+    /// Iterate over the integer points contained in this box, row by row.
+    ///
+    /// The box is treated as half-open, so points on its maximum edges are excluded.
+    pub fn points(&self) -> BoxPoints {
+        let (min, max) = self.min_max();
+        let rows = min.y()..max.y().max(min.y());
+
+        BoxPoints {
+            columns: min.x()..max.x().max(min.x()),
+            x: min.x(),
+            rows,
+        }
+    }
+}
+
+impl<T: Copy> Box<T> {
+    /// Iterate over the rows of pixels whose centers lie within this box.
+    ///
+    /// Each item is the Y coordinate of the row and the range of X coordinates that it
+    /// covers. A pixel at `(x, y)` is centered on `(x + 0.5, y + 0.5)`.
+    pub fn pixel_rows(&self) -> PixelRows
+    where
+        T: Real,
+    {
+        let half = T::one() / (T::one() + T::one());
+        let (min, max) = self.min_max();
+        let to_pixel = |value: T| {
+            (value - half)
+                .ceil()
+                .to_i32()
+                .unwrap_or(if value < T::zero() {
+                    i32::MIN
+                } else {
+                    i32::MAX
+                })
+        };
+
+        let (left, right) = (to_pixel(min.x()), to_pixel(max.x()));
+        let (top, bottom) = (to_pixel(min.y()), to_pixel(max.y()));
+
+        PixelRows {
+            columns: left..right.max(left),
+            rows: top..bottom.max(top),
+        }
+    }
+}
+
+/// The iterator returned by `Box::points`.
+#[derive(Debug, Clone)]
+pub struct BoxPoints {
+    /// The range of X coordinates in each row.
+    columns: Range<i32>,
+
+    /// The X coordinate of the next point.
+    x: i32,
+
+    /// The remaining rows.
+    rows: Range<i32>,
+}
+
+impl Iterator for BoxPoints {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.columns.is_empty() {
+            return None;
+        }
+
+        loop {
+            let y = self.rows.start;
+            if y >= self.rows.end {
+                return None;
+            }
+
+            if self.x < self.columns.end {
+                let x = self.x;
+                self.x += 1;
+                return Some(Point::new(x, y));
+            }
+
+            self.x = self.columns.start;
+            self.rows.start += 1;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let width = self.columns.len();
+        let remaining = match self.rows.len() {
+            0 => 0,
+            rows => (rows - 1) * width + (self.columns.end - self.x) as usize,
+        };
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for BoxPoints {}
+
+impl FusedIterator for BoxPoints {}
+
+/// The iterator returned by `Box::pixel_rows`.
+#[derive(Debug, Clone)]
+pub struct PixelRows {
+    /// The range of X coordinates in each row.
+    columns: Range<i32>,
+
+    /// The remaining rows.
+    rows: Range<i32>,
+}
+
+impl Iterator for PixelRows {
+    type Item = (i32, Range<i32>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(|y| (y, self.columns.clone()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for PixelRows {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.rows.next_back().map(|y| (y, self.columns.clone()))
+    }
+}
+
+impl ExactSizeIterator for PixelRows {}
+
+impl FusedIterator for PixelRows {}
+
+/// Clamp a value between a minimum and a maximum.
+fn clamp<T: PartialOrd>(value: T, min: T, max: T) -> T {
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+/// The iterator returned by `Box::grid`.
+#[derive(Debug, Clone)]
+pub struct BoxGrid<T: Copy> {
+    /// The box being divided.
+    bounds: Box<T>,
+
+    /// The number of rows in the grid.
+    rows: usize,
+
+    /// The number of columns in the grid.
+    columns: usize,
+
+    /// The index of the next cell.
+    index: usize,
 }
 
+impl<T: Copy> BoxGrid<T> {
+    /// Get the total number of cells in the grid.
+    fn cell_count(&self) -> usize {
+        self.rows * self.columns
+    }
+}
+
+impl<T: Real> Iterator for BoxGrid<T> {
+    type Item = Box<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.cell_count() {
+            return None;
+        }
+
+        let row = self.index / self.columns;
+        let column = self.index % self.columns;
+        self.index += 1;
+
+        let (min, max) = self.bounds.min_max();
+        let size = max - min;
+        let at = |index: usize, count: usize, min: T, size: T| {
+            let t = T::from(index).unwrap() / T::from(count).unwrap();
+            min + size * t
+        };
+
+        Some(Box::new(
+            Point::new(
+                at(column, self.columns, min.x(), size.x()),
+                at(row, self.rows, min.y(), size.y()),
+            ),
+            Point::new(
+                at(column + 1, self.columns, min.x(), size.x()),
+                at(row + 1, self.rows, min.y(), size.y()),
+            ),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.cell_count().saturating_sub(self.index);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Real> ExactSizeIterator for BoxGrid<T> {}
+
+impl<T: Real> FusedIterator for BoxGrid<T> {}
+
 impl<T: Copy + ops::Add<Output = T>> ops::Add<Vector<T>> for Box<T> {
     type Output = Self;
 
@@ -454,26 +850,7 @@ impl<T: Copy> Path<T> for Box<T> {
     type Iter = crate::iter::Five<PathEvent<T>>;
 
     fn path_iter(self) -> Self::Iter {
-        crate::iter::Five::from([
-            PathEvent::Begin { at: self.min() },
-            PathEvent::Line {
-                from: self.min(),
-                to: self.bottom_right(),
-            },
-            PathEvent::Line {
-                from: self.bottom_right(),
-                to: self.max(),
-            },
-            PathEvent::Line {
-                from: self.max(),
-                to: self.top_left(),
-            },
-            PathEvent::End {
-                last: self.top_left(),
-                first: self.min(),
-                close: true,
-            },
-        ])
+        self.path_iter_with_direction(Direction::Forwards)
     }
 
     fn approximate_length(self, _: T) -> T
@@ -523,3 +900,126 @@ impl<T: Copy> Shape<T> for Box<T> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let box_ = Box::new(Point::new(0.0, 0.0), Point::new(4.0, 2.0));
+        assert!(box_.contains(&Point::new(1.0, 1.0)));
+        assert!(box_.contains(&Point::new(0.0, 0.0)));
+        assert!(!box_.contains(&Point::new(4.0, 1.0)));
+        assert!(!box_.contains(&Point::new(-1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_contains_edges() {
+        // Each side of the box, on both axes.
+        let box_ = Box::new(Point::new(1, 2), Point::new(3, 5));
+        assert!(box_.contains(&Point::new(1, 2)));
+        assert!(box_.contains(&Point::new(2, 4)));
+        assert!(!box_.contains(&Point::new(0, 3)));
+        assert!(!box_.contains(&Point::new(3, 3)));
+        assert!(!box_.contains(&Point::new(2, 1)));
+        assert!(!box_.contains(&Point::new(2, 5)));
+
+        // An empty box contains nothing.
+        let empty = Box::new(Point::new(3, 3), Point::new(1, 1));
+        assert!(!empty.contains(&Point::new(2, 2)));
+    }
+
+    #[test]
+    fn test_inflate() {
+        let box_ = Box::new(Point::new(0.0, 0.0), Point::new(4.0, 2.0));
+        assert_eq!(
+            box_.inflate(1.0, 1.0),
+            Box::new(Point::new(-1.0, -1.0), Point::new(5.0, 3.0))
+        );
+        assert_eq!(
+            box_.deflate(1.0, 2.0),
+            Box::new(Point::new(1.0, 1.0), Point::new(3.0, 1.0))
+        );
+    }
+
+    #[test]
+    fn test_distance() {
+        let box_ = Box::new(Point::new(0.0, 0.0), Point::new(4.0, 2.0));
+        assert_eq!(box_.clamp_point(Point::new(7.0, 1.0)), Point::new(4.0, 1.0));
+        assert_eq!(box_.distance_to_point(Point::new(7.0, 6.0)), 5.0);
+        assert_eq!(box_.distance_to_point(Point::new(1.0, 1.0)), 0.0);
+
+        let other = Box::new(Point::new(-4.0, 5.0), Point::new(-3.0, 6.0));
+        assert_eq!(box_.distance_squared_to_box(&other), 18.0);
+        assert_eq!(other.distance_squared_to_box(&box_), 18.0);
+        assert_eq!(box_.distance_squared_to_box(&box_), 0.0);
+    }
+
+    #[test]
+    fn test_corners() {
+        let box_ = Box::new(Point::new(0.0, 0.0), Point::new(4.0, 2.0));
+        let mut corners = box_.corners();
+        assert_eq!(corners.nth(2), Some(Point::new(4.0, 2.0)));
+
+        let last = box_.edges().last().unwrap();
+        assert_eq!(last.to(), Point::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_pixels() {
+        let box_ = Box::new(Point::new(1, 2), Point::new(3, 4));
+        let mut points = box_.points();
+        assert_eq!(points.len(), 4);
+        assert_eq!(points.nth(2), Some(Point::new(1, 3)));
+        assert_eq!(points.len(), 1);
+
+        let box_ = Box::new(Point::new(0.4, 0.6), Point::new(2.5, 1.6));
+        let mut rows = box_.pixel_rows();
+        assert_eq!(rows.next(), Some((1, 0..2)));
+        assert_eq!(rows.next(), None);
+    }
+
+    #[test]
+    fn test_subdivide() {
+        let box_ = Box::new(Point::new(0.0, 0.0), Point::new(4.0, 2.0));
+        let (left, right) = box_.split_x(5.0);
+        assert_eq!(left, box_);
+        assert_eq!(right.size(), Size::new(0.0, 2.0));
+
+        let mut quadrants = box_.quadrants();
+        assert_eq!(
+            quadrants.nth(3),
+            Some(Box::new(Point::new(2.0, 1.0), Point::new(4.0, 2.0)))
+        );
+
+        let mut grid = box_.grid(2, 4);
+        assert_eq!(grid.len(), 8);
+        assert_eq!(
+            grid.nth(5),
+            Some(Box::new(Point::new(1.0, 1.0), Point::new(2.0, 2.0)))
+        );
+        assert_eq!(grid.count(), 2);
+    }
+
+    #[test]
+    fn test_path_direction() {
+        let box_ = Box::new(Point::new(0.0, 0.0), Point::new(4.0, 2.0));
+        let vertices = |direction| {
+            box_.path_iter_with_direction(direction)
+                .filter_map(|event| match event {
+                    PathEvent::Begin { at } | PathEvent::Line { to: at, .. } => Some(at),
+                    _ => None,
+                })
+                .collect::<alloc::vec::Vec<_>>()
+        };
+
+        let forwards = vertices(Direction::Forwards);
+        let mut backwards = vertices(Direction::Backwards);
+        assert_eq!(box_.path_iter().collect::<alloc::vec::Vec<_>>().len(), 5);
+        assert_eq!(forwards[0], backwards[0]);
+
+        backwards[1..].reverse();
+        assert_eq!(forwards, backwards);
+    }
+}
@@ -21,7 +21,7 @@ use core::fmt;
 use core::ops;
 
 use crate::pair::Quad;
-use num_traits::{real::Real, AsPrimitive, Bounded};
+use num_traits::{real::Real, AsPrimitive, Bounded, One};
 
 /// Four-channel color type.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -118,6 +118,15 @@ impl<T: Copy> Color<T> {
     pub fn alpha(&self) -> T {
         self.0[3]
     }
+
+    /// Linearly interpolate between two colors.
+    pub fn lerp(self, other: Self, t: T) -> Self
+    where
+        T: One + ops::Sub<Output = T> + ops::Mul<Output = T> + ops::Add<Output = T>,
+    {
+        let one_t = T::one() - t;
+        Color(self.0 * Quad::splat(one_t) + other.0 * Quad::splat(t))
+    }
 }
 
 impl<T: Copy> Color<T> {
@@ -20,6 +20,7 @@
 use num_traits::real::Real;
 
 use super::quad::{FlattenedInner as FlattenedQuad, QuadraticBezier};
+use crate::iter::Three;
 use crate::{point::Point, ApproxEq, Curve};
 
 /// A cubic bezier curve.
@@ -124,6 +125,102 @@ impl<T: Copy> CubicBezier<T> {
         )
     }
 
+    /// Approximate this curve with a chain of quadratic Bezier curves.
+    ///
+    /// Enough quadratics are used to keep each of them within `tolerance` of the part of
+    /// this curve that it replaces.
+    pub fn to_quadratics(self, tolerance: T) -> impl Iterator<Item = QuadraticBezier<T>>
+    where
+        T: Real + ApproxEq,
+    {
+        let count = self.quadratic_count(tolerance);
+        let step = T::one() / count;
+        let count = count.to_usize().unwrap_or(1);
+
+        (0..count).map(move |i| {
+            let start = T::from(i).unwrap() * step;
+            let end = if i + 1 == count {
+                T::one()
+            } else {
+                start + step
+            };
+
+            self.subsection(start..end).as_quadratic()
+        })
+    }
+
+    /// Get the number of quadratic curves needed to approximate this curve within the
+    /// given tolerance.
+    fn quadratic_count(&self, tolerance: T) -> T
+    where
+        T: Real,
+    {
+        let [from, control1, control2, to] = self.0;
+        let three = T::one() + T::one() + T::one();
+        let six = three + three;
+        let err = from.into_vector() - (control1.into_vector() * three)
+            + (control2.into_vector() * three)
+            - to.into_vector();
+        let err = err.length_squared();
+
+        (err / (T::from(432.0).unwrap() * tolerance * tolerance))
+            .powf(T::one() / six)
+            .ceil()
+            .max(T::one())
+    }
+
+    /// Split this curve at its extrema in the Y direction, so that each piece is
+    /// monotone in Y.
+    pub fn split_monotone(self) -> impl Iterator<Item = CubicBezier<T>>
+    where
+        T: Real + ApproxEq,
+    {
+        let [p0, p1, p2, p3] = self.0;
+        let (d0, d1, d2) = (p1.y() - p0.y(), p2.y() - p1.y(), p3.y() - p2.y());
+
+        // The roots of the derivative in Y, which is a quadratic in `t`.
+        let two = T::one() + T::one();
+        let a = d0 - d1 * two + d2;
+        let b = (d1 - d0) * two;
+        let c = d0;
+
+        let mut roots = [None, None];
+        if a.approx_eq(&T::zero()) {
+            if !b.approx_eq(&T::zero()) {
+                roots[0] = Some(-c / b);
+            }
+        } else {
+            let discriminant = b * b - two * two * a * c;
+
+            if discriminant >= T::zero() {
+                let root = discriminant.sqrt();
+                roots = [Some((-b - root) / (two * a)), Some((-b + root) / (two * a))];
+            }
+        }
+
+        // Only keep the roots that are strictly inside of the curve, in order.
+        let is_inside = |t: &T| *t > T::epsilon() && *t < T::one() - T::epsilon();
+        let (first, second) = match (roots[0].filter(is_inside), roots[1].filter(is_inside)) {
+            (Some(t1), Some(t2)) if t2 < t1 => (Some(t2), Some(t1)),
+            (Some(t1), Some(t2)) if t1.approx_eq(&t2) => (Some(t1), None),
+            (None, t) | (t, None) => (t, None),
+            (t1, t2) => (t1, t2),
+        };
+
+        match (first, second) {
+            (None, _) => Three::from([self]),
+            (Some(t1), None) => {
+                let (left, right) = self.split(t1);
+                Three::from([left, right])
+            }
+            (Some(t1), Some(t2)) => {
+                let (left, rest) = self.split(t1);
+                let (middle, right) = rest.split((t2 - t1) / (T::one() - t1));
+                Three::from([left, middle, right])
+            }
+        }
+    }
+
     fn gauss_arclen(&self, coeffs: &[(T, T)]) -> T
     where
         T: Real + ApproxEq,
@@ -194,8 +291,8 @@ impl<T: Real + ApproxEq> Curve<T> for CubicBezier<T> {
         );
 
         let dt = t1 - t0;
-        let ctrl1 = from + quad.eval(t0 * dt).into_vector() * dt;
-        let ctrl2 = to - quad.eval(t1 * dt).into_vector() * dt;
+        let ctrl1 = from + quad.eval(t0).into_vector() * dt;
+        let ctrl2 = to - quad.eval(t1).into_vector() * dt;
 
         Self::new(from, ctrl1, ctrl2, to)
     }
@@ -290,32 +387,25 @@ pub struct FlattenedCubic<T: Copy> {
     tolerance: T,
     range_step: T,
     range_start: T,
+    out: bool,
 }
 
 impl<T: Real + ApproxEq> FlattenedCubic<T> {
     fn new(curve: &CubicBezier<T>, tolerance: T) -> Self {
-        let quad_tolerance = tolerance * T::from(0.4).unwrap();
+        // Split the tolerance between approximating the curve with quadratics and
+        // flattening those quadratics, so that the two errors add up to at most
+        // `tolerance`.
+        let quad_tolerance = tolerance * T::from(0.2).unwrap();
         let flat_tolerance = tolerance * T::from(0.8).unwrap();
 
         // Get the number of quadratics needed to approximate the curve.
-        let [from, control1, control2, to] = curve.0;
-        let three = T::one() + T::one() + T::one();
-        let six = three + three;
-        let err = from.into_vector() - (control1.into_vector() * three)
-            + (control2.into_vector() * three)
-            - to.into_vector();
-        let err = err.length_squared();
-
-        let num_quads = (err / (T::from(432.0).unwrap() * tolerance * tolerance))
-            .powf(T::one() / six)
-            .ceil()
-            .max(T::one());
+        let num_quads = curve.quadratic_count(quad_tolerance);
 
         let range_step = T::one() / num_quads;
 
         // Start flattening the initial quad segment.
         let current_quad = curve.subsection(T::zero()..range_step).as_quadratic();
-        let current_quad = FlattenedQuad::new(&current_quad, quad_tolerance);
+        let current_quad = FlattenedQuad::new(&current_quad, flat_tolerance);
 
         FlattenedCubic {
             curve: *curve,
@@ -324,6 +414,7 @@ impl<T: Real + ApproxEq> FlattenedCubic<T> {
             tolerance: flat_tolerance,
             range_step,
             range_start: T::zero(),
+            out: false,
         }
     }
 }
@@ -332,14 +423,20 @@ impl<T: Real + ApproxEq> Iterator for FlattenedCubic<T> {
     type Item = Point<T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // Try to get the next point from the current quad.
-        if let Some(point) = self.current_quad.next() {
-            return Some(self.curve.eval(point));
+        // Try to get the next point from the current quad, mapping its parameter back
+        // onto the whole curve.
+        if let Some(t) = self.current_quad.next() {
+            return Some(self.curve.eval(self.range_start + t * self.range_step));
         }
 
-        // If we're out of quads, we're done.
+        // If we're out of quads, finish at the end of the curve.
         if self.remaining == 0 {
-            return None;
+            if self.out {
+                return None;
+            }
+
+            self.out = true;
+            return Some(self.curve.to());
         }
 
         // Otherwise, start flattening the next quad segment.
@@ -361,3 +458,96 @@ impl<T: Real + ApproxEq> Iterator for FlattenedCubic<T> {
         (self.remaining * self.current_quad.size_hint().0, None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_monotone() {
+        let curve = CubicBezier::new(
+            Point::new(0.0f32, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, -2.0),
+            Point::new(3.0, 0.0),
+        );
+
+        let pieces = curve.split_monotone();
+        assert_eq!(pieces.size_hint(), (3, Some(3)));
+
+        let mut last = curve.from();
+        for piece in pieces {
+            assert_eq!(piece.from(), last);
+            last = piece.to();
+
+            let rising = piece.to().y() > piece.from().y();
+
+            for i in 1..=8 {
+                let before = piece.eval((i - 1) as f32 / 8.0).y();
+                let after = piece.eval(i as f32 / 8.0).y();
+                assert!(after == before || (after > before) == rising);
+            }
+        }
+        assert_eq!(last, curve.to());
+
+        // A curve that is already monotone is left alone.
+        let curve = CubicBezier::new(
+            Point::new(0.0f32, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 2.0),
+            Point::new(3.0, 3.0),
+        );
+        assert_eq!(curve.split_monotone().count(), 1);
+    }
+
+    fn s_curve() -> CubicBezier<f64> {
+        CubicBezier::new(
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 20.0),
+            Point::new(20.0, -20.0),
+            Point::new(30.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn test_subsection() {
+        let curve = s_curve();
+
+        for &(t0, t1) in &[(0.0, 1.0), (0.25, 0.5), (0.1, 0.9), (0.6, 0.7)] {
+            let part = curve.subsection(t0..t1);
+            assert!(part.from().distance(curve.eval(t0)) < 1e-9);
+            assert!(part.to().distance(curve.eval(t1)) < 1e-9);
+
+            // The middle of the piece is the middle of its range on the whole curve.
+            let mid = curve.eval((t0 + t1) / 2.0);
+            assert!(part.eval(0.5).distance(mid) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_flatten_within_tolerance() {
+        let curve = s_curve();
+
+        for &tolerance in &[1.0, 0.1, 0.01] {
+            let mut points = alloc::vec![curve.from()];
+            points.extend(curve.flatten(tolerance));
+            assert_eq!(points.last(), Some(&curve.to()));
+
+            // Every point on the curve is close to the flattened polyline.
+            for i in 0..=200 {
+                let point = curve.eval(i as f64 / 200.0);
+                let distance = points
+                    .windows(2)
+                    .map(|pair| {
+                        let (from, to) = (pair[0], pair[1]);
+                        let along = to - from;
+                        let t =
+                            ((point - from).dot(along) / along.length_squared()).clamp(0.0, 1.0);
+                        point.distance(from + along * t)
+                    })
+                    .fold(f64::INFINITY, f64::min);
+                assert!(distance <= tolerance, "{} > {}", distance, tolerance);
+            }
+        }
+    }
+}
@@ -17,6 +17,7 @@
 
 //! Quadratic Bezier curves.
 
+use crate::iter::Two;
 use crate::path::{Path, PathEvent};
 use crate::{point::Point, ApproxEq, Curve, LineSegment};
 use num_traits::{real::Real, One};
@@ -115,6 +116,28 @@ impl<T: Copy> QuadraticBezier<T> {
         dist <= tolerance * tolerance * four
     }
 
+    /// Split this curve at its extremum in the Y direction, so that each piece is
+    /// monotone in Y.
+    pub fn split_monotone(self) -> impl Iterator<Item = QuadraticBezier<T>>
+    where
+        T: Real + ApproxEq,
+    {
+        let [p0, p1, p2] = self.0;
+        let denominator = p0.y() - p1.y() - p1.y() + p2.y();
+
+        if denominator.approx_eq(&T::zero()) {
+            return Two::from([self]);
+        }
+
+        let t = (p0.y() - p1.y()) / denominator;
+        if t > T::epsilon() && t < T::one() - T::epsilon() {
+            let (left, right) = self.split(t);
+            Two::from([left, right])
+        } else {
+            Two::from([self])
+        }
+    }
+
     /// Get the uncurved version of this curve.
     pub fn baseline(&self) -> LineSegment<T> {
         LineSegment::new(self.from(), self.to())
@@ -357,9 +380,22 @@ impl<T: Real> FlattenedInner<T> {
         let inv_integral_to = approx_parabola_inv_integral(integral_to);
         let div_inv_integral_diff = T::one() / (inv_integral_to - inv_integral_from);
 
-        // TODO(notgull): Check divisors to prevent NaNs.
         let count = (half * integral_diff.abs() * (scale / tolerance).sqrt()).ceil();
 
+        // Curves whose points are collinear can't be mapped to a parabola, and divide by
+        // zero above. They are flattened into a single line.
+        if !crate::is_finite(count) || !crate::is_finite(div_inv_integral_diff) || count < T::one()
+        {
+            return Self {
+                index: T::zero(),
+                integral_from: T::zero(),
+                integral_step: T::zero(),
+                inv_integral_from: T::zero(),
+                div_inv_integral_diff: T::one(),
+                count: T::one(),
+            };
+        }
+
         let integral_step = integral_diff / count;
 
         Self {
@@ -396,3 +432,39 @@ impl<T: Real> Iterator for FlattenedInner<T> {
         (size, Some(size))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_monotone() {
+        let curve = QuadraticBezier::new(
+            Point::new(0.0f32, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, 0.0),
+        );
+
+        let mut pieces = curve.split_monotone();
+        let (left, right) = (pieces.next().unwrap(), pieces.next().unwrap());
+        assert!(pieces.next().is_none());
+
+        assert_eq!(left.to(), Point::new(1.0, 1.0));
+        assert_eq!(left.to(), right.from());
+        assert_eq!(left.control().y(), left.to().y());
+    }
+
+    #[test]
+    fn test_flatten_collinear() {
+        let curve = QuadraticBezier::new(
+            Point::new(10.0f32, 10.0),
+            Point::new(5.0, 10.0),
+            Point::new(0.0, 10.0),
+        );
+
+        let points = Curve::flatten(&curve, 0.1).collect::<alloc::vec::Vec<_>>();
+        assert_eq!(points.first(), Some(&curve.from()));
+        assert_eq!(points.last(), Some(&curve.to()));
+        assert!(points.iter().all(|p| p.y() == 10.0));
+    }
+}
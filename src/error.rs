@@ -0,0 +1,97 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Errors that can occur while processing geometry.
+
+use crate::line::LineSegment;
+use crate::point::Point;
+use core::fmt;
+
+/// An error that occurred while processing a shape.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GeometryError<T: Copy> {
+    /// A segment of the shape has a coordinate that is infinite or `NaN`.
+    ///
+    /// For curves, the segment runs between the curve's end points.
+    NonFinite {
+        /// The offending segment.
+        segment: LineSegment<T>,
+    },
+
+    /// The tolerance is not a finite, positive number.
+    InvalidTolerance {
+        /// The offending tolerance.
+        tolerance: T,
+    },
+
+    /// The tessellator processed more events than it was allowed to.
+    TooManyEvents {
+        /// The maximum number of events.
+        limit: usize,
+    },
+
+    /// The tessellator's event queue grew larger than it was allowed to.
+    QueueTooLarge {
+        /// The maximum length of the queue.
+        limit: usize,
+    },
+
+    /// A subpath of the shape was left open.
+    OpenSubpath {
+        /// The first point of the subpath.
+        first: Point<T>,
+
+        /// The last point of the subpath.
+        last: Point<T>,
+    },
+}
+
+impl<T: Copy + fmt::Display> fmt::Display for GeometryError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeometryError::NonFinite { segment } => write!(
+                f,
+                "segment from ({}, {}) to ({}, {}) has a non-finite coordinate",
+                segment.from().x(),
+                segment.from().y(),
+                segment.to().x(),
+                segment.to().y()
+            ),
+            GeometryError::InvalidTolerance { tolerance } => {
+                write!(
+                    f,
+                    "tolerance {} is not a finite, positive number",
+                    tolerance
+                )
+            }
+            GeometryError::TooManyEvents { limit } => {
+                write!(f, "tessellation took more than {} events", limit)
+            }
+            GeometryError::QueueTooLarge { limit } => {
+                write!(f, "tessellation queued more than {} events", limit)
+            }
+            GeometryError::OpenSubpath { first, last } => write!(
+                f,
+                "subpath from ({}, {}) to ({}, {}) is not closed",
+                first.x(),
+                first.y(),
+                last.x(),
+                last.y()
+            ),
+        }
+    }
+}
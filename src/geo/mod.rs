@@ -0,0 +1,77 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Helpers for working with geographic coordinates.
+//!
+//! Geographic coordinates are represented as points whose X coordinate is the longitude
+//! and whose Y coordinate is the latitude, both in degrees.
+
+pub mod project;
+pub mod tile;
+
+#[cfg(feature = "alloc")]
+use crate::{point::Point, polygon::Polygon, polyline::Polyline};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// A geographic feature's geometry.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry<T: Copy> {
+    /// A single position.
+    Point(Point<T>),
+
+    /// An open chain of positions.
+    LineString(Polyline<T>),
+
+    /// Several open chains of positions.
+    MultiLineString(Vec<Polyline<T>>),
+
+    /// A polygon, which may have holes.
+    Polygon(Polygon<T>),
+
+    /// Several polygons.
+    MultiPolygon(Vec<Polygon<T>>),
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Copy> From<Point<T>> for Geometry<T> {
+    fn from(point: Point<T>) -> Self {
+        Geometry::Point(point)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Copy> From<Polyline<T>> for Geometry<T> {
+    fn from(line: Polyline<T>) -> Self {
+        Geometry::LineString(line)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Copy> From<Polygon<T>> for Geometry<T> {
+    fn from(polygon: Polygon<T>) -> Self {
+        Geometry::Polygon(polygon)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Copy> From<Vec<Polygon<T>>> for Geometry<T> {
+    fn from(polygons: Vec<Polygon<T>>) -> Self {
+        Geometry::MultiPolygon(polygons)
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Projections from latitude and longitude onto a plane.
+//!
+//! The planar algorithms in this crate expect Cartesian coordinates, so geographic data
+//! has to be projected before it can be used with them. All of these projections treat
+//! the Earth as a sphere with a radius of [`EARTH_RADIUS`] meters.
+
+use crate::point::Point;
+
+use num_traits::real::Real;
+
+/// The radius of the sphere used by these projections, in meters.
+pub const EARTH_RADIUS: f64 = 6_378_137.0;
+
+/// The largest latitude that Web Mercator can represent, in degrees.
+///
+/// This is the latitude at which the projected map becomes a square.
+pub const MAX_MERCATOR_LATITUDE: f64 = 85.051_128_779_806_6;
+
+/// Project a longitude and latitude into Web Mercator coordinates, in meters.
+///
+/// The latitude is clamped to [`MAX_MERCATOR_LATITUDE`]. The Y axis points north.
+pub fn web_mercator(lon_lat: Point<f64>) -> Point<f64> {
+    let lat = lon_lat
+        .y()
+        .clamp(-MAX_MERCATOR_LATITUDE, MAX_MERCATOR_LATITUDE)
+        .to_radians();
+    let y = Real::ln(Real::tan(core::f64::consts::FRAC_PI_4 + lat / 2.0));
+    Point::new(lon_lat.x().to_radians(), y) * EARTH_RADIUS
+}
+
+/// Convert Web Mercator coordinates, in meters, back into a longitude and latitude.
+pub fn web_mercator_inverse(point: Point<f64>) -> Point<f64> {
+    let point = point / EARTH_RADIUS;
+    let lat = 2.0 * Real::atan(Real::exp(point.y())) - core::f64::consts::FRAC_PI_2;
+    Point::new(point.x().to_degrees(), lat.to_degrees())
+}
+
+/// Project a longitude and latitude into Lambert cylindrical equal-area coordinates, in
+/// meters.
+///
+/// Unlike Web Mercator, this preserves areas, so areas computed from the projected
+/// points are areas on the sphere. Shapes far from the equator are distorted instead.
+pub fn equal_area(lon_lat: Point<f64>) -> Point<f64> {
+    let lat = lon_lat.y().clamp(-90.0, 90.0).to_radians();
+    Point::new(lon_lat.x().to_radians(), Real::sin(lat)) * EARTH_RADIUS
+}
+
+/// Convert Lambert cylindrical equal-area coordinates, in meters, back into a longitude
+/// and latitude.
+pub fn equal_area_inverse(point: Point<f64>) -> Point<f64> {
+    let point = point / EARTH_RADIUS;
+    let lat = Real::asin(point.y().clamp(-1.0, 1.0));
+    Point::new(point.x().to_degrees(), lat.to_degrees())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Point<f64>, b: Point<f64>, epsilon: f64) {
+        assert!(
+            (a.x() - b.x()).abs() < epsilon && (a.y() - b.y()).abs() < epsilon,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_web_mercator() {
+        // The corner of the square map.
+        let half = core::f64::consts::PI * EARTH_RADIUS;
+        let corner = web_mercator(Point::new(180.0, MAX_MERCATOR_LATITUDE));
+        assert_close(corner, Point::new(half, half), 1e-3);
+        assert_close(web_mercator(Point::new(0.0, 0.0)), Point::zero(), 1e-9);
+
+        let berlin = Point::new(13.4, 52.5);
+        assert_close(web_mercator_inverse(web_mercator(berlin)), berlin, 1e-9);
+    }
+
+    #[test]
+    fn test_equal_area() {
+        // The projected map has the same area as the sphere.
+        let top = equal_area(Point::new(180.0, 90.0));
+        let sphere = 4.0 * core::f64::consts::PI * EARTH_RADIUS * EARTH_RADIUS;
+        assert!((top.x() * top.y() * 4.0 - sphere).abs() / sphere < 1e-12);
+
+        let berlin = Point::new(13.4, 52.5);
+        assert_close(equal_area_inverse(equal_area(berlin)), berlin, 1e-9);
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Cutting geometry into vector tiles.
+//!
+//! Vector tiles store geometry in an integer coordinate space that covers the tile from
+//! `0` to [`EXTENT`] along both axes, with the Y axis pointing down. Geometry slightly
+//! outside of the tile is usually kept as well, so that lines and outlines that are
+//! drawn with some width don't get cut off at the tile's edges.
+
+#![cfg(feature = "alloc")]
+
+use super::Geometry;
+use crate::box2d::Box;
+use crate::point::Point;
+use crate::polygon::Polygon;
+use crate::polyline::{Polyline, SimplifyMethod};
+
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// The size of a tile in tile-local coordinates.
+pub const EXTENT: i32 = 4096;
+
+/// Clip a geometry to a tile, converting it into tile-local integer coordinates.
+///
+/// `tile` is the area covered by the tile, in the same space as the geometry and with
+/// the Y axis pointing up, as is the case for projected maps. Geometry is kept up to
+/// `buffer` tile-local units outside of the tile. Before being rounded to integers, lines
+/// and rings are simplified with a tolerance of one unit, since finer details can't be
+/// represented in the tile anyway.
+///
+/// Returns `None` if the tile has no area, or if none of the geometry is left after
+/// clipping.
+pub fn clip_to_tile(
+    geometry: &Geometry<f64>,
+    tile: Box<f64>,
+    buffer: f64,
+) -> Option<Geometry<i32>> {
+    let (min, max) = tile.min_max();
+    if max.x() <= min.x() || max.y() <= min.y() {
+        return None;
+    }
+
+    let extent = f64::from(EXTENT);
+    let scale = Point::new(extent / (max.x() - min.x()), extent / (max.y() - min.y()));
+    let to_tile = |point: Point<f64>| {
+        Point::new(
+            (point.x() - min.x()) * scale.x(),
+            (max.y() - point.y()) * scale.y(),
+        )
+    };
+
+    let window = Box::new(
+        Point::new(-buffer, -buffer),
+        Point::new(extent + buffer, extent + buffer),
+    );
+    let clipper = Clipper { window, to_tile };
+
+    match geometry {
+        Geometry::Point(point) => {
+            let point = to_tile(*point);
+            if window.contains(&point) {
+                Some(Geometry::Point(round(point)))
+            } else {
+                None
+            }
+        }
+        Geometry::LineString(line) => {
+            let mut lines = clipper.line(line);
+            match lines.len() {
+                0 => None,
+                1 => lines.pop().map(Geometry::LineString),
+                _ => Some(Geometry::MultiLineString(lines)),
+            }
+        }
+        Geometry::MultiLineString(lines) => {
+            let lines = lines
+                .iter()
+                .flat_map(|line| clipper.line(line))
+                .collect::<Vec<_>>();
+            if lines.is_empty() {
+                None
+            } else {
+                Some(Geometry::MultiLineString(lines))
+            }
+        }
+        Geometry::Polygon(polygon) => clipper.polygon(polygon).map(Geometry::Polygon),
+        Geometry::MultiPolygon(polygons) => {
+            let polygons = polygons
+                .iter()
+                .filter_map(|polygon| clipper.polygon(polygon))
+                .collect::<Vec<_>>();
+            if polygons.is_empty() {
+                None
+            } else {
+                Some(Geometry::MultiPolygon(polygons))
+            }
+        }
+    }
+}
+
+/// Clips geometry to the window around a tile.
+struct Clipper<F> {
+    /// The area to keep, in tile-local coordinates.
+    window: Box<f64>,
+
+    /// Converts a point into tile-local coordinates.
+    to_tile: F,
+}
+
+impl<F: Fn(Point<f64>) -> Point<f64>> Clipper<F> {
+    /// Clip a line, which may split it into several pieces.
+    fn line(&self, line: &Polyline<f64>) -> Vec<Polyline<i32>> {
+        let line = Polyline::new(line.points().iter().map(|&p| (self.to_tile)(p)).collect())
+            .simplify(SimplifyMethod::RamerDouglasPeucker, 1.0);
+
+        let mut pieces = Vec::new();
+        let mut current: Vec<Point<i32>> = Vec::new();
+        for segment in line.line_segments() {
+            match clip_segment(segment.from(), segment.to(), &self.window) {
+                Some((from, to)) => {
+                    let (from, to) = (round(from), round(to));
+                    if current.last() != Some(&from) {
+                        finish_line(&mut pieces, &mut current);
+                        current.push(from);
+                    }
+                    if current.last() != Some(&to) {
+                        current.push(to);
+                    }
+                }
+                None => finish_line(&mut pieces, &mut current),
+            }
+        }
+        finish_line(&mut pieces, &mut current);
+
+        pieces
+    }
+
+    /// Clip a polygon, returning `None` if its exterior is clipped away entirely.
+    fn polygon(&self, polygon: &Polygon<f64>) -> Option<Polygon<i32>> {
+        let exterior = self.ring(polygon.exterior())?;
+        let interiors = polygon
+            .interiors()
+            .iter()
+            .filter_map(|ring| self.ring(ring))
+            .collect();
+        Some(Polygon::new(exterior, interiors))
+    }
+
+    /// Clip a ring, returning `None` if nothing of it is left.
+    fn ring(&self, ring: &[Point<f64>]) -> Option<Vec<Point<i32>>> {
+        // close the ring while simplifying it, so that its first point is kept in place
+        let closed = ring
+            .iter()
+            .chain(ring.first())
+            .map(|&p| (self.to_tile)(p))
+            .collect();
+        let mut ring = Polyline::new(closed)
+            .simplify(SimplifyMethod::RamerDouglasPeucker, 1.0)
+            .into_points();
+        ring.pop();
+
+        let (min, max) = self.window.min_max();
+        let edges = [
+            (0, min.x(), false),
+            (0, max.x(), true),
+            (1, min.y(), false),
+            (1, max.y(), true),
+        ];
+        for &(axis, bound, upper) in &edges {
+            ring = clip_ring(&ring, axis, bound, upper);
+        }
+
+        let mut rounded = ring.into_iter().map(round).collect::<Vec<_>>();
+        rounded.dedup();
+        while rounded.len() > 1 && rounded.first() == rounded.last() {
+            rounded.pop();
+        }
+
+        if rounded.len() < 3 {
+            None
+        } else {
+            Some(rounded)
+        }
+    }
+}
+
+/// Move the current piece of a line into the list of pieces, if it is long enough.
+fn finish_line(pieces: &mut Vec<Polyline<i32>>, current: &mut Vec<Point<i32>>) {
+    if current.len() > 1 {
+        pieces.push(Polyline::new(core::mem::take(current)));
+    } else {
+        current.clear();
+    }
+}
+
+/// Clip a segment to a box using the Liang-Barsky algorithm.
+fn clip_segment(
+    from: Point<f64>,
+    to: Point<f64>,
+    window: &Box<f64>,
+) -> Option<(Point<f64>, Point<f64>)> {
+    let (min, max) = window.min_max();
+    let delta = to - from;
+    let mut range = (0.0, 1.0);
+
+    let checks = [
+        (-delta.x(), from.x() - min.x()),
+        (delta.x(), max.x() - from.x()),
+        (-delta.y(), from.y() - min.y()),
+        (delta.y(), max.y() - from.y()),
+    ];
+    for &(p, q) in &checks {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                range.0 = f64::max(range.0, t);
+            } else {
+                range.1 = f64::min(range.1, t);
+            }
+        }
+    }
+
+    if range.0 > range.1 {
+        None
+    } else {
+        Some((from + delta * range.0, from + delta * range.1))
+    }
+}
+
+/// Clip a ring against one side of a box using the Sutherland-Hodgman algorithm.
+fn clip_ring(ring: &[Point<f64>], axis: usize, bound: f64, upper: bool) -> Vec<Point<f64>> {
+    let coordinate = |point: Point<f64>| if axis == 0 { point.x() } else { point.y() };
+    let inside = |point: Point<f64>| {
+        if upper {
+            coordinate(point) <= bound
+        } else {
+            coordinate(point) >= bound
+        }
+    };
+
+    let mut clipped = Vec::with_capacity(ring.len() + 4);
+    let mut prev = match ring.last() {
+        Some(&last) => last,
+        None => return clipped,
+    };
+
+    for &point in ring {
+        if inside(point) != inside(prev) {
+            let t = (bound - coordinate(prev)) / (coordinate(point) - coordinate(prev));
+            clipped.push(prev.lerp(point, t));
+        }
+        if inside(point) {
+            clipped.push(point);
+        }
+        prev = point;
+    }
+
+    clipped
+}
+
+/// Round a point to integer coordinates.
+fn round(point: Point<f64>) -> Point<i32> {
+    Point::new(Real::round(point.x()) as i32, Real::round(point.y()) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn tile() -> Box<f64> {
+        Box::new(Point::new(0.0, 0.0), Point::new(100.0, 100.0))
+    }
+
+    #[test]
+    fn test_clip_point() {
+        let inside = Geometry::Point(Point::new(25.0, 75.0));
+        assert_eq!(
+            clip_to_tile(&inside, tile(), 0.0),
+            Some(Geometry::Point(Point::new(1024, 1024)))
+        );
+
+        let outside = Geometry::Point(Point::new(-1.0, 50.0));
+        assert_eq!(clip_to_tile(&outside, tile(), 0.0), None);
+        assert!(clip_to_tile(&outside, tile(), 64.0).is_some());
+    }
+
+    #[test]
+    fn test_clip_empty_tile() {
+        let point = Geometry::Point(Point::new(0.0, 0.0));
+        let flat = Box::new(Point::new(0.0, 0.0), Point::new(100.0, 0.0));
+        assert_eq!(clip_to_tile(&point, flat, 64.0), None);
+    }
+
+    #[test]
+    fn test_clip_line() {
+        // A line that leaves the tile and comes back is split in two.
+        let line = Geometry::LineString(Polyline::new(vec![
+            Point::new(10.0, 50.0),
+            Point::new(50.0, 150.0),
+            Point::new(90.0, 50.0),
+        ]));
+
+        match clip_to_tile(&line, tile(), 0.0) {
+            Some(Geometry::MultiLineString(lines)) => {
+                assert_eq!(lines.len(), 2);
+                assert_eq!(
+                    lines[0].points(),
+                    &[Point::new(410, 2048), Point::new(1229, 0)]
+                );
+            }
+            other => panic!("expected two lines, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_clip_polygon() {
+        // A square that hangs over the right edge of the tile.
+        let square = Polygon::new(
+            vec![
+                Point::new(50.0, 25.0),
+                Point::new(150.0, 25.0),
+                Point::new(150.0, 75.0),
+                Point::new(50.0, 75.0),
+            ],
+            vec![],
+        );
+
+        match clip_to_tile(&Geometry::Polygon(square), tile(), 16.0) {
+            Some(Geometry::Polygon(polygon)) => {
+                let mut xs = polygon.exterior().iter().map(|p| p.x()).collect::<Vec<_>>();
+                xs.sort_unstable();
+                xs.dedup();
+                assert_eq!(xs, [2048, 4112]);
+                assert_eq!(polygon.exterior().len(), 4);
+            }
+            other => panic!("expected a polygon, got {:?}", other),
+        }
+
+        let far = Polygon::new(
+            vec![
+                Point::new(200.0, 200.0),
+                Point::new(300.0, 200.0),
+                Point::new(300.0, 300.0),
+            ],
+            vec![],
+        );
+        assert_eq!(clip_to_tile(&Geometry::Polygon(far), tile(), 16.0), None);
+    }
+}
@@ -0,0 +1,288 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Reading and writing geometry in the GeoJSON format.
+//!
+//! Only the geometry objects that map onto this crate's types are supported: `Point`,
+//! `LineString`, `MultiLineString`, `Polygon` and `MultiPolygon`. GeoJSON repeats the
+//! first point of each ring at its end, while [`Polygon`] rings are implicitly closed,
+//! so the repeated point is removed while reading and added back while writing.
+
+#![cfg(feature = "geojson")]
+
+use crate::point::Point;
+use crate::polygon::Polygon;
+use crate::polyline::Polyline;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use serde_json::{json, Value};
+
+pub use crate::geo::Geometry;
+
+/// An error that occurred while reading GeoJSON.
+#[derive(Debug)]
+pub enum GeoJsonError {
+    /// The input is not valid JSON.
+    Json(serde_json::Error),
+
+    /// The geometry type is not one that this crate supports.
+    UnsupportedType(String),
+
+    /// The object is missing a field, or a field has the wrong shape.
+    Malformed(&'static str),
+}
+
+impl fmt::Display for GeoJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeoJsonError::Json(err) => write!(f, "invalid JSON: {}", err),
+            GeoJsonError::UnsupportedType(ty) => {
+                write!(f, "unsupported geometry type \"{}\"", ty)
+            }
+            GeoJsonError::Malformed(reason) => write!(f, "malformed geometry: {}", reason),
+        }
+    }
+}
+
+impl From<serde_json::Error> for GeoJsonError {
+    fn from(err: serde_json::Error) -> Self {
+        GeoJsonError::Json(err)
+    }
+}
+
+impl Geometry<f64> {
+    /// Parse a GeoJSON geometry object.
+    pub fn from_geojson(text: &str) -> Result<Self, GeoJsonError> {
+        let value: Value = serde_json::from_str(text)?;
+        Self::from_value(&value)
+    }
+
+    /// Write this geometry as a GeoJSON geometry object.
+    pub fn to_geojson(&self) -> String {
+        self.to_value().to_string()
+    }
+
+    /// Convert a parsed JSON value into a geometry.
+    fn from_value(value: &Value) -> Result<Self, GeoJsonError> {
+        let ty = value
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or(GeoJsonError::Malformed("missing \"type\""))?;
+        let coordinates = value
+            .get("coordinates")
+            .ok_or(GeoJsonError::Malformed("missing \"coordinates\""))?;
+
+        match ty {
+            "Point" => position(coordinates).map(Geometry::Point),
+            "LineString" => positions(coordinates)
+                .map(Polyline::new)
+                .map(Geometry::LineString),
+            "MultiLineString" => array(coordinates)?
+                .iter()
+                .map(|line| positions(line).map(Polyline::new))
+                .collect::<Result<_, _>>()
+                .map(Geometry::MultiLineString),
+            "Polygon" => polygon(coordinates).map(Geometry::Polygon),
+            "MultiPolygon" => array(coordinates)?
+                .iter()
+                .map(polygon)
+                .collect::<Result<_, _>>()
+                .map(Geometry::MultiPolygon),
+            ty => Err(GeoJsonError::UnsupportedType(ty.into())),
+        }
+    }
+
+    /// Convert this geometry into a JSON value.
+    fn to_value(&self) -> Value {
+        match self {
+            Geometry::Point(point) => json!({
+                "type": "Point",
+                "coordinates": position_value(*point),
+            }),
+            Geometry::LineString(line) => {
+                let coordinates = line.points().iter().copied().map(position_value);
+                json!({
+                    "type": "LineString",
+                    "coordinates": coordinates.collect::<Vec<_>>(),
+                })
+            }
+            Geometry::MultiLineString(lines) => {
+                let coordinates = lines.iter().map(|line| {
+                    let points = line.points().iter().copied().map(position_value);
+                    points.collect::<Vec<_>>()
+                });
+                json!({
+                    "type": "MultiLineString",
+                    "coordinates": coordinates.collect::<Vec<_>>(),
+                })
+            }
+            Geometry::Polygon(polygon) => json!({
+                "type": "Polygon",
+                "coordinates": polygon_value(polygon),
+            }),
+            Geometry::MultiPolygon(polygons) => json!({
+                "type": "MultiPolygon",
+                "coordinates": polygons.iter().map(polygon_value).collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+/// Get the elements of a JSON array.
+fn array(value: &Value) -> Result<&Vec<Value>, GeoJsonError> {
+    value
+        .as_array()
+        .ok_or(GeoJsonError::Malformed("expected an array"))
+}
+
+/// Read a position, ignoring any altitude.
+fn position(value: &Value) -> Result<Point<f64>, GeoJsonError> {
+    match array(value)?.as_slice() {
+        [x, y, ..] => match (x.as_f64(), y.as_f64()) {
+            (Some(x), Some(y)) => Ok(Point::new(x, y)),
+            _ => Err(GeoJsonError::Malformed("expected a number")),
+        },
+        _ => Err(GeoJsonError::Malformed(
+            "position has less than two numbers",
+        )),
+    }
+}
+
+/// Read an array of positions.
+fn positions(value: &Value) -> Result<Vec<Point<f64>>, GeoJsonError> {
+    array(value)?.iter().map(position).collect()
+}
+
+/// Read a linear ring, removing its repeated last point.
+fn ring(value: &Value) -> Result<Vec<Point<f64>>, GeoJsonError> {
+    let mut ring = positions(value)?;
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    Ok(ring)
+}
+
+/// Read the rings of a polygon.
+fn polygon(value: &Value) -> Result<Polygon<f64>, GeoJsonError> {
+    let mut rings = array(value)?.iter().map(ring);
+    let exterior = rings
+        .next()
+        .ok_or(GeoJsonError::Malformed("polygon has no rings"))??;
+    let interiors = rings.collect::<Result<_, _>>()?;
+    Ok(Polygon::new(exterior, interiors))
+}
+
+/// Write a position.
+fn position_value(point: Point<f64>) -> Value {
+    json!([point.x(), point.y()])
+}
+
+/// Write a linear ring, repeating its first point at the end.
+fn ring_value(ring: &[Point<f64>]) -> Value {
+    ring.iter()
+        .chain(ring.first())
+        .copied()
+        .map(position_value)
+        .collect()
+}
+
+/// Write the rings of a polygon.
+fn polygon_value(polygon: &Polygon<f64>) -> Value {
+    core::iter::once(polygon.exterior())
+        .chain(polygon.interiors().iter().map(Vec::as_slice))
+        .map(ring_value)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_round_trip() {
+        let text = r#"{
+            "type": "Polygon",
+            "coordinates": [
+                [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]],
+                [[2.0, 2.0], [2.0, 4.0], [4.0, 4.0], [4.0, 2.0], [2.0, 2.0]]
+            ]
+        }"#;
+
+        let geometry = Geometry::from_geojson(text).unwrap();
+        let polygon = match &geometry {
+            Geometry::Polygon(polygon) => polygon,
+            _ => panic!("expected a polygon"),
+        };
+        assert_eq!(polygon.exterior().len(), 4);
+        assert_eq!(polygon.interiors().len(), 1);
+
+        let written = geometry.to_geojson();
+        assert_eq!(Geometry::from_geojson(&written).unwrap(), geometry);
+    }
+
+    #[test]
+    fn test_geometry_types() {
+        let point = Geometry::from_geojson(r#"{"type": "Point", "coordinates": [1, 2, 3]}"#);
+        assert_eq!(point.unwrap(), Geometry::Point(Point::new(1.0, 2.0)));
+
+        let line = Geometry::from_geojson(
+            r#"{"type": "LineString", "coordinates": [[0, 0], [1, 1], [2, 0]]}"#,
+        );
+        assert_eq!(
+            line.unwrap(),
+            Geometry::LineString(Polyline::new(vec![
+                Point::new(0.0, 0.0),
+                Point::new(1.0, 1.0),
+                Point::new(2.0, 0.0),
+            ]))
+        );
+
+        let multi = Geometry::from_geojson(
+            r#"{"type": "MultiLineString", "coordinates": [[[0, 0], [1, 1]], [[2, 2], [3, 3]]]}"#,
+        )
+        .unwrap();
+        assert!(matches!(&multi, Geometry::MultiLineString(lines) if lines.len() == 2));
+        assert_eq!(Geometry::from_geojson(&multi.to_geojson()).unwrap(), multi);
+
+        let multi = Geometry::from_geojson(
+            r#"{"type": "MultiPolygon", "coordinates": [
+                [[[0, 0], [1, 0], [1, 1], [0, 0]]],
+                [[[5, 5], [6, 5], [6, 6], [5, 5]]]
+            ]}"#,
+        );
+        match multi.unwrap() {
+            Geometry::MultiPolygon(polygons) => assert_eq!(polygons.len(), 2),
+            _ => panic!("expected a multipolygon"),
+        }
+
+        assert!(matches!(
+            Geometry::from_geojson(r#"{"type": "GeometryCollection", "coordinates": []}"#),
+            Err(GeoJsonError::UnsupportedType(_))
+        ));
+        assert!(matches!(
+            Geometry::from_geojson(r#"{"type": "Point", "coordinates": [1]}"#),
+            Err(GeoJsonError::Malformed(_))
+        ));
+        assert!(matches!(
+            Geometry::from_geojson("{"),
+            Err(GeoJsonError::Json(_))
+        ));
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Linear and radial gradients.
+
+use crate::color::Color;
+use crate::point::Point;
+use num_traits::real::Real;
+
+use core::borrow::Borrow;
+
+/// How a gradient is extended outside of the `[0, 1]` range.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpreadMode {
+    /// Use the color of the nearest end stop.
+    #[default]
+    Pad,
+
+    /// Repeat the gradient.
+    Repeat,
+
+    /// Repeat the gradient, mirroring it every other time.
+    Reflect,
+}
+
+impl SpreadMode {
+    /// Map a gradient offset into the `[0, 1]` range.
+    fn apply<T: Real>(self, t: T) -> T {
+        match self {
+            SpreadMode::Pad => t.max(T::zero()).min(T::one()),
+            SpreadMode::Repeat => t - t.floor(),
+            SpreadMode::Reflect => {
+                let two = T::one() + T::one();
+                let t = t.abs() % two;
+
+                if t > T::one() {
+                    two - t
+                } else {
+                    t
+                }
+            }
+        }
+    }
+}
+
+/// A color at a specific offset in a gradient.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GradientStop<T: Copy> {
+    /// The offset of this stop, in the `[0, 1]` range.
+    offset: T,
+
+    /// The color at this stop.
+    color: Color<T>,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Copy + arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for GradientStop<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(GradientStop {
+            offset: arbitrary::Arbitrary::arbitrary(u)?,
+            color: arbitrary::Arbitrary::arbitrary(u)?,
+        })
+    }
+}
+
+impl<T: Copy> GradientStop<T> {
+    /// Create a new `GradientStop` from its offset and color.
+    pub fn new(offset: T, color: Color<T>) -> Self {
+        GradientStop { offset, color }
+    }
+
+    /// Get the offset of this stop.
+    pub fn offset(&self) -> T {
+        self.offset
+    }
+
+    /// Get the color of this stop.
+    pub fn color(&self) -> Color<T> {
+        self.color
+    }
+}
+
+/// Type alias for a gradient's unsized list of stops.
+type UnsizedStops<T> = [GradientStop<T>];
+
+/// A gradient that varies along the line between two points.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LinearGradient<T: Copy, Stops: ?Sized = UnsizedStops<T>> {
+    /// The point where the gradient's offset is zero.
+    start: Point<T>,
+
+    /// The point where the gradient's offset is one.
+    end: Point<T>,
+
+    /// How the gradient is extended past its ends.
+    spread: SpreadMode,
+
+    /// The color stops, sorted by offset.
+    stops: Stops,
+}
+
+impl<T: Copy, Stops: Borrow<UnsizedStops<T>>> LinearGradient<T, Stops> {
+    /// Create a new `LinearGradient` from its end points, spread mode and stops.
+    ///
+    /// The stops are expected to be sorted by their offsets.
+    pub fn new(start: Point<T>, end: Point<T>, spread: SpreadMode, stops: Stops) -> Self {
+        LinearGradient {
+            start,
+            end,
+            spread,
+            stops,
+        }
+    }
+}
+
+impl<T: Copy, Stops: Borrow<UnsizedStops<T>> + ?Sized> LinearGradient<T, Stops> {
+    /// Get the point where the gradient begins.
+    pub fn start(&self) -> Point<T> {
+        self.start
+    }
+
+    /// Get the point where the gradient ends.
+    pub fn end(&self) -> Point<T> {
+        self.end
+    }
+
+    /// Get the spread mode of the gradient.
+    pub fn spread(&self) -> SpreadMode {
+        self.spread
+    }
+
+    /// Get the color stops of the gradient.
+    pub fn stops(&self) -> &[GradientStop<T>] {
+        self.stops.borrow()
+    }
+
+    /// Get the color of the gradient at the given point.
+    pub fn eval(&self, point: Point<T>) -> Color<T>
+    where
+        T: Real,
+    {
+        let direction = self.end - self.start;
+        let length_squared = direction.length_squared();
+
+        // A degenerate gradient is treated as being past its end.
+        let t = if length_squared.is_zero() {
+            T::one()
+        } else {
+            (point - self.start).dot(direction) / length_squared
+        };
+
+        eval_stops(self.stops(), self.spread.apply(t))
+    }
+}
+
+/// A gradient that varies with the distance from a center point.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RadialGradient<T: Copy, Stops: ?Sized = UnsizedStops<T>> {
+    /// The center of the gradient, where its offset is zero.
+    center: Point<T>,
+
+    /// The distance from the center where the gradient's offset is one.
+    radius: T,
+
+    /// How the gradient is extended past its ends.
+    spread: SpreadMode,
+
+    /// The color stops, sorted by offset.
+    stops: Stops,
+}
+
+impl<T: Copy, Stops: Borrow<UnsizedStops<T>>> RadialGradient<T, Stops> {
+    /// Create a new `RadialGradient` from its center, radius, spread mode and stops.
+    ///
+    /// The stops are expected to be sorted by their offsets.
+    pub fn new(center: Point<T>, radius: T, spread: SpreadMode, stops: Stops) -> Self {
+        RadialGradient {
+            center,
+            radius,
+            spread,
+            stops,
+        }
+    }
+}
+
+impl<T: Copy, Stops: Borrow<UnsizedStops<T>> + ?Sized> RadialGradient<T, Stops> {
+    /// Get the center of the gradient.
+    pub fn center(&self) -> Point<T> {
+        self.center
+    }
+
+    /// Get the radius of the gradient.
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+
+    /// Get the spread mode of the gradient.
+    pub fn spread(&self) -> SpreadMode {
+        self.spread
+    }
+
+    /// Get the color stops of the gradient.
+    pub fn stops(&self) -> &[GradientStop<T>] {
+        self.stops.borrow()
+    }
+
+    /// Get the color of the gradient at the given point.
+    pub fn eval(&self, point: Point<T>) -> Color<T>
+    where
+        T: Real,
+    {
+        // A degenerate gradient is treated as being past its end.
+        let t = if self.radius.is_zero() {
+            T::one()
+        } else {
+            point.distance(self.center) / self.radius
+        };
+
+        eval_stops(self.stops(), self.spread.apply(t))
+    }
+}
+
+/// Find the color at the given offset in a sorted list of stops.
+fn eval_stops<T: Real>(stops: &[GradientStop<T>], t: T) -> Color<T> {
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Color::new(T::zero(), T::zero(), T::zero(), T::zero()),
+    };
+
+    if t <= first.offset {
+        return first.color;
+    }
+
+    // Find the first stop past the offset and blend it with the one before.
+    stops
+        .windows(2)
+        .find(|pair| t < pair[1].offset)
+        .map_or(last.color, |pair| {
+            let (before, after) = (pair[0], pair[1]);
+            let span = after.offset - before.offset;
+
+            if span.is_zero() {
+                after.color
+            } else {
+                before.color.lerp(after.color, (t - before.offset) / span)
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stops() -> [GradientStop<f32>; 2] {
+        [
+            GradientStop::new(0.0, Color::new(0.0, 0.0, 0.0, 1.0)),
+            GradientStop::new(1.0, Color::new(1.0, 1.0, 1.0, 1.0)),
+        ]
+    }
+
+    #[test]
+    fn test_linear() {
+        let gradient = LinearGradient::new(
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            SpreadMode::Pad,
+            stops(),
+        );
+
+        assert_eq!(gradient.eval(Point::new(5.0, 3.0)).red(), 0.5);
+        assert_eq!(gradient.eval(Point::new(-5.0, 0.0)).red(), 0.0);
+        assert_eq!(gradient.eval(Point::new(15.0, 0.0)).red(), 1.0);
+    }
+
+    #[test]
+    fn test_radial() {
+        let gradient = RadialGradient::new(Point::new(0.0, 0.0), 10.0, SpreadMode::Pad, stops());
+
+        assert_eq!(gradient.eval(Point::new(0.0, 0.0)).red(), 0.0);
+        assert_eq!(gradient.eval(Point::new(6.0, 8.0)).red(), 1.0);
+        assert_eq!(gradient.eval(Point::new(0.0, 2.5)).red(), 0.25);
+    }
+
+    #[test]
+    fn test_spread() {
+        assert_eq!(SpreadMode::Pad.apply(1.25f32), 1.0);
+        assert_eq!(SpreadMode::Repeat.apply(1.25f32), 0.25);
+        assert_eq!(SpreadMode::Reflect.apply(1.25f32), 0.75);
+        assert_eq!(SpreadMode::Reflect.apply(-0.25f32), 0.25);
+    }
+
+    #[test]
+    fn test_empty_stops() {
+        let gradient: LinearGradient<f32, [GradientStop<f32>; 0]> = LinearGradient::new(
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            SpreadMode::Pad,
+            [],
+        );
+
+        assert_eq!(gradient.eval(Point::new(0.5, 0.0)).alpha(), 0.0);
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Gathering points into grids of cells.
+//!
+//! A [`Grid`] stores a value for every cell, row by row, in the same layout that
+//! [`raster::contour`] expects. Counting points into a grid and then contouring it is a
+//! quick way to outline the dense areas of a scatter plot.
+//!
+//! [`raster::contour`]: crate::raster::contour
+
+#![cfg(feature = "alloc")]
+
+use crate::box2d::Box;
+use crate::point::Point;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Add;
+use num_traits::real::Real;
+use num_traits::Zero;
+
+/// A two-dimensional grid of values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Grid<V> {
+    /// The values in the grid, row by row.
+    values: Vec<V>,
+
+    /// The number of cells in each row.
+    width: usize,
+
+    /// The number of rows.
+    height: usize,
+}
+
+impl<V> Grid<V> {
+    /// Create a new `Grid` with every cell set to `value`.
+    ///
+    /// Returns `None` if the number of cells doesn't fit in a `usize`.
+    pub fn new(width: usize, height: usize, value: V) -> Option<Self>
+    where
+        V: Clone,
+    {
+        Some(Grid {
+            values: vec![value; width.checked_mul(height)?],
+            width,
+            height,
+        })
+    }
+
+    /// Get the number of cells in each row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the width and height of the grid.
+    pub fn dims(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Get the value of the cell at the given coordinates.
+    pub fn get(&self, x: usize, y: usize) -> Option<&V> {
+        self.index(x, y).map(|index| &self.values[index])
+    }
+
+    /// Get a mutable reference to the value of the cell at the given coordinates.
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut V> {
+        self.index(x, y).map(move |index| &mut self.values[index])
+    }
+
+    /// Get the values in the grid, row by row.
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Get the values in the grid, row by row, consuming it.
+    pub fn into_values(self) -> Vec<V> {
+        self.values
+    }
+
+    /// Apply a function to every value in the grid.
+    ///
+    /// This is useful for turning counts into floating point samples for
+    /// [`raster::contour`](crate::raster::contour).
+    pub fn map<U>(self, f: impl FnMut(V) -> U) -> Grid<U> {
+        Grid {
+            values: self.values.into_iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Get the index of the cell at the given coordinates.
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+}
+
+/// Count how many points fall into each cell of a grid.
+///
+/// See [`accumulate_weighted`] for how the grid is laid out.
+pub fn accumulate<T: Real>(
+    points: impl IntoIterator<Item = Point<T>>,
+    bounds: Box<T>,
+    cell_size: T,
+) -> Grid<u32> {
+    accumulate_weighted(
+        points.into_iter().map(|point| (point, 1)),
+        bounds,
+        cell_size,
+    )
+}
+
+/// Add up the weights of the points that fall into each cell of a grid.
+///
+/// The grid covers `bounds` with square cells of `cell_size`, starting at its minimum
+/// point, so the cell at `(x, y)` covers the area from `min + (x, y) * cell_size` to
+/// `min + (x + 1, y + 1) * cell_size`. The last row and column are cut off at the edges
+/// of `bounds`. Points outside of `bounds` are skipped, and the grid is empty if
+/// `cell_size` isn't positive or if the grid would have more cells than fit in a `usize`.
+pub fn accumulate_weighted<T, V>(
+    points: impl IntoIterator<Item = (Point<T>, V)>,
+    bounds: Box<T>,
+    cell_size: T,
+) -> Grid<V>
+where
+    T: Real,
+    V: Copy + Zero + Add<Output = V>,
+{
+    let (min, max) = bounds.min_max();
+    let cells = |length: T| {
+        if cell_size > T::zero() && length > T::zero() {
+            (length / cell_size).ceil().to_usize().unwrap_or(0)
+        } else {
+            0
+        }
+    };
+
+    let mut grid = match Grid::new(
+        cells(max.x() - min.x()),
+        cells(max.y() - min.y()),
+        V::zero(),
+    ) {
+        Some(grid) => grid,
+        None => {
+            return Grid {
+                values: Vec::new(),
+                width: 0,
+                height: 0,
+            }
+        }
+    };
+    if grid.values.is_empty() {
+        return grid;
+    }
+
+    // points on the maximum edges belong to the last row or column
+    let cell = |value: T, min: T, max: T, count: usize| {
+        if value < min || value > max {
+            None
+        } else {
+            let index = ((value - min) / cell_size).floor().to_usize()?;
+            Some(index.min(count - 1))
+        }
+    };
+
+    for (point, weight) in points {
+        let x = cell(point.x(), min.x(), max.x(), grid.width);
+        let y = cell(point.y(), min.y(), max.y(), grid.height);
+        if let (Some(x), Some(y)) = (x, y) {
+            let value = &mut grid.values[y * grid.width + x];
+            *value = *value + weight;
+        }
+    }
+
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds() -> Box<f64> {
+        Box::new(Point::new(0.0, 0.0), Point::new(10.0, 5.0))
+    }
+
+    #[test]
+    fn test_accumulate() {
+        let points = [
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(3.5, 0.5),
+            Point::new(10.0, 5.0),
+            Point::new(-1.0, 2.0),
+            Point::new(5.0, 5.5),
+        ];
+
+        let grid = accumulate(points.iter().copied(), bounds(), 3.0);
+        assert_eq!(grid.dims(), (4, 2));
+        assert_eq!(grid.values(), &[2, 1, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(grid.get(0, 0), Some(&2));
+        assert_eq!(grid.get(4, 0), None);
+
+        assert_eq!(
+            accumulate(points.iter().copied(), bounds(), 0.0).dims(),
+            (0, 0)
+        );
+    }
+
+    #[test]
+    fn test_new() {
+        let grid = Grid::new(3, 2, 7u8).unwrap();
+        assert_eq!(grid.dims(), (3, 2));
+        assert_eq!(grid.values(), &[7; 6]);
+
+        // The number of cells overflows.
+        assert_eq!(Grid::new(usize::MAX, 2, 0u8), None);
+        let huge = Box::new(Point::new(0.0, 0.0), Point::new(1e10, 1e10));
+        assert_eq!(accumulate(core::iter::empty(), huge, 1.0).dims(), (0, 0));
+    }
+
+    #[test]
+    fn test_accumulate_weighted() {
+        let points = [
+            (Point::new(0.5, 0.5), 0.25),
+            (Point::new(0.5, 0.75), 0.5),
+            (Point::new(9.5, 4.5), 2.0),
+        ];
+
+        let grid = accumulate_weighted(points.iter().copied(), bounds(), 5.0);
+        assert_eq!(grid.values(), &[0.75, 2.0]);
+
+        let doubled = grid.map(|value| value * 2.0);
+        assert_eq!(doubled.into_values(), [1.5, 4.0]);
+    }
+}
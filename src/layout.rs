@@ -0,0 +1,156 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Dividing boxes up for layouts.
+//!
+//! These are the building blocks of user interfaces and charts: splitting an area into a
+//! grid of cells, or into strips that take up a share of it each.
+
+use crate::box2d::Box;
+use crate::point::Point;
+
+use num_traits::real::Real;
+
+/// An axis to lay boxes out along.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Axis {
+    /// Boxes are placed side by side along the X axis.
+    X,
+
+    /// Boxes are placed one after another along the Y axis.
+    Y,
+}
+
+/// Divide a box into a grid of cells, with a gap of `gutter` between them.
+///
+/// The cells are yielded row by row, starting at the minimum point. There is no gap
+/// between the outer cells and the edges of `bounds`. If the gutters take up more room
+/// than there is, the cells are empty.
+pub fn grid<T: Real>(
+    bounds: Box<T>,
+    rows: usize,
+    cols: usize,
+    gutter: T,
+) -> impl Iterator<Item = Box<T>> {
+    let (min, max) = bounds.min_max();
+    let cell = |count: usize, size: T| {
+        let count = T::from(count).unwrap();
+        ((size - gutter * (count - T::one())) / count).max(T::zero())
+    };
+    let (width, height) = (cell(cols, max.x() - min.x()), cell(rows, max.y() - min.y()));
+
+    let cells = if cols == 0 { 0 } else { rows * cols };
+    (0..cells).map(move |index| {
+        let (row, col) = (index / cols, index % cols);
+        let origin = Point::new(
+            min.x() + (width + gutter) * T::from(col).unwrap(),
+            min.y() + (height + gutter) * T::from(row).unwrap(),
+        );
+        Box::new(origin, Point::new(origin.x() + width, origin.y() + height))
+    })
+}
+
+/// Split a box along an axis into one strip per weight, sized by the weights.
+///
+/// Each strip covers the full extent of `bounds` along the other axis. Negative weights
+/// count as zero, and if all of the weights are zero then every strip is empty.
+pub fn split_weighted<T: Real>(
+    bounds: Box<T>,
+    weights: &[T],
+    axis: Axis,
+) -> impl Iterator<Item = Box<T>> + '_ {
+    let (min, max) = bounds.min_max();
+    let total = weights
+        .iter()
+        .fold(T::zero(), |total, &weight| total + weight.max(T::zero()));
+
+    let (start, length) = match axis {
+        Axis::X => (min.x(), max.x() - min.x()),
+        Axis::Y => (min.y(), max.y() - min.y()),
+    };
+
+    // measure from the running total instead of adding up sizes, so that rounding
+    // errors don't build up and the last strip ends exactly at the edge
+    let offset = move |sum: T| {
+        if total > T::zero() {
+            start + length * (sum / total)
+        } else {
+            start
+        }
+    };
+
+    weights.iter().scan(T::zero(), move |sum, &weight| {
+        let from = offset(*sum);
+        *sum = *sum + weight.max(T::zero());
+        let to = offset(*sum);
+
+        Some(match axis {
+            Axis::X => Box::new(Point::new(from, min.y()), Point::new(to, max.y())),
+            Axis::Y => Box::new(Point::new(min.x(), from), Point::new(max.x(), to)),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn bounds() -> Box<f64> {
+        Box::new(Point::new(0.0, 0.0), Point::new(100.0, 50.0))
+    }
+
+    #[test]
+    fn test_grid() {
+        let cells = grid(bounds(), 2, 3, 5.0).collect::<Vec<_>>();
+        assert_eq!(cells.len(), 6);
+        assert_eq!(
+            cells[0],
+            Box::new(Point::new(0.0, 0.0), Point::new(30.0, 22.5))
+        );
+        assert_eq!(
+            cells[4],
+            Box::new(Point::new(35.0, 27.5), Point::new(65.0, 50.0))
+        );
+        assert_eq!(cells[5].min_max().1, Point::new(100.0, 50.0));
+
+        assert_eq!(grid(bounds(), 3, 0, 5.0).count(), 0);
+        assert_eq!(grid(bounds(), 0, 3, 5.0).count(), 0);
+    }
+
+    #[test]
+    fn test_split_weighted() {
+        let strips = split_weighted(bounds(), &[1.0, 2.0, -1.0, 1.0], Axis::X).collect::<Vec<_>>();
+        assert_eq!(
+            strips,
+            [
+                Box::new(Point::new(0.0, 0.0), Point::new(25.0, 50.0)),
+                Box::new(Point::new(25.0, 0.0), Point::new(75.0, 50.0)),
+                Box::new(Point::new(75.0, 0.0), Point::new(75.0, 50.0)),
+                Box::new(Point::new(75.0, 0.0), Point::new(100.0, 50.0)),
+            ]
+        );
+
+        let strips = split_weighted(bounds(), &[3.0, 2.0], Axis::Y).collect::<Vec<_>>();
+        assert_eq!(
+            strips[1],
+            Box::new(Point::new(0.0, 30.0), Point::new(100.0, 50.0))
+        );
+
+        assert!(split_weighted(bounds(), &[0.0, 0.0], Axis::Y).all(|strip| strip.area() == 0.0));
+    }
+}
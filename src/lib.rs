@@ -38,57 +38,92 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+/// Emit a `tracing` event at the given level, if the `tracing` feature is enabled.
+///
+/// Without the feature, the event and its arguments are compiled out entirely.
+#[cfg(feature = "alloc")]
+macro_rules! trace_event {
+    ($level:ident, $($arg:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)*);
+    }};
+}
+
 mod angle;
 mod arc;
 mod bentley_ottman;
+pub mod bounding;
 mod box2d;
 mod color;
 pub mod curve;
+mod error;
+pub mod geo;
+pub mod geojson;
+mod gradient;
+pub mod grid;
 mod iter;
+pub mod layout;
 mod line;
+pub mod pack;
 mod pair;
 pub mod path;
+pub mod placement;
+pub mod plan;
 mod point;
+pub mod polygon;
+pub mod polyline;
+pub mod raster;
 mod rect;
 pub mod region;
+pub mod scale;
+mod side_offsets;
 mod size;
+pub mod spatial;
 mod transform;
 mod trapezoid;
 mod triangle;
 
 pub use angle::Angle;
 pub use arc::Arc;
-pub use box2d::{BoundingBox, Box};
+#[cfg(feature = "alloc")]
+pub use bentley_ottman::{
+    boolean_trapezoids, merge_trapezoids, trapezoids_multi, trapezoids_tagged, BooleanOp,
+    CurvedTrapezoid, CurvedTrapezoids, MonotoneEdge, OpenPathPolicy, TaggedTrapezoids,
+    TessellationLimits, TessellationStats, Tessellator,
+};
+pub use box2d::{BoundingBox, Box, BoxGrid, BoxPoints, PixelRows};
 pub use color::Color;
 pub use curve::{CubicBezier, Curve, QuadraticBezier};
+pub use error::GeometryError;
+pub use gradient::{GradientStop, LinearGradient, RadialGradient, SpreadMode};
 pub use iter::{Four, Three, Two};
-pub use line::{Line, LineSegment, NhLineSegment};
-pub use path::{Path, PathBuffer, PathEvent, Shape, StraightPathEvent, Verb};
+pub use line::{Bresenham, Line, LineSegment, NhLineSegment, Supercover};
+pub use path::{Path, PathBuffer, PathEvent, PathSink, PathStats, Shape, StraightPathEvent, Verb};
 pub use point::{Point, Vector};
 pub use rect::Rect;
+pub use side_offsets::SideOffsets;
 pub use size::Size;
-pub use transform::{Affine, Rotation, Scale, Transform, Translation};
+#[cfg(feature = "alloc")]
+pub use transform::TransformStack;
+pub use transform::{
+    Affine, Camera2D, Rotation, Scale, Skew, Transform, Transformable, Translation,
+};
 pub use trapezoid::Trapezoid;
 pub use triangle::Triangle;
 
 use core::num::Wrapping;
 
 /// A general-purpose "direction" type.
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum Direction {
     /// The direction is forwards.
+    #[default]
     Forwards,
 
     /// The direction is backwards.
     Backwards,
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::Forwards
-    }
-}
-
 /// Simple trait for telling if one value is approximately equal to another.
 pub trait ApproxEq {
     /// Returns true if the values are approximately equal.
@@ -139,8 +174,31 @@ impl<T: ApproxEq> ApproxEq for Wrapping<T> {
     }
 }
 
+/// The rule used to decide which parts of a shape are inside of it.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum FillRule {
+    /// A point is inside if the path winds around it a non-zero number of times.
     Winding,
+
+    /// A point is inside if the path winds around it an odd number of times.
     EvenOdd,
 }
+
+impl FillRule {
+    /// Tell whether a winding number is inside under this rule.
+    pub(crate) fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::Winding => winding != 0,
+            FillRule::EvenOdd => winding & 1 != 0,
+        }
+    }
+}
+
+/// Tell whether a number is neither infinite nor `NaN`.
+///
+/// `Real` doesn't have `is_finite`, but subtracting either of those from themselves
+/// gives `NaN`.
+#[allow(clippy::eq_op)]
+pub(crate) fn is_finite<T: num_traits::real::Real>(value: T) -> bool {
+    value - value == T::zero()
+}
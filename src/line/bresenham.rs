@@ -0,0 +1,274 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Walking the grid cells along a line segment.
+
+use super::LineSegment;
+use crate::iter::Three;
+use crate::point::Point;
+
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+
+impl LineSegment<i32> {
+    /// Iterate over the grid cells along this segment using Bresenham's algorithm.
+    ///
+    /// Exactly one cell is visited for each step along the major axis, so the cells form
+    /// a thin, eight-connected line from `from` to `to`.
+    pub fn bresenham(&self) -> Bresenham {
+        let (from, to) = self.points();
+        let dx = (to.x() - from.x()).abs();
+        let dy = -(to.y() - from.y()).abs();
+
+        Bresenham {
+            current: from,
+            end: to,
+            step: Point::new((to.x() - from.x()).signum(), (to.y() - from.y()).signum()),
+            delta: Point::new(dx, dy),
+            error: dx + dy,
+            remaining: dx.max(-dy) as usize + 1,
+        }
+    }
+
+    /// Iterate over every grid cell that this segment passes through.
+    ///
+    /// Unlike `bresenham`, this visits every cell the segment crosses, so the cells form a
+    /// four-connected line. When the segment passes exactly through a corner, both of the
+    /// cells touching that corner are visited as well.
+    pub fn supercover(&self) -> Supercover {
+        let (from, to) = self.points();
+
+        Supercover {
+            steps: CellSteps::new(from, to),
+        }
+    }
+}
+
+/// The iterator returned by [`LineSegment::bresenham`].
+#[derive(Debug, Clone)]
+pub struct Bresenham {
+    /// The next cell to yield.
+    current: Point<i32>,
+
+    /// The last cell to yield.
+    end: Point<i32>,
+
+    /// The direction to step in along each axis.
+    step: Point<i32>,
+
+    /// The absolute X distance and negated absolute Y distance.
+    delta: Point<i32>,
+
+    /// The accumulated error term.
+    error: i32,
+
+    /// The number of cells left to yield.
+    remaining: usize,
+}
+
+impl Iterator for Bresenham {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let cell = self.current;
+        self.remaining -= 1;
+
+        if cell != self.end {
+            let [dx, dy] = [self.delta.x(), self.delta.y()];
+            let doubled = self.error * 2;
+
+            if doubled >= dy {
+                self.error += dy;
+                self.current = Point::new(self.current.x() + self.step.x(), self.current.y());
+            }
+            if doubled <= dx {
+                self.error += dx;
+                self.current = Point::new(self.current.x(), self.current.y() + self.step.y());
+            }
+        }
+
+        Some(cell)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Bresenham {}
+
+impl FusedIterator for Bresenham {}
+
+/// The iterator returned by [`LineSegment::supercover`].
+#[derive(Debug, Clone)]
+pub struct Supercover {
+    /// The steps from the first cell to the last.
+    steps: CellSteps,
+}
+
+impl Iterator for Supercover {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let length = self.steps.length();
+        let [nx, ny] = [i64::from(length.x()), i64::from(length.y())];
+
+        // Compare where the segment crosses the next vertical and horizontal cell borders.
+        self.steps.next_by(|progress| {
+            let [ix, iy] = [i64::from(progress.x()), i64::from(progress.y())];
+            ((1 + 2 * ix) * ny).cmp(&((1 + 2 * iy) * nx))
+        })
+    }
+}
+
+impl FusedIterator for Supercover {}
+
+/// Steps from cell to cell along a line, covering both sides of every corner it passes
+/// through.
+///
+/// This is shared by [`Supercover`] and the walk over floating point segments in
+/// `raster::cells`, which only differ in how they tell which cell border comes first.
+#[derive(Debug, Clone)]
+pub(crate) struct CellSteps {
+    /// The most recent cell that the line has moved into.
+    current: Point<i32>,
+
+    /// The direction to step in along each axis.
+    step: Point<i32>,
+
+    /// The number of cells to move along each axis.
+    length: Point<u32>,
+
+    /// The number of cells moved along each axis so far.
+    progress: Point<u32>,
+
+    /// Cells waiting to be yielded.
+    queue: Three<Point<i32>>,
+}
+
+impl CellSteps {
+    /// Begin stepping from one cell to another.
+    pub(crate) fn new(from: Point<i32>, to: Point<i32>) -> Self {
+        CellSteps {
+            current: from,
+            step: Point::new((to.x() - from.x()).signum(), (to.y() - from.y()).signum()),
+            length: Point::new(
+                (to.x() - from.x()).unsigned_abs(),
+                (to.y() - from.y()).unsigned_abs(),
+            ),
+            progress: Point::new(0, 0),
+            queue: Three::from([from]),
+        }
+    }
+
+    /// Get the number of cells to move along each axis.
+    pub(crate) fn length(&self) -> Point<u32> {
+        self.length
+    }
+
+    /// Get the next cell.
+    ///
+    /// While there are cells left to move along both axes, `first` is given the number of
+    /// cells moved along each axis so far. It returns `Less` if the line crosses the next
+    /// vertical border first, `Greater` if it crosses the next horizontal border first, and
+    /// `Equal` if it passes through the corner between them.
+    pub(crate) fn next_by(
+        &mut self,
+        first: impl FnOnce(Point<u32>) -> Ordering,
+    ) -> Option<Point<i32>> {
+        if let Some(cell) = self.queue.next() {
+            return Some(cell);
+        }
+
+        let [ix, iy] = [self.progress.x(), self.progress.y()];
+        let order = match (ix < self.length.x(), iy < self.length.y()) {
+            (false, false) => return None,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (true, true) => first(self.progress),
+        };
+
+        let [x, y] = [self.current.x(), self.current.y()];
+        let [sx, sy] = [self.step.x(), self.step.y()];
+
+        match order {
+            Ordering::Equal => {
+                // The line passes through a corner, so cover both sides of it.
+                self.current = Point::new(x + sx, y + sy);
+                self.progress = Point::new(ix + 1, iy + 1);
+                self.queue =
+                    Three::from([Point::new(x + sx, y), Point::new(x, y + sy), self.current]);
+            }
+            Ordering::Less => {
+                self.current = Point::new(x + sx, y);
+                self.progress = Point::new(ix + 1, iy);
+                self.queue = Three::from([self.current]);
+            }
+            Ordering::Greater => {
+                self.current = Point::new(x, y + sy);
+                self.progress = Point::new(ix, iy + 1);
+                self.queue = Three::from([self.current]);
+            }
+        }
+
+        self.queue.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bresenham() {
+        let line = LineSegment::new(Point::new(0, 0), Point::new(4, 2));
+        let mut cells = line.bresenham();
+
+        assert_eq!(cells.len(), 5);
+        for expected in [(0, 0), (1, 1), (2, 1), (3, 2), (4, 2)].iter() {
+            assert_eq!(cells.next(), Some(Point::new(expected.0, expected.1)));
+        }
+        assert_eq!(cells.next(), None);
+
+        // Lines going backwards end at their destination.
+        let line = LineSegment::new(Point::new(3, 5), Point::new(-2, 1));
+        assert_eq!(line.bresenham().last(), Some(Point::new(-2, 1)));
+
+        // A degenerate line only has one cell.
+        let line = LineSegment::new(Point::new(3, 3), Point::new(3, 3));
+        assert_eq!(line.bresenham().count(), 1);
+    }
+
+    #[test]
+    fn test_supercover() {
+        let line = LineSegment::new(Point::new(0, 0), Point::new(2, 2));
+        let mut cells = line.supercover();
+
+        for expected in [(0, 0), (1, 0), (0, 1), (1, 1), (2, 1), (1, 2), (2, 2)].iter() {
+            assert_eq!(cells.next(), Some(Point::new(expected.0, expected.1)));
+        }
+        assert_eq!(cells.next(), None);
+
+        let line = LineSegment::new(Point::new(0, 0), Point::new(3, -1));
+        assert_eq!(line.supercover().count(), 6);
+        assert_eq!(line.supercover().last(), Some(Point::new(3, -1)));
+    }
+}
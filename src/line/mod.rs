@@ -15,18 +15,23 @@
 // You should have received a copy of the GNU Affero General Public License 
 // along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
 
-use crate::iter::Three;
-use crate::path::{Path, PathEvent};
+use crate::iter::{Three, Two};
 use crate::pair::Quad;
-use crate::{ApproxEq, Point, Vector};
-use num_traits::{real::Real, Signed, Zero};
+use crate::path::{Path, PathEvent};
+use crate::{ApproxEq, Curve, Point, Vector};
+use num_traits::{real::Real, Zero};
 
 use core::convert::TryFrom;
 use core::fmt;
 use core::ops;
 
+mod bresenham;
 mod from_points;
 
+#[cfg(feature = "alloc")]
+pub(crate) use bresenham::CellSteps;
+pub use bresenham::{Bresenham, Supercover};
+
 /// An infinite line.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 #[repr(transparent)]
@@ -109,45 +114,46 @@ impl<T: Copy> Line<T> {
     }
 
     /// Tell whether or not this line intersects with another line.
+    ///
+    /// This is true exactly when [`Line::intersection`] returns a point, so lines that
+    /// are parallel as decided by [`Line::is_parallel`] never intersect.
     #[inline]
     pub fn intersects(&self, other: &Self) -> bool
     where
-        T: ApproxEq + Signed,
+        T: Real,
     {
-        !self
-            .direction()
-            .cross(other.direction())
-            .abs()
-            .approx_eq(&T::zero())
+        !parallel(self.direction(), other.direction())
+    }
+
+    /// Tell whether or not this line is parallel to another line.
+    ///
+    /// Lines are considered parallel when the sine of the angle between them is within
+    /// `T::epsilon()` of zero, so that the check does not depend on the scale of the
+    /// coordinates.
+    #[inline]
+    pub fn is_parallel(&self, other: &Self) -> bool
+    where
+        T: Real,
+    {
+        parallel(self.direction(), other.direction())
     }
 
     /// Get the intersection point of two lines.
     ///
-    /// Returns `None` if the lines are parallel.
+    /// Returns `None` if the lines are parallel, as decided by [`Line::is_parallel`].
     #[inline]
     pub fn intersection(&self, line: &Self) -> Option<Point<T>>
     where
         T: Real,
     {
-        // Taken from: https://docs.rs/lyon_geom/latest/src/lyon_geom/line.rs.html#550-566
-        // Get the inverse determinant of our vectors.
-        let det = self.direction().cross(line.direction());
-
-        // If the determinant is zero, lines are probably parallel.
-        if det <= T::epsilon() {
+        let (d1, d2) = (self.direction(), line.direction());
+        if parallel(d1, d2) {
             return None;
         }
 
-        let self_p2 = self.origin() + self.direction();
-        let other_p2 = line.origin() + line.direction();
-
-        let a = self.origin().into_vector().cross(self_p2.into_vector());
-        let b = line.origin().into_vector().cross(other_p2.into_vector());
-
-        Some(Point::new(
-            (a * line.direction().x() - b * self.direction().x()) / det,
-            (a * line.direction().y() - b * self.direction().y()) / det,
-        ))
+        // Solve for how far along this line the other line is crossed.
+        let t = (line.origin() - self.origin()).cross(d2) / d1.cross(d2);
+        Some(self.origin() + d1 * t)
     }
 
     /// Get the distance from this line to another point.
@@ -431,10 +437,10 @@ impl<T: PartialOrd + Copy> NhLineSegment<T> {
         self.line
             .intersection(&other.line)
             .and_then(|intersection| {
-                if self.top >= intersection.y()
-                    && self.bottom <= intersection.y()
-                    && other.top >= intersection.y()
-                    && other.bottom <= intersection.y()
+                if self.top <= intersection.y()
+                    && self.bottom >= intersection.y()
+                    && other.top <= intersection.y()
+                    && other.bottom >= intersection.y()
                 {
                     Some(intersection)
                 } else {
@@ -444,6 +450,46 @@ impl<T: PartialOrd + Copy> NhLineSegment<T> {
     }
 }
 
+impl<T: Real> Curve<T> for LineSegment<T> {
+    type FlattenIterator = Two<Point<T>>;
+    type Subsection = Self;
+    type Derivative = Vector<T>;
+
+    #[inline]
+    fn eval(&self, t: T) -> Point<T> {
+        self.from.lerp(self.to, t)
+    }
+
+    #[inline]
+    fn flatten(&self, _tolerance: T) -> Self::FlattenIterator {
+        Two::from([self.from, self.to])
+    }
+
+    #[inline]
+    fn split(self, index: T) -> (Self::Subsection, Self::Subsection) {
+        let mid = self.eval(index);
+        (
+            LineSegment::new(self.from, mid),
+            LineSegment::new(mid, self.to),
+        )
+    }
+
+    #[inline]
+    fn subsection(self, range: ops::Range<T>) -> Self::Subsection {
+        LineSegment::new(self.eval(range.start), self.eval(range.end))
+    }
+
+    #[inline]
+    fn length(&self, _accuracy: T) -> T {
+        LineSegment::length(self)
+    }
+
+    #[inline]
+    fn derivative(&self) -> Self::Derivative {
+        self.to - self.from
+    }
+}
+
 impl<T: Copy> Path<T> for LineSegment<T> {
     type Iter = Three<PathEvent<T>>;
 
@@ -480,6 +526,104 @@ impl<T: Real + ApproxEq> Path<T> for NhLineSegment<T> {
     }
 }
 
+impl<T: Copy + ops::Add<Output = T>> ops::Add<Vector<T>> for Line<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        Line::new(self.origin() + rhs, self.direction())
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::AddAssign<Vector<T>> for Line<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Vector<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::Sub<Vector<T>> for Line<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        Line::new(self.origin() - rhs, self.direction())
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::SubAssign<Vector<T>> for Line<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Vector<T>) {
+        *self = *self - rhs;
+    }
+}
+
+/// Scales the line about the origin.
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<T> for Line<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: T) -> Self::Output {
+        Line(self.0 * Quad::splat(rhs))
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::MulAssign<T> for Line<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::Add<Vector<T>> for LineSegment<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        LineSegment::new(self.from + rhs, self.to + rhs)
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::AddAssign<Vector<T>> for LineSegment<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Vector<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::Sub<Vector<T>> for LineSegment<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        LineSegment::new(self.from - rhs, self.to - rhs)
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::SubAssign<Vector<T>> for LineSegment<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Vector<T>) {
+        *self = *self - rhs;
+    }
+}
+
+/// Scales the line segment about the origin.
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<T> for LineSegment<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: T) -> Self::Output {
+        LineSegment::new(self.from * rhs, self.to * rhs)
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::MulAssign<T> for LineSegment<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
 #[inline]
 fn order<T: PartialOrd>(a: T, b: T) -> (T, T) {
     if a < b {
@@ -488,3 +632,132 @@ fn order<T: PartialOrd>(a: T, b: T) -> (T, T) {
         (b, a)
     }
 }
+
+/// Tell whether two directions are parallel, relative to their lengths.
+///
+/// Comparing the raw cross product against an epsilon would misclassify long, nearly
+/// parallel directions as crossing, and short, crossing directions as parallel.
+#[inline]
+fn parallel<T: Real>(a: Vector<T>, b: Vector<T>) -> bool {
+    a.cross(b).abs() <= T::epsilon() * a.length() * b.length()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intersection() {
+        let a = Line::between(Point::new(0.0f64, 0.0), Point::new(2.0, 2.0));
+        let b = Line::between(Point::new(0.0, 2.0), Point::new(2.0, 0.0));
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b), Some(Point::new(1.0, 1.0)));
+
+        // The order of the lines doesn't matter.
+        assert_eq!(b.intersection(&a), Some(Point::new(1.0, 1.0)));
+
+        let c = Line::new(Point::new(0.0, 1.0), Vector::new(1.0, 1.0));
+        assert!(!a.intersects(&c));
+        assert_eq!(a.intersection(&c), None);
+    }
+
+    #[test]
+    fn test_intersection_scale() {
+        // Tiny directions that cross at right angles.
+        let a = Line::new(Point::new(0.0f32, 0.0), Vector::new(1e-5, 0.0));
+        let b = Line::new(Point::new(3.0, -4.0), Vector::new(0.0, 1e-5));
+        assert!(!a.is_parallel(&b));
+        assert_eq!(a.intersection(&b), Some(Point::new(3.0, 0.0)));
+
+        // Huge directions that are parallel up to rounding.
+        let c = Line::new(Point::new(1e6f32, 1e6), Vector::new(1e6, 1e6));
+        let d = Line::new(Point::new(-5e5, 1e6), Vector::new(1e6 + 0.0625, 1e6));
+        assert!(c.is_parallel(&d));
+        assert_eq!(c.intersection(&d), None);
+
+        // Large coordinates that do cross.
+        let e = Line::between(Point::new(1e7f64, 1e7), Point::new(1e7 + 4.0, 1e7 + 4.0));
+        let f = Line::between(Point::new(1e7, 1e7 + 4.0), Point::new(1e7 + 4.0, 1e7));
+        assert!(!e.is_parallel(&f));
+        assert_eq!(e.intersection(&f), Some(Point::new(1e7 + 2.0, 1e7 + 2.0)));
+    }
+
+    #[test]
+    fn test_intersects_agrees() {
+        // Nearly parallel and crossing lines at very large and very small scales.
+        for &scale in &[1e6f64, 1e-6] {
+            let a = Line::new(Point::new(0.0, 0.0), Vector::new(scale, scale));
+            let lines = [
+                Line::new(Point::new(scale, 0.0), Vector::new(scale, scale)),
+                Line::new(
+                    Point::new(scale, 0.0),
+                    Vector::new(scale, scale * (1.0 + 1e-17)),
+                ),
+                Line::new(Point::new(scale, 0.0), Vector::new(scale, scale * 1.001)),
+                Line::new(Point::new(scale, 0.0), Vector::new(-scale, scale)),
+            ];
+
+            for line in &lines {
+                assert_eq!(a.intersects(line), a.intersection(line).is_some());
+            }
+            assert!(!a.intersects(&lines[0]));
+            assert!(a.intersects(&lines[3]));
+        }
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        let segment = |a: (f64, f64), b: (f64, f64)| {
+            NhLineSegment::new(Point::new(a.0, a.1), Point::new(b.0, b.1)).unwrap()
+        };
+
+        // Crossing in the middle.
+        let a = segment((0.0, 0.0), (4.0, 4.0));
+        let b = segment((0.0, 4.0), (4.0, 0.0));
+        assert_eq!(a.intersection(&b), Some(Point::new(2.0, 2.0)));
+        assert_eq!(b.intersection(&a), Some(Point::new(2.0, 2.0)));
+
+        // Touching where one ends on the other.
+        let c = segment((2.0, 2.0), (4.0, 0.0));
+        assert_eq!(a.intersection(&c), Some(Point::new(2.0, 2.0)));
+        let d = segment((4.0, 4.0), (6.0, 0.0));
+        assert_eq!(a.intersection(&d), Some(Point::new(4.0, 4.0)));
+
+        // The lines cross, but past the ends of the segments.
+        let e = segment((0.0, 8.0), (2.0, 6.0));
+        assert_eq!(a.line().intersection(&e.line()), Some(Point::new(4.0, 4.0)));
+        assert_eq!(a.intersection(&e), None);
+        let f = segment((5.0, 6.0), (7.0, 8.0));
+        assert_eq!(a.intersection(&f), None);
+    }
+
+    #[test]
+    fn test_operators() {
+        let segment = LineSegment::new(Point::new(1.0f32, 2.0), Point::new(3.0, 4.0));
+        let offset = Vector::new(1.0, -1.0);
+        assert_eq!(
+            segment + offset,
+            LineSegment::new(Point::new(2.0, 1.0), Point::new(4.0, 3.0))
+        );
+        assert_eq!(segment + offset - offset, segment);
+        assert_eq!(
+            segment * 2.0,
+            LineSegment::new(Point::new(2.0, 4.0), Point::new(6.0, 8.0))
+        );
+
+        let line = Line::new(Point::new(1.0f32, 2.0), Vector::new(1.0, 1.0));
+        assert_eq!(
+            line + offset,
+            Line::new(Point::new(2.0, 1.0), Vector::new(1.0, 1.0))
+        );
+        assert_eq!(
+            line * 2.0,
+            Line::new(Point::new(2.0, 4.0), Vector::new(2.0, 2.0))
+        );
+
+        let mut line = line;
+        line -= offset;
+        line *= 3.0;
+        assert_eq!(line, Line::new(Point::new(0.0, 9.0), Vector::new(3.0, 3.0)));
+    }
+}
@@ -0,0 +1,444 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Packing rectangles into atlases.
+//!
+//! This is used to lay out glyphs, sprites and other small images in a larger texture
+//! without overlapping. Two algorithms are available: the skyline packer is fast and
+//! works well for items with similar heights, like glyphs, while the guillotine packer
+//! is a little slower but handles items with mixed proportions better.
+
+#![cfg(feature = "alloc")]
+
+use crate::box2d::Box;
+use crate::point::Point;
+use crate::size::Size;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// The algorithm used to pack rectangles.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PackMethod {
+    /// Use a [`SkylinePacker`].
+    Skyline,
+
+    /// Use a [`GuillotinePacker`].
+    Guillotine,
+}
+
+/// Where an item was placed by [`pack`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Placement {
+    /// The index of the atlas that the item was placed in.
+    pub atlas: usize,
+
+    /// The area that the item covers in the atlas.
+    pub bounds: Box<i32>,
+}
+
+/// Pack a list of items into as many atlases as needed.
+///
+/// Every atlas covers the area of `atlas`, so the bounds of each placement are inside of
+/// `atlas` and the placement's `atlas` index tells them apart. Items are placed from the
+/// tallest to the shortest, which packs much tighter than placing them in order. Items
+/// that don't fit in an empty atlas get `None`.
+pub fn pack(items: &[Size<i32>], atlas: Box<i32>, method: PackMethod) -> Vec<Option<Placement>> {
+    let mut order = (0..items.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&i| {
+        let item = items[i];
+        (
+            core::cmp::Reverse(item.height()),
+            core::cmp::Reverse(item.width()),
+        )
+    });
+
+    let mut packers: Vec<Packer> = Vec::new();
+    let mut placements = vec![None; items.len()];
+
+    for i in order {
+        let item = items[i];
+        let existing = packers
+            .iter_mut()
+            .enumerate()
+            .find_map(|(index, packer)| packer.insert(item).map(|bounds| (index, bounds)));
+
+        placements[i] = existing
+            .or_else(|| {
+                let mut packer = Packer::new(atlas, method);
+                let bounds = packer.insert(item)?;
+                packers.push(packer);
+                Some((packers.len() - 1, bounds))
+            })
+            .map(|(atlas, bounds)| Placement { atlas, bounds });
+    }
+
+    placements
+}
+
+/// Either kind of packer.
+enum Packer {
+    /// A skyline packer.
+    Skyline(SkylinePacker),
+
+    /// A guillotine packer.
+    Guillotine(GuillotinePacker),
+}
+
+impl Packer {
+    /// Create a new, empty packer.
+    fn new(bounds: Box<i32>, method: PackMethod) -> Self {
+        match method {
+            PackMethod::Skyline => Packer::Skyline(SkylinePacker::new(bounds)),
+            PackMethod::Guillotine => Packer::Guillotine(GuillotinePacker::new(bounds)),
+        }
+    }
+
+    /// Place an item.
+    fn insert(&mut self, size: Size<i32>) -> Option<Box<i32>> {
+        match self {
+            Packer::Skyline(packer) => packer.insert(size),
+            Packer::Guillotine(packer) => packer.insert(size),
+        }
+    }
+}
+
+/// Packs rectangles into a single atlas by tracking the height of the packed area along
+/// its width.
+///
+/// Each item is placed where its top edge ends up lowest. Space below the skyline is
+/// never reused, so this works best when items are inserted from the tallest to the
+/// shortest.
+#[derive(Debug, Clone)]
+pub struct SkylinePacker {
+    /// The area of the atlas.
+    bounds: Box<i32>,
+
+    /// The segments of the skyline, from left to right.
+    skyline: Vec<Segment>,
+}
+
+/// A horizontal segment of the skyline.
+#[derive(Debug, Copy, Clone)]
+struct Segment {
+    /// The left edge of the segment.
+    x: i32,
+
+    /// The height of the skyline along this segment.
+    y: i32,
+
+    /// The width of the segment.
+    width: i32,
+}
+
+impl SkylinePacker {
+    /// Create a new, empty `SkylinePacker` for the given atlas area.
+    pub fn new(bounds: Box<i32>) -> Self {
+        let (min, max) = bounds.min_max();
+        SkylinePacker {
+            bounds,
+            skyline: vec![Segment {
+                x: min.x(),
+                y: min.y(),
+                width: (max.x() - min.x()).max(0),
+            }],
+        }
+    }
+
+    /// Get the area of the atlas.
+    pub fn bounds(&self) -> Box<i32> {
+        self.bounds
+    }
+
+    /// Place an item in the atlas, returning the area it covers.
+    ///
+    /// Returns `None` if there is no room left for the item.
+    pub fn insert(&mut self, size: Size<i32>) -> Option<Box<i32>> {
+        let (width, height) = (size.width(), size.height());
+        if !fits_empty(self.bounds, size) {
+            return None;
+        } else if width == 0 || height == 0 {
+            // empty items take up no space
+            let (min, _) = self.bounds.min_max();
+            return Some(Box::from_origin_and_size(min, size));
+        }
+
+        // find the spot where the top of the item is lowest, then furthest to the left
+        let (index, y) = (0..self.skyline.len())
+            .filter_map(|i| self.fit(i, width, height).map(|y| (i, y)))
+            .min_by_key(|&(i, y)| (y + height, self.skyline[i].x))?;
+
+        let x = self.skyline[index].x;
+        self.raise(index, x, y + height, width);
+        Some(Box::from_origin_and_size(Point::new(x, y), size))
+    }
+
+    /// Find the height that an item would sit at if its left edge is at the start of the
+    /// given segment.
+    fn fit(&self, index: usize, width: i32, height: i32) -> Option<i32> {
+        let (_, max) = self.bounds.min_max();
+        let x = self.skyline[index].x;
+        if x + width > max.x() {
+            return None;
+        }
+
+        // the item rests on the highest segment under it
+        let mut y = self.skyline[index].y;
+        let mut covered = 0;
+        for segment in &self.skyline[index..] {
+            if covered >= width {
+                break;
+            }
+
+            y = y.max(segment.y);
+            covered += segment.width;
+        }
+
+        if y + height > max.y() {
+            None
+        } else {
+            Some(y)
+        }
+    }
+
+    /// Raise the skyline to `y` between `x` and `x + width`, starting at the given
+    /// segment.
+    fn raise(&mut self, index: usize, x: i32, y: i32, width: i32) {
+        self.skyline.insert(index, Segment { x, y, width });
+
+        // cut the segments that are now under the new one
+        let right = x + width;
+        while let Some(segment) = self.skyline.get_mut(index + 1) {
+            if segment.x >= right {
+                break;
+            }
+
+            let overlap = right - segment.x;
+            if overlap >= segment.width {
+                self.skyline.remove(index + 1);
+            } else {
+                segment.x += overlap;
+                segment.width -= overlap;
+                break;
+            }
+        }
+
+        // merge neighbors at the same height
+        self.skyline.dedup_by(|next, prev| {
+            if prev.y == next.y {
+                prev.width += next.width;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
+/// Packs rectangles into a single atlas by splitting its free space into rectangles.
+///
+/// Each item is placed in the free rectangle that it fits best, and the rest of that
+/// rectangle is split in two along the shorter leftover side.
+#[derive(Debug, Clone)]
+pub struct GuillotinePacker {
+    /// The area of the atlas.
+    bounds: Box<i32>,
+
+    /// The rectangles of free space.
+    free: Vec<Box<i32>>,
+}
+
+impl GuillotinePacker {
+    /// Create a new, empty `GuillotinePacker` for the given atlas area.
+    pub fn new(bounds: Box<i32>) -> Self {
+        GuillotinePacker {
+            bounds,
+            free: if bounds.area() <= 0 {
+                vec![]
+            } else {
+                vec![bounds]
+            },
+        }
+    }
+
+    /// Get the area of the atlas.
+    pub fn bounds(&self) -> Box<i32> {
+        self.bounds
+    }
+
+    /// Place an item in the atlas, returning the area it covers.
+    ///
+    /// Returns `None` if there is no room left for the item.
+    pub fn insert(&mut self, size: Size<i32>) -> Option<Box<i32>> {
+        let (width, height) = (size.width(), size.height());
+        if !fits_empty(self.bounds, size) {
+            return None;
+        } else if width == 0 || height == 0 {
+            // empty items take up no space
+            let (min, _) = self.bounds.min_max();
+            return Some(Box::from_origin_and_size(min, size));
+        }
+
+        // find the free rectangle with the least area left over
+        let index = self
+            .free
+            .iter()
+            .enumerate()
+            .filter(|(_, free)| {
+                let free = free.size();
+                free.width() >= width && free.height() >= height
+            })
+            .min_by_key(|(_, free)| {
+                let free = free.size();
+                i64::from(free.width()) * i64::from(free.height())
+            })
+            .map(|(index, _)| index)?;
+
+        let free = self.free.swap_remove(index);
+        let (min, max) = free.min_max();
+        let corner = Point::new(min.x() + width, min.y() + height);
+
+        // split along the shorter leftover side, so that the larger piece stays as big
+        // as possible
+        let (right, top) = if max.x() - corner.x() <= max.y() - corner.y() {
+            (
+                Box::new(
+                    Point::new(corner.x(), min.y()),
+                    Point::new(max.x(), corner.y()),
+                ),
+                Box::new(Point::new(min.x(), corner.y()), max),
+            )
+        } else {
+            (
+                Box::new(Point::new(corner.x(), min.y()), max),
+                Box::new(
+                    Point::new(min.x(), corner.y()),
+                    Point::new(corner.x(), max.y()),
+                ),
+            )
+        };
+
+        self.free
+            .extend([right, top].iter().copied().filter(|free| free.area() > 0));
+        Some(Box::new(min, corner))
+    }
+}
+
+/// Tell whether an item could fit in an empty atlas.
+fn fits_empty(bounds: Box<i32>, size: Size<i32>) -> bool {
+    let atlas = bounds.size();
+    (0..=atlas.width()).contains(&size.width()) && (0..=atlas.height()).contains(&size.height())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atlas() -> Box<i32> {
+        Box::new(Point::new(0, 0), Point::new(64, 64))
+    }
+
+    fn items() -> Vec<Size<i32>> {
+        let mut items = vec![];
+        for i in 0..20 {
+            items.push(Size::new(4 + (i * 7) % 13, 4 + (i * 5) % 11));
+        }
+        items.push(Size::new(40, 8));
+        items.push(Size::new(8, 40));
+        items
+    }
+
+    fn check(items: &[Size<i32>], placements: &[Option<Placement>]) {
+        for (i, (item, placement)) in items.iter().zip(placements).enumerate() {
+            let placement = placement.unwrap();
+            assert_eq!(placement.bounds.size(), *item);
+            let (min, max) = placement.bounds.min_max();
+            assert!(min.x() >= 0 && min.y() >= 0 && max.x() <= 64 && max.y() <= 64);
+
+            for other in placements[i + 1..].iter().flatten() {
+                assert!(
+                    other.atlas != placement.atlas || !other.bounds.intersects(&placement.bounds),
+                    "{:?} overlaps {:?}",
+                    placement,
+                    other
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_pack() {
+        let items = items();
+        for &method in &[PackMethod::Skyline, PackMethod::Guillotine] {
+            let placements = pack(&items, atlas(), method);
+            check(&items, &placements);
+            assert!(placements.iter().all(|p| p.unwrap().atlas == 0));
+        }
+    }
+
+    #[test]
+    fn test_pack_overflow() {
+        // Five quarters of an atlas need two atlases, and oversized items don't fit.
+        let mut items = vec![Size::new(32, 32); 5];
+        items.push(Size::new(65, 1));
+
+        for &method in &[PackMethod::Skyline, PackMethod::Guillotine] {
+            let placements = pack(&items, atlas(), method);
+            assert_eq!(placements[5], None);
+            check(&items[..5], &placements[..5]);
+
+            let second = placements.iter().flatten().filter(|p| p.atlas == 1).count();
+            assert_eq!(second, 1);
+        }
+    }
+
+    #[test]
+    fn test_skyline() {
+        let mut packer = SkylinePacker::new(atlas());
+        assert_eq!(
+            packer.insert(Size::new(48, 16)),
+            Some(Box::new(Point::new(0, 0), Point::new(48, 16)))
+        );
+        assert_eq!(
+            packer.insert(Size::new(16, 8)),
+            Some(Box::new(Point::new(48, 0), Point::new(64, 8)))
+        );
+        assert_eq!(
+            packer.insert(Size::new(32, 8)),
+            Some(Box::new(Point::new(0, 16), Point::new(32, 24)))
+        );
+        assert_eq!(packer.insert(Size::new(64, 48)), None);
+    }
+
+    #[test]
+    fn test_guillotine() {
+        let mut packer = GuillotinePacker::new(atlas());
+        assert_eq!(
+            packer.insert(Size::new(48, 16)),
+            Some(Box::new(Point::new(0, 0), Point::new(48, 16)))
+        );
+        assert_eq!(
+            packer.insert(Size::new(16, 16)),
+            Some(Box::new(Point::new(48, 0), Point::new(64, 16)))
+        );
+        assert_eq!(
+            packer.insert(Size::new(64, 48)),
+            Some(Box::new(Point::new(0, 16), Point::new(64, 64)))
+        );
+        assert_eq!(packer.insert(Size::new(1, 1)), None);
+    }
+}
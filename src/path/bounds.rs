@@ -0,0 +1,159 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Bounding boxes of transformed paths.
+
+use super::{Path, PathEvent};
+use crate::curve::{CubicBezier, Curve, QuadraticBezier};
+use crate::transform::{Affine, Transform};
+use crate::{ApproxEq, Box};
+use num_traits::real::Real;
+
+/// Get the bounding box of a path after it has been transformed.
+///
+/// Bezier curves keep their shape under affine transformations, so this transforms the
+/// control points and bounds each curve by its end points and extrema. Unlike
+/// [`Shape::bounding_box`](super::Shape::bounding_box), the path is never flattened.
+///
+/// An empty path has a bounding box with no bounds.
+pub fn transformed_bounds<T: Real + ApproxEq, P: Path<T>>(path: P, transform: Affine<T>) -> Box<T> {
+    path.path_iter()
+        .map(|event| event.map(|point| transform.transform_point(point)))
+        .fold(Box::unbounded_real(), |bounds, event| match event {
+            PathEvent::Begin { at } => bounds.with_point(&at),
+            PathEvent::Line { from, to } => bounds.with_point(&from).with_point(&to),
+            PathEvent::Quadratic { from, control, to } => {
+                let curve = QuadraticBezier::new(from, control, to);
+                let extrema = [
+                    quadratic_extremum(from.x(), control.x(), to.x()),
+                    quadratic_extremum(from.y(), control.y(), to.y()),
+                ];
+
+                bounds
+                    .with_point(&from)
+                    .with_point(&to)
+                    .with_points(extrema.iter().flatten().map(|&t| curve.eval(t)))
+            }
+            PathEvent::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => {
+                let curve = CubicBezier::new(from, control1, control2, to);
+                let [x1, x2] = cubic_extrema(from.x(), control1.x(), control2.x(), to.x());
+                let [y1, y2] = cubic_extrema(from.y(), control1.y(), control2.y(), to.y());
+
+                bounds
+                    .with_point(&from)
+                    .with_point(&to)
+                    .with_points([x1, x2, y1, y2].iter().flatten().map(|&t| curve.eval(t)))
+            }
+            PathEvent::End { first, last, .. } => bounds.with_point(&first).with_point(&last),
+            PathEvent::__NonExhaustive => bounds,
+        })
+}
+
+/// Find the parameter where a quadratic curve turns around along one axis.
+fn quadratic_extremum<T: Real>(from: T, control: T, to: T) -> Option<T> {
+    let denominator = from - control - control + to;
+    if denominator.is_zero() {
+        return None;
+    }
+
+    inside((from - control) / denominator)
+}
+
+/// Find the parameters where a cubic curve turns around along one axis.
+fn cubic_extrema<T: Real>(from: T, control1: T, control2: T, to: T) -> [Option<T>; 2] {
+    // The derivative, divided by three, is a quadratic in `t`.
+    let two = T::one() + T::one();
+    let three = two + T::one();
+    let a = to - from + three * (control1 - control2);
+    let b = two * (from - control1 - control1 + control2);
+    let c = control1 - from;
+
+    let discriminant = b * b - two * two * a * c;
+    if discriminant < T::zero() {
+        return [None, None];
+    }
+
+    // Avoid cancellation when `a` is tiny, which is common after rotating a curve.
+    let root = if b < T::zero() {
+        -discriminant.sqrt()
+    } else {
+        discriminant.sqrt()
+    };
+    let q = -(b + root) / two;
+    if q.is_zero() {
+        return [None, None];
+    }
+
+    let first = if a.is_zero() { None } else { inside(q / a) };
+    [first, inside(c / q)]
+}
+
+/// Keep a curve parameter only if it lies strictly inside of the curve.
+fn inside<T: Real>(t: T) -> Option<T> {
+    if t > T::zero() && t < T::one() {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{PathBuffer, Verb};
+    use crate::{Angle, Point};
+
+    #[test]
+    fn test_transformed_bounds() {
+        // A parabolic arch only reaches halfway to its control point.
+        let arch = PathBuffer::new(
+            Point::new(0.0f32, 0.0),
+            [(
+                Point::new(2.0, 0.0),
+                Verb::Quadratic {
+                    control: Point::new(1.0, 2.0),
+                },
+            )],
+        );
+        let transform = Affine::scale(2.0, 1.0) * Affine::translate(1.0, 0.0);
+        assert_eq!(
+            transformed_bounds(&arch, transform),
+            Box::new(Point::new(2.0, 0.0), Point::new(6.0, 1.0))
+        );
+
+        // A cubic hump, turned on its side.
+        let hump = PathBuffer::new(
+            Point::new(0.0f32, 0.0),
+            [(
+                Point::new(1.0, 0.0),
+                Verb::Cubic {
+                    control1: Point::new(0.0, 1.0),
+                    control2: Point::new(1.0, 1.0),
+                },
+            )],
+        );
+        let bounds = transformed_bounds(&hump, Affine::rotate(Angle::quarter_turn()));
+        let (min, max) = bounds.min_max();
+        assert!(min.approx_eq(&Point::new(-0.75, 0.0)));
+        assert!(max.approx_eq(&Point::new(0.0, 1.0)));
+    }
+}
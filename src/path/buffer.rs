@@ -15,14 +15,23 @@
 // You should have received a copy of the GNU Affero General Public License 
 // along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
 
-use super::{Path, PathEvent};
+use super::{Path, PathEvent, Shape};
+#[cfg(feature = "alloc")]
+use crate::curve::CubicBezier;
 use crate::point::Point;
+use crate::ApproxEq;
 
 use core::borrow::Borrow;
 use core::fmt;
 use core::iter::FromIterator;
 use core::mem;
 use core::slice::Iter as SliceIter;
+use num_traits::real::Real;
+#[cfg(feature = "alloc")]
+use num_traits::NumCast;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
 /// A verb associated with a path.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -60,22 +69,163 @@ pub enum Verb<T: Copy> {
 /// Type alias for a path's unsized buffer.
 type UnsizedBuffer<T> = [(Point<T>, Verb<T>)];
 
+/// A `PathBuffer` that owns its points in a `Vec`.
+#[cfg(feature = "alloc")]
+pub type OwnedPathBuffer<T> = PathBuffer<T, Vec<(Point<T>, Verb<T>)>>;
+
 /// A path is a series of connected lines and curves.
 pub struct PathBuffer<T: Copy, Buf: ?Sized = UnsizedBuffer<T>> {
     /// The first point in the path.
     first: Point<T>,
 
+    /// Whether or not the final subpath is closed.
+    close: bool,
+
     /// The remaining points in the path.
     buffer: Buf,
 }
 
 impl<T: Copy, Buf: Borrow<UnsizedBuffer<T>>> PathBuffer<T, Buf> {
     /// Create a new `Path` from the first point and the remaining actions.
+    ///
+    /// The final subpath is left open.
     pub const fn new(first: Point<T>, buffer: Buf) -> Self {
-        PathBuffer { first, buffer }
+        PathBuffer {
+            first,
+            close: false,
+            buffer,
+        }
+    }
+
+    /// Set whether or not the final subpath is closed.
+    ///
+    /// Every other subpath stores this in the `Begin` verb that follows it.
+    pub fn with_close(mut self, close: bool) -> Self {
+        self.close = close;
+        self
+    }
+
+    /// Tell whether or not the final subpath is closed.
+    pub fn is_closed(&self) -> bool {
+        self.close
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<T: Copy, Buf: Borrow<UnsizedBuffer<T>> + ?Sized> PathBuffer<T, Buf> {
+    /// Create a new path by running every point in this one through a function.
+    ///
+    /// Returns `None` if the function returns `None` for any point.
+    pub fn try_map_points<U: Copy>(
+        &self,
+        mut f: impl FnMut(Point<T>) -> Option<Point<U>>,
+    ) -> Option<OwnedPathBuffer<U>> {
+        let first = f(self.first)?;
+        let buffer = self
+            .buffer
+            .borrow()
+            .iter()
+            .map(|&(point, verb)| {
+                let verb = match verb {
+                    Verb::Begin { close } => Verb::Begin { close },
+                    Verb::Line => Verb::Line,
+                    Verb::Quadratic { control } => Verb::Quadratic {
+                        control: f(control)?,
+                    },
+                    Verb::Cubic { control1, control2 } => Verb::Cubic {
+                        control1: f(control1)?,
+                        control2: f(control2)?,
+                    },
+                    Verb::__NonExhaustive => unreachable!(),
+                };
+
+                Some((f(point)?, verb))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(PathBuffer {
+            first,
+            close: self.close,
+            buffer,
+        })
+    }
+
+    /// Create a copy of this path where every cubic curve is replaced with a chain of
+    /// quadratic curves.
+    ///
+    /// Each quadratic stays within `tolerance` of the part of the cubic that it replaces.
+    /// This is useful for formats and rendering techniques that only support quadratic
+    /// curves, such as TrueType outlines.
+    pub fn to_quadratic_only(&self, tolerance: T) -> OwnedPathBuffer<T>
+    where
+        T: Real + ApproxEq,
+    {
+        let mut last = self.first;
+        let mut buffer = Vec::with_capacity(self.buffer.borrow().len());
+
+        for &(to, verb) in self.buffer.borrow() {
+            match verb {
+                Verb::Cubic { control1, control2 } => {
+                    let cubic = CubicBezier::new(last, control1, control2, to);
+                    buffer.extend(cubic.to_quadratics(tolerance).map(|quad| {
+                        let control = quad.control();
+                        (quad.to(), Verb::Quadratic { control })
+                    }));
+                }
+                verb => buffer.push((to, verb)),
+            }
+
+            last = to;
+        }
+
+        PathBuffer {
+            first: self.first,
+            close: self.close,
+            buffer,
+        }
+    }
+
+    /// Convert the coordinates of this path to another numeric type.
+    ///
+    /// This can be used to store `f64` paths as `f32` to save memory. Returns `None` if
+    /// any coordinate can't be represented in the new type.
+    pub fn cast<U: Copy + NumCast>(&self) -> Option<OwnedPathBuffer<U>>
+    where
+        T: NumCast,
+    {
+        self.try_map_points(|point| Some(Point::new(U::from(point.x())?, U::from(point.y())?)))
+    }
+
+    /// Quantize this path into 16.16 fixed-point coordinates.
+    ///
+    /// Coordinates are rounded to the nearest 1/65536th of a unit. Returns `None` if any
+    /// coordinate is outside of the `[-32768, 32768)` range that can be represented.
+    pub fn to_fixed(&self) -> Option<OwnedPathBuffer<i32>>
+    where
+        T: Real,
+    {
+        let one = T::from(FIXED_ONE)?;
+        let fixed = |value: T| (value * one).round().to_i32();
+        self.try_map_points(|point| Some(Point::new(fixed(point.x())?, fixed(point.y())?)))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Buf: Borrow<UnsizedBuffer<i32>> + ?Sized> PathBuffer<i32, Buf> {
+    /// Convert a path with 16.16 fixed-point coordinates, as created by
+    /// [`PathBuffer::to_fixed`], back into a floating-point path.
+    pub fn to_float<U: Real>(&self) -> OwnedPathBuffer<U> {
+        let one = U::from(FIXED_ONE).unwrap();
+        let float = |value: i32| U::from(value).unwrap() / one;
+        self.try_map_points(|point| Some(Point::new(float(point.x()), float(point.y()))))
+            .unwrap()
+    }
+}
+
+/// The value of one in 16.16 fixed-point coordinates.
+#[cfg(feature = "alloc")]
+const FIXED_ONE: i32 = 1 << 16;
+
 impl<T: Copy + fmt::Debug, Buf: FromIterator<(Point<T>, Verb<T>)>> FromIterator<PathEvent<T>>
     for PathBuffer<T, Buf>
 {
@@ -92,7 +242,12 @@ impl<T: Copy + fmt::Debug, Buf: FromIterator<(Point<T>, Verb<T>)>> FromIterator<
         let mut close_begin = false;
         let buffer = iter
             .filter_map(|event| match event {
-                PathEvent::Begin { at } => Some((at, Verb::Begin { close: close_begin })),
+                PathEvent::Begin { at } => Some((
+                    at,
+                    Verb::Begin {
+                        close: mem::take(&mut close_begin),
+                    },
+                )),
                 PathEvent::Line { to, .. } => Some((to, Verb::Line)),
                 PathEvent::Quadratic { control, to, .. } => Some((to, Verb::Quadratic { control })),
                 PathEvent::Cubic {
@@ -109,7 +264,11 @@ impl<T: Copy + fmt::Debug, Buf: FromIterator<(Point<T>, Verb<T>)>> FromIterator<
             })
             .collect();
 
-        PathBuffer { first, buffer }
+        PathBuffer {
+            first,
+            close: close_begin,
+            buffer,
+        }
     }
 }
 
@@ -125,6 +284,8 @@ impl<Seg: Borrow<(Point<T>, Verb<T>)>, T: Copy, Buf: IntoIterator<Item = Seg>> P
             is_first: true,
             remaining: self.buffer.into_iter(),
             begin_event: None,
+            close: self.close,
+            finished: false,
         }
     }
 }
@@ -139,6 +300,8 @@ impl<'a, T: Copy, Buf: Borrow<UnsizedBuffer<T>> + ?Sized> Path<T> for &'a PathBu
             is_first: true,
             remaining: self.buffer.borrow().iter(),
             begin_event: None,
+            close: self.close,
+            finished: false,
         }
     }
 }
@@ -160,6 +323,12 @@ pub struct PathBufferIterator<T: Copy, I> {
     /// The "Begin" verb is split into an "End" and "Begin" event. This is the "End"
     /// event that will be returned next.
     begin_event: Option<PathEvent<T>>,
+
+    /// Whether or not the final subpath is closed.
+    close: bool,
+
+    /// Whether or not the "End" event for the final subpath has been returned.
+    finished: bool,
 }
 
 impl<T: Copy, I> PathBufferIterator<T, I> {
@@ -206,11 +375,18 @@ impl<Seg: Borrow<(Point<T>, Verb<T>)>, T: Copy, I: Iterator<Item = Seg>> Iterato
         if self.is_first {
             self.is_first = false;
             Some(PathEvent::Begin { at: self.last })
-        } else {
-            self.remaining.next().map(|seg| {
-                let (to, verb) = *seg.borrow();
-                self.parse_verb(to, verb)
+        } else if let Some(seg) = self.remaining.next() {
+            let (to, verb) = *seg.borrow();
+            Some(self.parse_verb(to, verb))
+        } else if !self.finished {
+            self.finished = true;
+            Some(PathEvent::End {
+                first: self.begin,
+                last: self.last,
+                close: self.close,
             })
+        } else {
+            None
         }
     }
 
@@ -218,7 +394,9 @@ impl<Seg: Borrow<(Point<T>, Verb<T>)>, T: Copy, I: Iterator<Item = Seg>> Iterato
         let (mut lo, mut hi) = self.remaining.size_hint();
 
         // Check for additional events.
-        let add = (self.is_first as usize) + (self.begin_event.is_some() as usize);
+        let add = (self.is_first as usize)
+            + (self.begin_event.is_some() as usize)
+            + (!self.finished as usize);
         lo = lo.saturating_add(add);
 
         // The remaining events could all be Begin events which, while incomprehensible,
@@ -230,3 +408,320 @@ impl<Seg: Borrow<(Point<T>, Verb<T>)>, T: Copy, I: Iterator<Item = Seg>> Iterato
         (lo, hi)
     }
 }
+
+impl<Seg: Borrow<(Point<T>, Verb<T>)>, T: Copy, Buf: IntoIterator<Item = Seg>> Shape<T>
+    for PathBuffer<T, Buf>
+where
+    Buf: Borrow<UnsizedBuffer<T>>,
+{
+    #[cfg(feature = "alloc")]
+    fn trapezoids_with_fill_rule(
+        self,
+        tolerance: T,
+        fill_rule: crate::FillRule,
+    ) -> crate::bentley_ottman::Trapezoids<T>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        (&self).trapezoids_with_fill_rule(tolerance, fill_rule)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn curved_trapezoids(self, tolerance: T) -> crate::bentley_ottman::CurvedTrapezoids<T>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        (&self).curved_trapezoids(tolerance)
+    }
+
+    fn perimeter(self, accuracy: T) -> T
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        (&self).perimeter(accuracy)
+    }
+}
+
+/// Unclosed subpaths in a `PathBuffer` are treated as if they were closed.
+impl<T: Copy, Buf: Borrow<UnsizedBuffer<T>> + ?Sized> Shape<T> for &PathBuffer<T, Buf> {
+    #[cfg(feature = "alloc")]
+    fn trapezoids_with_fill_rule(
+        self,
+        tolerance: T,
+        fill_rule: crate::FillRule,
+    ) -> crate::bentley_ottman::Trapezoids<T>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        let segments = self
+            .segments(tolerance)
+            .close_open_subpaths(true)
+            .skip_degenerate(true);
+
+        crate::bentley_ottman::trapezoids(segments, fill_rule)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn curved_trapezoids(self, tolerance: T) -> crate::bentley_ottman::CurvedTrapezoids<T>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        let events = self.path_iter().map(close_subpath);
+
+        crate::bentley_ottman::curved_trapezoids(
+            crate::bentley_ottman::monotone_edges(events),
+            tolerance,
+            crate::FillRule::Winding,
+        )
+    }
+
+    fn perimeter(self, accuracy: T) -> T
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        // Add the implicit closing segments to the length of the path.
+        self.path_iter().fold(
+            self.approximate_length(accuracy),
+            |sum, event| match event {
+                PathEvent::End {
+                    first,
+                    last,
+                    close: false,
+                } => sum + (first - last).length(),
+                _ => sum,
+            },
+        )
+    }
+}
+
+/// Mark a subpath's "End" event as closed.
+#[cfg(feature = "alloc")]
+#[inline]
+fn close_subpath<T: Copy>(event: PathEvent<T>) -> PathEvent<T> {
+    match event {
+        PathEvent::End { first, last, .. } => PathEvent::End {
+            first,
+            last,
+            close: true,
+        },
+        event => event,
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::{FillRule, GeometryError, OpenPathPolicy};
+    use alloc::vec;
+
+    #[test]
+    fn test_shape() {
+        // An open triangle, which is treated as closed.
+        let path = PathBuffer::new(
+            Point::new(0.0f32, 0.0),
+            vec![
+                (Point::new(4.0, 0.0), Verb::Line),
+                (Point::new(4.0, 3.0), Verb::Line),
+            ],
+        );
+
+        assert_eq!((&path).path_iter().count(), 4);
+        assert!(((&path).area(0.01) - 6.0).abs() < 0.01);
+        assert!((path.perimeter(0.01) - 12.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_events() {
+        let path: OwnedPathBuffer<f32> = PathBuffer::new(
+            Point::new(0.0, 0.0),
+            vec![
+                (Point::new(4.0, 0.0), Verb::Line),
+                (Point::new(4.0, 3.0), Verb::Line),
+            ],
+        );
+        let end = |close| PathEvent::End {
+            first: Point::new(0.0, 0.0),
+            last: Point::new(4.0, 3.0),
+            close,
+        };
+        let expected = |close| {
+            vec![
+                PathEvent::Begin {
+                    at: Point::new(0.0, 0.0),
+                },
+                PathEvent::Line {
+                    from: Point::new(0.0, 0.0),
+                    to: Point::new(4.0, 0.0),
+                },
+                PathEvent::Line {
+                    from: Point::new(4.0, 0.0),
+                    to: Point::new(4.0, 3.0),
+                },
+                end(close),
+            ]
+        };
+
+        // An open path ends with an open subpath.
+        assert!(!path.is_closed());
+        assert_eq!((&path).path_iter().collect::<Vec<_>>(), expected(false));
+
+        // A closed path says so, and keeps saying so after a round trip.
+        let path = path.with_close(true);
+        assert_eq!((&path).path_iter().collect::<Vec<_>>(), expected(true));
+        let copy: OwnedPathBuffer<f32> = (&path).path_iter().collect();
+        assert!(copy.is_closed());
+        assert_eq!(copy.path_iter().collect::<Vec<_>>(), expected(true));
+    }
+
+    #[test]
+    fn test_open_policy() {
+        // A closed square followed by an open triangle.
+        let path = PathBuffer::new(
+            Point::new(10.0f32, 10.0),
+            vec![
+                (Point::new(12.0, 10.0), Verb::Line),
+                (Point::new(12.0, 12.0), Verb::Line),
+                (Point::new(10.0, 12.0), Verb::Line),
+                (Point::new(0.0, 0.0), Verb::Begin { close: true }),
+                (Point::new(4.0, 0.0), Verb::Line),
+                (Point::new(4.0, 3.0), Verb::Line),
+            ],
+        );
+
+        let area = |path: &PathBuffer<f32, _>, fill_rule, policy| {
+            path.trapezoids_with_open_policy(0.01, fill_rule, policy)
+                .map(|traps| traps.fold(0.0, |area, trap| area + trap.area(0.01)))
+        };
+
+        assert_eq!(
+            area(&path, FillRule::Winding, OpenPathPolicy::AutoClose),
+            Ok(10.0)
+        );
+        assert_eq!(
+            area(&path, FillRule::Winding, OpenPathPolicy::SkipOpen),
+            Ok(4.0)
+        );
+        assert_eq!(
+            area(&path, FillRule::Winding, OpenPathPolicy::Error),
+            Err(GeometryError::OpenSubpath {
+                first: Point::new(0.0, 0.0),
+                last: Point::new(4.0, 3.0),
+            })
+        );
+
+        // A closed square with an open square inside of it, going the same way.
+        let nested = PathBuffer::new(
+            Point::new(0.0f32, 0.0),
+            vec![
+                (Point::new(4.0, 0.0), Verb::Line),
+                (Point::new(4.0, 4.0), Verb::Line),
+                (Point::new(0.0, 4.0), Verb::Line),
+                (Point::new(1.0, 1.0), Verb::Begin { close: true }),
+                (Point::new(3.0, 1.0), Verb::Line),
+                (Point::new(3.0, 3.0), Verb::Line),
+                (Point::new(1.0, 3.0), Verb::Line),
+            ],
+        );
+
+        let auto_close = OpenPathPolicy::AutoClose;
+        assert_eq!(area(&nested, FillRule::Winding, auto_close), Ok(16.0));
+        assert_eq!(area(&nested, FillRule::EvenOdd, auto_close), Ok(12.0));
+        assert_eq!(
+            area(&nested, FillRule::EvenOdd, OpenPathPolicy::SkipOpen),
+            Ok(16.0)
+        );
+    }
+
+    #[test]
+    fn test_curved_trapezoids() {
+        // A parabolic segment, which has an area of two thirds of its bounding box.
+        let path = PathBuffer::new(
+            Point::new(0.0f32, 0.0),
+            vec![(
+                Point::new(4.0, 0.0),
+                Verb::Quadratic {
+                    control: Point::new(2.0, 6.0),
+                },
+            )],
+        );
+
+        let area = (&path)
+            .curved_trapezoids(0.01)
+            .fold(0.0, |area, trapezoid| area + trapezoid.area(0.01));
+        assert!((area - 8.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_compact() {
+        let path = PathBuffer::new(
+            Point::new(0.1f64, -2.5),
+            vec![
+                (Point::new(4.0, 0.0), Verb::Line),
+                (
+                    Point::new(4.0, 3.0),
+                    Verb::Cubic {
+                        control1: Point::new(5.0, 1.0),
+                        control2: Point::new(5.0, 2.0),
+                    },
+                ),
+                (Point::new(10.0, 10.0), Verb::Begin { close: true }),
+                (
+                    Point::new(12.0, 10.0),
+                    Verb::Quadratic {
+                        control: Point::new(11.0, 11.0),
+                    },
+                ),
+            ],
+        );
+
+        let single = path.cast::<f32>().unwrap();
+        assert_eq!(single.cast::<f64>().unwrap().path_iter().count(), 7);
+        assert!(path.cast::<u8>().is_none());
+
+        let fixed = path.to_fixed().unwrap();
+        assert_eq!(fixed.first, Point::new(6554, -163840));
+        let restored = fixed.to_float::<f64>();
+        for (a, b) in (&path).path_iter().zip((&restored).path_iter()) {
+            let round = |p: Point<f64>| Point::new((p.x() * 1e4).round(), (p.y() * 1e4).round());
+            assert_eq!(a.map(round), b.map(round));
+        }
+
+        let huge = PathBuffer::new(Point::new(40000.0f32, 0.0), vec![]);
+        assert!(huge.to_fixed().is_none());
+    }
+
+    #[test]
+    fn test_quadratic_only() {
+        let path = PathBuffer::new(
+            Point::new(0.0f64, 0.0),
+            vec![
+                (
+                    Point::new(10.0, 0.0),
+                    Verb::Cubic {
+                        control1: Point::new(0.0, 10.0),
+                        control2: Point::new(10.0, 10.0),
+                    },
+                ),
+                (Point::new(5.0, -5.0), Verb::Line),
+            ],
+        );
+
+        let quads = path.to_quadratic_only(0.01);
+        let stats = (&quads).stats();
+        assert_eq!(stats.cubics, 0);
+        assert!(stats.quads > 1);
+        assert_eq!(stats.lines, 1);
+
+        // The last quadratic ends where the cubic did, and the area is about the same.
+        assert_eq!(quads.buffer[stats.quads - 1].0, Point::new(10.0, 0.0));
+        let area = (&path).area(0.001);
+        assert!((area - 85.0).abs() < 0.1);
+        assert!(((&quads).area(0.001) - area).abs() < 0.1);
+    }
+}
@@ -0,0 +1,233 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Shapes with holes cut out of them.
+
+use super::{Path, PathEvent, Shape};
+use crate::point::Point;
+
+use alloc::vec::Vec;
+use core::iter::Copied;
+use core::slice::Iter as SliceIter;
+use num_traits::real::Real;
+
+/// A shape with any number of holes cut out of it.
+///
+/// Holes are reversed as they're added when needed, so that they wind in the opposite
+/// direction to the outer shape. This way they are cut out under the non-zero fill rule,
+/// no matter which direction they were drawn in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Compound<T: Copy> {
+    /// The events of the outer shape, followed by the events of the holes.
+    events: Vec<PathEvent<T>>,
+
+    /// Whether the outer shape has a positive signed area.
+    positive: bool,
+}
+
+impl<T: Real> Compound<T> {
+    /// Create a new `Compound` from its outer shape, without any holes.
+    pub fn new<S: Shape<T>>(outer: S) -> Self {
+        let events: Vec<_> = outer.path_iter().collect();
+        let positive = signed_area(&events) >= T::zero();
+
+        Compound { events, positive }
+    }
+
+    /// Cut a hole out of this shape.
+    pub fn with_hole<S: Shape<T>>(mut self, hole: S) -> Self {
+        self.add_hole(hole);
+        self
+    }
+
+    /// Cut a hole out of this shape, in place.
+    ///
+    /// Every subpath of the hole is reversed if it winds in the same direction as the
+    /// outer shape.
+    pub fn add_hole<S: Shape<T>>(&mut self, hole: S) {
+        let mut start = self.events.len();
+        for event in hole.path_iter() {
+            if let PathEvent::Begin { .. } = event {
+                start = self.events.len();
+            }
+
+            self.events.push(event);
+
+            if let PathEvent::End { .. } = event {
+                let subpath = &mut self.events[start..];
+                if (signed_area(subpath) > T::zero()) == self.positive {
+                    reverse(subpath);
+                }
+            }
+        }
+    }
+
+    /// Get the events of the outer shape followed by the holes.
+    pub fn events(&self) -> &[PathEvent<T>] {
+        &self.events
+    }
+}
+
+impl<'a, T: Copy> Path<T> for &'a Compound<T> {
+    type Iter = Copied<SliceIter<'a, PathEvent<T>>>;
+
+    fn path_iter(self) -> Self::Iter {
+        self.events.iter().copied()
+    }
+}
+
+impl<T: Copy> Shape<T> for &Compound<T> {}
+
+/// Get the signed area of a series of events, treating every subpath as closed.
+fn signed_area<T: Real>(events: &[PathEvent<T>]) -> T {
+    let two = T::one() + T::one();
+    let cross = |a: Point<T>, b: Point<T>| a.x() * b.y() - a.y() * b.x();
+
+    // Green's theorem, using the exact areas under quadratic and cubic curves.
+    let doubled = events.iter().fold(T::zero(), |area, event| match *event {
+        PathEvent::Line { from, to }
+        | PathEvent::End {
+            last: from,
+            first: to,
+            ..
+        } => area + cross(from, to),
+        PathEvent::Quadratic { from, control, to } => {
+            let sum = (cross(from, control) + cross(control, to)) * two + cross(from, to);
+            area + sum / T::from(3).unwrap()
+        }
+        PathEvent::Cubic {
+            from,
+            control1,
+            control2,
+            to,
+        } => {
+            let three = T::from(3).unwrap();
+            let six = T::from(6).unwrap();
+            let sum = cross(from, control1) * six
+                + cross(from, control2) * three
+                + cross(from, to)
+                + cross(control1, control2) * three
+                + cross(control1, to) * three
+                + cross(control2, to) * six;
+            area + sum / T::from(10).unwrap()
+        }
+        _ => area,
+    });
+
+    doubled / two
+}
+
+/// Reverse the direction of a subpath in place.
+fn reverse<T: Copy>(subpath: &mut [PathEvent<T>]) {
+    // The segments between the `Begin` and `End` events run backwards in reverse order.
+    let len = subpath.len();
+    if len >= 2 {
+        subpath[1..len - 1].reverse();
+    }
+
+    for event in subpath.iter_mut() {
+        *event = match *event {
+            PathEvent::Begin { at } => PathEvent::Begin { at },
+            PathEvent::Line { from, to } => PathEvent::Line { from: to, to: from },
+            PathEvent::Quadratic { from, control, to } => PathEvent::Quadratic {
+                from: to,
+                control,
+                to: from,
+            },
+            PathEvent::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => PathEvent::Cubic {
+                from: to,
+                control1: control2,
+                control2: control1,
+                to: from,
+            },
+            PathEvent::End { first, last, close } => PathEvent::End {
+                first: last,
+                last: first,
+                close,
+            },
+            event => event,
+        };
+    }
+
+    // The subpath now starts where it used to end.
+    if let (Some(PathEvent::End { first, .. }), true) = (subpath.last().copied(), len >= 2) {
+        subpath[0] = PathEvent::Begin { at: first };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polygon::Polygon;
+    use crate::{Box, QuadraticBezier};
+    use alloc::vec;
+
+    fn square(min: f32, max: f32) -> Box<f32> {
+        Box::new(Point::new(min, min), Point::new(max, max))
+    }
+
+    #[test]
+    fn test_holes_reversed() {
+        let outer = square(0.0, 10.0);
+        let backwards = Polygon::new(
+            vec![
+                Point::new(5.0, 5.0),
+                Point::new(5.0, 7.0),
+                Point::new(7.0, 7.0),
+                Point::new(7.0, 5.0),
+            ],
+            vec![],
+        );
+        let compound = Compound::new(outer)
+            .with_hole(square(1.0, 3.0))
+            .with_hole(&backwards);
+
+        let outer_area = signed_area(&compound.events()[..5]);
+        assert_eq!(
+            outer_area,
+            signed_area(&outer.path_iter().collect::<Vec<_>>())
+        );
+
+        for hole in compound.events()[5..].chunks(5) {
+            assert_eq!(signed_area(hole), -outer_area.signum() * 4.0);
+            assert!(matches!(hole[0], PathEvent::Begin { .. }));
+            assert!(matches!(hole[4], PathEvent::End { .. }));
+        }
+
+        assert_eq!((&compound).area(0.1), 92.0);
+    }
+
+    #[test]
+    fn test_reverse_curve() {
+        let curve = QuadraticBezier::new(
+            Point::new(0.0f32, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, 0.0),
+        );
+        let mut events: Vec<_> = curve.path_iter().collect();
+        let area = signed_area(&events);
+
+        reverse(&mut events);
+        assert_eq!(signed_area(&events), -area);
+        assert_eq!(events[0], PathEvent::Begin { at: curve.to() });
+    }
+}
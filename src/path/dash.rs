@@ -0,0 +1,540 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Breaking paths up into dashes.
+
+use super::{Path, PathEvent};
+use crate::curve::{CubicBezier, Curve, QuadraticBezier};
+use crate::line::LineSegment;
+use crate::point::Point;
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use core::iter::Copied;
+use core::ops::Range;
+use core::slice::Iter as SliceIter;
+use num_traits::real::Real;
+
+/// A pattern of dashes and gaps to break a path up into.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DashPattern<'a, T> {
+    /// The lengths of the dashes and the gaps between them, starting with a dash.
+    ///
+    /// A pattern with an odd number of lengths is repeated twice, so that the dashes
+    /// and gaps swap places the second time around.
+    pub lengths: &'a [T],
+
+    /// How far into the pattern each subpath starts.
+    pub offset: T,
+}
+
+/// A path that has been broken up into dashes.
+///
+/// This is returned by [`dash`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dashes<T: Copy> {
+    /// The events of the dashes.
+    events: Vec<PathEvent<T>>,
+}
+
+impl<T: Copy> Dashes<T> {
+    /// Get the events of the dashes.
+    pub fn events(&self) -> &[PathEvent<T>] {
+        &self.events
+    }
+}
+
+impl<'a, T: Copy> Path<T> for &'a Dashes<T> {
+    type Iter = Copied<SliceIter<'a, PathEvent<T>>>;
+
+    fn path_iter(self) -> Self::Iter {
+        self.events.iter().copied()
+    }
+}
+
+/// Break a path up into dashes.
+///
+/// Every dash is an open subpath, so stroking the result caps both ends of each dash.
+/// Curves are kept as curves, and the ends of the dashes are placed along them by arc
+/// length, to within `accuracy`. The pattern starts over at the beginning of every
+/// subpath. When a closed subpath starts and ends in the middle of a dash, the two
+/// halves are joined into one dash, and a closed subpath that is never interrupted by
+/// a gap stays closed.
+///
+/// If the pattern is empty, has a negative length in it, or only has lengths of zero,
+/// the path is returned without any dashes.
+pub fn dash<T, P>(path: P, pattern: &DashPattern<'_, T>, accuracy: T) -> Dashes<T>
+where
+    T: Real + ApproxEq,
+    P: Path<T>,
+{
+    let total = pattern
+        .lengths
+        .iter()
+        .fold(T::zero(), |total, &length| total + length);
+    let valid = pattern
+        .lengths
+        .iter()
+        .all(|&length| length >= T::zero() && crate::is_finite(length));
+    if !valid || total <= T::zero() {
+        return Dashes {
+            events: path.path_iter().collect(),
+        };
+    }
+
+    let mut dasher = Dasher::new(pattern, total, accuracy);
+    for event in path.path_iter() {
+        match event {
+            PathEvent::Begin { at } => dasher.begin(at),
+            PathEvent::Line { from, to } => dasher.piece(Piece::Line(LineSegment::new(from, to))),
+            PathEvent::Quadratic { from, control, to } => {
+                dasher.piece(Piece::Quadratic(QuadraticBezier::new(from, control, to)))
+            }
+            PathEvent::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => dasher.piece(Piece::Cubic(CubicBezier::new(from, control1, control2, to))),
+            PathEvent::End { first, last, close } => {
+                if close && !first.approx_eq(&last) {
+                    dasher.piece(Piece::Line(LineSegment::new(last, first)));
+                }
+                dasher.end(close);
+            }
+            PathEvent::__NonExhaustive => {}
+        }
+    }
+
+    Dashes {
+        events: dasher.events,
+    }
+}
+
+/// A single segment of a path.
+#[derive(Debug, Copy, Clone)]
+enum Piece<T: Copy> {
+    /// A straight line.
+    Line(LineSegment<T>),
+
+    /// A quadratic curve.
+    Quadratic(QuadraticBezier<T>),
+
+    /// A cubic curve.
+    Cubic(CubicBezier<T>),
+}
+
+impl<T: Real + ApproxEq> Piece<T> {
+    /// Get the point at `t` along the segment.
+    fn eval(&self, t: T) -> Point<T> {
+        match self {
+            Piece::Line(line) => line.eval(t),
+            Piece::Quadratic(quad) => quad.eval(t),
+            Piece::Cubic(cubic) => cubic.eval(t),
+        }
+    }
+
+    /// Get the length of the segment up to `t`.
+    fn length_to(&self, t: T, accuracy: T) -> T {
+        match *self {
+            Piece::Line(line) => line.length() * t,
+            Piece::Quadratic(quad) => quad.subsection(T::zero()..t).length(accuracy),
+            Piece::Cubic(cubic) => cubic.subsection(T::zero()..t).length(accuracy),
+        }
+    }
+
+    /// Find where along the segment the given length is reached.
+    fn t_at_length(&self, length: T, total: T, accuracy: T) -> T {
+        /// The most times to halve the range that `t` could be in.
+        const MAX_STEPS: usize = 64;
+
+        if let Piece::Line(_) = self {
+            return length / total;
+        }
+
+        let (mut low, mut high) = (T::zero(), T::one());
+        let mut t = length / total;
+        for _ in 0..MAX_STEPS {
+            let error = self.length_to(t, accuracy) - length;
+            if error.abs() <= accuracy {
+                break;
+            }
+
+            if error > T::zero() {
+                high = t;
+            } else {
+                low = t;
+            }
+            t = (low + high) / (T::one() + T::one());
+        }
+
+        t
+    }
+
+    /// Get the event for the part of the segment in `range`.
+    fn event(&self, range: Range<T>) -> PathEvent<T> {
+        match *self {
+            Piece::Line(line) => {
+                let (from, to) = line.subsection(range).points();
+                PathEvent::Line { from, to }
+            }
+            Piece::Quadratic(quad) => {
+                let [from, control, to] = quad.subsection(range).points();
+                PathEvent::Quadratic { from, control, to }
+            }
+            Piece::Cubic(cubic) => {
+                let [from, control1, control2, to] = cubic.subsection(range).points();
+                PathEvent::Cubic {
+                    from,
+                    control1,
+                    control2,
+                    to,
+                }
+            }
+        }
+    }
+}
+
+/// Walks along a path, keeping track of where it is in the dash pattern.
+struct Dasher<'a, T: Copy> {
+    /// The pattern to follow.
+    lengths: &'a [T],
+
+    /// The pattern's offset, wrapped into the length of one repetition.
+    offset: T,
+
+    /// Whether the pattern has an odd number of lengths.
+    odd: bool,
+
+    /// The accuracy to measure curves with.
+    accuracy: T,
+
+    /// The index of the current length in the pattern, counting both repetitions of an
+    /// odd pattern.
+    index: usize,
+
+    /// How much of the current length is left.
+    remaining: T,
+
+    /// The point where the current dash began, if the walk is inside of a dash.
+    dash: Option<Point<T>>,
+
+    /// The last point reached along the path.
+    current: Point<T>,
+
+    /// The index of the `Begin` event of the dash at the start of the subpath, while
+    /// that dash is still going.
+    first_start: Option<usize>,
+
+    /// The events of the dash at the start of the subpath, once it has ended.
+    first_dash: Option<Range<usize>>,
+
+    /// The events of the finished dashes.
+    events: Vec<PathEvent<T>>,
+}
+
+impl<'a, T: Real + ApproxEq> Dasher<'a, T> {
+    /// Create a new walk over a pattern with the given total length.
+    fn new(pattern: &DashPattern<'a, T>, total: T, accuracy: T) -> Self {
+        let odd = pattern.lengths.len() % 2 == 1;
+        let period = if odd { total + total } else { total };
+        let mut offset = pattern.offset % period;
+        if offset < T::zero() {
+            offset = offset + period;
+        }
+
+        Dasher {
+            lengths: pattern.lengths,
+            offset,
+            odd,
+            accuracy,
+            index: 0,
+            remaining: T::zero(),
+            dash: None,
+            current: Point::new(T::zero(), T::zero()),
+            first_start: None,
+            first_dash: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Get the number of lengths in one repetition of the pattern.
+    fn count(&self) -> usize {
+        if self.odd {
+            self.lengths.len() * 2
+        } else {
+            self.lengths.len()
+        }
+    }
+
+    /// Tell whether the current length is a dash.
+    fn on(&self) -> bool {
+        self.index & 1 == 0
+    }
+
+    /// Move on to the next length in the pattern.
+    fn advance(&mut self) {
+        self.index = (self.index + 1) % self.count();
+        self.remaining = self.lengths[self.index % self.lengths.len()];
+    }
+
+    /// Start a new subpath, restarting the pattern.
+    fn begin(&mut self, at: Point<T>) {
+        self.index = 0;
+        self.remaining = self.lengths[0];
+        self.current = at;
+        self.first_start = None;
+        self.first_dash = None;
+
+        // skip ahead by the offset, stopping inside of the length that it ends in
+        let mut offset = self.offset;
+        while offset >= self.remaining {
+            offset = offset - self.remaining;
+            self.advance();
+        }
+        self.remaining = self.remaining - offset;
+
+        if self.on() {
+            self.first_start = Some(self.events.len());
+            self.start_dash(at);
+        }
+    }
+
+    /// Begin a dash at the given point.
+    fn start_dash(&mut self, at: Point<T>) {
+        self.events.push(PathEvent::Begin { at });
+        self.dash = Some(at);
+    }
+
+    /// Finish the current dash, if there is one.
+    fn end_dash(&mut self) {
+        if let Some(first) = self.dash.take() {
+            self.events.push(PathEvent::End {
+                first,
+                last: self.current,
+                close: false,
+            });
+
+            if let Some(start) = self.first_start.take() {
+                self.first_dash = Some(start..self.events.len());
+            }
+        }
+    }
+
+    /// Walk along a segment of the path.
+    fn piece(&mut self, piece: Piece<T>) {
+        let length = piece.length_to(T::one(), self.accuracy);
+        let mut walked = T::zero();
+        let mut t = T::zero();
+
+        // stop at every point along the segment where a dash or gap ends
+        while walked + self.remaining < length {
+            walked = walked + self.remaining;
+            let next = piece.t_at_length(walked, length, self.accuracy);
+            if self.dash.is_some() && next > t {
+                self.events.push(piece.event(t..next));
+            }
+            t = next;
+            self.current = piece.eval(t);
+
+            if self.on() {
+                self.end_dash();
+            }
+            self.advance();
+            if self.on() {
+                self.start_dash(self.current);
+            }
+        }
+
+        self.remaining = self.remaining - (length - walked);
+        if self.dash.is_some() && t < T::one() {
+            self.events.push(piece.event(t..T::one()));
+        }
+        self.current = piece.eval(T::one());
+    }
+
+    /// Finish a subpath.
+    fn end(&mut self, close: bool) {
+        if !close || self.dash.is_none() {
+            self.end_dash();
+            return;
+        }
+
+        if self.first_start.take().is_some() {
+            // the whole subpath is one dash, so it stays closed
+            if let Some(first) = self.dash.take() {
+                self.events.push(PathEvent::End {
+                    first,
+                    last: self.current,
+                    close: true,
+                });
+            }
+            return;
+        }
+
+        if let Some(first) = self.first_dash.take() {
+            // join the first dash onto the end of the last one, leaving out its Begin and End
+            let joined: Vec<_> = self.events.drain(first).collect();
+            self.events
+                .extend(joined[1..joined.len() - 1].iter().copied());
+            if let Some(&PathEvent::End { last, .. }) = joined.last() {
+                self.current = last;
+            }
+        }
+
+        self.end_dash();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polyline::Polyline;
+    use crate::Rect;
+    use crate::Size;
+
+    /// Get the number of dashes in a dashed path, and the length of each of them.
+    fn dash_lengths(dashes: &Dashes<f64>) -> Vec<f64> {
+        let mut lengths = Vec::new();
+        for event in dashes.path_iter() {
+            match event {
+                PathEvent::Begin { .. } => lengths.push(0.0),
+                PathEvent::Line { from, to } => *lengths.last_mut().unwrap() += from.distance(to),
+                PathEvent::Quadratic { from, control, to } => {
+                    *lengths.last_mut().unwrap() +=
+                        QuadraticBezier::new(from, control, to).length(1e-6)
+                }
+                _ => {}
+            }
+        }
+        lengths
+    }
+
+    #[test]
+    fn test_dash_line() {
+        let line = LineSegment::new(Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+        let pattern = DashPattern {
+            lengths: &[3.0, 1.0],
+            offset: 0.0,
+        };
+
+        let dashes = dash(line, &pattern, 0.001);
+        assert_eq!(dash_lengths(&dashes), [3.0, 3.0, 2.0]);
+        assert!(matches!(
+            dashes.events().last(),
+            Some(PathEvent::End { close: false, .. })
+        ));
+
+        // An offset moves the pattern back, and an odd pattern swaps dashes and gaps.
+        let pattern = DashPattern {
+            lengths: &[2.0],
+            offset: 1.0,
+        };
+        assert_eq!(dash_lengths(&dash(line, &pattern, 0.001)), [1.0, 2.0, 2.0]);
+
+        // Patterns that can't be followed leave the path alone.
+        let pattern = DashPattern {
+            lengths: &[0.0, 0.0],
+            offset: 0.0,
+        };
+        let dashes = dash(line, &pattern, 0.001);
+        assert_eq!(
+            dashes.events(),
+            line.path_iter().collect::<Vec<_>>().as_slice()
+        );
+    }
+
+    #[test]
+    fn test_dash_curve() {
+        // The dashes along a curve are measured by arc length.
+        let curve = QuadraticBezier::new(
+            Point::new(0.0, 0.0),
+            Point::new(5.0, 10.0),
+            Point::new(10.0, 0.0),
+        );
+        let pattern = DashPattern {
+            lengths: &[2.0, 1.0],
+            offset: 0.0,
+        };
+
+        let lengths = dash_lengths(&dash(curve, &pattern, 1e-6));
+        let total = curve.length(1e-6);
+        assert_eq!(lengths.len(), (total / 3.0).ceil() as usize);
+        for length in &lengths[..lengths.len() - 1] {
+            assert!((length - 2.0).abs() < 1e-4, "{:?}", lengths);
+        }
+    }
+
+    #[test]
+    fn test_dash_closed() {
+        let square = Rect::new(Point::new(0.0, 0.0), Size::new(4.0, 4.0));
+
+        // A dash over the starting corner is joined into one piece.
+        let pattern = DashPattern {
+            lengths: &[2.0, 2.0],
+            offset: 1.0,
+        };
+        let dashes = dash(square, &pattern, 0.001);
+        assert_eq!(dash_lengths(&dashes), [2.0, 2.0, 2.0, 2.0]);
+
+        let pattern = DashPattern {
+            lengths: &[1.0, 2.0],
+            offset: 0.0,
+        };
+        let dashes = dash(square, &pattern, 0.001);
+        let lengths = dash_lengths(&dashes);
+        assert_eq!(lengths.len(), 5);
+        assert_eq!(lengths[4], 2.0);
+
+        // Without any gaps, the square stays closed.
+        let pattern = DashPattern {
+            lengths: &[20.0, 1.0],
+            offset: 0.0,
+        };
+        let dashes = dash(square, &pattern, 0.001);
+        assert!(matches!(
+            dashes.events().last(),
+            Some(PathEvent::End { close: true, .. })
+        ));
+        assert_eq!(dash_lengths(&dashes), [16.0]);
+    }
+
+    #[test]
+    fn test_dash_polyline() {
+        // A dash continues around corners.
+        let path = Polyline::new(alloc::vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 2.0),
+        ]);
+        let pattern = DashPattern {
+            lengths: &[3.0, 0.5],
+            offset: 0.0,
+        };
+
+        let dashes = dash(&path, &pattern, 0.001);
+        assert_eq!(dash_lengths(&dashes), [3.0, 0.5]);
+        assert_eq!(
+            dashes
+                .events()
+                .iter()
+                .filter(|e| matches!(e, PathEvent::Line { .. }))
+                .count(),
+            3
+        );
+    }
+}
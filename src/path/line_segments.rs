@@ -25,7 +25,43 @@ use num_traits::real::Real;
 
 /// The iterator returned by `Path::segments`.
 #[derive(Debug, Clone)]
-pub struct LineSegments<T: Copy, P>(pub(crate) Flattened<T, P>);
+pub struct LineSegments<T: Copy, P> {
+    /// The flattened path.
+    inner: Flattened<T, P>,
+
+    /// Whether or not to close subpaths that were left open.
+    close_open: bool,
+
+    /// Whether or not to skip segments with a length of zero.
+    skip_degenerate: bool,
+}
+
+impl<T: Copy, P> LineSegments<T, P> {
+    /// Create a new `LineSegments` over a flattened path.
+    pub(crate) fn new(inner: Flattened<T, P>) -> Self {
+        LineSegments {
+            inner,
+            close_open: false,
+            skip_degenerate: false,
+        }
+    }
+
+    /// Emit the implicit closing segment for subpaths that were not explicitly closed.
+    ///
+    /// Closed subpaths always emit their closing segment.
+    #[inline]
+    pub fn close_open_subpaths(mut self, close: bool) -> Self {
+        self.close_open = close;
+        self
+    }
+
+    /// Skip segments that start and end at the same point.
+    #[inline]
+    pub fn skip_degenerate(mut self, skip: bool) -> Self {
+        self.skip_degenerate = skip;
+        self
+    }
+}
 
 impl<T: Real + ApproxEq, P: Iterator<Item = PathEvent<T>>> Iterator for LineSegments<T, P> {
     type Item = LineSegment<T>;
@@ -33,22 +69,52 @@ impl<T: Real + ApproxEq, P: Iterator<Item = PathEvent<T>>> Iterator for LineSegm
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            match self.0.next() {
+            let (from, to) = match self.inner.next() {
                 None => return None,
-                Some(StraightPathEvent::Begin { .. })
-                | Some(StraightPathEvent::End { close: false, .. }) => continue,
+                Some(StraightPathEvent::Begin { .. }) => continue,
+                Some(StraightPathEvent::End { close: false, .. }) if !self.close_open => continue,
                 Some(StraightPathEvent::Line { from, to })
                 | Some(StraightPathEvent::End {
                     first: to,
                     last: from,
-                    close: true,
-                }) => return Some(LineSegment::new(from, to)),
+                    ..
+                }) => (from, to),
                 _ => unreachable!(),
+            };
+
+            if self.skip_degenerate && from.approx_eq(&to) {
+                continue;
             }
+
+            return Some(LineSegment::new(from, to));
         }
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        self.0.size_hint()
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path::Path;
+    use crate::{LineSegment, Point, Triangle};
+
+    #[test]
+    fn test_options() {
+        let segment = LineSegment::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0));
+        assert_eq!(segment.segments(0.1).count(), 1);
+        assert_eq!(segment.segments(0.1).close_open_subpaths(true).count(), 2);
+
+        let degenerate = LineSegment::new(Point::new(1.0f32, 1.0), Point::new(1.0, 1.0));
+        assert_eq!(degenerate.segments(0.1).count(), 1);
+        assert_eq!(degenerate.segments(0.1).skip_degenerate(true).count(), 0);
+
+        let triangle = Triangle::new(
+            Point::new(0.0f32, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 1.0),
+        );
+        assert_eq!(triangle.segments(0.1).count(), 3);
     }
 }
@@ -0,0 +1,347 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Placing markers, such as arrowheads, along a path.
+
+#[cfg(feature = "alloc")]
+use super::Path;
+use super::PathEvent;
+use crate::point::Point;
+#[cfg(feature = "alloc")]
+use crate::point::Vector;
+use crate::transform::{Affine, Transform};
+use crate::{Angle, Triangle};
+
+use num_traits::real::Real;
+
+/// The vertices of a path that markers should be placed at.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarkerSpec {
+    /// Place a marker at the first vertex of the path.
+    pub start: bool,
+
+    /// Place a marker at every vertex other than the first and last.
+    pub mid: bool,
+
+    /// Place a marker at the last vertex of the path.
+    pub end: bool,
+}
+
+impl MarkerSpec {
+    /// Place markers at every vertex of the path.
+    #[inline]
+    pub fn all() -> Self {
+        MarkerSpec {
+            start: true,
+            mid: true,
+            end: true,
+        }
+    }
+
+    /// Place markers at the first and last vertices of the path.
+    #[inline]
+    pub fn ends() -> Self {
+        MarkerSpec {
+            start: true,
+            mid: false,
+            end: true,
+        }
+    }
+}
+
+/// Where along the path a marker is placed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarkerKind {
+    /// The first vertex of the path.
+    Start,
+
+    /// A vertex in the middle of the path.
+    Mid,
+
+    /// The last vertex of the path.
+    End,
+}
+
+/// A marker placed at a vertex of a path.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Marker<T: Copy> {
+    /// Where along the path this marker is placed.
+    kind: MarkerKind,
+
+    /// The position of the marker.
+    position: Point<T>,
+
+    /// The direction the marker faces.
+    angle: Angle<T>,
+}
+
+impl<T: Copy> Marker<T> {
+    /// Create a new `Marker` from its kind, position and direction.
+    pub fn new(kind: MarkerKind, position: Point<T>, angle: Angle<T>) -> Self {
+        Marker {
+            kind,
+            position,
+            angle,
+        }
+    }
+
+    /// Get where along the path this marker is placed.
+    pub fn kind(&self) -> MarkerKind {
+        self.kind
+    }
+
+    /// Get the position of this marker.
+    pub fn position(&self) -> Point<T> {
+        self.position
+    }
+
+    /// Get the direction this marker faces.
+    ///
+    /// At the start and end of the path, this is the direction of the path. Elsewhere,
+    /// it is halfway between the incoming and outgoing directions.
+    pub fn angle(&self) -> Angle<T> {
+        self.angle
+    }
+
+    /// Get the transform that moves marker geometry into place.
+    ///
+    /// Marker geometry should be centered on the origin and face along the positive X
+    /// axis.
+    pub fn transform(&self) -> Affine<T>
+    where
+        T: Real,
+    {
+        Affine::translate(self.position.x(), self.position.y()) * Affine::rotate(self.angle)
+    }
+
+    /// Get a triangular arrowhead with its tip at this marker.
+    pub fn arrowhead(&self, length: T, width: T) -> Triangle<T>
+    where
+        T: Real,
+    {
+        let half = width / (T::one() + T::one());
+        let arrow = Triangle::new(
+            Point::new(T::zero(), T::zero()),
+            Point::new(-length, half),
+            Point::new(-length, -half),
+        );
+
+        self.transform().transform_triangle(arrow)
+    }
+
+    /// Move the events of some marker geometry into place.
+    pub fn place<G>(&self, geometry: G) -> impl Iterator<Item = PathEvent<T>>
+    where
+        T: Real,
+        G: IntoIterator<Item = PathEvent<T>>,
+    {
+        let transform = self.transform();
+        geometry
+            .into_iter()
+            .map(move |event| event.map(|point| transform.transform_point(point)))
+    }
+}
+
+/// A vertex of a path, along with the directions of the segments around it.
+#[cfg(feature = "alloc")]
+#[derive(Copy, Clone)]
+struct Vertex<T: Copy> {
+    /// The position of the vertex.
+    position: Point<T>,
+
+    /// The direction of the segment leading into the vertex.
+    incoming: Option<Vector<T>>,
+
+    /// The direction of the segment leading out of the vertex.
+    outgoing: Option<Vector<T>>,
+}
+
+/// Find the markers to place along a path.
+#[cfg(feature = "alloc")]
+pub fn markers<T: Real, P: Path<T>>(path: P, spec: MarkerSpec) -> alloc::vec::Vec<Marker<T>> {
+    let mut vertices = alloc::vec::Vec::<Vertex<T>>::new();
+    let mut subpath_start = 0;
+
+    // Use the first of the given directions that isn't zero.
+    let direction = |candidates: &[Vector<T>]| {
+        candidates
+            .iter()
+            .copied()
+            .find(|v| !v.length_squared().is_zero())
+    };
+
+    let connect = |vertices: &mut alloc::vec::Vec<Vertex<T>>,
+                   to: Point<T>,
+                   outgoing: Option<Vector<T>>,
+                   incoming: Option<Vector<T>>| {
+        if let Some(last) = vertices.last_mut() {
+            last.outgoing = outgoing;
+        }
+        vertices.push(Vertex {
+            position: to,
+            incoming,
+            outgoing: None,
+        });
+    };
+
+    for event in path.path_iter() {
+        match event {
+            PathEvent::Begin { at } => {
+                subpath_start = vertices.len();
+                vertices.push(Vertex {
+                    position: at,
+                    incoming: None,
+                    outgoing: None,
+                });
+            }
+            PathEvent::Line { from, to } => {
+                let d = direction(&[to - from]);
+                connect(&mut vertices, to, d, d);
+            }
+            PathEvent::Quadratic { from, control, to } => connect(
+                &mut vertices,
+                to,
+                direction(&[control - from, to - from]),
+                direction(&[to - control, to - from]),
+            ),
+            PathEvent::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => connect(
+                &mut vertices,
+                to,
+                direction(&[control1 - from, control2 - from, to - from]),
+                direction(&[to - control2, to - control1, to - from]),
+            ),
+            PathEvent::End {
+                first,
+                last,
+                close: true,
+            } => {
+                // The closing segment leads back into the start of the subpath.
+                let closing = direction(&[first - last]);
+                if closing.is_some() {
+                    connect(&mut vertices, first, closing, closing);
+                }
+
+                let first_outgoing = vertices[subpath_start].outgoing;
+                let incoming = vertices.last().and_then(|v| v.incoming);
+                if let Some(last) = vertices.last_mut() {
+                    last.outgoing = first_outgoing;
+                }
+                vertices[subpath_start].incoming = incoming;
+            }
+            _ => {}
+        }
+    }
+
+    let count = vertices.len();
+    let mut markers = alloc::vec::Vec::new();
+
+    for (i, vertex) in vertices.into_iter().enumerate() {
+        let angle = match (vertex.incoming, vertex.outgoing) {
+            (Some(incoming), Some(outgoing)) => {
+                let bisector = incoming.normalize() + outgoing.normalize();
+                if bisector.length_squared().is_zero() {
+                    incoming.angle()
+                } else {
+                    bisector.angle()
+                }
+            }
+            (Some(direction), None) | (None, Some(direction)) => direction.angle(),
+            (None, None) => Angle::from_radians(T::zero()),
+        };
+
+        let mut push = |kind| markers.push(Marker::new(kind, vertex.position, angle));
+        if i == 0 && spec.start {
+            push(MarkerKind::Start);
+        }
+        if i > 0 && i + 1 < count && spec.mid {
+            push(MarkerKind::Mid);
+        }
+        if i + 1 == count && spec.end {
+            push(MarkerKind::End);
+        }
+    }
+
+    markers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{Path, PathBuffer, Verb};
+    use alloc::vec::Vec;
+
+    fn angle_close(angle: Angle<f32>, degrees: f32) -> bool {
+        (angle.degrees() - degrees).abs() < 1e-3
+    }
+
+    #[test]
+    fn test_polyline() {
+        let path = PathBuffer::new(
+            Point::new(0.0f32, 0.0),
+            [
+                (Point::new(10.0, 0.0), Verb::Line),
+                (Point::new(10.0, 10.0), Verb::Line),
+            ],
+        );
+
+        let markers = markers(&path, MarkerSpec::all());
+        assert_eq!(markers.len(), 3);
+        assert_eq!(markers[0].kind(), MarkerKind::Start);
+        assert!(angle_close(markers[0].angle(), 0.0));
+        assert_eq!(markers[1].position(), Point::new(10.0, 0.0));
+        assert!(angle_close(markers[1].angle(), 45.0));
+        assert_eq!(markers[2].kind(), MarkerKind::End);
+        assert!(angle_close(markers[2].angle(), 90.0));
+    }
+
+    #[test]
+    fn test_curve_tangents() {
+        let curve = crate::QuadraticBezier::new(
+            Point::new(0.0f32, 0.0),
+            Point::new(0.0, 5.0),
+            Point::new(5.0, 5.0),
+        );
+
+        let markers = markers(curve, MarkerSpec::ends());
+        assert_eq!(markers.len(), 2);
+        assert!(angle_close(markers[0].angle(), 90.0));
+        assert!(angle_close(markers[1].angle(), 0.0));
+    }
+
+    #[test]
+    fn test_arrowhead() {
+        let marker = Marker::new(
+            MarkerKind::End,
+            Point::new(5.0f32, 5.0),
+            Angle::from_degrees(90.0),
+        );
+
+        let arrow = marker.arrowhead(2.0, 2.0);
+        assert_eq!(arrow.a(), Point::new(5.0, 5.0));
+        assert!(arrow.b().distance(Point::new(4.0, 3.0)) < 1e-5);
+
+        let placed = marker.place(arrow.path_iter()).collect::<Vec<_>>();
+        assert_eq!(placed.len(), 4);
+    }
+}
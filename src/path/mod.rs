@@ -16,23 +16,79 @@
 // along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
 
 use crate::curve::Curve;
-use crate::{ApproxEq, Point};
+use crate::{ApproxEq, Direction, Point, Size};
 
 use core::slice::Iter as SliceIter;
 use num_traits::real::Real;
 
+mod bounds;
+pub use bounds::transformed_bounds;
+
 mod buffer;
+#[cfg(feature = "alloc")]
+pub use buffer::OwnedPathBuffer;
 pub use buffer::{PathBuffer, Verb};
 
+#[cfg(feature = "alloc")]
+mod compound;
+#[cfg(feature = "alloc")]
+pub use compound::Compound;
+
+#[cfg(feature = "alloc")]
+mod dash;
+#[cfg(feature = "alloc")]
+pub use dash::{dash, DashPattern, Dashes};
+
 mod flatten;
 pub use flatten::Flattened;
 
 mod line_segments;
 pub use line_segments::LineSegments;
 
+mod markers;
+#[cfg(feature = "alloc")]
+pub use markers::markers;
+pub use markers::{Marker, MarkerKind, MarkerSpec};
+
+#[cfg(feature = "alloc")]
+mod outline;
+#[cfg(feature = "alloc")]
+pub use outline::from_trapezoids;
+
+mod pixel_snap;
+pub use pixel_snap::SnapMode;
+#[cfg(feature = "alloc")]
+pub use pixel_snap::{pixel_snap, PixelSnapped};
+
 mod shape;
 pub use shape::Shape;
 
+mod sink;
+#[cfg(all(feature = "ttf-parser", feature = "alloc"))]
+pub use sink::glyph_outline;
+#[cfg(feature = "ttf-parser")]
+pub use sink::OutlineSink;
+pub use sink::PathSink;
+
+mod snapped;
+pub use snapped::Snapped;
+
+mod stats;
+pub use stats::PathStats;
+
+#[cfg(feature = "alloc")]
+mod stroke;
+#[cfg(feature = "alloc")]
+pub use stroke::{
+    stroke, stroke_dashed, stroke_with_transform, stroke_with_width, LineCap, LineJoin, Stroke,
+    StrokeOptions,
+};
+
+#[cfg(feature = "alloc")]
+mod svg;
+#[cfg(feature = "alloc")]
+pub use svg::{SvgPath, SvgPathError};
+
 /// An object that can be represented by a series of `PathEvent`s.
 pub trait Path<T: Copy> {
     /// The type of the iterator returned by `path_iter`.
@@ -76,7 +132,34 @@ pub trait Path<T: Copy> {
         Self: Sized,
         T: Real + ApproxEq,
     {
-        LineSegments(self.flatten(tolerance))
+        LineSegments::new(self.flatten(tolerance))
+    }
+
+    /// Snap every point in the path to the nearest corner of a grid.
+    fn snapped(self, cell: Size<T>) -> Snapped<T, Self::Iter>
+    where
+        Self: Sized,
+        T: Real,
+    {
+        Snapped::new(self.path_iter(), cell)
+    }
+
+    /// Draw this path into a sink, one command at a time.
+    fn walk(self, sink: &mut impl PathSink<T>)
+    where
+        Self: Sized,
+    {
+        for event in self.path_iter() {
+            sink::send(sink, event);
+        }
+    }
+
+    /// Count the subpaths and segments in this path in a single pass.
+    fn stats(self) -> PathStats
+    where
+        Self: Sized,
+    {
+        PathStats::collect(self.path_iter())
     }
 
     /// Get the total length of this path.
@@ -170,6 +253,55 @@ pub enum PathEvent<T: Copy> {
     __NonExhaustive,
 }
 
+/// Get the events of a closed path through three points, in the given direction.
+///
+/// Going backwards still starts at the first point.
+pub(crate) fn closed_triangle<T: Copy>(
+    points: [Point<T>; 3],
+    direction: Direction,
+) -> crate::iter::Four<PathEvent<T>> {
+    let [a, b, c] = match direction {
+        Direction::Forwards => points,
+        Direction::Backwards => [points[0], points[2], points[1]],
+    };
+
+    crate::iter::Four::from([
+        PathEvent::Begin { at: a },
+        PathEvent::Line { from: a, to: b },
+        PathEvent::Line { from: b, to: c },
+        PathEvent::End {
+            last: c,
+            first: a,
+            close: true,
+        },
+    ])
+}
+
+/// Get the events of a closed path through four points, in the given direction.
+///
+/// Going backwards still starts at the first point.
+pub(crate) fn closed_quad<T: Copy>(
+    points: [Point<T>; 4],
+    direction: Direction,
+) -> crate::iter::Five<PathEvent<T>> {
+    let [a, b, c, d] = match direction {
+        Direction::Forwards => points,
+        Direction::Backwards => [points[0], points[3], points[2], points[1]],
+    };
+
+    crate::iter::Five::from([
+        PathEvent::Begin { at: a },
+        PathEvent::Line { from: a, to: b },
+        PathEvent::Line { from: b, to: c },
+        PathEvent::Line { from: c, to: d },
+        PathEvent::End {
+            last: d,
+            first: a,
+            close: true,
+        },
+    ])
+}
+
 /// Events that can occur when a path consists only of straight lines.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum StraightPathEvent<T: Copy> {
@@ -204,6 +336,41 @@ pub enum StraightPathEvent<T: Copy> {
     __NonExhaustive,
 }
 
+impl<T: Copy> PathEvent<T> {
+    /// Apply a function to every point in this event.
+    pub fn map<U: Copy>(self, mut f: impl FnMut(Point<T>) -> Point<U>) -> PathEvent<U> {
+        match self {
+            PathEvent::Begin { at } => PathEvent::Begin { at: f(at) },
+            PathEvent::Line { from, to } => PathEvent::Line {
+                from: f(from),
+                to: f(to),
+            },
+            PathEvent::Quadratic { from, control, to } => PathEvent::Quadratic {
+                from: f(from),
+                control: f(control),
+                to: f(to),
+            },
+            PathEvent::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => PathEvent::Cubic {
+                from: f(from),
+                control1: f(control1),
+                control2: f(control2),
+                to: f(to),
+            },
+            PathEvent::End { first, last, close } => PathEvent::End {
+                first: f(first),
+                last: f(last),
+                close,
+            },
+            PathEvent::__NonExhaustive => PathEvent::__NonExhaustive,
+        }
+    }
+}
+
 impl<T: Copy> From<StraightPathEvent<T>> for PathEvent<T> {
     fn from(value: StraightPathEvent<T>) -> Self {
         match value {
@@ -0,0 +1,275 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Rebuilding the outline of a shape from its trapezoids.
+
+use super::{OwnedPathBuffer, PathBuffer, Verb};
+use crate::line::Line;
+use crate::point::Point;
+use crate::trapezoid::Trapezoid;
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num_traits::real::Real;
+
+/// An edge of the outline, going from the first point to the second.
+type Edge<T> = (Point<T>, Point<T>);
+
+/// Stitch a set of non-overlapping trapezoids back together into the outline of the area
+/// that they cover.
+///
+/// Sides that are shared between trapezoids cancel out, so only the boundary of the area
+/// is left. Outer contours wind in the same direction as [`Trapezoid`]'s path, and holes
+/// wind the other way, so the outline fills the same area under either fill rule.
+/// Points closer together than `tolerance` are treated as the same point, and points that
+/// lie within `tolerance` of a straight line between their neighbours are removed.
+///
+/// This is useful for getting the outline of a shape after its fill rule has been
+/// applied, by stitching together the output of [`Shape::trapezoids`].
+///
+/// [`Shape::trapezoids`]: super::Shape::trapezoids
+pub fn from_trapezoids<T: Real + ApproxEq>(
+    trapezoids: impl IntoIterator<Item = Trapezoid<T>>,
+    tolerance: T,
+) -> OwnedPathBuffer<T> {
+    let trapezoids: Vec<_> = trapezoids
+        .into_iter()
+        .filter(|trapezoid| trapezoid.top() < trapezoid.bottom())
+        .collect();
+
+    // Every Y coordinate where a side could start or end.
+    let mut ys: Vec<T> = trapezoids
+        .iter()
+        .flat_map(|trapezoid| [trapezoid.top(), trapezoid.bottom()])
+        .collect();
+    ys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    ys.dedup_by(|a, b| (*a - *b).abs() <= tolerance);
+
+    let mut edges = side_edges(&trapezoids, &ys, tolerance);
+    for &y in &ys {
+        horizontal_edges(&trapezoids, y, tolerance, &mut edges);
+    }
+
+    // Follow the edges around each contour.
+    let mut contours = Vec::new();
+    while let Some((first, mut last)) = edges.pop() {
+        let mut contour = alloc::vec![first];
+
+        while last.distance(first) > tolerance {
+            let next = match edges
+                .iter()
+                .position(|(from, _)| from.distance(last) <= tolerance)
+            {
+                Some(next) => next,
+                None => break,
+            };
+
+            contour.push(last);
+            last = edges.swap_remove(next).1;
+        }
+
+        simplify(&mut contour, tolerance);
+        if contour.len() >= 3 {
+            contours.push(contour);
+        }
+    }
+
+    let mut contours = contours.into_iter();
+    let first = match contours.next() {
+        Some(first) => first,
+        None => return PathBuffer::new(Point::zero(), Vec::new()),
+    };
+
+    let mut buffer: Vec<_> = first[1..]
+        .iter()
+        .map(|&point| (point, Verb::Line))
+        .collect();
+    buffer.push((first[0], Verb::Line));
+    for contour in contours {
+        buffer.push((contour[0], Verb::Begin { close: true }));
+        buffer.extend(contour[1..].iter().map(|&point| (point, Verb::Line)));
+        buffer.push((contour[0], Verb::Line));
+    }
+
+    PathBuffer::new(first[0], buffer).with_close(true)
+}
+
+/// Get the left and right sides of the trapezoids, split at every Y coordinate, with the
+/// sides that are shared between two trapezoids removed.
+fn side_edges<T: Real + ApproxEq>(
+    trapezoids: &[Trapezoid<T>],
+    ys: &[T],
+    tolerance: T,
+) -> Vec<Edge<T>> {
+    let mut edges: Vec<Edge<T>> = Vec::new();
+    let x_at = |line: Line<T>, y: T| line.point_at_y(y).map_or(line.origin().x(), |p| p.x());
+
+    for trapezoid in trapezoids {
+        let inside = ys
+            .iter()
+            .copied()
+            .filter(|&y| y - trapezoid.top() > tolerance && trapezoid.bottom() - y > tolerance);
+        let mut cuts: Vec<T> = core::iter::once(trapezoid.top())
+            .chain(inside)
+            .chain(core::iter::once(trapezoid.bottom()))
+            .collect();
+        cuts.dedup();
+
+        for band in cuts.windows(2) {
+            let (top, bottom) = (band[0], band[1]);
+            let left = (
+                Point::new(x_at(trapezoid.left(), bottom), bottom),
+                Point::new(x_at(trapezoid.left(), top), top),
+            );
+            let right = (
+                Point::new(x_at(trapezoid.right(), top), top),
+                Point::new(x_at(trapezoid.right(), bottom), bottom),
+            );
+
+            for edge in [left, right] {
+                // A side going the other way along the same points cancels this one out.
+                let opposite = edges.iter().position(|&(from, to)| {
+                    from.distance(edge.1) <= tolerance && to.distance(edge.0) <= tolerance
+                });
+
+                match opposite {
+                    Some(opposite) => {
+                        edges.swap_remove(opposite);
+                    }
+                    None => edges.push(edge),
+                }
+            }
+        }
+    }
+
+    edges
+}
+
+/// Add the horizontal edges at the given Y coordinate.
+///
+/// These are the parts of the line that are covered on one side but not the other.
+fn horizontal_edges<T: Real + ApproxEq>(
+    trapezoids: &[Trapezoid<T>],
+    y: T,
+    tolerance: T,
+    edges: &mut Vec<Edge<T>>,
+) {
+    let near = |a: T| (a - y).abs() <= tolerance;
+    let above: Vec<_> = trapezoids
+        .iter()
+        .filter(|trapezoid| near(trapezoid.bottom()))
+        .map(|trapezoid| {
+            let bottom = trapezoid.bottom_segment();
+            (bottom.from().x(), bottom.to().x())
+        })
+        .collect();
+    let below: Vec<_> = trapezoids
+        .iter()
+        .filter(|trapezoid| near(trapezoid.top()))
+        .map(|trapezoid| {
+            let top = trapezoid.top_segment();
+            (top.from().x(), top.to().x())
+        })
+        .collect();
+
+    let mut xs: Vec<T> = above
+        .iter()
+        .chain(&below)
+        .flat_map(|&(left, right)| [left, right])
+        .collect();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    xs.dedup_by(|a, b| (*a - *b).abs() <= tolerance);
+
+    let covers = |spans: &[(T, T)], x: T| spans.iter().any(|&(left, right)| left < x && x < right);
+    for pair in xs.windows(2) {
+        let (left, right) = (Point::new(pair[0], y), Point::new(pair[1], y));
+        let middle = (pair[0] + pair[1]) / (T::one() + T::one());
+
+        match (covers(&above, middle), covers(&below, middle)) {
+            // The bottom of the area above goes from right to left.
+            (true, false) => edges.push((right, left)),
+            // The top of the area below goes from left to right.
+            (false, true) => edges.push((left, right)),
+            _ => {}
+        }
+    }
+}
+
+/// Remove points that lie on the straight line between their neighbours.
+fn simplify<T: Real + ApproxEq>(contour: &mut Vec<Point<T>>, tolerance: T) {
+    let mut i = 0;
+    while contour.len() >= 3 && i < contour.len() {
+        let len = contour.len();
+        let prev = contour[(i + len - 1) % len];
+        let next = contour[(i + 1) % len];
+
+        let redundant = prev.distance(next) <= tolerance
+            || Line::between(prev, next).distance(contour[i]) <= tolerance;
+        if redundant {
+            contour.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::{Path, Shape};
+    use crate::polygon::Polygon;
+    use crate::FillRule;
+    use alloc::vec;
+
+    fn square(min: f32, max: f32) -> Vec<Point<f32>> {
+        vec![
+            Point::new(min, min),
+            Point::new(max, min),
+            Point::new(max, max),
+            Point::new(min, max),
+        ]
+    }
+
+    #[test]
+    fn test_from_trapezoids() {
+        // Two overlapping squares, merged into one outline.
+        let shape = Polygon::new(square(0.0, 4.0), vec![square(2.0, 6.0)]);
+        let outline = from_trapezoids((&shape).trapezoids(0.1), 0.01);
+
+        let stats = (&outline).stats();
+        assert_eq!(stats.subpaths, 1);
+        assert_eq!(stats.lines, 8);
+        assert_eq!((&outline).area(0.1), 28.0);
+
+        // A hole is kept as its own contour.
+        let shape = Polygon::new(square(0.0, 10.0), vec![square(2.0, 4.0)]);
+        let trapezoids = (&shape).trapezoids_with_fill_rule(0.1, FillRule::EvenOdd);
+        let outline = from_trapezoids(trapezoids, 0.01);
+
+        assert_eq!((&outline).stats().subpaths, 2);
+        assert_eq!((&outline).area(0.1), 96.0);
+        assert_eq!((&outline).perimeter(0.1), 48.0);
+    }
+
+    #[test]
+    fn test_from_trapezoids_empty() {
+        let outline = from_trapezoids(core::iter::empty::<Trapezoid<f32>>(), 0.01);
+        assert_eq!((&outline).stats().lines, 0);
+    }
+}
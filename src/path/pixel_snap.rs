@@ -0,0 +1,259 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Snap the straight edges of a path to pixel boundaries.
+
+#[cfg(feature = "alloc")]
+use super::{Path, PathEvent};
+#[cfg(feature = "alloc")]
+use crate::point::Point;
+#[cfg(feature = "alloc")]
+use crate::ApproxEq;
+
+use num_traits::real::Real;
+
+#[cfg(feature = "alloc")]
+use alloc::{collections::VecDeque, vec::Vec};
+
+/// The boundaries that `pixel_snap` moves edges onto.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapMode {
+    /// Snap edges to the nearest integer coordinate.
+    ///
+    /// This is what rectangle fills want, so that their edges don't straddle pixels.
+    #[default]
+    Integer,
+
+    /// Snap edges to the center of the pixel they lie in.
+    ///
+    /// This is what one pixel wide hairlines want, so that they cover exactly one row or
+    /// column of pixels.
+    HalfPixel,
+}
+
+impl SnapMode {
+    /// Snap a single coordinate.
+    fn snap<T: Real>(self, value: T) -> T {
+        match self {
+            SnapMode::Integer => value.round(),
+            SnapMode::HalfPixel => {
+                let half = T::one() / (T::one() + T::one());
+                value.floor() + half
+            }
+        }
+    }
+}
+
+/// Snap the horizontal and vertical edges of a path to pixel boundaries.
+///
+/// The end points of horizontal edges have their Y coordinate snapped, and the end points
+/// of vertical edges have their X coordinate snapped. Control points of curves are left
+/// untouched, although the curves still move to stay attached to any snapped edges.
+#[cfg(feature = "alloc")]
+pub fn pixel_snap<T: Real + ApproxEq, P: Path<T>>(
+    path: P,
+    mode: SnapMode,
+) -> PixelSnapped<T, P::Iter> {
+    PixelSnapped {
+        iter: path.path_iter(),
+        mode,
+        pending: VecDeque::new(),
+    }
+}
+
+/// The iterator returned by `pixel_snap`.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct PixelSnapped<T: Copy, P> {
+    /// The path iterator we're snapping.
+    iter: P,
+
+    /// The boundaries to snap to.
+    mode: SnapMode,
+
+    /// The snapped events of the current subpath.
+    pending: VecDeque<PathEvent<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Real + ApproxEq, P: Iterator<Item = PathEvent<T>>> PixelSnapped<T, P> {
+    /// Read the next subpath out of the iterator and snap it.
+    ///
+    /// A closing edge isn't known until the end of the subpath, and it can move the first
+    /// point, so the entire subpath has to be read before anything can be emitted.
+    fn fill(&mut self) {
+        let mut events = Vec::new();
+        for event in self.iter.by_ref() {
+            let end = matches!(event, PathEvent::End { .. });
+            events.push(event);
+
+            if end {
+                break;
+            }
+        }
+
+        // The end points of each event, along with whether to snap their X and Y.
+        let mut points = Vec::with_capacity(events.len());
+        for event in &events {
+            match *event {
+                PathEvent::Begin { at } => points.push((at, false, false)),
+                PathEvent::Line { to, .. } => {
+                    points.push((to, false, false));
+                    let last = points.len() - 1;
+                    if last > 0 {
+                        mark_edge(&mut points, last - 1, last);
+                    }
+                }
+                PathEvent::Quadratic { to, .. } | PathEvent::Cubic { to, .. } => {
+                    points.push((to, false, false))
+                }
+                PathEvent::End { close: true, .. } if points.len() > 1 => {
+                    let last = points.len() - 1;
+                    mark_edge(&mut points, last, 0);
+                }
+                _ => {}
+            }
+        }
+
+        let mode = self.mode;
+        let snapped = |index: usize| {
+            let (point, x, y) = points[index];
+            Point::new(
+                if x { mode.snap(point.x()) } else { point.x() },
+                if y { mode.snap(point.y()) } else { point.y() },
+            )
+        };
+
+        // Rebuild the events out of the snapped points.
+        let mut index = 0usize;
+        for event in events {
+            let from = index.saturating_sub(1);
+            self.pending.push_back(match event {
+                PathEvent::Begin { .. } => PathEvent::Begin { at: snapped(index) },
+                PathEvent::Line { .. } => PathEvent::Line {
+                    from: snapped(from),
+                    to: snapped(index),
+                },
+                PathEvent::Quadratic { control, .. } => PathEvent::Quadratic {
+                    from: snapped(from),
+                    control,
+                    to: snapped(index),
+                },
+                PathEvent::Cubic {
+                    control1, control2, ..
+                } => PathEvent::Cubic {
+                    from: snapped(from),
+                    control1,
+                    control2,
+                    to: snapped(index),
+                },
+                PathEvent::End { close, .. } => PathEvent::End {
+                    first: snapped(0),
+                    last: snapped(points.len() - 1),
+                    close,
+                },
+                event => event,
+            });
+
+            if !matches!(event, PathEvent::End { .. }) {
+                index += 1;
+            }
+        }
+    }
+}
+
+/// A point on a subpath, along with whether its X and Y coordinates should be snapped.
+#[cfg(feature = "alloc")]
+type SnapPoint<T> = (Point<T>, bool, bool);
+
+/// Mark the end points of an edge to be snapped, if the edge is horizontal or vertical.
+#[cfg(feature = "alloc")]
+fn mark_edge<T: Copy + ApproxEq>(points: &mut [SnapPoint<T>], from: usize, to: usize) {
+    let (a, b) = (points[from].0, points[to].0);
+    let vertical = a.x().approx_eq(&b.x());
+    let horizontal = a.y().approx_eq(&b.y());
+
+    for index in [from, to] {
+        points[index].1 |= vertical;
+        points[index].2 |= horizontal;
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Real + ApproxEq, P: Iterator<Item = PathEvent<T>>> Iterator for PixelSnapped<T, P> {
+    type Item = PathEvent<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending.is_empty() {
+            self.fill();
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Real + ApproxEq, P: Iterator<Item = PathEvent<T>>> Path<T> for PixelSnapped<T, P> {
+    type Iter = Self;
+
+    fn path_iter(self) -> Self::Iter {
+        self
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::{Box, QuadraticBezier};
+
+    #[test]
+    fn test_snap_rect() {
+        let rect = Box::new(Point::new(0.3f32, 0.6), Point::new(10.4, 5.2));
+
+        let snapped = pixel_snap(rect, SnapMode::Integer);
+        assert!(snapped.clone().rectilinear());
+        let first = snapped.clone().find_map(|event| match event {
+            PathEvent::Begin { at } => Some(at),
+            _ => None,
+        });
+        assert_eq!(first, Some(Point::new(0.0, 1.0)));
+
+        let points: Vec<_> = pixel_snap(rect, SnapMode::HalfPixel)
+            .filter_map(|event| match event {
+                PathEvent::Line { to, .. } => Some(to),
+                _ => None,
+            })
+            .collect();
+        assert!(points
+            .iter()
+            .all(|p| p.x().fract() == 0.5 && p.y().fract() == 0.5));
+    }
+
+    #[test]
+    fn test_curves_untouched() {
+        let curve = QuadraticBezier::new(
+            Point::new(0.3f32, 0.3),
+            Point::new(5.3, 10.3),
+            Point::new(10.3, 0.3),
+        );
+
+        let events: Vec<_> = pixel_snap(curve, SnapMode::Integer).collect();
+        assert_eq!(events, curve.path_iter().collect::<Vec<_>>());
+    }
+}
@@ -19,7 +19,12 @@
 
 use super::Path;
 use crate::box2d::Box;
+#[cfg(feature = "alloc")]
+use crate::point::Point;
 use crate::{ApproxEq, FillRule};
+
+#[cfg(feature = "alloc")]
+use core::{cmp, ops::Range};
 use num_traits::real::Real;
 
 /// Represents a closed path, or a specific shape.
@@ -28,26 +33,256 @@ use num_traits::real::Real;
 pub trait Shape<T: Copy>: Path<T> {
     /// Tesselate this shape into a form that can be represented by a series of
     /// trapezoids.
+    ///
+    /// This uses the non-zero fill rule.
     #[cfg(feature = "alloc")]
     fn trapezoids(self, tolerance: T) -> crate::bentley_ottman::Trapezoids<T>
     where
         Self: Sized,
         T: Real + ApproxEq,
     {
-        crate::bentley_ottman::trapezoids(self.segments(tolerance), FillRule::Winding)
+        self.trapezoids_with_fill_rule(tolerance, FillRule::Winding)
+    }
+
+    /// Tesselate this shape into trapezoids, using the given fill rule to decide which
+    /// parts of it are inside.
+    #[cfg(feature = "alloc")]
+    fn trapezoids_with_fill_rule(
+        self,
+        tolerance: T,
+        fill_rule: FillRule,
+    ) -> crate::bentley_ottman::Trapezoids<T>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        crate::bentley_ottman::trapezoids(self.segments(tolerance).skip_degenerate(true), fill_rule)
+    }
+
+    /// Tesselate the part of this shape that lies inside of a box into trapezoids.
+    ///
+    /// Segments outside of the box are trimmed or dropped before they reach the
+    /// tessellator, which saves a lot of work when only a small part of a large shape
+    /// is visible.
+    #[cfg(feature = "alloc")]
+    fn trapezoids_clipped(self, tolerance: T, clip: Box<T>) -> crate::bentley_ottman::Trapezoids<T>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        crate::bentley_ottman::trapezoids(
+            self.segments(tolerance)
+                .skip_degenerate(true)
+                .flat_map(move |segment| crate::bentley_ottman::clip_segment(segment, &clip))
+                .filter(|segment| segment.from() != segment.to()),
+            FillRule::Winding,
+        )
+    }
+
+    /// Tesselate this shape into trapezoids, returning an error instead of panicking on
+    /// invalid input.
+    ///
+    /// This checks that the tolerance is a positive number and that every coordinate in
+    /// the shape is finite before tessellating it. `trapezoids` panics when it runs into
+    /// a `NaN` coordinate.
+    #[cfg(feature = "alloc")]
+    fn try_trapezoids(
+        self,
+        tolerance: T,
+    ) -> Result<crate::bentley_ottman::Trapezoids<T>, crate::GeometryError<T>>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        crate::bentley_ottman::try_trapezoids(self.path_iter(), tolerance, FillRule::Winding)
+    }
+
+    /// Tesselate this shape into trapezoids, returning an error if the tessellator does
+    /// more work than `limits` allow.
+    ///
+    /// This makes the same checks as `try_trapezoids`. The whole shape is tessellated up
+    /// front, so that hitting a limit never leaves the caller with only part of the shape.
+    #[cfg(feature = "alloc")]
+    fn try_trapezoids_with_limits(
+        self,
+        tolerance: T,
+        limits: crate::TessellationLimits,
+    ) -> Result<alloc::vec::Vec<crate::Trapezoid<T>>, crate::GeometryError<T>>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        crate::bentley_ottman::try_trapezoids_with_limits(
+            self.path_iter(),
+            tolerance,
+            FillRule::Winding,
+            limits,
+        )
+    }
+
+    /// Tesselate this shape into trapezoids using the given fill rule, using `policy` to
+    /// decide what to do with subpaths that were left open.
+    ///
+    /// Subpaths that end where they started are never considered to be open.
+    #[cfg(feature = "alloc")]
+    fn trapezoids_with_open_policy(
+        self,
+        tolerance: T,
+        fill_rule: FillRule,
+        policy: crate::OpenPathPolicy,
+    ) -> Result<crate::bentley_ottman::Trapezoids<T>, crate::GeometryError<T>>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        crate::bentley_ottman::trapezoids_with_open_policy(
+            self.path_iter(),
+            tolerance,
+            fill_rule,
+            policy,
+        )
+    }
+
+    /// Tesselate this shape into trapezoids without flattening its curves first.
+    ///
+    /// Curves are split into pieces that are monotone in Y and kept as curves, so the
+    /// sides of the trapezoids may be curved. `tolerance` is how close the sweep has to
+    /// get to the points where edges cross.
+    #[cfg(feature = "alloc")]
+    fn curved_trapezoids(self, tolerance: T) -> crate::bentley_ottman::CurvedTrapezoids<T>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        crate::bentley_ottman::curved_trapezoids(
+            crate::bentley_ottman::monotone_edges(self.path_iter()),
+            tolerance,
+            FillRule::Winding,
+        )
     }
 
     /// Get the area of the shape.
+    ///
+    /// Holes are only subtracted if they wind in the opposite direction to the shape
+    /// around them, since this uses the non-zero fill rule.
     #[cfg(feature = "alloc")]
     fn area(self, accuracy: T) -> T
     where
         Self: Sized,
         T: Real + ApproxEq,
     {
-        self.trapezoids(accuracy)
+        self.area_with_fill_rule(accuracy, FillRule::Winding)
+    }
+
+    /// Get the area of the shape, using the given fill rule to decide which parts of it
+    /// are inside.
+    #[cfg(feature = "alloc")]
+    fn area_with_fill_rule(self, accuracy: T, fill_rule: FillRule) -> T
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        self.trapezoids_with_fill_rule(accuracy, fill_rule)
             .fold(T::zero(), |area, trapezoid| area + trapezoid.area(accuracy))
     }
 
+    /// Tell whether a point is inside of the shape.
+    ///
+    /// This checks the point against the same trapezoids that are used to rasterize the
+    /// shape, using the non-zero fill rule.
+    #[cfg(feature = "alloc")]
+    fn contains_point(self, point: Point<T>, tolerance: T) -> bool
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        self.contains_point_with_fill_rule(point, tolerance, FillRule::Winding)
+    }
+
+    /// Tell whether a point is inside of the shape, using the given fill rule.
+    #[cfg(feature = "alloc")]
+    fn contains_point_with_fill_rule(
+        self,
+        point: Point<T>,
+        tolerance: T,
+        fill_rule: FillRule,
+    ) -> bool
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        self.trapezoids_with_fill_rule(tolerance, fill_rule)
+            .any(|trapezoid| trapezoid.contains(point))
+    }
+
+    /// Get the ranges of X coordinates that are inside of the shape along a horizontal line.
+    ///
+    /// The ranges are sorted from left to right and don't touch one another. The shape is
+    /// flattened with the given tolerance, and a row exactly on a vertex counts as below it,
+    /// in the same way as [`Shape::contains_point`].
+    #[cfg(feature = "alloc")]
+    fn scanline_spans(
+        self,
+        y: T,
+        tolerance: T,
+        fill_rule: FillRule,
+    ) -> alloc::vec::IntoIter<Range<T>>
+    where
+        Self: Sized,
+        T: Real + ApproxEq,
+    {
+        // Find where each edge crosses the line, and which way it crosses.
+        let mut crossings: alloc::vec::Vec<(T, i32)> = self
+            .segments(tolerance)
+            .close_open_subpaths(true)
+            .filter_map(|segment| {
+                let (from, to) = (segment.from(), segment.to());
+                let (top, bottom, winding) = if from.y() < to.y() {
+                    (from, to, 1)
+                } else {
+                    (to, from, -1)
+                };
+
+                if y < top.y() || y >= bottom.y() {
+                    return None;
+                }
+
+                let t = (y - top.y()) / (bottom.y() - top.y());
+                Some((top.x() + (bottom.x() - top.x()) * t, winding))
+            })
+            .collect();
+        crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(cmp::Ordering::Equal));
+
+        let inside = |winding: i32| match fill_rule {
+            FillRule::Winding => winding != 0,
+            FillRule::EvenOdd => winding & 1 != 0,
+        };
+
+        let mut spans: alloc::vec::Vec<Range<T>> = alloc::vec::Vec::new();
+        let mut winding = 0;
+        let mut start = None;
+        for (x, crossing) in crossings {
+            winding += crossing;
+
+            match (start, inside(winding)) {
+                (None, true) => start = Some(x),
+                (Some(left), false) => {
+                    start = None;
+
+                    // Join spans that touch, such as either side of a vertex.
+                    match spans.last_mut() {
+                        Some(last) if last.end >= left => last.end = x,
+                        _ if left < x => spans.push(left..x),
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        spans.into_iter()
+    }
+
     /// Get the perimeter of the shape.
     ///
     /// By default, this is implemented by just calling the `length` method on the path.
@@ -72,3 +307,70 @@ pub trait Shape<T: Copy>: Path<T> {
             })
     }
 }
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::polygon::Polygon;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn square(min: f32, max: f32) -> Vec<Point<f32>> {
+        vec![
+            Point::new(min, min),
+            Point::new(max, min),
+            Point::new(max, max),
+            Point::new(min, max),
+        ]
+    }
+
+    #[test]
+    fn test_contains_point() {
+        let shape = Polygon::new(square(0.0, 10.0), vec![square(2.0, 4.0)]);
+
+        assert!((&shape).contains_point(Point::new(1.0, 1.0), 0.1));
+        assert!((&shape).contains_point(Point::new(3.0, 3.0), 0.1));
+        assert!(!(&shape).contains_point(Point::new(11.0, 3.0), 0.1));
+        assert!(!(&shape).contains_point_with_fill_rule(
+            Point::new(3.0, 3.0),
+            0.1,
+            FillRule::EvenOdd
+        ));
+    }
+
+    #[test]
+    fn test_scanline_spans() {
+        let shape = Polygon::new(square(0.0, 10.0), vec![square(2.0, 4.0)]);
+
+        let spans: Vec<_> = (&shape)
+            .scanline_spans(3.0, 0.1, FillRule::EvenOdd)
+            .collect();
+        assert_eq!(spans, [0.0..2.0, 4.0..10.0]);
+
+        let spans: Vec<_> = (&shape)
+            .scanline_spans(3.0, 0.1, FillRule::Winding)
+            .collect();
+        assert_eq!(spans, [0.0..10.0]);
+
+        assert_eq!(
+            (&shape)
+                .scanline_spans(11.0, 0.1, FillRule::Winding)
+                .count(),
+            0
+        );
+
+        // Sloped edges are crossed partway along.
+        let triangle = Polygon::new(
+            vec![
+                Point::new(0.0f32, 0.0),
+                Point::new(4.0, 4.0),
+                Point::new(0.0, 8.0),
+            ],
+            Vec::new(),
+        );
+        let spans: Vec<_> = (&triangle)
+            .scanline_spans(2.0, 0.1, FillRule::Winding)
+            .collect();
+        assert_eq!(spans, [0.0..2.0]);
+    }
+}
@@ -0,0 +1,307 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Push-style consumers of paths.
+
+use super::PathEvent;
+use crate::point::Point;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Something that a path can be drawn into, one command at a time.
+///
+/// This is the push-style counterpart to iterating over a path's events, and matches the
+/// callback-based path APIs of most graphics libraries. Paths are drawn into sinks with
+/// [`Path::walk`].
+///
+/// [`Path::walk`]: crate::path::Path::walk
+pub trait PathSink<T: Copy> {
+    /// Start a new subpath at the given point.
+    fn move_to(&mut self, to: Point<T>);
+
+    /// Draw a line from the current point to the given point.
+    fn line_to(&mut self, to: Point<T>);
+
+    /// Draw a quadratic Bezier curve from the current point to the given point.
+    fn quad_to(&mut self, control: Point<T>, to: Point<T>);
+
+    /// Draw a cubic Bezier curve from the current point to the given point.
+    fn cubic_to(&mut self, control1: Point<T>, control2: Point<T>, to: Point<T>);
+
+    /// Close the current subpath with a line back to its starting point.
+    fn close(&mut self);
+
+    /// End the current subpath without closing it.
+    ///
+    /// Most graphics libraries end subpaths implicitly, so this does nothing by default.
+    fn end(&mut self) {}
+}
+
+impl<T: Copy, S: PathSink<T> + ?Sized> PathSink<T> for &mut S {
+    fn move_to(&mut self, to: Point<T>) {
+        (**self).move_to(to)
+    }
+
+    fn line_to(&mut self, to: Point<T>) {
+        (**self).line_to(to)
+    }
+
+    fn quad_to(&mut self, control: Point<T>, to: Point<T>) {
+        (**self).quad_to(control, to)
+    }
+
+    fn cubic_to(&mut self, control1: Point<T>, control2: Point<T>, to: Point<T>) {
+        (**self).cubic_to(control1, control2, to)
+    }
+
+    fn close(&mut self) {
+        (**self).close()
+    }
+
+    fn end(&mut self) {
+        (**self).end()
+    }
+}
+
+/// Adapts a [`PathSink`] into a `ttf_parser::OutlineBuilder`, so that glyph outlines can
+/// be drawn into it.
+#[cfg(feature = "ttf-parser")]
+#[derive(Debug, Clone, Default)]
+pub struct OutlineSink<S>(pub S);
+
+#[cfg(feature = "ttf-parser")]
+impl<S: PathSink<f32>> ttf_parser::OutlineBuilder for OutlineSink<S> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.0.move_to(Point::new(x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.0.line_to(Point::new(x, y));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.0.quad_to(Point::new(x1, y1), Point::new(x, y));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.0
+            .cubic_to(Point::new(x1, y1), Point::new(x2, y2), Point::new(x, y));
+    }
+
+    fn close(&mut self) {
+        self.0.close();
+    }
+}
+
+/// Get the outline of a glyph from a font, in font units.
+///
+/// Returns `None` if the glyph has no outline. The outline can be tessellated directly,
+/// since every subpath in a glyph outline is closed.
+#[cfg(all(feature = "ttf-parser", feature = "alloc"))]
+pub fn glyph_outline(
+    face: &ttf_parser::Face<'_>,
+    glyph: ttf_parser::GlyphId,
+) -> Option<super::OwnedPathBuffer<f32>> {
+    let mut sink = OutlineSink(Vec::new());
+    face.outline_glyph(glyph, &mut sink)?;
+
+    if sink.0.is_empty() {
+        None
+    } else {
+        Some(sink.0.into_iter().collect())
+    }
+}
+
+/// Send a single path event to a sink.
+pub(crate) fn send<T: Copy>(sink: &mut impl PathSink<T>, event: PathEvent<T>) {
+    match event {
+        PathEvent::Begin { at } => sink.move_to(at),
+        PathEvent::Line { to, .. } => sink.line_to(to),
+        PathEvent::Quadratic { control, to, .. } => sink.quad_to(control, to),
+        PathEvent::Cubic {
+            control1,
+            control2,
+            to,
+            ..
+        } => sink.cubic_to(control1, control2, to),
+        PathEvent::End { close: true, .. } => sink.close(),
+        PathEvent::End { close: false, .. } => sink.end(),
+        PathEvent::__NonExhaustive => {}
+    }
+}
+
+/// Records the commands as a list of path events.
+///
+/// Drawing commands that come before any `move_to` start at the origin.
+#[cfg(feature = "alloc")]
+impl<T: Copy + num_traits::Zero> PathSink<T> for Vec<PathEvent<T>> {
+    fn move_to(&mut self, to: Point<T>) {
+        self.end();
+        self.push(PathEvent::Begin { at: to });
+    }
+
+    fn line_to(&mut self, to: Point<T>) {
+        let from = begin_segment(self);
+        self.push(PathEvent::Line { from, to });
+    }
+
+    fn quad_to(&mut self, control: Point<T>, to: Point<T>) {
+        let from = begin_segment(self);
+        self.push(PathEvent::Quadratic { from, control, to });
+    }
+
+    fn cubic_to(&mut self, control1: Point<T>, control2: Point<T>, to: Point<T>) {
+        let from = begin_segment(self);
+        self.push(PathEvent::Cubic {
+            from,
+            control1,
+            control2,
+            to,
+        });
+    }
+
+    fn close(&mut self) {
+        end_subpath(self, true);
+    }
+
+    fn end(&mut self) {
+        end_subpath(self, false);
+    }
+}
+
+/// Get the point that the next segment starts from, starting a subpath if there isn't one.
+#[cfg(feature = "alloc")]
+fn begin_segment<T: Copy + num_traits::Zero>(events: &mut Vec<PathEvent<T>>) -> Point<T> {
+    let current = match events.last() {
+        Some(&PathEvent::Begin { at }) => return at,
+        Some(&PathEvent::Line { to, .. })
+        | Some(&PathEvent::Quadratic { to, .. })
+        | Some(&PathEvent::Cubic { to, .. }) => return to,
+        Some(&PathEvent::End { first, .. }) => first,
+        _ => Point::new(T::zero(), T::zero()),
+    };
+
+    events.push(PathEvent::Begin { at: current });
+    current
+}
+
+/// End the open subpath at the end of the list, if there is one.
+#[cfg(feature = "alloc")]
+fn end_subpath<T: Copy>(events: &mut Vec<PathEvent<T>>, close: bool) {
+    let last = match events.last() {
+        Some(&PathEvent::Begin { at }) => at,
+        Some(&PathEvent::Line { to, .. })
+        | Some(&PathEvent::Quadratic { to, .. })
+        | Some(&PathEvent::Cubic { to, .. }) => to,
+        _ => return,
+    };
+
+    let first = events
+        .iter()
+        .rev()
+        .find_map(|event| match *event {
+            PathEvent::Begin { at } => Some(at),
+            _ => None,
+        })
+        .unwrap_or(last);
+
+    events.push(PathEvent::End { first, last, close });
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use crate::path::Path;
+    use crate::{Box, QuadraticBezier};
+
+    #[test]
+    fn test_record() {
+        let shape = Box::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0));
+        let mut events = Vec::new();
+        shape.walk(&mut events);
+        assert_eq!(events, shape.path_iter().collect::<Vec<_>>());
+
+        let curve = QuadraticBezier::new(
+            Point::new(0.0f32, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 0.0),
+        );
+        let mut events = Vec::new();
+        curve.walk(&mut events);
+        assert_eq!(events, curve.path_iter().collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "ttf-parser")]
+    #[test]
+    fn test_outline_sink() {
+        use crate::path::{OwnedPathBuffer, Shape};
+        use ttf_parser::OutlineBuilder;
+
+        // Draw a square the same way that a glyph would be drawn.
+        let mut sink = OutlineSink(Vec::new());
+        sink.move_to(0.0, 0.0);
+        sink.line_to(10.0, 0.0);
+        sink.line_to(10.0, 10.0);
+        sink.quad_to(5.0, 10.0, 0.0, 10.0);
+        sink.close();
+
+        let path: OwnedPathBuffer<f32> = sink.0.into_iter().collect();
+        assert_eq!((&path).area(0.01), 100.0);
+    }
+
+    #[test]
+    fn test_implicit_begin() {
+        let mut events = Vec::new();
+        events.line_to(Point::new(1.0f32, 0.0));
+        events.line_to(Point::new(1.0, 1.0));
+        events.close();
+        events.line_to(Point::new(0.0, 1.0));
+        events.end();
+
+        let origin = Point::new(0.0, 0.0);
+        assert_eq!(
+            events,
+            [
+                PathEvent::Begin { at: origin },
+                PathEvent::Line {
+                    from: origin,
+                    to: Point::new(1.0, 0.0),
+                },
+                PathEvent::Line {
+                    from: Point::new(1.0, 0.0),
+                    to: Point::new(1.0, 1.0),
+                },
+                PathEvent::End {
+                    first: origin,
+                    last: Point::new(1.0, 1.0),
+                    close: true,
+                },
+                PathEvent::Begin { at: origin },
+                PathEvent::Line {
+                    from: origin,
+                    to: Point::new(0.0, 1.0),
+                },
+                PathEvent::End {
+                    first: origin,
+                    last: Point::new(0.0, 1.0),
+                    close: false,
+                },
+            ]
+        );
+    }
+}
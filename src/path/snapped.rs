@@ -0,0 +1,67 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Snap the points of a path to a grid.
+
+use super::{Path, PathEvent};
+use crate::size::Size;
+
+use core::iter::FusedIterator;
+use num_traits::real::Real;
+
+/// The iterator returned by `Path::snapped`.
+#[derive(Debug, Clone)]
+pub struct Snapped<T: Copy, P> {
+    /// The path iterator we're snapping.
+    iter: P,
+
+    /// The size of a grid cell.
+    cell: Size<T>,
+}
+
+impl<T: Copy, P> Snapped<T, P> {
+    pub(crate) fn new(iter: P, cell: Size<T>) -> Self {
+        Self { iter, cell }
+    }
+}
+
+impl<T: Real, P: Iterator<Item = PathEvent<T>>> Iterator for Snapped<T, P> {
+    type Item = PathEvent<T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let cell = self.cell;
+        self.iter
+            .next()
+            .map(|event| event.map(|point| point.snap_to_grid(cell)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Real, P: FusedIterator<Item = PathEvent<T>>> FusedIterator for Snapped<T, P> {}
+
+impl<T: Real, P: Iterator<Item = PathEvent<T>>> Path<T> for Snapped<T, P> {
+    type Iter = Self;
+
+    fn path_iter(self) -> Self::Iter {
+        self
+    }
+}
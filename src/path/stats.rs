@@ -0,0 +1,123 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Summary statistics for a path.
+
+use super::PathEvent;
+
+/// Counts of the subpaths and segments that make up a path.
+///
+/// This is returned by `Path::stats`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathStats {
+    /// The number of subpaths in the path.
+    pub subpaths: usize,
+
+    /// The number of straight line segments in the path.
+    ///
+    /// This does not include the implicit segments used to close subpaths.
+    pub lines: usize,
+
+    /// The number of quadratic Bezier curves in the path.
+    pub quads: usize,
+
+    /// The number of cubic Bezier curves in the path.
+    pub cubics: usize,
+
+    /// Whether or not every subpath in the path is closed.
+    ///
+    /// This is `true` for a path with no subpaths.
+    pub is_closed_all: bool,
+}
+
+impl Default for PathStats {
+    #[inline]
+    fn default() -> Self {
+        PathStats {
+            subpaths: 0,
+            lines: 0,
+            quads: 0,
+            cubics: 0,
+            is_closed_all: true,
+        }
+    }
+}
+
+impl PathStats {
+    /// Get the total number of line and curve segments in the path.
+    #[inline]
+    pub fn segments(&self) -> usize {
+        self.lines + self.quads + self.cubics
+    }
+
+    /// Compute the statistics for a series of path events.
+    pub(super) fn collect<T: Copy>(events: impl Iterator<Item = PathEvent<T>>) -> Self {
+        let mut stats = PathStats::default();
+
+        // Whether the current subpath is still waiting for its `End` event.
+        let mut open = false;
+
+        for event in events {
+            match event {
+                PathEvent::Begin { .. } => {
+                    stats.subpaths += 1;
+                    stats.is_closed_all &= !open;
+                    open = true;
+                }
+                PathEvent::Line { .. } => stats.lines += 1,
+                PathEvent::Quadratic { .. } => stats.quads += 1,
+                PathEvent::Cubic { .. } => stats.cubics += 1,
+                PathEvent::End { close, .. } => {
+                    stats.is_closed_all &= close;
+                    open = false;
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        // A subpath that never ended was never closed.
+        stats.is_closed_all &= !open;
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::path::Path;
+    use crate::{Box, LineSegment, Point};
+
+    #[test]
+    fn test_box_stats() {
+        let stats = Box::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0)).stats();
+
+        assert_eq!(stats.subpaths, 1);
+        assert_eq!(stats.lines, 3);
+        assert_eq!(stats.segments(), 3);
+        assert!(stats.is_closed_all);
+    }
+
+    #[test]
+    fn test_open_stats() {
+        let stats = LineSegment::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0)).stats();
+
+        assert_eq!(stats.subpaths, 1);
+        assert_eq!(stats.lines, 1);
+        assert_eq!(stats.quads + stats.cubics, 0);
+        assert!(!stats.is_closed_all);
+    }
+}
@@ -0,0 +1,638 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Turning paths into the outlines of their strokes.
+//!
+//! Stroking a path produces a closed shape that covers every point within half of the
+//! stroke's width of the path, with its ends and corners shaped by the caps and joins.
+//! The outline overlaps itself around sharp corners, so it has to be filled with the
+//! non-zero fill rule.
+
+use super::{dash, DashPattern, Flattened, Path, PathEvent, Shape, StraightPathEvent};
+use crate::point::{Point, Vector};
+use crate::transform::{Affine, Transform};
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use core::iter::Copied;
+use core::slice::Iter as SliceIter;
+use num_traits::real::Real;
+use num_traits::FloatConst;
+
+/// The shape drawn at the ends of open subpaths.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LineCap {
+    /// The stroke ends exactly at the end of the path.
+    Butt,
+
+    /// The stroke ends in a half circle centered on the end of the path.
+    Round,
+
+    /// The stroke extends past the end of the path by half of its width.
+    Square,
+}
+
+/// The shape drawn at the corners between segments.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LineJoin {
+    /// The outer edges are extended until they meet, unless that would pass the miter
+    /// limit, in which case the corner is beveled instead.
+    Miter,
+
+    /// The corner is rounded off with a circular arc.
+    Round,
+
+    /// The corner is cut off with a straight line.
+    Bevel,
+}
+
+/// The options used to stroke a path.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StrokeOptions<T> {
+    /// The width of the stroke.
+    pub width: T,
+
+    /// The shape drawn at the ends of open subpaths.
+    pub cap: LineCap,
+
+    /// The shape drawn at the corners between segments.
+    pub join: LineJoin,
+
+    /// The largest ratio between the length of a miter and the stroke's width before the
+    /// corner is beveled instead.
+    pub miter_limit: T,
+
+    /// How far curves and arcs may stray from their exact shape.
+    pub tolerance: T,
+}
+
+impl<T: Real> Default for StrokeOptions<T> {
+    fn default() -> Self {
+        let two = T::one() + T::one();
+        StrokeOptions {
+            width: T::one(),
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: two * two,
+            tolerance: T::one() / (two * two * two),
+        }
+    }
+}
+
+/// The outline of a stroked path.
+///
+/// This is returned by [`stroke`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stroke<T: Copy> {
+    /// The events of the outline.
+    events: Vec<PathEvent<T>>,
+}
+
+impl<T: Copy> Stroke<T> {
+    /// Get the events of the outline.
+    pub fn events(&self) -> &[PathEvent<T>] {
+        &self.events
+    }
+}
+
+impl<'a, T: Copy> Path<T> for &'a Stroke<T> {
+    type Iter = Copied<SliceIter<'a, PathEvent<T>>>;
+
+    fn path_iter(self) -> Self::Iter {
+        self.events.iter().copied()
+    }
+}
+
+impl<T: Copy> Shape<T> for &Stroke<T> {}
+
+/// Get the outline of a path's stroke.
+///
+/// Curves are flattened to within the options' tolerance before the path is stroked.
+/// Closed subpaths are joined at their first point, while open subpaths get caps on
+/// both of their ends. Subpaths made up of a single point get a round or square dot,
+/// depending on the cap.
+pub fn stroke<T, P>(path: P, options: &StrokeOptions<T>) -> Stroke<T>
+where
+    T: Real + FloatConst + ApproxEq,
+    P: Path<T>,
+{
+    stroke_with_width(path, options, |_| options.width)
+}
+
+/// Get the outline of a path's stroke after the path has been transformed.
+///
+/// The path is transformed first and then stroked, so the options' width and tolerance
+/// are in the transformed space. Stroking first and transforming the outline afterwards
+/// would stretch the stroke along with the path, making it thicker in one direction
+/// than the other under a non-uniform scale or a skew.
+pub fn stroke_with_transform<T, P>(
+    path: P,
+    options: &StrokeOptions<T>,
+    transform: &Affine<T>,
+) -> Stroke<T>
+where
+    T: Real + FloatConst + ApproxEq,
+    P: Path<T>,
+{
+    let events = path
+        .path_iter()
+        .map(|event| event.map(|point| transform.transform_point(point)));
+    stroke(Flattened::new(events, options.tolerance), options)
+}
+
+/// Get the outline of a dashed stroke.
+///
+/// The path is broken up with [`dash`](super::dash), measuring curves to within the
+/// options' tolerance, and each dash is stroked as an open subpath, so both of its ends
+/// get the options' cap.
+pub fn stroke_dashed<T, P>(
+    path: P,
+    options: &StrokeOptions<T>,
+    pattern: &DashPattern<'_, T>,
+) -> Stroke<T>
+where
+    T: Real + FloatConst + ApproxEq,
+    P: Path<T>,
+{
+    stroke(&dash(path, pattern, options.tolerance), options)
+}
+
+/// Get the outline of a path's stroke, with a width that changes along the path.
+///
+/// `width` is given how far along a subpath a point is, as a fraction of the subpath's
+/// length from zero at its start to one at its end, and returns the full width of the
+/// stroke there. This is useful for tapered brush and ink strokes. The width in
+/// `options` is not used. Closed subpaths wrap around from one back to zero at their
+/// first point.
+///
+/// The sides of the stroke are straight between the flattened points of the path, and
+/// segments are split further wherever the width strays from a straight taper by more
+/// than the options' tolerance. Otherwise, this works in the same way as [`stroke`].
+pub fn stroke_with_width<T, P>(
+    path: P,
+    options: &StrokeOptions<T>,
+    width: impl Fn(T) -> T,
+) -> Stroke<T>
+where
+    T: Real + FloatConst + ApproxEq,
+    P: Path<T>,
+{
+    let stroker = Stroker { options };
+    let mut events = Vec::new();
+    let mut points: Vec<Point<T>> = Vec::new();
+
+    for event in path.flatten(options.tolerance) {
+        match event {
+            StraightPathEvent::Begin { at } => {
+                points.clear();
+                points.push(at);
+            }
+            StraightPathEvent::Line { to, .. } => match points.last() {
+                Some(last) if last.approx_eq(&to) => {}
+                _ => points.push(to),
+            },
+            StraightPathEvent::End { close, .. } => {
+                if close && points.len() > 2 && points[0].approx_eq(&points[points.len() - 1]) {
+                    points.pop();
+                }
+
+                let closed = close && points.len() > 2;
+                let (points, widths) = sample_widths(&points, closed, options.tolerance, &width);
+                if closed {
+                    stroker.closed(&points, &widths, &mut events);
+                } else {
+                    stroker.open(&points, &widths, &mut events);
+                }
+            }
+            StraightPathEvent::__NonExhaustive => {}
+        }
+    }
+
+    Stroke { events }
+}
+
+/// Find half of the stroke's width at each point of a subpath, adding points where the
+/// width doesn't change linearly between them.
+fn sample_widths<T: Real>(
+    points: &[Point<T>],
+    closed: bool,
+    tolerance: T,
+    width: &impl Fn(T) -> T,
+) -> (Vec<Point<T>>, Vec<T>) {
+    /// How many times a segment may be split in half.
+    const MAX_DEPTH: usize = 8;
+
+    let two = T::one() + T::one();
+    let half_width = |t: T| width(t).max(T::zero()) / two;
+
+    // Closed subpaths come back around to their first point.
+    let ends = points
+        .iter()
+        .copied()
+        .chain(points.first().filter(|_| closed).copied());
+    let mut distances = Vec::with_capacity(points.len() + 1);
+    let mut total = T::zero();
+    let mut last: Option<Point<T>> = None;
+    for point in ends.clone() {
+        if let Some(last) = last {
+            total = total + (point - last).length();
+        }
+        distances.push(total);
+        last = Some(point);
+    }
+    let t_at = |distance: T| {
+        if total > T::zero() {
+            distance / total
+        } else {
+            T::zero()
+        }
+    };
+
+    let mut sampled = Vec::with_capacity(points.len());
+    let mut widths = Vec::with_capacity(points.len());
+    let mut stack = Vec::new();
+    let ends = ends.zip(distances.iter().map(|&distance| t_at(distance)));
+    let mut previous: Option<(Point<T>, T, T)> = None;
+
+    for (point, t) in ends {
+        let current = (point, t, half_width(t));
+        if let Some(previous) = previous {
+            // Split the segment in half until the width between its ends is close to
+            // a straight line.
+            stack.push((current, 0));
+            let mut from = previous;
+            while let Some((to, depth)) = stack.pop() {
+                let mid_t = (from.1 + to.1) / two;
+                let mid = (from.0.midpoint(to.0), mid_t, half_width(mid_t));
+                if depth < MAX_DEPTH && (mid.2 - (from.2 + to.2) / two).abs() > tolerance {
+                    stack.push((to, depth + 1));
+                    stack.push((mid, depth + 1));
+                } else {
+                    sampled.push(to.0);
+                    widths.push(to.2);
+                    from = to;
+                }
+            }
+        } else {
+            sampled.push(point);
+            widths.push(current.2);
+        }
+        previous = Some(current);
+    }
+
+    // The closing point is the same as the first point.
+    if closed && sampled.len() > 1 {
+        sampled.pop();
+        widths.pop();
+    }
+
+    (sampled, widths)
+}
+
+/// Builds the outlines of subpaths.
+struct Stroker<'a, T> {
+    /// The options to stroke with.
+    options: &'a StrokeOptions<T>,
+}
+
+impl<T: Real + FloatConst + ApproxEq> Stroker<'_, T> {
+    /// Stroke an open subpath as a single contour, where `widths` holds half of the
+    /// stroke's width at each point.
+    fn open(&self, points: &[Point<T>], widths: &[T], events: &mut Vec<PathEvent<T>>) {
+        let mut contour = Vec::new();
+
+        if let [point] = *points {
+            // a lone point only shows up as a dot
+            let direction = Vector::new(T::one(), T::zero());
+            let normal = direction.perp() * widths[0];
+            self.cap(point, normal, direction, &mut contour);
+            self.cap(point, -normal, -direction, &mut contour);
+            if self.options.cap == LineCap::Butt {
+                contour.clear();
+            }
+        } else if points.len() > 1 {
+            let reversed = points.iter().rev().copied().collect::<Vec<_>>();
+            let reversed_widths = widths.iter().rev().copied().collect::<Vec<_>>();
+            let (start, end) = (points[0], points[points.len() - 1]);
+
+            self.side(points, widths, false, &mut contour);
+            let direction = (end - points[points.len() - 2]).normalize();
+            self.cap(
+                end,
+                direction.perp() * widths[widths.len() - 1],
+                direction,
+                &mut contour,
+            );
+
+            self.side(&reversed, &reversed_widths, false, &mut contour);
+            let direction = (start - points[1]).normalize();
+            self.cap(start, direction.perp() * widths[0], direction, &mut contour);
+        }
+
+        push_contour(&contour, events);
+    }
+
+    /// Stroke a closed subpath as two contours, one on either side of it.
+    fn closed(&self, points: &[Point<T>], widths: &[T], events: &mut Vec<PathEvent<T>>) {
+        let mut contour = Vec::new();
+        self.side(points, widths, true, &mut contour);
+        push_contour(&contour, events);
+
+        contour.clear();
+        let reversed = points.iter().rev().copied().collect::<Vec<_>>();
+        let reversed_widths = widths.iter().rev().copied().collect::<Vec<_>>();
+        self.side(&reversed, &reversed_widths, true, &mut contour);
+        push_contour(&contour, events);
+    }
+
+    /// Add the points along the left side of the stroke, including the joins.
+    fn side(&self, points: &[Point<T>], widths: &[T], closed: bool, contour: &mut Vec<Point<T>>) {
+        let len = points.len();
+        // the normal of the segment starting at `i`, scaled to the width at `at`
+        let normal = |i: usize, at: usize| {
+            (points[(i + 1) % len] - points[i]).normalize().perp() * widths[at]
+        };
+
+        if closed {
+            for (i, &point) in points.iter().enumerate() {
+                self.join(point, normal((i + len - 1) % len, i), normal(i, i), contour);
+            }
+        } else {
+            contour.push(points[0] + normal(0, 0));
+            for (i, &point) in points.iter().enumerate().take(len - 1).skip(1) {
+                self.join(point, normal(i - 1, i), normal(i, i), contour);
+            }
+            contour.push(points[len - 1] + normal(len - 2, len - 1));
+        }
+    }
+
+    /// Add the points of the join around `point`, between the segments with the given
+    /// left-hand normals.
+    fn join(
+        &self,
+        point: Point<T>,
+        incoming: Vector<T>,
+        outgoing: Vector<T>,
+        contour: &mut Vec<Point<T>>,
+    ) {
+        let cross = incoming.cross(outgoing);
+        let dot = incoming.dot(outgoing);
+        let half_width = incoming.length();
+
+        // the segments continue in the same direction, or the stroke has no width here
+        if half_width.approx_eq(&T::zero())
+            || (dot > T::zero() && (cross / dot).approx_eq(&T::zero()))
+        {
+            contour.push(point + incoming);
+            return;
+        }
+
+        // on the inside of the corner, go through the corner itself so that the outline
+        // overlaps itself instead of leaving a gap
+        if cross > T::zero() {
+            contour.push(point + incoming);
+            contour.push(point);
+            contour.push(point + outgoing);
+            return;
+        }
+
+        contour.push(point + incoming);
+        match self.options.join {
+            LineJoin::Bevel => {}
+            LineJoin::Round => {
+                let sweep = cross.atan2(dot);
+                self.arc(point, incoming, sweep, contour);
+            }
+            LineJoin::Miter => {
+                // the miter's length relative to the width is one over the cosine of
+                // half of the angle between the normals
+                let bisector = (incoming + outgoing).normalize();
+                let cosine = bisector.dot(incoming) / half_width;
+                if cosine > T::zero() && T::one() / cosine <= self.options.miter_limit {
+                    contour.push(point + bisector * (half_width / cosine));
+                }
+            }
+        }
+        contour.push(point + outgoing);
+    }
+
+    /// Add the points of a cap at the end of the path, going from the left-hand side to
+    /// the right-hand side.
+    fn cap(
+        &self,
+        point: Point<T>,
+        normal: Vector<T>,
+        direction: Vector<T>,
+        contour: &mut Vec<Point<T>>,
+    ) {
+        match self.options.cap {
+            LineCap::Butt => {}
+            LineCap::Square => {
+                let extension = direction * normal.length();
+                contour.push(point + normal + extension);
+                contour.push(point - normal + extension);
+            }
+            LineCap::Round => {
+                contour.push(point + normal);
+                self.arc(point, normal, -T::PI(), contour);
+                contour.push(point - normal);
+            }
+        }
+    }
+
+    /// Add the points strictly inside of an arc around `center`, starting at `center +
+    /// start` and sweeping counter-clockwise by `sweep` radians.
+    fn arc(&self, center: Point<T>, start: Vector<T>, sweep: T, contour: &mut Vec<Point<T>>) {
+        let radius = start.length();
+        if radius <= T::zero() {
+            return;
+        }
+
+        let tolerance = self.options.tolerance.min(radius);
+        let step = (T::one() - tolerance / radius).acos() * (T::one() + T::one());
+        let steps = (sweep.abs() / step).ceil().to_usize().unwrap_or(1).max(1);
+
+        let angle = sweep / T::from(steps).unwrap_or_else(T::one);
+        let (sin, cos) = angle.sin_cos();
+        let mut offset = start;
+        for _ in 1..steps {
+            offset = Vector::new(
+                offset.x() * cos - offset.y() * sin,
+                offset.x() * sin + offset.y() * cos,
+            );
+            contour.push(center + offset);
+        }
+    }
+}
+
+/// Add a closed contour to the list of events.
+fn push_contour<T: Copy>(contour: &[Point<T>], events: &mut Vec<PathEvent<T>>) {
+    let (first, last) = match (contour.first(), contour.last()) {
+        (Some(&first), Some(&last)) if contour.len() > 2 => (first, last),
+        _ => return,
+    };
+
+    events.push(PathEvent::Begin { at: first });
+    events.extend(contour.windows(2).map(|pair| PathEvent::Line {
+        from: pair[0],
+        to: pair[1],
+    }));
+    events.push(PathEvent::End {
+        first,
+        last,
+        close: true,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LineSegment;
+    use alloc::vec;
+
+    fn options(cap: LineCap, join: LineJoin) -> StrokeOptions<f64> {
+        StrokeOptions {
+            width: 2.0,
+            cap,
+            join,
+            tolerance: 0.001,
+            ..StrokeOptions::default()
+        }
+    }
+
+    fn area(stroke: &Stroke<f64>) -> f64 {
+        stroke.area(0.001)
+    }
+
+    #[test]
+    fn test_caps() {
+        let line = LineSegment::new(Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+
+        let butt = stroke(line, &options(LineCap::Butt, LineJoin::Miter));
+        assert!((area(&butt) - 20.0).abs() < 1e-6);
+
+        let square = stroke(line, &options(LineCap::Square, LineJoin::Miter));
+        assert!((area(&square) - 24.0).abs() < 1e-6);
+
+        let round = stroke(line, &options(LineCap::Round, LineJoin::Miter));
+        assert!((area(&round) - (20.0 + core::f64::consts::PI)).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_joins() {
+        // A right angle, turning both ways.
+        let corners = [
+            [
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(10.0, 10.0),
+            ],
+            [
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(10.0, -10.0),
+            ],
+        ];
+
+        for corner in &corners {
+            let path = crate::polyline::Polyline::new(corner.to_vec());
+            let area_with = |join| area(&stroke(&path, &options(LineCap::Butt, join)));
+
+            // Two 10x2 strokes that overlap in a 1x1 square, plus the outer corner.
+            assert!((area_with(LineJoin::Bevel) - 39.5).abs() < 1e-6);
+            assert!((area_with(LineJoin::Miter) - 40.0).abs() < 1e-6);
+            assert!(
+                (area_with(LineJoin::Round) - (39.0 + core::f64::consts::PI / 4.0)).abs() < 0.01
+            );
+        }
+    }
+
+    #[test]
+    fn test_miter_limit() {
+        // A very sharp corner gets beveled.
+        let path = crate::polyline::Polyline::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(0.0, 1.0),
+        ]);
+        let mut options = options(LineCap::Butt, LineJoin::Miter);
+        let limited = stroke(&path, &options);
+        options.miter_limit = 100.0;
+        let unlimited = stroke(&path, &options);
+
+        let reach = |stroke: &Stroke<f64>| stroke.bounding_box(0.001).max().x();
+        assert!(reach(&limited) < 12.0);
+        assert!(reach(&unlimited) > 20.0);
+    }
+
+    #[test]
+    fn test_closed() {
+        // A closed square stroke is the outer square minus the inner square.
+        let square = crate::Rect::new(Point::new(0.0, 0.0), crate::Size::new(10.0, 10.0));
+        let outline = stroke(square, &options(LineCap::Butt, LineJoin::Miter));
+        assert!((area(&outline) - (144.0 - 64.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_variable_width() {
+        let line = LineSegment::new(Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+        let options = options(LineCap::Butt, LineJoin::Miter);
+
+        // A linear taper from 4 down to 0 is a triangle.
+        let taper = stroke_with_width(line, &options, |t| 4.0 * (1.0 - t));
+        assert!((area(&taper) - 20.0).abs() < 1e-6);
+
+        // A swelling width is sampled finely enough to follow the curve, which has an
+        // area of 2 / pi of its bounding box.
+        let swell = stroke_with_width(line, &options, |t| 2.0 * (t * core::f64::consts::PI).sin());
+        assert!(swell.events().len() > 8);
+        assert!((area(&swell) - 40.0 / core::f64::consts::PI).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_dashed() {
+        let line = LineSegment::new(Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+        let pattern = DashPattern {
+            lengths: &[1.0, 3.0],
+            offset: 0.0,
+        };
+
+        // Three dashes, each of which gets its own caps.
+        let butt = stroke_dashed(line, &options(LineCap::Butt, LineJoin::Miter), &pattern);
+        assert!((area(&butt) - 6.0).abs() < 1e-6);
+
+        let square = stroke_dashed(line, &options(LineCap::Square, LineJoin::Miter), &pattern);
+        assert!((area(&square) - 18.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_transformed() {
+        let line = LineSegment::new(Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+        let options = options(LineCap::Butt, LineJoin::Miter);
+
+        // Stretching the path doesn't stretch the stroke's width along with it.
+        let stretch = Affine::scale(2.0, 5.0);
+        let outline = stroke_with_transform(line, &options, &stretch);
+        assert!((area(&outline) - 40.0).abs() < 1e-6);
+        let bounds = outline.bounding_box(0.001);
+        assert!((bounds.max().y() - bounds.min().y() - 2.0).abs() < 1e-6);
+
+        let vertical = LineSegment::new(Point::new(0.0, 0.0), Point::new(0.0, 10.0));
+        let outline = stroke_with_transform(vertical, &options, &stretch);
+        assert!((area(&outline) - 100.0).abs() < 1e-6);
+    }
+}
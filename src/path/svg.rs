@@ -0,0 +1,618 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Parsing SVG path data.
+
+use super::{OwnedPathBuffer, Path, PathEvent, Shape};
+use crate::point::{Point, Vector};
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::Copied;
+use core::slice::Iter as SliceIter;
+use core::str::FromStr;
+use num_traits::real::Real;
+use num_traits::FloatConst;
+
+/// A path parsed from the `d` attribute of an SVG `<path>` element.
+///
+/// Every command of the SVG path syntax is supported, in both its absolute and relative
+/// forms. Elliptical arcs are converted into cubic curves, so the path only contains
+/// the events that the rest of the crate expects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgPath<T: Copy> {
+    /// The events of the path.
+    events: Vec<PathEvent<T>>,
+}
+
+impl<T: Real + FloatConst> SvgPath<T> {
+    /// Parse SVG path data.
+    pub fn parse(data: &str) -> Result<Self, SvgPathError> {
+        let mut parser = Parser {
+            bytes: data.as_bytes(),
+            position: 0,
+            events: Vec::new(),
+            current: Point::new(T::zero(), T::zero()),
+            start: Point::new(T::zero(), T::zero()),
+            open: false,
+            reflect: None,
+        };
+
+        parser.parse()?;
+        Ok(SvgPath {
+            events: parser.events,
+        })
+    }
+}
+
+impl<T: Copy> SvgPath<T> {
+    /// Get the events of the path.
+    pub fn events(&self) -> &[PathEvent<T>] {
+        &self.events
+    }
+
+    /// Convert this path into a path buffer.
+    ///
+    /// The buffer is closed if the final subpath ends with a `Z` command. Returns `None`
+    /// if the path is empty.
+    pub fn to_path_buffer(&self) -> Option<OwnedPathBuffer<T>>
+    where
+        T: fmt::Debug,
+    {
+        if self.events.is_empty() {
+            None
+        } else {
+            Some(self.events.iter().copied().collect())
+        }
+    }
+}
+
+impl<T: Real + FloatConst> FromStr for SvgPath<T> {
+    type Err = SvgPathError;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        Self::parse(data)
+    }
+}
+
+impl<'a, T: Copy> Path<T> for &'a SvgPath<T> {
+    type Iter = Copied<SliceIter<'a, PathEvent<T>>>;
+
+    fn path_iter(self) -> Self::Iter {
+        self.events.iter().copied()
+    }
+}
+
+impl<T: Copy> Shape<T> for &SvgPath<T> {}
+
+/// An error that occurred while parsing SVG path data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SvgPathError {
+    /// A character that isn't a command was found where a command was expected.
+    UnknownCommand {
+        /// The byte offset of the character.
+        position: usize,
+
+        /// The offending character.
+        command: char,
+    },
+
+    /// A number or flag was expected, but something else was found.
+    ExpectedNumber {
+        /// The byte offset where the number was expected.
+        position: usize,
+    },
+
+    /// The path data doesn't start with a move command.
+    MissingMoveTo {
+        /// The byte offset of the first command.
+        position: usize,
+    },
+}
+
+impl fmt::Display for SvgPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgPathError::UnknownCommand { position, command } => {
+                write!(f, "unknown command {:?} at byte {}", command, position)
+            }
+            SvgPathError::ExpectedNumber { position } => {
+                write!(f, "expected a number at byte {}", position)
+            }
+            SvgPathError::MissingMoveTo { position } => {
+                write!(
+                    f,
+                    "path data must start with a move command, at byte {}",
+                    position
+                )
+            }
+        }
+    }
+}
+
+/// The state of the parser.
+struct Parser<'a, T: Copy> {
+    /// The path data.
+    bytes: &'a [u8],
+
+    /// The byte offset of the next character.
+    position: usize,
+
+    /// The events parsed so far.
+    events: Vec<PathEvent<T>>,
+
+    /// The current point.
+    current: Point<T>,
+
+    /// The first point of the current subpath.
+    start: Point<T>,
+
+    /// Whether a subpath has begun but not ended.
+    open: bool,
+
+    /// The control point of the previous command, reflected for smooth curves.
+    reflect: Option<(u8, Point<T>)>,
+}
+
+impl<T: Real + FloatConst> Parser<'_, T> {
+    /// Parse all of the path data.
+    fn parse(&mut self) -> Result<(), SvgPathError> {
+        let mut command = None;
+
+        loop {
+            self.skip_separators();
+            let position = self.position;
+            let byte = match self.bytes.get(position) {
+                Some(&byte) => byte,
+                None => break,
+            };
+
+            // numbers after a command repeat it, except that moves turn into lines
+            let letter = if byte.is_ascii_alphabetic() {
+                self.position += 1;
+                byte
+            } else {
+                match command {
+                    Some(b'M') => b'L',
+                    Some(b'm') => b'l',
+                    Some(c) if !c.eq_ignore_ascii_case(&b'z') => c,
+                    _ => return Err(self.unknown_command(position)),
+                }
+            };
+
+            if command.is_none() && !letter.eq_ignore_ascii_case(&b'm') {
+                return Err(SvgPathError::MissingMoveTo { position });
+            }
+
+            self.command(letter, position)?;
+            command = Some(letter);
+        }
+
+        self.end(false);
+        Ok(())
+    }
+
+    /// Parse the arguments of a single command and add its events.
+    fn command(&mut self, letter: u8, position: usize) -> Result<(), SvgPathError> {
+        let relative = letter.is_ascii_lowercase();
+        let origin = if relative {
+            self.current.into_vector()
+        } else {
+            Vector::new(T::zero(), T::zero())
+        };
+
+        let mut reflect = None;
+        match letter.to_ascii_uppercase() {
+            b'M' => {
+                let to = self.point()? + origin;
+                self.end(false);
+                self.events.push(PathEvent::Begin { at: to });
+                self.current = to;
+                self.start = to;
+                self.open = true;
+            }
+            b'L' => {
+                let to = self.point()? + origin;
+                self.line(to);
+            }
+            b'H' => {
+                let x = self.number()? + origin.x();
+                self.line(Point::new(x, self.current.y()));
+            }
+            b'V' => {
+                let y = self.number()? + origin.y();
+                self.line(Point::new(self.current.x(), y));
+            }
+            b'C' | b'S' => {
+                let control1 = if letter.eq_ignore_ascii_case(&b'c') {
+                    self.point()? + origin
+                } else {
+                    self.reflected(b'C')
+                };
+                let control2 = self.point()? + origin;
+                let to = self.point()? + origin;
+
+                let from = self.begin();
+                self.events.push(PathEvent::Cubic {
+                    from,
+                    control1,
+                    control2,
+                    to,
+                });
+                self.current = to;
+                reflect = Some((b'C', control2));
+            }
+            b'Q' | b'T' => {
+                let control = if letter.eq_ignore_ascii_case(&b'q') {
+                    self.point()? + origin
+                } else {
+                    self.reflected(b'Q')
+                };
+                let to = self.point()? + origin;
+
+                let from = self.begin();
+                self.events.push(PathEvent::Quadratic { from, control, to });
+                self.current = to;
+                reflect = Some((b'Q', control));
+            }
+            b'A' => {
+                let radii = Vector::new(self.number()?.abs(), self.number()?.abs());
+                let rotation = self.number()?.to_radians();
+                let large_arc = self.flag()?;
+                let sweep = self.flag()?;
+                let to = self.point()? + origin;
+                self.arc(radii, rotation, large_arc, sweep, to);
+            }
+            b'Z' => self.end(true),
+            _ => return Err(self.unknown_command(position)),
+        }
+
+        self.reflect = reflect;
+        Ok(())
+    }
+
+    /// Begin a new subpath at the current point if the previous one was closed.
+    ///
+    /// Returns the current point.
+    fn begin(&mut self) -> Point<T> {
+        if !self.open {
+            self.events.push(PathEvent::Begin { at: self.current });
+            self.start = self.current;
+            self.open = true;
+        }
+
+        self.current
+    }
+
+    /// End the current subpath, if there is one.
+    fn end(&mut self, close: bool) {
+        if self.open {
+            self.events.push(PathEvent::End {
+                first: self.start,
+                last: self.current,
+                close,
+            });
+            self.open = false;
+        }
+
+        if close {
+            self.current = self.start;
+        }
+    }
+
+    /// Add a line from the current point.
+    fn line(&mut self, to: Point<T>) {
+        let from = self.begin();
+        self.events.push(PathEvent::Line { from, to });
+        self.current = to;
+    }
+
+    /// Get the first control point of a smooth curve.
+    ///
+    /// This is the reflection of the previous control point if the previous command was
+    /// the same kind of curve, or the current point otherwise.
+    fn reflected(&self, kind: u8) -> Point<T> {
+        match self.reflect {
+            Some((previous, control)) if previous == kind => {
+                self.current + (self.current - control)
+            }
+            _ => self.current,
+        }
+    }
+
+    /// Add an elliptical arc from the current point, as a series of cubic curves.
+    ///
+    /// This follows the conversion from endpoint to center parameterization given in the
+    /// implementation notes of the SVG specification.
+    fn arc(&mut self, radii: Vector<T>, rotation: T, large_arc: bool, sweep: bool, to: Point<T>) {
+        let from = self.current;
+        if from == to {
+            return;
+        }
+        if radii.x().is_zero() || radii.y().is_zero() {
+            self.line(to);
+            return;
+        }
+
+        let two = T::one() + T::one();
+        let (sin, cos) = rotation.sin_cos();
+        let rotate =
+            |v: Vector<T>| Vector::new(cos * v.x() - sin * v.y(), sin * v.x() + cos * v.y());
+
+        // move the end points into the frame of the ellipse
+        let half = (from - to) / two;
+        let p = Vector::new(
+            cos * half.x() + sin * half.y(),
+            cos * half.y() - sin * half.x(),
+        );
+
+        // scale up radii that are too small to reach from one end point to the other
+        let lambda = (p.x() / radii.x()).powi(2) + (p.y() / radii.y()).powi(2);
+        let (rx, ry) = if lambda > T::one() {
+            (radii.x() * lambda.sqrt(), radii.y() * lambda.sqrt())
+        } else {
+            (radii.x(), radii.y())
+        };
+
+        let (rx2, ry2) = (rx * rx, ry * ry);
+        let (px2, py2) = (p.x() * p.x(), p.y() * p.y());
+        let mut coefficient = ((rx2 * ry2 - rx2 * py2 - ry2 * px2) / (rx2 * py2 + ry2 * px2))
+            .max(T::zero())
+            .sqrt();
+        if large_arc == sweep {
+            coefficient = -coefficient;
+        }
+        let c = Vector::new(
+            coefficient * rx * p.y() / ry,
+            -coefficient * ry * p.x() / rx,
+        );
+        let center = from.midpoint(to) + rotate(c);
+
+        let start = ((p.y() - c.y()) / ry).atan2((p.x() - c.x()) / rx);
+        let end = ((-p.y() - c.y()) / ry).atan2((-p.x() - c.x()) / rx);
+        let mut delta = end - start;
+        if sweep && delta < T::zero() {
+            delta = delta + two * T::PI();
+        } else if !sweep && delta > T::zero() {
+            delta = delta - two * T::PI();
+        }
+
+        // split the arc into pieces of at most a quarter turn
+        let pieces = (delta.abs() / T::FRAC_PI_2()).ceil().max(T::one());
+        let step = delta / pieces;
+        let handle = (step / (two * two)).tan() * (two + two) / (two + T::one());
+        let point = |angle: T| center + rotate(Vector::new(rx * angle.cos(), ry * angle.sin()));
+        let tangent = |angle: T| rotate(Vector::new(-rx * angle.sin(), ry * angle.cos())) * handle;
+
+        let count = pieces.to_usize().unwrap_or(1);
+        let begin = self.begin();
+        let mut previous = begin;
+        for i in 1..=count {
+            let (a, b) = (
+                start + step * T::from(i - 1).unwrap(),
+                start + step * T::from(i).unwrap(),
+            );
+            let end = if i == count { to } else { point(b) };
+            self.events.push(PathEvent::Cubic {
+                from: previous,
+                control1: previous + tangent(a),
+                control2: end - tangent(b),
+                to: end,
+            });
+            previous = end;
+        }
+        self.current = to;
+    }
+
+    /// Skip over whitespace and at most one comma.
+    fn skip_separators(&mut self) {
+        let mut comma = false;
+        while let Some(&byte) = self.bytes.get(self.position) {
+            match byte {
+                b' ' | b'\t' | b'\n' | b'\r' | b'\x0c' => {}
+                b',' if !comma => comma = true,
+                _ => break,
+            }
+            self.position += 1;
+        }
+    }
+
+    /// Parse a pair of numbers.
+    fn point(&mut self) -> Result<Point<T>, SvgPathError> {
+        Ok(Point::new(self.number()?, self.number()?))
+    }
+
+    /// Parse a number.
+    fn number(&mut self) -> Result<T, SvgPathError> {
+        self.skip_separators();
+        let start = self.position;
+        let bytes = self.bytes;
+        let digits = |mut i: usize| {
+            while matches!(bytes.get(i), Some(byte) if byte.is_ascii_digit()) {
+                i += 1;
+            }
+            i
+        };
+
+        let mut end = start;
+        if let Some(b'+') | Some(b'-') = bytes.get(end) {
+            end += 1;
+        }
+        let integer = end;
+        end = digits(end);
+        let mut has_digits = end > integer;
+        if bytes.get(end) == Some(&b'.') {
+            let fraction = end + 1;
+            end = digits(fraction);
+            has_digits |= end > fraction;
+        }
+        if !has_digits {
+            return Err(SvgPathError::ExpectedNumber { position: start });
+        }
+
+        // only take the exponent if it has digits, since it could be the start of an
+        // unrelated command
+        if let Some(b'e') | Some(b'E') = bytes.get(end) {
+            let mut exponent = end + 1;
+            if let Some(b'+') | Some(b'-') = bytes.get(exponent) {
+                exponent += 1;
+            }
+            let exponent_end = digits(exponent);
+            if exponent_end > exponent {
+                end = exponent_end;
+            }
+        }
+
+        self.position = end;
+        core::str::from_utf8(&bytes[start..end])
+            .ok()
+            .and_then(|text| text.parse::<f64>().ok())
+            .and_then(T::from)
+            .ok_or(SvgPathError::ExpectedNumber { position: start })
+    }
+
+    /// Parse an arc flag, which may be written without a separator after it.
+    fn flag(&mut self) -> Result<bool, SvgPathError> {
+        self.skip_separators();
+        let flag = match self.bytes.get(self.position) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => {
+                return Err(SvgPathError::ExpectedNumber {
+                    position: self.position,
+                })
+            }
+        };
+
+        self.position += 1;
+        Ok(flag)
+    }
+
+    /// Make an error for an unknown command at the given position.
+    fn unknown_command(&self, position: usize) -> SvgPathError {
+        let command = core::str::from_utf8(&self.bytes[position..])
+            .ok()
+            .and_then(|rest| rest.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+
+        SvgPathError::UnknownCommand { position, command }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &str) -> SvgPath<f64> {
+        data.parse().unwrap()
+    }
+
+    #[test]
+    fn test_commands() {
+        // The same square, written in different ways.
+        let squares = [
+            "M0 0 L10 0 L10 10 L0 10 Z",
+            "m0,0 h10 v10 h-10 z",
+            "M0 0 10 0 10 10 0 10z",
+            "M0-0L1e1,0l0 10H0Z",
+        ];
+        for square in &squares {
+            let path = parse(square);
+            assert_eq!(path.events().len(), 5, "{}", square);
+            assert_eq!(path.area(0.01), 100.0, "{}", square);
+        }
+
+        let curves = parse("M0 0 C0 10 10 10 10 0 S20 -10 20 0 Q25 5 30 0 T40 0");
+        assert_eq!(
+            curves.events()[2],
+            PathEvent::Cubic {
+                from: Point::new(10.0, 0.0),
+                control1: Point::new(10.0, -10.0),
+                control2: Point::new(20.0, -10.0),
+                to: Point::new(20.0, 0.0),
+            }
+        );
+        assert_eq!(
+            curves.events()[4],
+            PathEvent::Quadratic {
+                from: Point::new(30.0, 0.0),
+                control: Point::new(35.0, -5.0),
+                to: Point::new(40.0, 0.0),
+            }
+        );
+
+        // Drawing after closing a subpath starts a new one at the same place.
+        let path = parse("M5 5 l1 0 0 1 z l-1 0 0 -1 z");
+        assert_eq!(
+            path.events()[4],
+            PathEvent::Begin {
+                at: Point::new(5.0, 5.0)
+            }
+        );
+        assert_eq!(path.to_path_buffer().unwrap().path_iter().count(), 8);
+        assert!(path.to_path_buffer().unwrap().is_closed());
+        assert!(!parse("M0 0 L1 0").to_path_buffer().unwrap().is_closed());
+        assert!(parse("").to_path_buffer().is_none());
+    }
+
+    #[test]
+    fn test_arcs() {
+        // A circle made from two half circles, with the flags written without separators.
+        let circle = parse("M0 5 a5 5 0 1 0 10 0 a5,5 0 10-10 0z");
+        assert!((circle.area(0.001) - 25.0 * core::f64::consts::PI).abs() < 0.1);
+
+        // Radii that are too small are scaled up.
+        let small = parse("M0 0 A1 1 0 0 1 10 0 Z");
+        let bounds = small.bounding_box(0.001);
+        assert!((bounds.min_max().0.y() + 5.0).abs() < 0.01);
+
+        // A rotated ellipse has the same area.
+        let ellipse = parse("M0 0 A10 5 45 1 1 0.01 0.01 Z");
+        assert!((ellipse.area(0.001) - 50.0 * core::f64::consts::PI).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_errors() {
+        let parse = |data: &str| data.parse::<SvgPath<f64>>();
+        assert_eq!(
+            parse("L0 0"),
+            Err(SvgPathError::MissingMoveTo { position: 0 })
+        );
+        assert_eq!(
+            parse("M0 0 X1 1"),
+            Err(SvgPathError::UnknownCommand {
+                position: 5,
+                command: 'X'
+            })
+        );
+        assert_eq!(
+            parse("M0 0 L1"),
+            Err(SvgPathError::ExpectedNumber { position: 7 })
+        );
+        assert_eq!(
+            parse("M0 0 A1 1 0 2 0 1 1"),
+            Err(SvgPathError::ExpectedNumber { position: 12 })
+        );
+        assert_eq!(
+            parse("M0 0 z 1"),
+            Err(SvgPathError::UnknownCommand {
+                position: 7,
+                command: '1'
+            })
+        );
+    }
+}
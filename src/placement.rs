@@ -0,0 +1,269 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Finding good places to put labels and icons on shapes.
+
+#![cfg(feature = "alloc")]
+
+use crate::path::Shape;
+use crate::point::Point;
+use crate::polygon::{rings, winding};
+use crate::size::Size;
+use crate::ApproxEq;
+
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num_traits::real::Real;
+
+/// Find the most interior point of a shape, for placing a label of the given size.
+///
+/// This is the pole of inaccessibility: the point inside of the shape that is furthest
+/// from its boundary, found by refining a quadtree of cells in the same way as
+/// `polylabel`. Distances are measured with the shape stretched so that the label is
+/// square, which favors spots with the same proportions as the label. The point is found
+/// to within a sixteenth of the label's smaller dimension.
+///
+/// The shape is filled using the non-zero fill rule. If the shape is empty, this returns
+/// the origin.
+pub fn find_label_position<T, S>(shape: S, label_size: Size<T>) -> Point<T>
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    let (width, height) = (label_size.width().abs(), label_size.height().abs());
+    let smaller = width.min(height);
+
+    // Stretch the shape horizontally so that the label becomes a square.
+    let stretch = if smaller.is_zero() {
+        T::one()
+    } else {
+        height / width
+    };
+    let tolerance = if smaller.is_zero() {
+        T::from(0.1).unwrap()
+    } else {
+        smaller / T::from(16).unwrap()
+    };
+
+    let rings = rings(shape, tolerance)
+        .into_iter()
+        .map(|ring| {
+            ring.into_iter()
+                .map(|p| Point::new(p.x() * stretch, p.y()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let points = rings.iter().flatten();
+    let (min, max) = match points.clone().next() {
+        Some(&first) => points.fold((first, first), |(min, max), &p| (min.min(p), max.max(p))),
+        None => return Point::new(T::zero(), T::zero()),
+    };
+
+    let best = pole(&rings, min, max, tolerance * stretch.min(T::one()));
+    Point::new(best.x() / stretch, best.y())
+}
+
+/// A square cell being searched for the pole.
+struct Cell<T: Copy> {
+    /// The center of the cell.
+    center: Point<T>,
+
+    /// Half of the width of the cell.
+    half: T,
+
+    /// The signed distance from the center to the boundary.
+    distance: T,
+
+    /// The largest distance that any point in the cell could have.
+    potential: T,
+}
+
+impl<T: Real> Cell<T> {
+    /// Create a new cell and measure its distance to the boundary.
+    fn new(center: Point<T>, half: T, rings: &[Vec<Point<T>>]) -> Self {
+        let distance = signed_distance(center, rings);
+        Cell {
+            center,
+            half,
+            distance,
+            potential: distance + half * (T::one() + T::one()).sqrt(),
+        }
+    }
+}
+
+impl<T: Real> PartialEq for Cell<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Real> Eq for Cell<T> {}
+
+impl<T: Real> PartialOrd for Cell<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Real> Ord for Cell<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.potential
+            .partial_cmp(&other.potential)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Find the point inside of the rings that is furthest from their edges.
+fn pole<T: Real>(rings: &[Vec<Point<T>>], min: Point<T>, max: Point<T>, precision: T) -> Point<T> {
+    let two = T::one() + T::one();
+    let size = max - min;
+    let cell_size = size.min_element();
+
+    let center = min.midpoint(max);
+    let mut best = Cell::new(center, T::zero(), rings);
+    if cell_size <= T::zero() {
+        return best.center;
+    }
+
+    // Cover the bounding box with square cells.
+    let mut queue = BinaryHeap::new();
+    let half = cell_size / two;
+    let mut x = min.x();
+    while x < max.x() {
+        let mut y = min.y();
+        while y < max.y() {
+            queue.push(Cell::new(Point::new(x + half, y + half), half, rings));
+            y = y + cell_size;
+        }
+        x = x + cell_size;
+    }
+
+    while let Some(cell) = queue.pop() {
+        if cell.distance > best.distance {
+            best = Cell::new(cell.center, T::zero(), rings);
+        }
+
+        // Stop refining cells that can't do any better than the best so far.
+        if cell.potential - best.distance <= precision {
+            continue;
+        }
+
+        let half = cell.half / two;
+        for &(dx, dy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)].iter() {
+            let offset = crate::Vector::new(T::from(dx).unwrap(), T::from(dy).unwrap()) * half;
+            queue.push(Cell::new(cell.center + offset, half, rings));
+        }
+    }
+
+    best.center
+}
+
+/// Get the distance from a point to the edges of the rings, negated if it is outside.
+fn signed_distance<T: Real>(point: Point<T>, rings: &[Vec<Point<T>>]) -> T {
+    let mut inside = 0;
+    let mut nearest = T::max_value();
+
+    for ring in rings {
+        inside += winding(ring, point);
+
+        for (i, &from) in ring.iter().enumerate() {
+            let to = ring[(i + 1) % ring.len()];
+            let edge = to - from;
+            let length = edge.length_squared();
+
+            let t = if length.is_zero() {
+                T::zero()
+            } else {
+                ((point - from).dot(edge) / length)
+                    .max(T::zero())
+                    .min(T::one())
+            };
+            nearest = nearest.min(point.distance_squared(from + edge * t));
+        }
+    }
+
+    let distance = nearest.sqrt();
+    if inside != 0 {
+        distance
+    } else {
+        -distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polygon::Polygon;
+    use alloc::vec;
+
+    #[test]
+    fn test_l_shape() {
+        // The thick part of an L is at the bottom.
+        let shape = Polygon::new(
+            vec![
+                Point::new(0.0f64, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(10.0, 6.0),
+                Point::new(2.0, 6.0),
+                Point::new(2.0, 10.0),
+                Point::new(0.0, 10.0),
+            ],
+            Vec::new(),
+        );
+
+        let point = find_label_position(&shape, Size::new(1.0, 1.0));
+        assert!((point.y() - 3.0).abs() < 0.1, "{:?}", point);
+        assert!(point.x() > 3.0 && point.x() < 9.0, "{:?}", point);
+    }
+
+    #[test]
+    fn test_hole() {
+        // A square with a hole in the middle pushes the label to the side.
+        let mut hole = vec![
+            Point::new(3.0f64, 3.0),
+            Point::new(7.0, 3.0),
+            Point::new(7.0, 7.0),
+            Point::new(3.0, 7.0),
+        ];
+        hole.reverse();
+        let shape = Polygon::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(10.0, 10.0),
+                Point::new(0.0, 10.0),
+            ],
+            vec![hole],
+        );
+
+        let point = find_label_position(&shape, Size::new(1.0, 1.0));
+        let center = Point::new(5.0, 5.0);
+        assert!(point.distance(center) > 3.0, "{:?}", point);
+        assert!(signed_distance(point, &[shape.exterior().to_vec()]) > 1.0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let shape = Polygon::<f32>::default();
+        assert_eq!(
+            find_label_position(&shape, Size::new(1.0, 1.0)),
+            Point::new(0.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,577 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Planning how paths are drawn, for pen plotters, other machines and renderers.
+
+#![cfg(feature = "alloc")]
+
+use crate::box2d::Box;
+use crate::path::{
+    Flattened, OwnedPathBuffer, Path, PathBuffer, PathEvent, StraightPathEvent, Verb,
+};
+use crate::point::Point;
+use crate::transform::{Affine, Transform};
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use num_traits::real::Real;
+use num_traits::Zero;
+
+/// Options for [`order_paths`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrderOptions<T: Copy> {
+    /// Where the pen is before drawing the first path.
+    pub origin: Point<T>,
+
+    /// Whether paths may be drawn backwards.
+    pub allow_reverse: bool,
+
+    /// Whether to improve the greedy ordering using 2-opt.
+    ///
+    /// This has no effect unless `allow_reverse` is set, since 2-opt reverses runs of
+    /// paths.
+    pub two_opt: bool,
+}
+
+impl<T: Copy + Zero> Default for OrderOptions<T> {
+    fn default() -> Self {
+        OrderOptions {
+            origin: Point::new(T::zero(), T::zero()),
+            allow_reverse: true,
+            two_opt: true,
+        }
+    }
+}
+
+/// A path in the order produced by [`order_paths`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PlannedPath {
+    /// The index of the path in the original list.
+    pub index: usize,
+
+    /// Whether the path should be drawn backwards.
+    pub reversed: bool,
+}
+
+/// Order a list of paths to reduce the distance travelled between them.
+///
+/// The distance is measured from the end of each path to the start of the next one,
+/// starting at `options.origin`. Paths are first chained greedily by picking the
+/// nearest path each time, which is then optionally refined with 2-opt. Empty paths are
+/// placed at the end.
+pub fn order_paths<T, I>(paths: I, options: OrderOptions<T>) -> Vec<PlannedPath>
+where
+    T: Real,
+    I: IntoIterator,
+    I::Item: Path<T>,
+{
+    let mut empty = Vec::new();
+    let mut ends = Vec::new();
+    for (index, path) in paths.into_iter().enumerate() {
+        match endpoints(path) {
+            Some((start, end)) => ends.push((index, start, end)),
+            None => empty.push(index),
+        }
+    }
+
+    let entry = |step: &PlannedPath, ends: &[(usize, Point<T>, Point<T>)]| {
+        let (_, start, end) = ends[step.index];
+        if step.reversed {
+            end
+        } else {
+            start
+        }
+    };
+    let exit = |step: &PlannedPath, ends: &[(usize, Point<T>, Point<T>)]| {
+        let (_, start, end) = ends[step.index];
+        if step.reversed {
+            start
+        } else {
+            end
+        }
+    };
+
+    // Greedily pick the nearest path. Indices refer to `ends` until the end.
+    let mut order = Vec::with_capacity(ends.len());
+    let mut used = alloc::vec![false; ends.len()];
+    let mut pen = options.origin;
+    for _ in 0..ends.len() {
+        let mut best: Option<(T, PlannedPath)> = None;
+
+        for (i, &(_, start, end)) in ends.iter().enumerate().filter(|(i, _)| !used[*i]) {
+            let mut consider = |distance: T, reversed| match best {
+                Some((d, _)) if d <= distance => {}
+                _ => best = Some((distance, PlannedPath { index: i, reversed })),
+            };
+
+            consider(pen.distance_squared(start), false);
+            if options.allow_reverse {
+                consider(pen.distance_squared(end), true);
+            }
+        }
+
+        if let Some((_, step)) = best {
+            used[step.index] = true;
+            pen = exit(&step, &ends);
+            order.push(step);
+        }
+    }
+
+    if options.two_opt && options.allow_reverse {
+        // Reverse runs of paths while doing so shortens the trip.
+        let mut improved = true;
+        while improved {
+            improved = false;
+
+            for i in 0..order.len() {
+                for j in i + 1..order.len() {
+                    let before = if i == 0 {
+                        options.origin
+                    } else {
+                        exit(&order[i - 1], &ends)
+                    };
+                    let after = order.get(j + 1).map(|step| entry(step, &ends));
+                    let leg = |from: Point<T>, to: Option<Point<T>>| {
+                        to.map_or(T::zero(), |to| from.distance(to))
+                    };
+
+                    let current = before.distance(entry(&order[i], &ends))
+                        + leg(exit(&order[j], &ends), after);
+                    let swapped = before.distance(exit(&order[j], &ends))
+                        + leg(entry(&order[i], &ends), after);
+
+                    if swapped < current - T::epsilon() * current.max(T::one()) {
+                        order[i..=j].reverse();
+                        for step in &mut order[i..=j] {
+                            step.reversed = !step.reversed;
+                        }
+                        improved = true;
+                    }
+                }
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .map(|step| PlannedPath {
+            index: ends[step.index].0,
+            reversed: step.reversed,
+        })
+        .chain(empty.into_iter().map(|index| PlannedPath {
+            index,
+            reversed: false,
+        }))
+        .collect()
+}
+
+/// Get the points where the pen starts and stops while drawing a path.
+fn endpoints<T: Copy, P: Path<T>>(path: P) -> Option<(Point<T>, Point<T>)> {
+    let mut start = None;
+    let mut end = None;
+
+    for event in path.path_iter() {
+        match event {
+            PathEvent::Begin { at } => {
+                start = start.or(Some(at));
+                end = Some(at);
+            }
+            PathEvent::Line { to, .. }
+            | PathEvent::Quadratic { to, .. }
+            | PathEvent::Cubic { to, .. } => end = Some(to),
+            PathEvent::End {
+                first, close: true, ..
+            } => end = Some(first),
+            _ => {}
+        }
+    }
+
+    start.zip(end)
+}
+
+/// A single subpath, broken out of a path.
+struct Subpath<T: Copy> {
+    /// The first point of the subpath.
+    first: Point<T>,
+
+    /// The remaining points of the subpath.
+    rest: Vec<(Point<T>, Verb<T>)>,
+}
+
+impl<T: Copy> Subpath<T> {
+    /// Get the last point of the subpath.
+    fn last(&self) -> Point<T> {
+        self.rest.last().map_or(self.first, |&(point, _)| point)
+    }
+
+    /// Reverse the direction of the subpath.
+    fn reverse(self) -> Self {
+        let mut rest = Vec::with_capacity(self.rest.len());
+        let mut previous = self.first;
+
+        // Each verb describes how to reach its point from the previous one.
+        for (point, verb) in self.rest.iter().copied() {
+            let verb = match verb {
+                Verb::Cubic { control1, control2 } => Verb::Cubic {
+                    control1: control2,
+                    control2: control1,
+                },
+                verb => verb,
+            };
+            rest.push((core::mem::replace(&mut previous, point), verb));
+        }
+
+        rest.reverse();
+        Subpath {
+            first: previous,
+            rest,
+        }
+    }
+}
+
+/// Join paths whose ends are within `tolerance` of each other.
+///
+/// Every subpath of the input becomes its own path, and open subpaths are then chained
+/// together end to end, reversing them where needed. Closed subpaths are left alone and
+/// stay closed.
+pub fn join_paths<T, I>(paths: I, tolerance: T) -> Vec<OwnedPathBuffer<T>>
+where
+    T: Real,
+    I: IntoIterator,
+    I::Item: Path<T>,
+{
+    let mut open = Vec::new();
+    let mut joined = Vec::new();
+
+    for path in paths {
+        let mut current: Option<Subpath<T>> = None;
+
+        for event in path.path_iter() {
+            let push = |current: &mut Option<Subpath<T>>, point, verb| {
+                if let Some(subpath) = current {
+                    subpath.rest.push((point, verb));
+                }
+            };
+
+            match event {
+                PathEvent::Begin { at } => {
+                    current = Some(Subpath {
+                        first: at,
+                        rest: Vec::new(),
+                    })
+                }
+                PathEvent::Line { to, .. } => push(&mut current, to, Verb::Line),
+                PathEvent::Quadratic { control, to, .. } => {
+                    push(&mut current, to, Verb::Quadratic { control })
+                }
+                PathEvent::Cubic {
+                    control1,
+                    control2,
+                    to,
+                    ..
+                } => push(&mut current, to, Verb::Cubic { control1, control2 }),
+                PathEvent::End { close, .. } => {
+                    if let Some(subpath) = current.take() {
+                        if close {
+                            joined.push(
+                                PathBuffer::new(subpath.first, subpath.rest).with_close(true),
+                            );
+                        } else {
+                            open.push(Some(subpath));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // A path may stop without ending its last subpath, which leaves it open.
+        if let Some(subpath) = current {
+            open.push(Some(subpath));
+        }
+    }
+
+    let close_to = |a: Point<T>, b: Point<T>| a.distance(b) <= tolerance;
+
+    for i in 0..open.len() {
+        let mut chain = match open[i].take() {
+            Some(chain) => chain,
+            None => continue,
+        };
+
+        // Extend the chain forwards, and then backwards, for as long as possible.
+        for backwards in [false, true].iter().copied() {
+            if backwards {
+                chain = chain.reverse();
+            }
+
+            while let Some((j, reverse)) = open.iter().enumerate().find_map(|(j, other)| {
+                let other = other.as_ref()?;
+                if close_to(chain.last(), other.first) {
+                    Some((j, false))
+                } else if close_to(chain.last(), other.last()) {
+                    Some((j, true))
+                } else {
+                    None
+                }
+            }) {
+                let mut next = open[j].take().unwrap();
+                if reverse {
+                    next = next.reverse();
+                }
+                chain.rest.extend(next.rest);
+            }
+
+            if backwards {
+                chain = chain.reverse();
+            }
+        }
+
+        joined.push(PathBuffer::new(chain.first, chain.rest));
+    }
+
+    joined
+}
+
+/// A summary of the work needed to draw a scene, as returned by [`scene_report`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SceneReport<T: Copy> {
+    /// The number of paths in the scene.
+    pub paths: usize,
+
+    /// The total length of every path, once flattened.
+    ///
+    /// This includes the segments that close closed subpaths, but not the travel
+    /// between subpaths.
+    pub length: T,
+
+    /// The bounds of everything that is drawn, or `None` if nothing is drawn.
+    pub bounds: Option<Box<T>>,
+
+    /// An estimate of the number of trapezoids needed to fill every path.
+    ///
+    /// This is the number of flattened edges that aren't horizontal, which is how many
+    /// trapezoids a shape without self-intersections tessellates into, give or take.
+    pub trapezoids: usize,
+}
+
+/// Measure a scene of transformed paths in a single pass.
+///
+/// Each path is transformed before it is flattened, so `tolerance` is in the same units
+/// as the output.
+pub fn scene_report<T, I, P>(scene: I, tolerance: T) -> SceneReport<T>
+where
+    T: Real + ApproxEq,
+    I: IntoIterator<Item = (Affine<T>, P)>,
+    P: Path<T>,
+{
+    let mut report = SceneReport {
+        paths: 0,
+        length: T::zero(),
+        bounds: None,
+        trapezoids: 0,
+    };
+
+    for (transform, path) in scene {
+        report.paths += 1;
+
+        let events = path
+            .path_iter()
+            .map(|event| event.map(|point| transform.transform_point(point)));
+
+        for event in Flattened::new(events, tolerance) {
+            let (from, to, drawn) = match event {
+                StraightPathEvent::Begin { at } => {
+                    let bounds = report
+                        .bounds
+                        .map_or(Box::new(at, at), |b| b.with_point(&at));
+                    report.bounds = Some(bounds);
+                    continue;
+                }
+                StraightPathEvent::Line { from, to } => (from, to, true),
+                StraightPathEvent::End { first, last, close } => (last, first, close),
+                _ => continue,
+            };
+
+            if drawn {
+                report.length = report.length + from.distance(to);
+            }
+            if let Some(bounds) = &mut report.bounds {
+                *bounds = bounds.with_point(&to);
+            }
+            if !from.y().approx_eq(&to.y()) {
+                report.trapezoids += 1;
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::line::LineSegment;
+    use alloc::vec;
+
+    fn line(x1: f32, y1: f32, x2: f32, y2: f32) -> LineSegment<f32> {
+        LineSegment::new(Point::new(x1, y1), Point::new(x2, y2))
+    }
+
+    #[test]
+    fn test_order_greedy() {
+        let paths = [
+            line(10.0, 0.0, 11.0, 0.0),
+            line(2.0, 0.0, 1.0, 0.0),
+            line(3.0, 0.0, 9.0, 0.0),
+        ];
+
+        let order = order_paths(paths.iter().copied(), OrderOptions::default());
+        assert_eq!(
+            order,
+            vec![
+                PlannedPath {
+                    index: 1,
+                    reversed: true
+                },
+                PlannedPath {
+                    index: 2,
+                    reversed: false
+                },
+                PlannedPath {
+                    index: 0,
+                    reversed: false
+                },
+            ]
+        );
+
+        let options = OrderOptions {
+            allow_reverse: false,
+            ..OrderOptions::default()
+        };
+        let order = order_paths(paths.iter().copied(), options);
+        assert!(order.iter().all(|step| !step.reversed));
+        assert_eq!(order[0].index, 1);
+    }
+
+    #[test]
+    fn test_order_two_opt() {
+        // Greedy goes right first and then has to come all the way back.
+        let paths = [
+            line(1.0, 0.0, 1.0, 1.0),
+            line(-1.5, 0.0, -1.5, 1.0),
+            line(-3.0, 0.0, -3.0, 1.0),
+            line(30.0, 0.0, 30.0, 1.0),
+        ];
+
+        let travel = |order: &[PlannedPath]| {
+            let mut pen = Point::new(0.0f32, 0.0);
+            let mut total = 0.0;
+            for step in order {
+                let segment = paths[step.index];
+                let (start, end) = if step.reversed {
+                    (segment.to(), segment.from())
+                } else {
+                    (segment.from(), segment.to())
+                };
+                total += pen.distance(start);
+                pen = end;
+            }
+            total
+        };
+
+        let greedy = OrderOptions {
+            two_opt: false,
+            ..OrderOptions::default()
+        };
+        let greedy = order_paths(paths.iter().copied(), greedy);
+        let improved = order_paths(paths.iter().copied(), OrderOptions::default());
+        assert!(travel(&improved) < travel(&greedy));
+    }
+
+    #[test]
+    fn test_join() {
+        let paths = [
+            line(0.0, 0.0, 1.0, 0.0),
+            line(2.0, 0.0, 1.0, 0.001),
+            line(5.0, 5.0, 6.0, 6.0),
+            line(-1.0, 0.0, 0.0, 0.0),
+        ];
+
+        let joined = join_paths(paths.iter().copied(), 0.01);
+        assert_eq!(joined.len(), 2);
+
+        let ends = endpoints(&joined[0]).unwrap();
+        assert_eq!(ends, (Point::new(-1.0, 0.0), Point::new(2.0, 0.0)));
+        assert_eq!((&joined[0]).stats().lines, 3);
+    }
+
+    #[test]
+    fn test_join_keeps_closed() {
+        let square = Box::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0));
+        let joined = join_paths(vec![square], 0.01);
+        assert_eq!(joined.len(), 1);
+        assert!(joined[0].is_closed());
+        assert_eq!((&joined[0]).stats().lines, 3);
+    }
+
+    /// A path that stops without ending its subpath.
+    struct Unended(Vec<PathEvent<f32>>);
+
+    impl Path<f32> for Unended {
+        type Iter = vec::IntoIter<PathEvent<f32>>;
+
+        fn path_iter(self) -> Self::Iter {
+            self.0.into_iter()
+        }
+    }
+
+    #[test]
+    fn test_join_unended() {
+        let (a, b) = (Point::new(1.0, 0.0), Point::new(2.0, 0.0));
+        let unended = Unended(vec![
+            PathEvent::Begin { at: a },
+            PathEvent::Line { from: a, to: b },
+        ]);
+
+        let joined = join_paths(vec![unended], 0.01);
+        assert_eq!(joined.len(), 1);
+        assert_eq!(endpoints(&joined[0]), Some((a, b)));
+        assert!(!joined[0].is_closed());
+    }
+
+    #[test]
+    fn test_scene_report() {
+        let square = Box::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0));
+        let flat = Box::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0));
+        let report = scene_report(
+            vec![
+                (Affine::scale(2.0, 2.0), square),
+                (Affine::translate(5.0, 5.0), flat),
+            ],
+            0.1,
+        );
+
+        assert_eq!(report.paths, 2);
+        assert_eq!(report.length, 10.0);
+        assert_eq!(
+            report.bounds,
+            Some(Box::new(Point::new(0.0, 0.0), Point::new(6.0, 5.0)))
+        );
+        assert_eq!(report.trapezoids, 2);
+    }
+}
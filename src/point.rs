@@ -15,7 +15,9 @@
 // You should have received a copy of the GNU Affero General Public License 
 // along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
 
+use crate::angle::Angle;
 use crate::pair::{Double, Quad};
+use crate::size::Size;
 use crate::transform::Transformable;
 use crate::ApproxEq;
 
@@ -139,6 +141,18 @@ macro_rules! two_dimensional {
             pub fn from_tuple((a, b): (T, T)) -> Self {
                 $name(Double::new([a, b]))
             }
+
+            /// Get a copy with the X coordinate replaced.
+            #[inline]
+            pub fn with_x(self, x: T) -> Self {
+                $name(Double::new([x, self.y()]))
+            }
+
+            /// Get a copy with the Y coordinate replaced.
+            #[inline]
+            pub fn with_y(self, y: T) -> Self {
+                $name(Double::new([self.x(), y]))
+            }
         }
 
         impl<T: Copy + Zero> $name<T> {
@@ -516,6 +530,92 @@ impl<T: Copy> Vector<T> {
     {
         other.scale_uniform(self.dot(other) / other.length_squared())
     }
+
+    /// Reflect this vector across a surface with the given unit normal.
+    #[inline]
+    pub fn reflect(self, normal: Self) -> Self
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + ops::Mul<Output = T>,
+    {
+        let dot = self.dot(normal);
+        self - normal * (dot + dot)
+    }
+
+    /// Get the smaller of the two coordinates.
+    #[inline]
+    pub fn min_element(self) -> T
+    where
+        T: PartialOrd,
+    {
+        if self.y() < self.x() {
+            self.y()
+        } else {
+            self.x()
+        }
+    }
+
+    /// Get the larger of the two coordinates.
+    #[inline]
+    pub fn max_element(self) -> T
+    where
+        T: PartialOrd,
+    {
+        if self.y() > self.x() {
+            self.y()
+        } else {
+            self.x()
+        }
+    }
+
+    /// Create a unit vector pointing in the direction of the given angle.
+    #[inline]
+    pub fn from_angle(angle: Angle<T>) -> Self
+    where
+        T: Real,
+    {
+        let (sin, cos) = angle.sin_cos();
+        Vector::new(cos, sin)
+    }
+
+    /// Rotate this vector by the given angle.
+    #[inline]
+    pub fn rotate(self, angle: Angle<T>) -> Self
+    where
+        T: Real,
+    {
+        let (sin, cos) = angle.sin_cos();
+        Vector::new(
+            self.x() * cos - self.y() * sin,
+            self.x() * sin + self.y() * cos,
+        )
+    }
+
+    /// Get the vector perpendicular to this one, rotated 90 degrees counter-clockwise.
+    #[inline]
+    pub fn perp(self) -> Self
+    where
+        T: ops::Neg<Output = T>,
+    {
+        Vector::new(-self.y(), self.x())
+    }
+
+    /// Get the angle between the positive X axis and this vector.
+    #[inline]
+    pub fn angle(self) -> Angle<T>
+    where
+        T: Real,
+    {
+        Angle::from_radians(self.y().atan2(self.x()))
+    }
+
+    /// Get the signed angle needed to rotate this vector onto another vector.
+    #[inline]
+    pub fn angle_between(self, other: Self) -> Angle<T>
+    where
+        T: Real,
+    {
+        Angle::from_radians(self.cross(other).atan2(self.dot(other)))
+    }
 }
 
 impl<T: Copy> Point<T> {
@@ -537,6 +637,15 @@ impl<T: Copy> Point<T> {
         (self - other).length_squared()
     }
 
+    /// Snap this point to the nearest corner of a grid with the given cell size.
+    #[inline]
+    pub fn snap_to_grid(self, cell: Size<T>) -> Self
+    where
+        T: Real,
+    {
+        Self((self.0 / cell.0).round() * cell.0)
+    }
+
     /// Get the midpoint between this point and another point.
     #[inline]
     pub fn midpoint(self, other: Self) -> Self
@@ -550,7 +659,7 @@ impl<T: Copy> Point<T> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Point, Vector};
+    use super::{Angle, Point, Size, Vector};
 
     #[test]
     fn test_point() {
@@ -651,8 +760,49 @@ mod tests {
         let vector1 = Vector::new(3.0, 4.0);
         let vector2 = Vector::new(1.0, 2.0);
         let result = vector1.project(vector2);
-        assert_eq!(result.x(), 1.2);
-        assert_eq!(result.y(), 2.4);
+
+        // (3, 4) · (1, 2) = 11 and |(1, 2)|² = 5, so the projection is (1, 2) * 2.2.
+        assert_eq!(result.x(), 2.2);
+        assert_eq!(result.y(), 4.4);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let vector = Vector::new(1.0f32, 0.0);
+        let result = vector.rotate(Angle::quarter_turn());
+        assert!(result.x().abs() < 1e-6);
+        assert!((result.y() - 1.0).abs() < 1e-6);
+        assert_eq!(vector.perp(), Vector::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_angle() {
+        let vector = Vector::new(0.0f32, 2.0);
+        assert!((vector.angle().radians() - core::f32::consts::FRAC_PI_2).abs() < 1e-6);
+
+        let other = Vector::new(1.0f32, 0.0);
+        let between = vector.angle_between(other);
+        assert!((between.radians() + core::f32::consts::FRAC_PI_2).abs() < 1e-6);
+
+        let unit: Vector<f32> = Vector::from_angle(Angle::half_turn());
+        assert!((unit.x() + 1.0).abs() < 1e-6);
+        assert!(unit.y().abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_reflect() {
+        let vector = Vector::new(1.0, -1.0);
+        let result = vector.reflect(Vector::new(0.0, 1.0));
+        assert_eq!(result, Vector::new(1.0, 1.0));
+        assert_eq!(result.with_x(3.0).min_element(), 1.0);
+        assert_eq!(result.with_y(5.0).max_element(), 5.0);
+    }
+
+    #[test]
+    fn test_snap_to_grid() {
+        let point = Point::new(13.0, -7.0);
+        let result = point.snap_to_grid(Size::new(5.0, 4.0));
+        assert_eq!(result, Point::new(15.0, -8.0));
     }
 
     #[test]
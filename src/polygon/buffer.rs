@@ -0,0 +1,197 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Growing and shrinking shapes by a fixed distance.
+
+use super::overlay::overlay;
+use super::{assemble, rings, signed_area, Polygon};
+use crate::path::Shape;
+use crate::point::{Point, Vector};
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// Grow or shrink a shape by the given distance.
+///
+/// A positive `distance` grows the shape to cover every point within `distance` of it,
+/// while a negative `distance` shrinks it to the points further than `-distance` from its
+/// boundary. Corners are rounded, and holes shrink as the shape grows and vice versa.
+///
+/// Curves in the shape and the rounded corners are flattened to within `arc_tolerance`.
+/// The shape is filled using the non-zero fill rule.
+pub fn buffer<T, S>(shape: S, distance: T, arc_tolerance: T) -> Vec<Polygon<T>>
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    // Clean the shape up first, so that the rings are simple and consistently oriented.
+    let shape = overlay(&rings(shape, arc_tolerance), &[], |w| w[0] != 0);
+    if distance.is_zero() {
+        return assemble(shape);
+    }
+
+    // The rings have the shape on their left, so the outside is on their right.
+    let grow = distance > T::zero();
+    let radius = distance.abs();
+    let mut pieces = Vec::new();
+
+    for ring in &shape {
+        for (i, &point) in ring.iter().enumerate() {
+            let next = ring[(i + 1) % ring.len()];
+            let prev = ring[(i + ring.len() - 1) % ring.len()];
+
+            let normal = |from: Point<T>, to: Point<T>| {
+                let perp = (to - from).normalize().perp();
+                if grow {
+                    -perp * radius
+                } else {
+                    perp * radius
+                }
+            };
+
+            // The band along the edge.
+            let offset = normal(point, next);
+            pieces.push(oriented(alloc::vec![
+                point,
+                point + offset,
+                next + offset,
+                next
+            ]));
+
+            // The rounded corner, if the ring turns away from the side being offset.
+            let (incoming, outgoing) = (normal(prev, point), offset);
+            let turn = (point - prev).cross(next - point);
+            if (grow && turn > T::zero()) || (!grow && turn < T::zero()) {
+                pieces.push(oriented(corner(
+                    point,
+                    incoming,
+                    outgoing,
+                    radius,
+                    arc_tolerance,
+                )));
+            }
+        }
+    }
+
+    let result = if grow {
+        overlay(&shape, &pieces, |w| w[0] > 0 || w[1] > 0)
+    } else {
+        overlay(&shape, &pieces, |w| w[0] > 0 && w[1] == 0)
+    };
+
+    assemble(result)
+}
+
+/// Build a wedge around `center` that sweeps from one offset to another.
+fn corner<T: Real>(
+    center: Point<T>,
+    from: Vector<T>,
+    to: Vector<T>,
+    radius: T,
+    tolerance: T,
+) -> Vec<Point<T>> {
+    let sweep = from.angle_between(to).radians();
+
+    // Use enough steps that the chords stay within the tolerance of the arc.
+    let two = T::one() + T::one();
+    let step = if tolerance < radius {
+        two * (T::one() - tolerance / radius).acos()
+    } else {
+        sweep.abs()
+    };
+    let steps = (sweep.abs() / step).ceil().to_usize().unwrap_or(1).max(1);
+
+    let mut wedge = alloc::vec![center, center + from];
+    for i in 1..steps {
+        let angle = sweep * T::from(i).unwrap() / T::from(steps).unwrap();
+        wedge.push(center + from.rotate(crate::Angle::from_radians(angle)));
+    }
+    wedge.push(center + to);
+
+    wedge
+}
+
+/// Make sure a ring has a positive signed area.
+fn oriented<T: Real>(mut ring: Vec<Point<T>>) -> Vec<Point<T>> {
+    if signed_area(&ring) < T::zero() {
+        ring.reverse();
+    }
+
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::box2d::Box;
+
+    fn area(polygons: &[Polygon<f64>]) -> f64 {
+        polygons.iter().map(|polygon| polygon.area(0.01)).sum()
+    }
+
+    #[test]
+    fn test_grow() {
+        let shape = Box::new(Point::new(0.0f64, 0.0), Point::new(4.0, 2.0));
+        let polygons = buffer(shape, 1.0, 0.001);
+
+        // The rectangle, four bands and four quarter circles.
+        let expected = 8.0 + 12.0 + core::f64::consts::PI;
+        assert_eq!(polygons.len(), 1);
+        assert!((area(&polygons) - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_shrink() {
+        let shape = Box::new(Point::new(0.0f64, 0.0), Point::new(4.0, 2.0));
+
+        let polygons = buffer(shape, -0.5, 0.001);
+        assert_eq!(polygons.len(), 1);
+        assert!((area(&polygons) - 3.0).abs() < 1e-6);
+
+        assert!(buffer(shape, -1.0, 0.001).is_empty());
+    }
+
+    #[test]
+    fn test_holes() {
+        // A square frame, whose hole closes up once it grows enough.
+        let mut inner = alloc::vec![
+            Point::new(1.0f64, 1.0),
+            Point::new(3.0, 1.0),
+            Point::new(3.0, 3.0),
+            Point::new(1.0, 3.0),
+        ];
+        inner.reverse();
+        let frame = Polygon::new(
+            alloc::vec![
+                Point::new(0.0, 0.0),
+                Point::new(4.0, 0.0),
+                Point::new(4.0, 4.0),
+                Point::new(0.0, 4.0),
+            ],
+            alloc::vec![inner],
+        );
+
+        let grown = buffer(&frame, 0.5, 0.001);
+        assert_eq!(grown.len(), 1);
+        assert_eq!(grown[0].interiors().len(), 1);
+        assert!((signed_area(&grown[0].interiors()[0]) + 1.0).abs() < 1e-6);
+
+        let closed = buffer(&frame, 1.5, 0.001);
+        assert!(closed[0].interiors().is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Intersecting convex polygons in linear time.
+
+use super::{crossing, signed_area, winding, Crossing};
+use crate::point::Point;
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// Which polygon's boundary is currently inside the other one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Inside {
+    /// No crossing has been found yet.
+    Unknown,
+
+    /// The first polygon is inside the second.
+    A,
+
+    /// The second polygon is inside the first.
+    B,
+}
+
+/// Intersect two convex polygons.
+///
+/// This walks around both polygons at once using O'Rourke's algorithm, so it runs in
+/// `O(n + m)` time. The polygons can be in either orientation, but must be convex. The
+/// result is a convex ring with a positive signed area, or an empty list if the
+/// polygons only touch or don't overlap at all.
+pub fn intersect_convex<T: Real>(a: &[Point<T>], b: &[Point<T>]) -> Vec<Point<T>> {
+    if a.len() < 3 || b.len() < 3 {
+        return Vec::new();
+    }
+
+    let (p, q) = (counter_clockwise(a), counter_clockwise(b));
+    let (n, m) = (p.len(), q.len());
+
+    let mut result = Vec::new();
+    let mut inside = Inside::Unknown;
+    let (mut i, mut j) = (0, 0);
+    let (mut advanced_a, mut advanced_b) = (0, 0);
+
+    let side =
+        |from: Point<T>, to: Point<T>, point: Point<T>| sign((to - from).cross(point - from));
+
+    loop {
+        let (p1, p2) = (p[(i + n - 1) % n], p[i]);
+        let (q1, q2) = (q[(j + m - 1) % m], q[j]);
+        let (da, db) = (p2 - p1, q2 - q1);
+
+        let turn = sign(da.cross(db));
+        let a_of_b = side(q1, q2, p2);
+        let b_of_a = side(p1, p2, q2);
+
+        match crossing(p1, p2, q1, q2) {
+            Crossing::Point { point, .. } => {
+                // Go around both polygons once more from the first crossing.
+                if inside == Inside::Unknown && result.is_empty() {
+                    advanced_a = 0;
+                    advanced_b = 0;
+                }
+                push(&mut result, point);
+
+                if a_of_b > 0 {
+                    inside = Inside::A;
+                } else if b_of_a > 0 {
+                    inside = Inside::B;
+                }
+            }
+            Crossing::Overlap if da.dot(db) < T::zero() => {
+                // The polygons only share an edge.
+                return Vec::new();
+            }
+            _ => {}
+        }
+
+        // Advance whichever edge is "behind" the other one.
+        let advance_a = if turn == 0 && a_of_b < 0 && b_of_a < 0 {
+            // The edges are parallel and face apart, so the polygons are disjoint.
+            return Vec::new();
+        } else if turn == 0 && a_of_b == 0 && b_of_a == 0 {
+            inside != Inside::A
+        } else if turn >= 0 {
+            b_of_a > 0
+        } else {
+            a_of_b <= 0
+        };
+
+        if advance_a {
+            if inside == Inside::A {
+                push(&mut result, p2);
+            }
+            i = (i + 1) % n;
+            advanced_a += 1;
+        } else {
+            if inside == Inside::B {
+                push(&mut result, q2);
+            }
+            j = (j + 1) % m;
+            advanced_b += 1;
+        }
+
+        let done = advanced_a >= n && advanced_b >= m;
+        if done || advanced_a >= 2 * n || advanced_b >= 2 * m {
+            break;
+        }
+    }
+
+    if inside == Inside::Unknown {
+        // The boundaries never cross, so one polygon is either inside the other or they
+        // are apart.
+        return if winding(&q, p[0]) != 0 {
+            p.into_owned()
+        } else if winding(&p, q[0]) != 0 {
+            q.into_owned()
+        } else {
+            Vec::new()
+        };
+    }
+
+    // The walk may return to the first point.
+    while result.len() > 1 && result.first() == result.last() {
+        result.pop();
+    }
+
+    if result.len() < 3 {
+        result.clear();
+    }
+
+    result
+}
+
+/// Make sure a ring is counter-clockwise, copying it if it has to be reversed.
+fn counter_clockwise<T: Real>(ring: &[Point<T>]) -> Cow<'_, [Point<T>]> {
+    if signed_area(ring) < T::zero() {
+        Cow::Owned(ring.iter().rev().copied().collect())
+    } else {
+        Cow::Borrowed(ring)
+    }
+}
+
+/// Add a point to the result, unless it repeats the previous one.
+fn push<T: Real>(result: &mut Vec<Point<T>>, point: Point<T>) {
+    if result.last() != Some(&point) {
+        result.push(point);
+    }
+}
+
+/// Get the sign of a number as an integer.
+fn sign<T: Real>(value: T) -> i32 {
+    if value > T::zero() {
+        1
+    } else if value < T::zero() {
+        -1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Point<f64>> {
+        vec![
+            Point::new(x, y),
+            Point::new(x + size, y),
+            Point::new(x + size, y + size),
+            Point::new(x, y + size),
+        ]
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let result = intersect_convex(&square(0.0, 0.0, 2.0), &square(1.0, 1.0, 2.0));
+        assert_eq!(result.len(), 4);
+        assert!((signed_area(&result) - 1.0).abs() < 1e-9);
+
+        // Orientation doesn't matter.
+        let mut reversed = square(1.0, 1.0, 2.0);
+        reversed.reverse();
+        let result = intersect_convex(&square(0.0, 0.0, 2.0), &reversed);
+        assert!((signed_area(&result) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_triangle_and_square() {
+        let triangle = [
+            Point::new(-1.0, 0.5),
+            Point::new(3.0, 0.5),
+            Point::new(1.0, 3.0),
+        ];
+
+        let result = intersect_convex(&triangle, &square(0.0, 0.0, 2.0));
+        assert!(result.len() >= 4);
+
+        // A rectangle up to y = 1.75, and then a trapezoid up to y = 2.
+        let expected = 2.0 * 1.25 + 0.5 * (2.0 + 1.6) * 0.25;
+        assert!((signed_area(&result) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_contained_and_disjoint() {
+        let inner = square(1.0, 1.0, 1.0);
+        assert_eq!(intersect_convex(&square(0.0, 0.0, 4.0), &inner), inner);
+        assert_eq!(intersect_convex(&inner, &square(0.0, 0.0, 4.0)), inner);
+        assert!(intersect_convex(&square(0.0, 0.0, 1.0), &square(5.0, 5.0, 1.0)).is_empty());
+        assert!(intersect_convex(&square(0.0, 0.0, 1.0), &square(1.0, 0.0, 1.0)).is_empty());
+
+        let same = intersect_convex(&square(0.0, 0.0, 1.0), &square(0.0, 0.0, 1.0));
+        assert!((signed_area(&same) - 1.0).abs() < 1e-9);
+    }
+}
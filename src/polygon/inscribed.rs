@@ -0,0 +1,217 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Approximating the largest rectangle inside of a shape.
+
+use super::{rings, winding};
+use crate::box2d::Box;
+use crate::path::Shape;
+use crate::point::Point;
+use crate::ApproxEq;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// Approximate the largest axis-aligned rectangle that fits inside of a shape.
+///
+/// The shape's bounding box is split into a grid of square cells that are `tolerance`
+/// units wide, and the largest rectangle made up of cells that lie entirely inside of the
+/// shape is returned. The rectangle is always contained in the shape, but it may be up to
+/// `tolerance` units smaller than the true largest rectangle on each side.
+///
+/// The shape is filled using the non-zero fill rule. Returns `None` if no cell fits
+/// inside of the shape.
+pub fn largest_inscribed_rect<T, S>(shape: S, tolerance: T) -> Option<Box<T>>
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    let rings = rings(shape, tolerance);
+
+    let mut points = rings.iter().flatten();
+    let first = *points.next()?;
+    let (min, max) = points.fold((first, first), |(min, max), &p| (min.min(p), max.max(p)));
+
+    let columns = ((max.x() - min.x()) / tolerance).ceil().to_usize()?;
+    let rows = ((max.y() - min.y()) / tolerance).ceil().to_usize()?;
+    if columns == 0 || rows == 0 {
+        return None;
+    }
+
+    let cell = |column: usize, row: usize| {
+        let corner = Point::new(
+            min.x() + T::from(column).unwrap() * tolerance,
+            min.y() + T::from(row).unwrap() * tolerance,
+        );
+        Box::new(corner, corner + crate::Vector::new(tolerance, tolerance))
+    };
+    let index = |value: T, start: T, count: usize| {
+        ((value - start) / tolerance)
+            .floor()
+            .to_usize()
+            .unwrap_or(0)
+            .min(count - 1)
+    };
+
+    // Block off every cell that an edge passes through.
+    let mut blocked = vec![false; columns * rows];
+    for ring in &rings {
+        for (i, &from) in ring.iter().enumerate() {
+            let to = ring[(i + 1) % ring.len()];
+            let (low, high) = (from.min(to), from.max(to));
+
+            for row in index(low.y(), min.y(), rows)..=index(high.y(), min.y(), rows) {
+                for column in index(low.x(), min.x(), columns)..=index(high.x(), min.x(), columns) {
+                    if touches(from, to, &cell(column, row)) {
+                        blocked[row * columns + column] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    // Find the largest rectangle of free cells, using the histogram of free cells above
+    // each row.
+    let mut heights = vec![0usize; columns];
+    let mut best_area = 0;
+    let mut best = None;
+    let mut stack = Vec::new();
+
+    for row in 0..rows {
+        for (column, height) in heights.iter_mut().enumerate() {
+            let free = !blocked[row * columns + column] && {
+                let center = cell(column, row).center();
+                rings.iter().map(|ring| winding(ring, center)).sum::<i32>() != 0
+            };
+            *height = if free { *height + 1 } else { 0 };
+        }
+
+        stack.clear();
+        for column in 0..=columns {
+            let height = heights.get(column).copied().unwrap_or(0);
+
+            while let Some(&top) = stack.last() {
+                if heights[top] < height {
+                    break;
+                }
+                stack.pop();
+
+                let top_height = heights[top];
+                let start = stack.last().map_or(0, |&left| left + 1);
+                let area = top_height * (column - start);
+                if area > best_area {
+                    best_area = area;
+                    best = Some([start, row + 1 - top_height, column, row + 1]);
+                }
+            }
+
+            stack.push(column);
+        }
+    }
+
+    best.map(|[left, top, right, bottom]| {
+        Box::new(cell(left, top).min(), cell(right - 1, bottom - 1).max())
+    })
+}
+
+/// Tell whether a line segment touches a box, by clipping it against the box's sides.
+fn touches<T: Real>(from: Point<T>, to: Point<T>, bounds: &Box<T>) -> bool {
+    let delta = to - from;
+    let (mut start, mut end) = (T::zero(), T::one());
+
+    let sides = [
+        (-delta.x(), from.x() - bounds.min().x()),
+        (delta.x(), bounds.max().x() - from.x()),
+        (-delta.y(), from.y() - bounds.min().y()),
+        (delta.y(), bounds.max().y() - from.y()),
+    ];
+
+    for &(p, q) in sides.iter() {
+        if p.is_zero() {
+            if q < T::zero() {
+                return false;
+            }
+        } else {
+            let t = q / p;
+            if p < T::zero() {
+                start = start.max(t);
+            } else {
+                end = end.min(t);
+            }
+        }
+    }
+
+    start <= end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polygon::Polygon;
+
+    #[test]
+    fn test_l_shape() {
+        let shape = Polygon::new(
+            vec![
+                Point::new(0.0f64, 0.0),
+                Point::new(10.0, 0.0),
+                Point::new(10.0, 4.0),
+                Point::new(3.0, 4.0),
+                Point::new(3.0, 10.0),
+                Point::new(0.0, 10.0),
+            ],
+            Vec::new(),
+        );
+
+        let rect = largest_inscribed_rect(&shape, 0.25).unwrap();
+        assert!(rect.min().x() >= 0.0 && rect.min().y() >= 0.0);
+        assert!(rect.max().x() <= 10.0 && rect.max().y() <= 4.0);
+
+        // The bottom bar is the largest part, with an area of 40.
+        let area = Box::area(&rect);
+        assert!(area > 30.0 && area <= 40.0, "{:?}", rect);
+    }
+
+    #[test]
+    fn test_triangle() {
+        let shape = Polygon::new(
+            vec![
+                Point::new(0.0f64, 0.0),
+                Point::new(8.0, 0.0),
+                Point::new(0.0, 8.0),
+            ],
+            Vec::new(),
+        );
+
+        // The largest rectangle in a right triangle takes up half of its area.
+        let rect = largest_inscribed_rect(&shape, 0.1).unwrap();
+        assert!(
+            Box::area(&rect) > 14.0 && Box::area(&rect) <= 16.0,
+            "{:?}",
+            rect
+        );
+        assert!(rect.max().x() + rect.max().y() <= 8.0);
+    }
+
+    #[test]
+    fn test_too_small() {
+        let shape = Box::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0));
+        assert!(largest_inscribed_rect(shape, 0.75).is_none());
+        assert!(largest_inscribed_rect(&Polygon::<f32>::default(), 1.0).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Approximating the medial axis of a shape.
+
+use super::{rings, winding};
+use crate::line::LineSegment;
+use crate::path::Shape;
+use crate::point::Point;
+use crate::ApproxEq;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// Approximate the medial axis of a shape.
+///
+/// The medial axis is made up of the points inside of the shape that are equally close
+/// to two or more parts of its boundary, and runs down the middle of the shape. It is
+/// approximated by sampling the boundary every `tolerance` units and taking the edges of
+/// the samples' Voronoi diagram that are inside of the shape. Edges between neighboring
+/// samples are pruned, since they only lead back to the boundary.
+///
+/// The shape is filled using the non-zero fill rule.
+pub fn medial_axis<T, S>(shape: S, tolerance: T) -> Vec<LineSegment<T>>
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    let rings = rings(shape, tolerance);
+
+    // Sample the boundary, keeping track of where each sample came from.
+    let mut samples = Vec::new();
+    let mut origins = Vec::new();
+    for (ring_index, ring) in rings.iter().enumerate() {
+        let start = samples.len();
+
+        for (i, &from) in ring.iter().enumerate() {
+            let to = ring[(i + 1) % ring.len()];
+            let steps = (from.distance(to) / tolerance)
+                .ceil()
+                .to_usize()
+                .unwrap_or(1)
+                .max(1);
+
+            for step in 0..steps {
+                let t = T::from(step).unwrap() / T::from(steps).unwrap();
+                samples.push(from.lerp(to, t));
+            }
+        }
+
+        let count = samples.len() - start;
+        origins.extend((0..count).map(|i| (ring_index, i, count)));
+    }
+
+    let inside = |point: Point<T>| rings.iter().map(|ring| winding(ring, point)).sum::<i32>() != 0;
+    let neighbors = |a: usize, b: usize| {
+        let ((ring_a, i, count), (ring_b, j, _)) = (origins[a], origins[b]);
+        ring_a == ring_b && ((i + 1) % count == j || (j + 1) % count == i)
+    };
+
+    // Every Delaunay edge shared by two triangles is dual to a Voronoi edge between the
+    // triangles' circumcenters.
+    let triangles = delaunay(&samples);
+    let mut shared = BTreeMap::<(usize, usize), Vec<Point<T>>>::new();
+    for &(triangle, center) in &triangles {
+        for k in 0..3 {
+            let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+            shared.entry((a.min(b), a.max(b))).or_default().push(center);
+        }
+    }
+
+    shared
+        .into_iter()
+        .filter(|&((a, b), ref centers)| centers.len() == 2 && !neighbors(a, b))
+        .map(|(_, centers)| LineSegment::new(centers[0], centers[1]))
+        .filter(|segment| {
+            segment.from() != segment.to() && inside(segment.from()) && inside(segment.to())
+        })
+        .collect()
+}
+
+/// A triangle, by the indices of its points, along with its circumcenter.
+type Triangle<T> = ([usize; 3], Point<T>);
+
+/// Compute the Delaunay triangulation of a set of points using Bowyer-Watson.
+fn delaunay<T: Real>(points: &[Point<T>]) -> Vec<Triangle<T>> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let (min, max) = points
+        .iter()
+        .fold((points[0], points[0]), |(min, max), &p| {
+            (min.min(p), max.max(p))
+        });
+    let size = (max - min).max_element().max(T::one());
+    let center = min.midpoint(max);
+    let far = size * T::from(64).unwrap();
+
+    // Start with a triangle that is large enough to contain every point.
+    let mut all = points.to_vec();
+    let n = all.len();
+    all.push(center + crate::Vector::new(-far, -far));
+    all.push(center + crate::Vector::new(far, -far));
+    all.push(center + crate::Vector::new(T::zero(), far));
+
+    let circle =
+        |triangle: [usize; 3]| circumcircle(all[triangle[0]], all[triangle[1]], all[triangle[2]]);
+    let mut triangles = alloc::vec![([n, n + 1, n + 2], circle([n, n + 1, n + 2]))];
+
+    for (index, &point) in points.iter().enumerate() {
+        let (bad, good): (Vec<_>, Vec<_>) =
+            triangles
+                .into_iter()
+                .partition(|&(_, (center, radius_squared))| {
+                    point.distance_squared(center) < radius_squared
+                });
+        triangles = good;
+
+        // The edges that only belong to one bad triangle bound the hole left behind.
+        let mut edges = BTreeMap::<(usize, usize), (usize, usize)>::new();
+        for (triangle, _) in bad {
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                let key = (a.min(b), a.max(b));
+                if edges.remove(&key).is_none() {
+                    edges.insert(key, (a, b));
+                }
+            }
+        }
+
+        for (_, (a, b)) in edges {
+            let triangle = [a, b, index];
+            triangles.push((triangle, circle(triangle)));
+        }
+    }
+
+    triangles
+        .into_iter()
+        .filter(|(triangle, _)| triangle.iter().all(|&i| i < n))
+        .map(|(triangle, (center, _))| (triangle, center))
+        .collect()
+}
+
+/// Get the center and squared radius of the circle through three points.
+fn circumcircle<T: Real>(a: Point<T>, b: Point<T>, c: Point<T>) -> (Point<T>, T) {
+    let (ab, ac) = (b - a, c - a);
+    let denom = (ab.cross(ac)) * (T::one() + T::one());
+
+    if denom.is_zero() {
+        // Collinear points have an infinitely large circle.
+        return (a, T::max_value());
+    }
+
+    let (ab2, ac2) = (ab.length_squared(), ac.length_squared());
+    let offset = crate::Vector::new(
+        (ac.y() * ab2 - ab.y() * ac2) / denom,
+        (ab.x() * ac2 - ac.x() * ab2) / denom,
+    );
+
+    (a + offset, offset.length_squared())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::box2d::Box;
+
+    fn distance_to_segment(point: Point<f64>, segment: &LineSegment<f64>) -> f64 {
+        let (from, to) = segment.points();
+        let t = ((point - from).dot(to - from) / (to - from).length_squared()).clamp(0.0, 1.0);
+        point.distance(from.lerp(to, t))
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let shape = Box::new(Point::new(0.0f64, 0.0), Point::new(10.0, 2.0));
+        let axis = medial_axis(shape, 0.1);
+        assert!(!axis.is_empty());
+
+        // The middle of the rectangle is covered by the axis.
+        for i in 2..=8 {
+            let point = Point::new(i as f64, 1.0);
+            let nearest = axis
+                .iter()
+                .map(|segment| distance_to_segment(point, segment))
+                .fold(f64::INFINITY, f64::min);
+            assert!(nearest < 0.1, "{:?} is {} away", point, nearest);
+        }
+
+        // Nothing is left hugging the long sides.
+        for segment in &axis {
+            for point in [segment.from(), segment.to()].iter() {
+                let x_margin = point.x().min(10.0 - point.x());
+                assert!(point.y() > 0.1 || x_margin < 1.0, "{:?}", point);
+            }
+        }
+    }
+
+    #[test]
+    fn test_circumcircle() {
+        let (center, radius_squared) = circumcircle(
+            Point::new(0.0f64, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(0.0, 2.0),
+        );
+
+        assert_eq!(center, Point::new(1.0, 1.0));
+        assert_eq!(radius_squared, 2.0);
+    }
+}
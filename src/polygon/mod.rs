@@ -0,0 +1,350 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Polygons with holes, and operations on them.
+
+#![cfg(feature = "alloc")]
+
+use crate::path::{Path, PathEvent, Shape, StraightPathEvent};
+use crate::point::Point;
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use core::slice::Iter as SliceIter;
+use num_traits::real::Real;
+
+mod buffer;
+mod convex;
+mod inscribed;
+mod medial;
+mod overlay;
+mod relate;
+pub(crate) mod sweep;
+mod validate;
+
+pub use buffer::buffer;
+pub use convex::intersect_convex;
+pub use inscribed::largest_inscribed_rect;
+pub use medial::medial_axis;
+pub use relate::Relation;
+pub use validate::ValidationError;
+
+/// A polygon, made up of an exterior ring and any number of interior rings.
+///
+/// Rings are implicitly closed, so the first point is not repeated at the end. By
+/// convention, the exterior has a positive signed area and the interiors, which cut
+/// holes out of the polygon, have a negative signed area.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polygon<T: Copy> {
+    /// The outer boundary of the polygon.
+    exterior: Vec<Point<T>>,
+
+    /// The holes in the polygon.
+    interiors: Vec<Vec<Point<T>>>,
+}
+
+impl<T: Copy> Polygon<T> {
+    /// Create a new `Polygon` from its exterior and interior rings.
+    pub fn new(exterior: Vec<Point<T>>, interiors: Vec<Vec<Point<T>>>) -> Self {
+        Polygon {
+            exterior,
+            interiors,
+        }
+    }
+
+    /// Get the exterior ring of this polygon.
+    pub fn exterior(&self) -> &[Point<T>] {
+        &self.exterior
+    }
+
+    /// Get the interior rings of this polygon.
+    pub fn interiors(&self) -> &[Vec<Point<T>>] {
+        &self.interiors
+    }
+
+    /// Split this polygon into its exterior and interior rings.
+    pub fn into_rings(self) -> (Vec<Point<T>>, Vec<Vec<Point<T>>>) {
+        (self.exterior, self.interiors)
+    }
+}
+
+impl<'a, T: Copy> Path<T> for &'a Polygon<T> {
+    type Iter = PolygonIter<'a, T>;
+
+    fn path_iter(self) -> Self::Iter {
+        PolygonIter {
+            exterior: Some(&self.exterior),
+            interiors: self.interiors.iter(),
+            ring: &[],
+            index: 0,
+        }
+    }
+}
+
+impl<T: Copy> Shape<T> for &Polygon<T> {}
+
+/// An iterator over the events in a [`Polygon`].
+#[derive(Debug, Clone)]
+pub struct PolygonIter<'a, T: Copy> {
+    /// The exterior ring, if it hasn't been started yet.
+    exterior: Option<&'a [Point<T>]>,
+
+    /// The remaining interior rings.
+    interiors: SliceIter<'a, Vec<Point<T>>>,
+
+    /// The ring currently being iterated over.
+    ring: &'a [Point<T>],
+
+    /// The index of the next event in the current ring.
+    index: usize,
+}
+
+impl<'a, T: Copy> Iterator for PolygonIter<'a, T> {
+    type Item = PathEvent<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Move on to the next non-empty ring if this one is finished.
+        while self.index > self.ring.len() || self.ring.is_empty() {
+            self.ring = match self.exterior.take() {
+                Some(exterior) => exterior,
+                None => self.interiors.next()?,
+            };
+            self.index = 0;
+        }
+
+        let index = self.index;
+        self.index += 1;
+
+        Some(match index {
+            0 => PathEvent::Begin { at: self.ring[0] },
+            i if i == self.ring.len() => PathEvent::End {
+                last: self.ring[i - 1],
+                first: self.ring[0],
+                close: true,
+            },
+            i => PathEvent::Line {
+                from: self.ring[i - 1],
+                to: self.ring[i],
+            },
+        })
+    }
+}
+
+impl<'a, T: Copy> FusedIterator for PolygonIter<'a, T> {}
+
+/// Get the signed area of a ring, which is positive for counter-clockwise rings when the
+/// Y axis points up.
+pub(crate) fn signed_area<T: Real>(ring: &[Point<T>]) -> T {
+    let last = match ring.last() {
+        Some(&last) => last,
+        None => return T::zero(),
+    };
+
+    let twice = ring
+        .iter()
+        .scan(last, |prev, &point| {
+            let from = core::mem::replace(prev, point);
+            Some(from.into_vector().cross(point.into_vector()))
+        })
+        .fold(T::zero(), |sum, cross| sum + cross);
+
+    twice / (T::one() + T::one())
+}
+
+/// Get the winding number of a ring around a point.
+pub(crate) fn winding<T: Real>(ring: &[Point<T>], point: Point<T>) -> i32 {
+    let last = match ring.last() {
+        Some(&last) => last,
+        None => return 0,
+    };
+
+    ring.iter()
+        .scan(last, |prev, &to| Some((core::mem::replace(prev, to), to)))
+        .map(|(from, to)| {
+            let side = (to - from).cross(point - from);
+            if from.y() <= point.y() && point.y() < to.y() && side > T::zero() {
+                1
+            } else if to.y() <= point.y() && point.y() < from.y() && side < T::zero() {
+                -1
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+/// How two segments intersect.
+pub(crate) enum Crossing<T: Copy> {
+    /// The segments don't intersect.
+    None,
+
+    /// The segments cross at a single point, possibly at one of their ends.
+    Point {
+        /// The point where the segments cross.
+        point: Point<T>,
+
+        /// How far along the first segment the point is, from zero to one.
+        t: T,
+
+        /// How far along the second segment the point is, from zero to one.
+        u: T,
+    },
+
+    /// The segments are collinear and overlap.
+    Overlap,
+}
+
+/// Find where the segment `a1..a2` meets the segment `b1..b2`.
+pub(crate) fn crossing<T: Real>(
+    a1: Point<T>,
+    a2: Point<T>,
+    b1: Point<T>,
+    b2: Point<T>,
+) -> Crossing<T> {
+    let (r, s) = (a2 - a1, b2 - b1);
+    let denom = r.cross(s);
+
+    if denom.is_zero() {
+        // Parallel segments only meet if they lie on the same line and overlap.
+        if !(b1 - a1).cross(r).is_zero() {
+            return Crossing::None;
+        }
+
+        let length = r.length_squared();
+        let t1 = (b1 - a1).dot(r) / length;
+        let t2 = (b2 - a1).dot(r) / length;
+        return if t1.max(t2) >= T::zero() && t1.min(t2) <= T::one() {
+            Crossing::Overlap
+        } else {
+            Crossing::None
+        };
+    }
+
+    let t = (b1 - a1).cross(s) / denom;
+    let u = (b1 - a1).cross(r) / denom;
+    let unit = |x: T| x >= T::zero() && x <= T::one();
+
+    if unit(t) && unit(u) {
+        Crossing::Point {
+            point: a1 + r * t,
+            t,
+            u,
+        }
+    } else {
+        Crossing::None
+    }
+}
+
+/// Flatten a path into a list of rings, treating every subpath as closed.
+pub(crate) fn rings<T, P>(path: P, tolerance: T) -> Vec<Vec<Point<T>>>
+where
+    T: Real + ApproxEq,
+    P: Path<T>,
+{
+    let mut rings = Vec::new();
+    let mut current = Vec::new();
+
+    for event in path.flatten(tolerance) {
+        match event {
+            StraightPathEvent::Begin { at } => current.push(at),
+            StraightPathEvent::Line { to, .. } => current.push(to),
+            StraightPathEvent::End { .. } => {
+                if current.len() > 1 && current.first() == current.last() {
+                    current.pop();
+                }
+
+                if current.len() > 2 {
+                    rings.push(core::mem::take(&mut current));
+                } else {
+                    current.clear();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    rings
+}
+
+/// Gather rings into polygons, using their orientation to tell exteriors from holes.
+///
+/// Each hole is assigned to the smallest exterior that contains it. Holes that aren't
+/// contained in any exterior are dropped.
+pub(crate) fn assemble<T: Real>(rings: Vec<Vec<Point<T>>>) -> Vec<Polygon<T>> {
+    let (exteriors, holes): (Vec<_>, Vec<_>) = rings
+        .into_iter()
+        .map(|ring| (signed_area(&ring), ring))
+        .partition(|(area, _)| *area > T::zero());
+
+    let mut polygons = exteriors
+        .into_iter()
+        .map(|(area, ring)| (area, Polygon::new(ring, Vec::new())))
+        .collect::<Vec<_>>();
+
+    for (_, hole) in holes {
+        let owner = polygons
+            .iter_mut()
+            .filter(|(_, polygon)| winding(&polygon.exterior, hole[0]) != 0)
+            .min_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+
+        if let Some((_, polygon)) = owner {
+            polygon.interiors.push(hole);
+        }
+    }
+
+    polygons.into_iter().map(|(_, polygon)| polygon).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(min: f32, max: f32) -> Vec<Point<f32>> {
+        [(min, min), (max, min), (max, max), (min, max)]
+            .iter()
+            .map(|&(x, y)| Point::new(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn test_path_iter() {
+        let mut hole = square(1.0, 2.0);
+        hole.reverse();
+        let polygon = Polygon::new(square(0.0, 4.0), alloc::vec![hole]);
+
+        assert_eq!((&polygon).path_iter().count(), 10);
+        assert_eq!((&polygon).area(0.1), 15.0);
+        assert_eq!(signed_area(polygon.exterior()), 16.0);
+
+        // Like every other shape, a hole only counts if it winds the other way.
+        let polygon = Polygon::new(square(0.0, 4.0), alloc::vec![square(1.0, 2.0)]);
+        assert_eq!((&polygon).area(0.1), 16.0);
+    }
+
+    #[test]
+    fn test_assemble() {
+        let mut hole = square(1.0, 2.0);
+        hole.reverse();
+        let rings = alloc::vec![hole, square(0.0, 4.0), square(5.0, 6.0)];
+
+        let polygons = assemble(rings);
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].interiors().len(), 1);
+        assert!(polygons[1].interiors().is_empty());
+    }
+}
@@ -0,0 +1,529 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Resolving overlapping rings into the outline of the area they cover.
+
+use super::sweep::overlapping_pairs;
+use crate::bentley_ottman::AbsoluteEq;
+use crate::point::Point;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num_traits::real::Real;
+
+/// Combine two sets of rings, keeping the areas whose winding numbers pass `keep`.
+///
+/// `keep` is given the winding number of each set of rings around a point. The result
+/// is a list of rings that bound the kept area, with the kept area always on their left
+/// side. This means that exteriors have a positive signed area and holes have a negative
+/// signed area.
+///
+/// Every edge is split where it crosses or touches another edge, and each piece is kept
+/// if it separates a kept area from one that isn't. Both steps sweep over the edges, so
+/// edges are only compared against the edges level with them. This is close to
+/// `O(n log n)` for typical rings, and only reaches `O(n²)` when most of the edges are
+/// level with one another.
+pub(crate) fn overlay<T: Real>(
+    subject: &[Vec<Point<T>>],
+    clip: &[Vec<Point<T>>],
+    keep: impl Fn([i32; 2]) -> bool,
+) -> Vec<Vec<Point<T>>> {
+    let mut graph = Graph::default();
+    for (operand, rings) in [subject, clip].iter().enumerate() {
+        for ring in rings.iter() {
+            graph.add_ring(ring, operand);
+        }
+    }
+
+    graph.split();
+    let pieces = graph.pieces();
+    let sides = graph.sides(&pieces);
+
+    // Keep the pieces that lie on the boundary of the kept area.
+    let mut kept = Vec::new();
+    for (&((a, b), multiplicity), &(left, right)) in pieces.iter().zip(sides.iter()) {
+        match (keep(left), keep(right)) {
+            (true, false) => kept.push((a, b)),
+            (false, true) => kept.push((b, a)),
+            _ => {}
+        }
+
+        debug_assert_ne!(multiplicity, [0, 0]);
+    }
+
+    graph.chain(kept)
+}
+
+/// The vertices and edges being combined.
+struct Graph<T: Copy> {
+    /// Every vertex in the graph.
+    vertices: Vec<Point<T>>,
+
+    /// The index of each vertex, by its coordinates.
+    indices: BTreeMap<(AbsoluteEq<T>, AbsoluteEq<T>), usize>,
+
+    /// The edges between vertices, along with the operand that they came from.
+    edges: Vec<(usize, usize, usize)>,
+
+    /// The points where each edge needs to be split, by parameter along the edge.
+    splits: Vec<Vec<(T, usize)>>,
+}
+
+impl<T: Copy> Default for Graph<T> {
+    fn default() -> Self {
+        Graph {
+            vertices: Vec::new(),
+            indices: BTreeMap::new(),
+            edges: Vec::new(),
+            splits: Vec::new(),
+        }
+    }
+}
+
+impl<T: Real> Graph<T> {
+    /// The tolerance used to tell if parameters or directions are the same.
+    fn epsilon() -> T {
+        T::epsilon().sqrt()
+    }
+
+    /// Get the index of a vertex, adding it if it doesn't exist yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either coordinate of `point` is `NaN`.
+    fn vertex(&mut self, point: Point<T>) -> usize {
+        let vertices = &mut self.vertices;
+        *self
+            .indices
+            .entry((AbsoluteEq(point.x()), AbsoluteEq(point.y())))
+            .or_insert_with(|| {
+                vertices.push(point);
+                vertices.len() - 1
+            })
+    }
+
+    /// Add the edges of a ring.
+    fn add_ring(&mut self, ring: &[Point<T>], operand: usize) {
+        let indices = ring.iter().map(|&p| self.vertex(p)).collect::<Vec<_>>();
+
+        for (i, &from) in indices.iter().enumerate() {
+            let to = indices[(i + 1) % indices.len()];
+            if from != to {
+                self.edges.push((from, to, operand));
+                self.splits.push(Vec::new());
+            }
+        }
+    }
+
+    /// Find the points where edges cross or touch each other.
+    fn split(&mut self) {
+        let eps = Self::epsilon();
+        let interior = |t: T| t > eps && t < T::one() - eps;
+
+        let segments = self
+            .edges
+            .iter()
+            .map(|&(from, to, _)| (self.vertices[from], self.vertices[to]))
+            .collect::<Vec<_>>();
+
+        for (i, j) in overlapping_pairs(&segments) {
+            let (a, b, _) = self.edges[i];
+            let (c, d, _) = self.edges[j];
+            let (p, p2) = (self.vertices[a], self.vertices[b]);
+            let (q, q2) = (self.vertices[c], self.vertices[d]);
+
+            let (r, s, qp) = (p2 - p, q2 - q, q - p);
+            let denom = r.cross(s);
+
+            if denom.abs() <= eps * r.length() * s.length() {
+                // The edges are parallel, so they only touch if they are collinear.
+                if qp.cross(r).abs() > eps * r.length() * qp.length() {
+                    continue;
+                }
+
+                let project = |point: Point<T>, from: Point<T>, dir| {
+                    (point - from).dot(dir) / crate::Vector::length_squared(dir)
+                };
+
+                for &(vertex, point) in [(c, q), (d, q2)].iter() {
+                    let t = project(point, p, r);
+                    if interior(t) {
+                        self.splits[i].push((t, vertex));
+                    }
+                }
+                for &(vertex, point) in [(a, p), (b, p2)].iter() {
+                    let u = project(point, q, s);
+                    if interior(u) {
+                        self.splits[j].push((u, vertex));
+                    }
+                }
+                continue;
+            }
+
+            let t = qp.cross(s) / denom;
+            let u = qp.cross(r) / denom;
+            if t < -eps || t > T::one() + eps || u < -eps || u > T::one() + eps {
+                continue;
+            }
+
+            // Reuse an existing vertex if the edges meet at one.
+            let vertex = if !interior(t) {
+                if t < T::one() / (T::one() + T::one()) {
+                    a
+                } else {
+                    b
+                }
+            } else if !interior(u) {
+                if u < T::one() / (T::one() + T::one()) {
+                    c
+                } else {
+                    d
+                }
+            } else {
+                self.vertex(p + r * t)
+            };
+
+            if interior(t) {
+                self.splits[i].push((t, vertex));
+            }
+            if interior(u) {
+                self.splits[j].push((u, vertex));
+            }
+        }
+    }
+
+    /// Break the edges up at their splits, and merge pieces that lie on top of each other.
+    ///
+    /// Each piece goes from its lower vertex index to its higher one, and has the number
+    /// of times each operand's edges run along it in that direction.
+    fn pieces(&mut self) -> Vec<((usize, usize), [i32; 2])> {
+        let mut pieces = BTreeMap::new();
+
+        for (&(from, to, operand), splits) in self.edges.iter().zip(self.splits.iter_mut()) {
+            splits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+            let mut last = from;
+            for vertex in splits.iter().map(|&(_, v)| v).chain(Some(to)) {
+                if vertex != last {
+                    let (key, sign) = if last < vertex {
+                        ((last, vertex), 1)
+                    } else {
+                        ((vertex, last), -1)
+                    };
+
+                    let entry = pieces.entry(key).or_insert([0, 0]);
+                    entry[operand] += sign;
+                    last = vertex;
+                }
+            }
+        }
+
+        pieces
+            .into_iter()
+            .filter(|(_, multiplicity)| *multiplicity != [0, 0])
+            .collect()
+    }
+
+    /// Get the winding numbers on the left and right sides of every piece.
+    ///
+    /// A ray is cast from the middle of each piece along whichever axis crosses it most
+    /// cleanly. The rays are swept across the other axis, so that each one is only tested
+    /// against the pieces level with it.
+    fn sides(&self, pieces: &[((usize, usize), [i32; 2])]) -> Vec<([i32; 2], [i32; 2])> {
+        let mut sides = alloc::vec![([0, 0], [0, 0]); pieces.len()];
+
+        for &along_x in [true, false].iter() {
+            // Swapping the axes means that the sweep always goes along Y.
+            let flip = |p: Point<T>| {
+                if along_x {
+                    p
+                } else {
+                    Point::new(p.y(), p.x())
+                }
+            };
+            let ends = |i: usize| {
+                let ((a, b), _) = pieces[i];
+                (flip(self.vertices[a]), flip(self.vertices[b]))
+            };
+            let span = |i: usize| {
+                let (p, q) = ends(i);
+                (p.y().min(q.y()), p.y().max(q.y()))
+            };
+            let height = |i: usize| {
+                let (p, q) = ends(i);
+                p.midpoint(q).y()
+            };
+            let compare = |a: T, b: T| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+
+            let mut rays = (0..pieces.len())
+                .filter(|&i| {
+                    let (p, q) = ends(i);
+                    let direction = q - p;
+                    direction.y().abs() >= direction.x().abs()
+                })
+                .collect::<Vec<_>>();
+            rays.sort_by(|&a, &b| compare(height(a), height(b)));
+
+            let mut order = (0..pieces.len()).collect::<Vec<_>>();
+            order.sort_by(|&a, &b| compare(span(a).0, span(b).0));
+
+            let mut next = 0;
+            let mut active = Vec::new();
+            for index in rays {
+                let y = height(index);
+                while next < order.len() && span(order[next]).0 <= y {
+                    active.push(order[next]);
+                    next += 1;
+                }
+                active.retain(|&i| span(i).1 > y);
+
+                sides[index] = self.side(pieces, index, along_x, &active);
+            }
+        }
+
+        sides
+    }
+
+    /// Get the winding numbers on the left and right sides of a piece, by casting a ray
+    /// from its middle across the `level` pieces.
+    fn side(
+        &self,
+        pieces: &[((usize, usize), [i32; 2])],
+        index: usize,
+        along_x: bool,
+        level: &[usize],
+    ) -> ([i32; 2], [i32; 2]) {
+        let ((a, b), multiplicity) = pieces[index];
+        let (from, to) = (self.vertices[a], self.vertices[b]);
+        let direction = to - from;
+        let middle = from.midpoint(to);
+
+        // Swapping the axes mirrors the plane, which flips the winding numbers.
+        let flip = |p: Point<T>| {
+            if along_x {
+                p
+            } else {
+                Point::new(p.y(), p.x())
+            }
+        };
+
+        let point = flip(middle);
+        let mut winding = [0, 0];
+        for &i in level {
+            if i == index {
+                continue;
+            }
+
+            let ((c, d), other) = pieces[i];
+            let (p, q) = (flip(self.vertices[c]), flip(self.vertices[d]));
+            let side = (q - p).cross(point - p);
+            let crossing = if p.y() <= point.y() && point.y() < q.y() && side > T::zero() {
+                1
+            } else if q.y() <= point.y() && point.y() < p.y() && side < T::zero() {
+                -1
+            } else {
+                0
+            };
+
+            let crossing = if along_x { crossing } else { -crossing };
+            winding[0] += crossing * other[0];
+            winding[1] += crossing * other[1];
+        }
+
+        // The ray leaves towards +X (or +Y), which is either the piece's left or right.
+        let ray_on_left = if along_x {
+            direction.y() < T::zero()
+        } else {
+            direction.x() > T::zero()
+        };
+
+        let other = [
+            winding[0] + if ray_on_left { -1 } else { 1 } * multiplicity[0],
+            winding[1] + if ray_on_left { -1 } else { 1 } * multiplicity[1],
+        ];
+
+        if ray_on_left {
+            (winding, other)
+        } else {
+            (other, winding)
+        }
+    }
+
+    /// Connect directed edges into rings.
+    ///
+    /// Rings that pass through the same vertex twice are split into separate rings there,
+    /// so that every ring is simple.
+    fn chain(&self, edges: Vec<(usize, usize)>) -> Vec<Vec<Point<T>>> {
+        let mut outgoing = BTreeMap::<usize, Vec<usize>>::new();
+        for (i, &(from, _)) in edges.iter().enumerate() {
+            outgoing.entry(from).or_default().push(i);
+        }
+
+        let mut used = alloc::vec![false; edges.len()];
+        let mut rings = Vec::new();
+        let mut finish = |ring: Vec<usize>| {
+            let ring = remove_collinear(ring.into_iter().map(|v| self.vertices[v]).collect());
+            if ring.len() > 2 {
+                rings.push(ring);
+            }
+        };
+
+        for start in 0..edges.len() {
+            if used[start] {
+                continue;
+            }
+
+            let mut path = alloc::vec![edges[start].0];
+            let mut visited = BTreeMap::new();
+            visited.insert(edges[start].0, 0);
+            let mut current = start;
+            loop {
+                used[current] = true;
+                let to = edges[current].1;
+
+                // Cut off a loop if this vertex has already been visited.
+                match visited.get(&to).copied() {
+                    Some(index) => {
+                        let ring = path.split_off(index);
+                        for vertex in &ring[1..] {
+                            visited.remove(vertex);
+                        }
+                        finish(ring);
+                        if index == 0 {
+                            break;
+                        }
+                        path.push(to);
+                    }
+                    None => {
+                        visited.insert(to, path.len());
+                        path.push(to);
+                    }
+                }
+
+                let next = outgoing
+                    .get(&to)
+                    .and_then(|candidates| candidates.iter().copied().find(|&e| !used[e]));
+                match next {
+                    Some(next) => current = next,
+                    None => break,
+                }
+            }
+        }
+
+        rings
+    }
+}
+
+/// Remove the points of a ring that lie on a straight line between their neighbors.
+fn remove_collinear<T: Real>(ring: Vec<Point<T>>) -> Vec<Point<T>> {
+    let eps = T::epsilon().sqrt();
+    let mut ring = ring;
+    let mut changed = true;
+
+    while changed && ring.len() > 2 {
+        changed = false;
+        let len = ring.len();
+
+        for i in 0..len {
+            let prev = ring[(i + len - 1) % len];
+            let next = ring[(i + 1) % len];
+            let (a, b) = (ring[i] - prev, next - ring[i]);
+
+            if a.cross(b).abs() <= eps * a.length() * b.length() && a.dot(b) > T::zero() {
+                ring.remove(i);
+                changed = true;
+                break;
+            }
+        }
+    }
+
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::polygon::signed_area;
+    use alloc::vec;
+
+    fn square(x: f32, y: f32, size: f32) -> Vec<Point<f32>> {
+        vec![
+            Point::new(x, y),
+            Point::new(x + size, y),
+            Point::new(x + size, y + size),
+            Point::new(x, y + size),
+        ]
+    }
+
+    #[test]
+    fn test_union() {
+        let rings = overlay(&[square(0.0, 0.0, 2.0)], &[square(1.0, 1.0, 2.0)], |w| {
+            w[0] > 0 || w[1] > 0
+        });
+
+        assert_eq!(rings.len(), 1);
+        assert_eq!(rings[0].len(), 8);
+        assert_eq!(signed_area(&rings[0]), 7.0);
+    }
+
+    #[test]
+    fn test_difference() {
+        let rings = overlay(&[square(0.0, 0.0, 4.0)], &[square(1.0, 1.0, 2.0)], |w| {
+            w[0] > 0 && w[1] <= 0
+        });
+
+        assert_eq!(rings.len(), 2);
+        let total: f32 = rings.iter().map(|ring| signed_area(ring)).sum();
+        assert_eq!(total, 12.0);
+    }
+
+    #[test]
+    fn test_touching_corners() {
+        // The outline of squares that touch at a corner passes through that corner twice,
+        // and is split into a ring for each square.
+        let rings = overlay(&[square(0.0, 0.0, 1.0), square(1.0, 1.0, 1.0)], &[], |w| {
+            w[0] > 0
+        });
+
+        assert_eq!(rings.len(), 2);
+        for ring in &rings {
+            assert_eq!(ring.len(), 4);
+            assert_eq!(signed_area(ring), 1.0);
+        }
+
+        // The same happens when a hole touches the exterior at a corner.
+        let rings = overlay(&[square(0.0, 0.0, 2.0)], &[square(0.0, 0.0, 1.0)], |w| {
+            w[0] > 0 && w[1] <= 0
+        });
+        assert_eq!(rings.len(), 1);
+        assert_eq!(rings[0].len(), 6);
+        assert_eq!(signed_area(&rings[0]), 3.0);
+    }
+
+    #[test]
+    fn test_shared_edge() {
+        // Squares that share an edge merge into a single rectangle.
+        let rings = overlay(&[square(0.0, 0.0, 1.0), square(1.0, 0.0, 1.0)], &[], |w| {
+            w[0] > 0
+        });
+
+        assert_eq!(rings.len(), 1);
+        assert_eq!(rings[0].len(), 4);
+        assert_eq!(signed_area(&rings[0]), 2.0);
+    }
+}
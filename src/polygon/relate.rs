@@ -0,0 +1,523 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Spatial relationships between polygons and other geometry.
+
+use super::sweep::overlapping_pairs;
+use super::{signed_area, Polygon};
+use crate::line::LineSegment;
+use crate::point::{Point, Vector};
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num_traits::real::Real;
+
+/// How two pieces of geometry relate to each other.
+///
+/// This is a cut-down version of the DE-9IM model, which only tracks the parts of the
+/// intersection matrix needed for the common predicates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Relation {
+    /// The interiors of the two pieces of geometry intersect.
+    interiors: bool,
+
+    /// The boundaries of the two pieces of geometry meet.
+    boundaries: bool,
+
+    /// The interior of the first piece of geometry reaches outside of the second.
+    first_outside: bool,
+
+    /// The interior of the second piece of geometry reaches outside of the first.
+    second_outside: bool,
+}
+
+impl Relation {
+    /// Whether the interiors of the two pieces of geometry intersect.
+    pub fn interiors_intersect(&self) -> bool {
+        self.interiors
+    }
+
+    /// Whether the boundaries of the two pieces of geometry meet.
+    pub fn boundaries_meet(&self) -> bool {
+        self.boundaries
+    }
+
+    /// Whether the first piece of geometry contains the second.
+    ///
+    /// This means that no part of the second is outside of the first, and that their
+    /// interiors intersect.
+    pub fn contains(&self) -> bool {
+        self.interiors && !self.second_outside
+    }
+
+    /// Whether the first piece of geometry is within the second.
+    pub fn within(&self) -> bool {
+        self.interiors && !self.first_outside
+    }
+
+    /// Whether the two pieces of geometry partially overlap.
+    ///
+    /// This means that their interiors intersect, but each of them also reaches outside
+    /// of the other. For a polygon and a segment, this is the segment crossing the
+    /// polygon's boundary.
+    pub fn overlaps(&self) -> bool {
+        self.interiors && self.first_outside && self.second_outside
+    }
+
+    /// Whether the two pieces of geometry touch without their interiors intersecting.
+    pub fn touches(&self) -> bool {
+        !self.interiors && self.boundaries
+    }
+
+    /// Whether the two pieces of geometry have no points in common.
+    pub fn disjoint(&self) -> bool {
+        !self.interiors && !self.boundaries
+    }
+
+    /// Whether the two pieces of geometry have any points in common.
+    pub fn intersects(&self) -> bool {
+        !self.disjoint()
+    }
+}
+
+/// Where a point is, relative to a polygon.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Location {
+    /// The point is inside of the polygon.
+    Inside,
+
+    /// The point is on the boundary of the polygon.
+    ///
+    /// This includes whether the boundary runs in the same direction as the piece of
+    /// geometry that the point came from.
+    Boundary { same_direction: bool },
+
+    /// The point is outside of the polygon.
+    Outside,
+}
+
+impl<T: Real> Polygon<T> {
+    /// Get how this polygon relates to another polygon.
+    ///
+    /// Both polygons are expected to be valid, although the orientations of their rings
+    /// do not matter.
+    ///
+    /// The edges of each polygon are split where they meet the other's, and the pieces
+    /// are then located inside of, outside of or on the other polygon. Both steps sweep
+    /// over the edges, so edges are only compared against the edges level with them
+    /// rather than against every edge of the other polygon.
+    pub fn relate(&self, other: &Polygon<T>) -> Relation {
+        let (a, b) = (edges(self), edges(other));
+        let mut relation = Relation::default();
+
+        for (first, second, flipped) in [(&a, &b, false), (&b, &a, true)].iter().copied() {
+            let (this_outside, other_outside) = if flipped {
+                (&mut relation.second_outside, &mut relation.first_outside)
+            } else {
+                (&mut relation.first_outside, &mut relation.second_outside)
+            };
+            let mut touched = false;
+
+            let mut pieces = Vec::new();
+            for (&(from, to), nearby) in first.iter().zip(nearby(first, second)) {
+                let (split, met) = split(from, to, &nearby);
+                touched |= met;
+                pieces.extend(split);
+            }
+
+            let middles = pieces
+                .iter()
+                .map(|&(start, end)| (start.midpoint(end), end - start))
+                .collect::<Vec<_>>();
+            for location in locate_all(&middles, second) {
+                match location {
+                    Location::Inside => {
+                        relation.interiors = true;
+                        *other_outside = true;
+                    }
+                    Location::Boundary { same_direction } => {
+                        touched = true;
+                        if same_direction {
+                            relation.interiors = true;
+                        } else {
+                            *this_outside = true;
+                            *other_outside = true;
+                        }
+                    }
+                    Location::Outside => *this_outside = true,
+                }
+            }
+
+            relation.boundaries |= touched;
+        }
+
+        relation
+    }
+
+    /// Get how this polygon relates to a line segment.
+    pub fn relate_segment(&self, segment: LineSegment<T>) -> Relation {
+        let edges = edges(self);
+        let (pieces, met) = split(segment.from(), segment.to(), &edges);
+
+        // Polygons always have some area that a segment can't cover.
+        let mut relation = Relation {
+            boundaries: met,
+            first_outside: true,
+            ..Relation::default()
+        };
+
+        let direction = segment.to() - segment.from();
+        let ends = [(segment.from(), direction), (segment.to(), direction)];
+        for location in locate_all(&ends, &edges) {
+            match location {
+                Location::Boundary { .. } => relation.boundaries = true,
+                Location::Outside => relation.second_outside = true,
+                Location::Inside => {}
+            }
+        }
+
+        let middles = pieces
+            .iter()
+            .map(|&(start, end)| (start.midpoint(end), end - start))
+            .collect::<Vec<_>>();
+        for location in locate_all(&middles, &edges) {
+            match location {
+                Location::Inside => relation.interiors = true,
+                Location::Boundary { .. } => relation.boundaries = true,
+                Location::Outside => relation.second_outside = true,
+            }
+        }
+
+        relation
+    }
+}
+
+/// An edge of a polygon, or a piece of a segment.
+type Edge<T> = (Point<T>, Point<T>);
+
+/// The tolerance used to tell if a point lies on an edge, relative to its length.
+fn epsilon<T: Real>() -> T {
+    T::epsilon().sqrt()
+}
+
+/// Get the edges of a polygon, oriented so that its interior is on their left.
+fn edges<T: Real>(polygon: &Polygon<T>) -> Vec<Edge<T>> {
+    let mut edges = Vec::new();
+
+    let rings = Some((polygon.exterior(), true)).into_iter().chain(
+        polygon
+            .interiors()
+            .iter()
+            .map(|ring| (ring.as_slice(), false)),
+    );
+    for (ring, exterior) in rings {
+        let flip = (signed_area(ring) > T::zero()) != exterior;
+
+        for (i, &from) in ring.iter().enumerate() {
+            let to = ring[(i + 1) % ring.len()];
+            if from != to {
+                edges.push(if flip { (to, from) } else { (from, to) });
+            }
+        }
+    }
+
+    edges
+}
+
+/// Find the edges of `second` that each edge of `first` might meet.
+fn nearby<T: Real>(first: &[Edge<T>], second: &[Edge<T>]) -> Vec<Vec<Edge<T>>> {
+    let all = first.iter().chain(second).copied().collect::<Vec<_>>();
+    let mut nearby = alloc::vec![Vec::new(); first.len()];
+
+    // Pairs come with the lower index first, so the edge from `first` comes first.
+    for (i, j) in overlapping_pairs(&all) {
+        if i < first.len() && j >= first.len() {
+            nearby[i].push(second[j - first.len()]);
+        }
+    }
+
+    nearby
+}
+
+/// Split a segment at every point where it meets the given edges.
+///
+/// Also returns whether the segment meets any of the edges at all.
+fn split<T: Real>(from: Point<T>, to: Point<T>, edges: &[Edge<T>]) -> (Vec<Edge<T>>, bool) {
+    let eps = epsilon::<T>();
+    let r = to - from;
+    let mut params = alloc::vec![T::zero(), T::one()];
+    let mut met = false;
+
+    for &(p, q) in edges {
+        let s = q - p;
+        let denom = r.cross(s);
+
+        if denom.abs() <= eps * r.length() * s.length() {
+            // Collinear edges split the segment where they start and end.
+            if (p - from).cross(r).abs() > eps * r.length() * (p - from).length() {
+                continue;
+            }
+
+            let t1 = (p - from).dot(r) / r.length_squared();
+            let t2 = (q - from).dot(r) / r.length_squared();
+            if t1.max(t2) >= -eps && t1.min(t2) <= T::one() + eps {
+                met = true;
+                params.extend(
+                    [t1, t2]
+                        .iter()
+                        .copied()
+                        .filter(|t| *t > T::zero() && *t < T::one()),
+                );
+            }
+            continue;
+        }
+
+        let t = (p - from).cross(s) / denom;
+        let u = (p - from).cross(r) / denom;
+        if t >= -eps && t <= T::one() + eps && u >= -eps && u <= T::one() + eps {
+            met = true;
+            if t > T::zero() && t < T::one() {
+                params.push(t);
+            }
+        }
+    }
+
+    params.sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+    params.dedup_by(|a, b| (*a - *b).abs() <= eps);
+
+    let pieces = params
+        .windows(2)
+        .map(|pair| (from + r * pair[0], from + r * pair[1]))
+        .collect();
+    (pieces, met)
+}
+
+/// Find where each point is relative to the polygon with the given edges.
+///
+/// The points are swept from top to bottom, so that each one is only checked against the
+/// edges level with it. See [`locate`] for what the directions are for.
+fn locate_all<T: Real>(points: &[(Point<T>, Vector<T>)], edges: &[Edge<T>]) -> Vec<Location> {
+    let eps = epsilon::<T>();
+    let compare = |a: T, b: T| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+
+    // Widen the edges by the tolerance used for points on the boundary.
+    let span = |i: usize| {
+        let (from, to) = edges[i];
+        let pad = eps * (to - from).length();
+        (from.y().min(to.y()) - pad, from.y().max(to.y()) + pad)
+    };
+
+    let mut order = (0..edges.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| compare(span(a).0, span(b).0));
+    let mut queries = (0..points.len()).collect::<Vec<_>>();
+    queries.sort_by(|&a, &b| compare(points[a].0.y(), points[b].0.y()));
+
+    let mut locations = alloc::vec![Location::Outside; points.len()];
+    let mut next = 0;
+    let mut active = Vec::new();
+    for index in queries {
+        let (point, direction) = points[index];
+        while next < order.len() && span(order[next]).0 <= point.y() {
+            active.push(order[next]);
+            next += 1;
+        }
+        active.retain(|&i| span(i).1 >= point.y());
+
+        locations[index] = locate(point, direction, active.iter().map(|&i| edges[i]));
+    }
+
+    locations
+}
+
+/// Find where a point is relative to the polygon with the given edges.
+///
+/// `direction` is the direction of the geometry that the point is on, which is compared
+/// against the direction of the boundary if the point is on it. Edges that don't cross
+/// the point's height can be left out.
+fn locate<T: Real>(
+    point: Point<T>,
+    direction: Vector<T>,
+    edges: impl IntoIterator<Item = Edge<T>>,
+) -> Location {
+    let eps = epsilon::<T>();
+    let mut winding = 0;
+
+    for (from, to) in edges {
+        let edge = to - from;
+        let side = edge.cross(point - from);
+
+        // Check if the point is on the edge itself.
+        let along = (point - from).dot(edge);
+        if side.abs() <= eps * edge.length_squared()
+            && along >= T::zero()
+            && along <= edge.length_squared()
+        {
+            return Location::Boundary {
+                same_direction: edge.dot(direction) > T::zero(),
+            };
+        }
+
+        if from.y() <= point.y() && point.y() < to.y() && side > T::zero() {
+            winding += 1;
+        } else if to.y() <= point.y() && point.y() < from.y() && side < T::zero() {
+            winding -= 1;
+        }
+    }
+
+    if winding != 0 {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn square(x: f64, y: f64, size: f64) -> Polygon<f64> {
+        Polygon::new(
+            vec![
+                Point::new(x, y),
+                Point::new(x + size, y),
+                Point::new(x + size, y + size),
+                Point::new(x, y + size),
+            ],
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_polygons() {
+        let big = square(0.0, 0.0, 4.0);
+
+        let overlapping = big.relate(&square(3.0, 3.0, 2.0));
+        assert!(overlapping.overlaps());
+        assert!(!overlapping.contains() && !overlapping.within());
+
+        let inner = big.relate(&square(1.0, 1.0, 1.0));
+        assert!(inner.contains() && !inner.within() && !inner.overlaps());
+
+        let sharing_edge = big.relate(&square(0.0, 0.0, 1.0));
+        assert!(sharing_edge.contains() && !sharing_edge.touches());
+
+        let equal = big.relate(&square(0.0, 0.0, 4.0));
+        assert!(equal.contains() && equal.within() && !equal.overlaps());
+
+        let adjacent = big.relate(&square(4.0, 1.0, 1.0));
+        assert!(adjacent.touches() && !adjacent.disjoint());
+
+        let corner = big.relate(&square(4.0, 4.0, 1.0));
+        assert!(corner.touches());
+
+        assert!(big.relate(&square(5.0, 5.0, 1.0)).disjoint());
+    }
+
+    #[test]
+    fn test_holes() {
+        let frame = Polygon::new(
+            square(0.0, 0.0, 4.0).into_rings().0,
+            vec![square(1.0, 1.0, 2.0).into_rings().0],
+        );
+
+        // The hole has the same orientation as the exterior, which is corrected for.
+        let filling = frame.relate(&square(1.0, 1.0, 2.0));
+        assert!(filling.touches());
+
+        let island = frame.relate(&square(1.5, 1.5, 1.0));
+        assert!(island.disjoint());
+
+        let whole = square(0.0, 0.0, 4.0).relate(&frame);
+        assert!(whole.contains() && !whole.within());
+    }
+
+    #[test]
+    fn test_touching_vertex() {
+        let big = square(0.0, 0.0, 4.0);
+
+        // A diamond whose left corner rests on the middle of the square's right edge.
+        let diamond = Polygon::new(
+            vec![
+                Point::new(4.0, 2.0),
+                Point::new(5.0, 1.0),
+                Point::new(6.0, 2.0),
+                Point::new(5.0, 3.0),
+            ],
+            Vec::new(),
+        );
+        let relation = big.relate(&diamond);
+        assert!(relation.touches() && relation.boundaries_meet());
+        assert!(!relation.interiors_intersect());
+        assert!(diamond.relate(&big).touches());
+
+        // A triangle whose tip meets the square's corner.
+        let triangle = Polygon::new(
+            vec![
+                Point::new(4.0, 4.0),
+                Point::new(6.0, 5.0),
+                Point::new(5.0, 6.0),
+            ],
+            Vec::new(),
+        );
+        assert!(big.relate(&triangle).touches());
+        assert!(triangle.relate(&big).touches());
+    }
+
+    #[test]
+    fn test_inside_hole() {
+        let frame = Polygon::new(
+            square(0.0, 0.0, 6.0).into_rings().0,
+            vec![square(1.0, 1.0, 4.0).into_rings().0],
+        );
+
+        // Floating in the hole without touching its edges.
+        let island = square(2.0, 2.0, 2.0);
+        assert!(frame.relate(&island).disjoint());
+        assert!(island.relate(&frame).disjoint());
+        assert!(!square(0.0, 0.0, 6.0).relate(&island).disjoint());
+
+        // Filling the hole from corner to corner of its edges.
+        let diamond = Polygon::new(
+            vec![
+                Point::new(3.0, 1.0),
+                Point::new(5.0, 3.0),
+                Point::new(3.0, 5.0),
+                Point::new(1.0, 3.0),
+            ],
+            Vec::new(),
+        );
+        let relation = frame.relate(&diamond);
+        assert!(relation.touches());
+        assert!(!relation.contains() && !relation.within());
+        assert!(diamond.relate(&frame).touches());
+    }
+
+    #[test]
+    fn test_segments() {
+        let big = square(0.0, 0.0, 4.0);
+        let segment = |x1, y1, x2, y2| LineSegment::new(Point::new(x1, y1), Point::new(x2, y2));
+
+        assert!(big.relate_segment(segment(1.0, 1.0, 3.0, 3.0)).contains());
+        assert!(big.relate_segment(segment(0.0, 1.0, 3.0, 3.0)).contains());
+        assert!(big.relate_segment(segment(2.0, 2.0, 6.0, 2.0)).overlaps());
+        assert!(big.relate_segment(segment(0.0, 0.0, 4.0, 0.0)).touches());
+        assert!(big.relate_segment(segment(4.0, 2.0, 6.0, 2.0)).touches());
+        assert!(big.relate_segment(segment(5.0, 2.0, 6.0, 2.0)).disjoint());
+        assert!(!big.relate_segment(segment(1.0, 1.0, 3.0, 3.0)).within());
+    }
+}
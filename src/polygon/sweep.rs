@@ -0,0 +1,99 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Finding segments that might touch, without comparing every pair.
+
+use crate::point::Point;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num_traits::real::Real;
+
+/// Find the pairs of segments whose bounding boxes overlap or touch.
+///
+/// The segments are swept from top to bottom, and each one is only compared against the
+/// segments whose vertical extent it overlaps. This takes `O(n log n + k)` time, where
+/// `k` is the number of segments that share a row, instead of comparing all `O(n²)`
+/// pairs. The pairs are returned with the lower index first.
+///
+/// This is used instead of the Bentley-Ottmann sweep in `bentley_ottman`, since that
+/// leaves out horizontal segments and doesn't report segments that only touch, both of
+/// which matter when working with rings.
+pub(crate) fn overlapping_pairs<T: Real>(segments: &[(Point<T>, Point<T>)]) -> Vec<(usize, usize)> {
+    let extent = |i: usize| {
+        let (from, to) = segments[i];
+        (
+            from.x().min(to.x()),
+            from.x().max(to.x()),
+            from.y().min(to.y()),
+            from.y().max(to.y()),
+        )
+    };
+
+    let mut order = (0..segments.len()).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| {
+        extent(a)
+            .2
+            .partial_cmp(&extent(b).2)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let mut pairs = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    for i in order {
+        let (min_x, max_x, top, _) = extent(i);
+
+        // Segments that end above this one can't touch it or anything after it.
+        active.retain(|&j| extent(j).3 >= top);
+
+        for &j in &active {
+            let (other_min_x, other_max_x, _, _) = extent(j);
+            if other_min_x <= max_x && min_x <= other_max_x {
+                pairs.push((i.min(j), i.max(j)));
+            }
+        }
+
+        active.push(i);
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_overlapping_pairs() {
+        let segment = |a: (f32, f32), b: (f32, f32)| (Point::new(a.0, a.1), Point::new(b.0, b.1));
+        let segments = [
+            segment((0.0, 0.0), (4.0, 4.0)),
+            segment((0.0, 4.0), (4.0, 0.0)),
+            // This touches the first two at their bottom right corner.
+            segment((4.0, 4.0), (8.0, 4.0)),
+            // This is beside the others, level with them.
+            segment((10.0, 0.0), (10.0, 4.0)),
+            // This is below the others.
+            segment((0.0, 5.0), (4.0, 6.0)),
+        ];
+
+        let mut pairs = overlapping_pairs(&segments);
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![(0, 1), (0, 2), (1, 2)]);
+    }
+}
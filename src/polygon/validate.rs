@@ -0,0 +1,425 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Checking that polygons are well-formed.
+
+use super::overlay::overlay;
+use super::sweep::overlapping_pairs;
+use super::{assemble, crossing, signed_area, winding, Crossing, Polygon};
+use crate::point::Point;
+
+use alloc::vec::Vec;
+use core::fmt;
+use num_traits::real::Real;
+
+/// A reason that a polygon is invalid, as returned by [`Polygon::validate`].
+///
+/// Rings are referred to by index, where `0` is the exterior and `i + 1` is the interior
+/// at index `i`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValidationError {
+    /// A ring has fewer than three distinct points.
+    TooFewPoints {
+        /// The ring with too few points.
+        ring: usize,
+    },
+
+    /// A point in a ring is the same as the point before it.
+    DuplicatePoint {
+        /// The ring containing the point.
+        ring: usize,
+
+        /// The index of the repeated point.
+        index: usize,
+    },
+
+    /// A ring doubles back on itself at a point.
+    Spike {
+        /// The ring containing the spike.
+        ring: usize,
+
+        /// The index of the point at the tip of the spike.
+        index: usize,
+    },
+
+    /// The edges of a ring cross each other, or cross the edges of another ring.
+    SelfIntersection {
+        /// The first ring involved in the intersection.
+        ring: usize,
+
+        /// The second ring involved in the intersection, which may be the same ring.
+        other: usize,
+    },
+
+    /// A ring has the wrong orientation.
+    ///
+    /// The exterior should have a positive signed area, and interiors should have a
+    /// negative signed area.
+    WrongOrientation {
+        /// The ring with the wrong orientation.
+        ring: usize,
+    },
+
+    /// An interior ring is not inside of the exterior.
+    HoleOutside {
+        /// The interior ring that is outside.
+        ring: usize,
+    },
+
+    /// An interior ring is inside of another interior ring.
+    NestedHole {
+        /// The interior ring that is inside of the other.
+        ring: usize,
+
+        /// The interior ring that contains it.
+        outer: usize,
+    },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ValidationError::TooFewPoints { ring } => {
+                write!(f, "ring {} has fewer than three points", ring)
+            }
+            ValidationError::DuplicatePoint { ring, index } => {
+                write!(f, "point {} of ring {} is repeated", index, ring)
+            }
+            ValidationError::Spike { ring, index } => {
+                write!(f, "ring {} has a spike at point {}", ring, index)
+            }
+            ValidationError::SelfIntersection { ring, other } if ring == other => {
+                write!(f, "ring {} intersects itself", ring)
+            }
+            ValidationError::SelfIntersection { ring, other } => {
+                write!(f, "rings {} and {} intersect", ring, other)
+            }
+            ValidationError::WrongOrientation { ring } => {
+                write!(f, "ring {} has the wrong orientation", ring)
+            }
+            ValidationError::HoleOutside { ring } => {
+                write!(f, "interior ring {} is outside of the exterior", ring)
+            }
+            ValidationError::NestedHole { ring, outer } => {
+                write!(
+                    f,
+                    "interior ring {} is inside of interior ring {}",
+                    ring, outer
+                )
+            }
+        }
+    }
+}
+
+impl<T: Real> Polygon<T> {
+    /// Check that this polygon is well-formed.
+    ///
+    /// A valid polygon has rings with at least three points, no repeated consecutive
+    /// points, no spikes and no edges that cross. Its rings must follow the orientation
+    /// convention, and its interiors must be inside of its exterior but not inside of each
+    /// other. Interiors may touch the exterior or each other at single points.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let rings = || {
+            Some(self.exterior())
+                .into_iter()
+                .chain(self.interiors().iter().map(|ring| ring.as_slice()))
+                .enumerate()
+        };
+
+        for (ring, points) in rings() {
+            check_points(ring, points)?;
+        }
+
+        check_crossings(&rings().map(|(_, points)| points).collect::<Vec<_>>())?;
+
+        for (ring, points) in rings() {
+            let area = signed_area(points);
+            if (ring == 0 && area <= T::zero()) || (ring > 0 && area >= T::zero()) {
+                return Err(ValidationError::WrongOrientation { ring });
+            }
+        }
+
+        for (ring, points) in rings().skip(1) {
+            let exterior = self.exterior();
+            let outside = points
+                .iter()
+                .filter(|&&point| !on_ring(exterior, point))
+                .any(|&point| winding(exterior, point) == 0);
+
+            if outside {
+                return Err(ValidationError::HoleOutside { ring });
+            }
+        }
+
+        // The rings don't cross, so one point that isn't on the other ring is enough to
+        // tell if a hole is inside of it.
+        for (ring, points) in rings().skip(1) {
+            for (outer, outer_points) in rings().skip(1).filter(|&(outer, _)| outer != ring) {
+                let inside = match points.iter().find(|&&point| !on_ring(outer_points, point)) {
+                    Some(&point) => winding(outer_points, point) != 0,
+                    None => true,
+                };
+
+                if inside {
+                    return Err(ValidationError::NestedHole { ring, outer });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Repair this polygon, splitting it into valid polygons.
+    ///
+    /// The rings are resolved using the even-odd fill rule, so self-intersections are
+    /// split apart, holes with the wrong orientation still cut holes, and holes outside of
+    /// the exterior become polygons of their own. Repeated points and spikes are removed,
+    /// and the rings of the result follow the orientation convention.
+    ///
+    /// The rings are combined by sweeping over their edges, so this takes about
+    /// `O(n log n)` time for `n` edges, plus the number of places where edges cross. It
+    /// only degrades towards `O(n²)` when most of the edges share the same rows, such as
+    /// for a polygon made of many long, thin, overlapping spikes.
+    pub fn make_valid(&self) -> Vec<Polygon<T>> {
+        let mut rings = Vec::with_capacity(self.interiors().len() + 1);
+        rings.push(self.exterior().to_vec());
+        rings.extend(self.interiors().iter().cloned());
+
+        assemble(overlay(&rings, &[], |w| w[0] % 2 != 0))
+    }
+}
+
+/// Check the points of a ring for duplicates and spikes.
+fn check_points<T: Real>(ring: usize, points: &[Point<T>]) -> Result<(), ValidationError> {
+    let len = points.len();
+
+    for (index, &point) in points.iter().enumerate() {
+        if point == points[(index + len - 1) % len] && len > 1 {
+            return Err(ValidationError::DuplicatePoint { ring, index });
+        }
+    }
+
+    if len < 3 {
+        return Err(ValidationError::TooFewPoints { ring });
+    }
+
+    for (index, &point) in points.iter().enumerate() {
+        let incoming = point - points[(index + len - 1) % len];
+        let outgoing = points[(index + 1) % len] - point;
+
+        if incoming.cross(outgoing).is_zero() && incoming.dot(outgoing) < T::zero() {
+            return Err(ValidationError::Spike { ring, index });
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the rings for edges that cross, either within a ring or between two rings.
+///
+/// The edges are swept over, so that each is only compared against the edges level with
+/// it. If several pairs of rings cross, the first pair is reported.
+fn check_crossings<T: Real>(rings: &[&[Point<T>]]) -> Result<(), ValidationError> {
+    let edges = rings
+        .iter()
+        .enumerate()
+        .flat_map(|(ring, points)| {
+            (0..points.len()).map(move |i| (ring, i, points[i], points[(i + 1) % points.len()]))
+        })
+        .collect::<Vec<_>>();
+    let segments = edges
+        .iter()
+        .map(|&(_, _, from, to)| (from, to))
+        .collect::<Vec<_>>();
+    let strictly_inside = |t: T| t > T::zero() && t < T::one();
+
+    let crossed = overlapping_pairs(&segments)
+        .into_iter()
+        .filter_map(|(a, b)| {
+            let (ring, i, a1, a2) = edges[a];
+            let (other, j, b1, b2) = edges[b];
+            let (ring, other) = (ring.min(other), ring.max(other));
+
+            // Neighboring edges in the same ring always share a point.
+            let len = rings[ring].len();
+            let (i, j) = (i.min(j), i.max(j));
+            let adjacent = ring == other && (j == i + 1 || (i == 0 && j == len - 1));
+
+            let crosses = match crossing(a1, a2, b1, b2) {
+                Crossing::Overlap => !adjacent,
+                Crossing::Point { t, u, .. } => {
+                    let proper = strictly_inside(t) && strictly_inside(u);
+                    (ring == other && !adjacent) || proper
+                }
+                Crossing::None => false,
+            };
+
+            if crosses {
+                Some((ring, other))
+            } else {
+                None
+            }
+        })
+        .min();
+
+    match crossed {
+        Some((ring, other)) => Err(ValidationError::SelfIntersection { ring, other }),
+        None => Ok(()),
+    }
+}
+
+/// Tell if a point lies on one of the edges of a ring.
+fn on_ring<T: Real>(ring: &[Point<T>], point: Point<T>) -> bool {
+    ring.iter().enumerate().any(|(i, &from)| {
+        let to = ring[(i + 1) % ring.len()];
+        let (edge, offset) = (to - from, point - from);
+
+        offset.cross(edge).is_zero()
+            && offset.dot(edge) >= T::zero()
+            && offset.dot(edge) <= edge.length_squared()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn ring(points: &[(f64, f64)]) -> Vec<Point<f64>> {
+        points.iter().map(|&(x, y)| Point::new(x, y)).collect()
+    }
+
+    fn square() -> Vec<Point<f64>> {
+        ring(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)])
+    }
+
+    #[test]
+    fn test_valid() {
+        let hole = ring(&[(1.0, 1.0), (1.0, 2.0), (2.0, 2.0), (2.0, 1.0)]);
+        let touching = ring(&[(0.0, 2.0), (1.0, 3.0), (1.0, 2.5)]);
+        let polygon = Polygon::new(square(), vec![hole, touching]);
+
+        assert_eq!(polygon.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_points() {
+        let duplicate = ring(&[(0.0, 0.0), (4.0, 0.0), (4.0, 0.0), (0.0, 4.0)]);
+        assert_eq!(
+            Polygon::new(duplicate, Vec::new()).validate(),
+            Err(ValidationError::DuplicatePoint { ring: 0, index: 2 })
+        );
+
+        let spike = ring(&[(0.0, 0.0), (4.0, 0.0), (6.0, 0.0), (4.0, 0.0), (0.0, 4.0)]);
+        assert_eq!(
+            Polygon::new(spike, Vec::new()).validate(),
+            Err(ValidationError::Spike { ring: 0, index: 2 })
+        );
+
+        let short = ring(&[(0.0, 0.0), (4.0, 0.0)]);
+        assert_eq!(
+            Polygon::new(square(), vec![short]).validate(),
+            Err(ValidationError::TooFewPoints { ring: 1 })
+        );
+    }
+
+    #[test]
+    fn test_invalid_rings() {
+        let bowtie = ring(&[(0.0, 0.0), (4.0, 4.0), (4.0, 0.0), (0.0, 4.0)]);
+        assert_eq!(
+            Polygon::new(bowtie, Vec::new()).validate(),
+            Err(ValidationError::SelfIntersection { ring: 0, other: 0 })
+        );
+
+        let crossing = ring(&[(3.0, 1.0), (3.0, 2.0), (5.0, 2.0), (5.0, 1.0)]);
+        assert_eq!(
+            Polygon::new(square(), vec![crossing]).validate(),
+            Err(ValidationError::SelfIntersection { ring: 0, other: 1 })
+        );
+
+        let mut reversed = square();
+        reversed.reverse();
+        assert_eq!(
+            Polygon::new(reversed, Vec::new()).validate(),
+            Err(ValidationError::WrongOrientation { ring: 0 })
+        );
+
+        let outside = ring(&[(5.0, 5.0), (5.0, 6.0), (6.0, 6.0), (6.0, 5.0)]);
+        let error = Polygon::new(square(), vec![outside])
+            .validate()
+            .unwrap_err();
+        assert_eq!(error, ValidationError::HoleOutside { ring: 1 });
+        assert_eq!(
+            alloc::format!("{}", error),
+            "interior ring 1 is outside of the exterior"
+        );
+    }
+
+    #[test]
+    fn test_nested_hole() {
+        let hole = ring(&[(0.5, 0.5), (0.5, 3.5), (3.5, 3.5), (3.5, 0.5)]);
+        let nested = ring(&[(1.0, 1.0), (1.0, 2.0), (2.0, 2.0), (2.0, 1.0)]);
+        let error = Polygon::new(square(), vec![hole.clone(), nested.clone()])
+            .validate()
+            .unwrap_err();
+        assert_eq!(error, ValidationError::NestedHole { ring: 2, outer: 1 });
+        assert_eq!(
+            alloc::format!("{}", error),
+            "interior ring 2 is inside of interior ring 1"
+        );
+
+        // Touching the outer hole at a point doesn't make it any less nested.
+        let touching = ring(&[(0.5, 2.0), (1.5, 3.0), (2.5, 2.0), (1.5, 1.0)]);
+        assert_eq!(
+            Polygon::new(square(), vec![touching, hole.clone()]).validate(),
+            Err(ValidationError::NestedHole { ring: 1, outer: 2 })
+        );
+
+        // Holes beside each other are fine.
+        let beside = ring(&[(2.5, 1.0), (2.5, 2.0), (3.5, 2.0), (3.5, 1.0)]);
+        assert_eq!(
+            Polygon::new(square(), vec![nested, beside]).validate(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_make_valid() {
+        let bowtie = ring(&[(0.0, 0.0), (4.0, 4.0), (4.0, 0.0), (0.0, 4.0)]);
+        let fixed = Polygon::new(bowtie, Vec::new()).make_valid();
+        assert_eq!(fixed.len(), 2);
+        assert!(fixed.iter().all(|polygon| polygon.validate().is_ok()));
+
+        // A hole with the wrong orientation, along with a spike and a repeated point.
+        let hole = ring(&[(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 2.0)]);
+        let exterior = ring(&[
+            (0.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 0.0),
+            (4.0, 4.0),
+            (6.0, 4.0),
+            (4.0, 4.0),
+            (0.0, 4.0),
+        ]);
+        let fixed = Polygon::new(exterior, vec![hole]).make_valid();
+
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed[0].validate(), Ok(()));
+        assert_eq!(fixed[0].exterior().len(), 4);
+        assert_eq!(signed_area(&fixed[0].interiors()[0]), -1.0);
+    }
+}
@@ -0,0 +1,684 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Open chains of points.
+
+#![cfg(feature = "alloc")]
+
+use crate::line::LineSegment;
+use crate::path::{OwnedPathBuffer, Path, PathBuffer, PathEvent, StraightPathEvent, Verb};
+use crate::point::Point;
+use crate::ApproxEq;
+
+use alloc::collections::BinaryHeap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+use core::mem;
+use core::slice::Windows;
+use num_traits::real::Real;
+
+/// An open chain of points, connected by straight lines.
+///
+/// This sits between a plain slice of points and a full path. It is useful for things
+/// like GPS traces and strokes, where only straight lines are needed but measuring the
+/// chain is common.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polyline<T: Copy> {
+    /// The points in the chain.
+    points: Vec<Point<T>>,
+}
+
+impl<T: Copy> Polyline<T> {
+    /// Create a new `Polyline` from its points.
+    pub fn new(points: Vec<Point<T>>) -> Self {
+        Polyline { points }
+    }
+
+    /// Get the points in this polyline.
+    pub fn points(&self) -> &[Point<T>] {
+        &self.points
+    }
+
+    /// Get the points in this polyline, consuming it.
+    pub fn into_points(self) -> Vec<Point<T>> {
+        self.points
+    }
+
+    /// Get the number of points in this polyline.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Tell whether this polyline has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Iterate over the line segments between consecutive points.
+    pub fn line_segments(&self) -> PolylineSegments<'_, T> {
+        PolylineSegments {
+            windows: self.points.windows(2),
+        }
+    }
+
+    /// Convert this polyline into a path buffer.
+    ///
+    /// Returns `None` if the polyline has no points.
+    pub fn to_path_buffer(&self) -> Option<OwnedPathBuffer<T>> {
+        let (&first, rest) = self.points.split_first()?;
+        Some(PathBuffer::new(
+            first,
+            rest.iter().map(|&point| (point, Verb::Line)).collect(),
+        ))
+    }
+
+    /// Round off the corners of this polyline.
+    ///
+    /// Each corner is replaced with a quadratic curve that uses the corner as its
+    /// control point. The curve starts and ends `radius` away from the corner, or
+    /// halfway along the neighboring segments if they are too short for that. Returns
+    /// `None` if the polyline has no points.
+    pub fn smooth(&self, radius: T) -> Option<OwnedPathBuffer<T>>
+    where
+        T: Real,
+    {
+        let (&first, rest) = self.points.split_first()?;
+        let half = T::one() / (T::one() + T::one());
+        let mut buffer = Vec::with_capacity(rest.len() * 2);
+
+        for window in self.points.windows(3) {
+            let (before, corner, after) = (window[0], window[1], window[2]);
+            let (incoming, outgoing) = (corner - before, after - corner);
+            let cut = radius
+                .min(incoming.length() * half)
+                .min(outgoing.length() * half);
+
+            if cut > T::zero() {
+                let start = corner - incoming.normalize() * cut;
+                let end = corner + outgoing.normalize() * cut;
+                buffer.push((start, Verb::Line));
+                buffer.push((end, Verb::Quadratic { control: corner }));
+            } else {
+                buffer.push((corner, Verb::Line));
+            }
+        }
+
+        if let Some(&last) = rest.last() {
+            buffer.push((last, Verb::Line));
+        }
+
+        Some(PathBuffer::new(first, buffer))
+    }
+
+    /// Smooth this polyline using Chaikin's corner cutting algorithm.
+    ///
+    /// Every iteration cuts each corner off at a quarter of the way along its
+    /// neighboring segments, roughly doubling the number of points. The first and last
+    /// points are kept in place.
+    pub fn chaikin(&self, iterations: usize) -> Self
+    where
+        T: Real,
+    {
+        let quarter = T::one() / (T::one() + T::one() + T::one() + T::one());
+        let mut points = self.points.clone();
+
+        for _ in 0..iterations {
+            let (first, last) = match (points.first(), points.last()) {
+                (Some(&first), Some(&last)) if points.len() > 2 => (first, last),
+                _ => break,
+            };
+
+            let segments = points.len() - 1;
+            let mut cut = Vec::with_capacity(segments * 2);
+            cut.push(first);
+            for (i, pair) in points.windows(2).enumerate() {
+                let (a, b) = (pair[0], pair[1]);
+                if i > 0 {
+                    cut.push(a.lerp(b, quarter));
+                }
+                if i + 1 < segments {
+                    cut.push(b.lerp(a, quarter));
+                }
+            }
+            cut.push(last);
+
+            points = cut;
+        }
+
+        Polyline::new(points)
+    }
+
+    /// Get the total length of this polyline.
+    pub fn length(&self) -> T
+    where
+        T: Real,
+    {
+        self.line_segments()
+            .fold(T::zero(), |length, segment| length + segment.length())
+    }
+
+    /// Get the point that is `distance` along this polyline from its first point.
+    ///
+    /// The distance is clamped to the length of the polyline. Returns `None` if the
+    /// polyline has no points.
+    pub fn point_at_distance(&self, distance: T) -> Option<Point<T>>
+    where
+        T: Real,
+    {
+        let mut remaining = distance;
+        for segment in self.line_segments() {
+            let length = segment.length();
+            if remaining <= length {
+                if length <= T::zero() {
+                    return Some(segment.from());
+                }
+
+                let t = remaining.max(T::zero()) / length;
+                return Some(segment.from().lerp(segment.to(), t));
+            }
+            remaining = remaining - length;
+        }
+
+        self.points.last().copied()
+    }
+
+    /// Get the point on this polyline that is closest to `point`.
+    ///
+    /// Returns `None` if the polyline has no points.
+    pub fn nearest_point(&self, point: Point<T>) -> Option<Point<T>>
+    where
+        T: Real,
+    {
+        if let [only] = self.points[..] {
+            return Some(only);
+        }
+
+        self.line_segments()
+            .map(|segment| closest_on_segment(point, segment.from(), segment.to()))
+            .map(|closest| (closest.distance_squared(point), closest))
+            .fold(
+                None,
+                |best: Option<(T, Point<T>)>, (distance, closest)| match best {
+                    Some((best_distance, _)) if best_distance <= distance => best,
+                    _ => Some((distance, closest)),
+                },
+            )
+            .map(|(_, closest)| closest)
+    }
+
+    /// Simplify this polyline using the given method.
+    ///
+    /// For [`SimplifyMethod::RamerDouglasPeucker`], `tolerance` is the furthest that
+    /// the simplified polyline may stray from the original. For
+    /// [`SimplifyMethod::VisvalingamWhyatt`], it is the area of the smallest triangle
+    /// that a point may form with its neighbors without being removed. The first and
+    /// last points are always kept.
+    pub fn simplify(&self, method: SimplifyMethod, tolerance: T) -> Self
+    where
+        T: Real,
+    {
+        let points = &self.points;
+        if points.len() < 3 {
+            return self.clone();
+        }
+
+        let keep = match method {
+            SimplifyMethod::RamerDouglasPeucker => ramer_douglas_peucker(points, tolerance),
+            SimplifyMethod::VisvalingamWhyatt => visvalingam_whyatt(points, tolerance),
+        };
+
+        Polyline::new(
+            points
+                .iter()
+                .zip(keep)
+                .filter(|&(_, keep)| keep)
+                .map(|(&point, _)| point)
+                .collect(),
+        )
+    }
+
+    /// Flatten a path into polylines, one for each of its subpaths.
+    ///
+    /// Closed subpaths repeat their first point at the end.
+    pub fn from_path(path: impl Path<T>, tolerance: T) -> Vec<Self>
+    where
+        T: Real + ApproxEq,
+    {
+        let mut polylines = Vec::new();
+        let mut points = Vec::new();
+
+        for event in path.flatten(tolerance) {
+            match event {
+                StraightPathEvent::Begin { at } => points.push(at),
+                StraightPathEvent::Line { to, .. } => points.push(to),
+                StraightPathEvent::End { first, close, .. } => {
+                    if close {
+                        points.push(first);
+                    }
+                    polylines.push(Polyline::new(mem::take(&mut points)));
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        polylines
+    }
+}
+
+/// The algorithm used to simplify a [`Polyline`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SimplifyMethod {
+    /// Keep the points that are furthest from the simplified line.
+    ///
+    /// This keeps the overall shape of the line as close as possible.
+    RamerDouglasPeucker,
+
+    /// Remove the points that form the smallest triangles with their neighbors.
+    ///
+    /// This gives smoother results, which are preferred for cartography.
+    VisvalingamWhyatt,
+}
+
+impl<T: Copy> From<Vec<Point<T>>> for Polyline<T> {
+    fn from(points: Vec<Point<T>>) -> Self {
+        Polyline::new(points)
+    }
+}
+
+impl<'a, T: Copy> Path<T> for &'a Polyline<T> {
+    type Iter = PolylineIter<'a, T>;
+
+    fn path_iter(self) -> Self::Iter {
+        PolylineIter {
+            points: &self.points,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the events in a [`Polyline`].
+#[derive(Debug, Clone)]
+pub struct PolylineIter<'a, T: Copy> {
+    /// The points in the polyline.
+    points: &'a [Point<T>],
+
+    /// The index of the next event.
+    index: usize,
+}
+
+impl<'a, T: Copy> Iterator for PolylineIter<'a, T> {
+    type Item = PathEvent<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let points = self.points;
+        if points.is_empty() || self.index > points.len() {
+            return None;
+        }
+
+        let index = self.index;
+        self.index += 1;
+
+        Some(match index {
+            0 => PathEvent::Begin { at: points[0] },
+            i if i == points.len() => PathEvent::End {
+                last: points[i - 1],
+                first: points[0],
+                close: false,
+            },
+            i => PathEvent::Line {
+                from: points[i - 1],
+                to: points[i],
+            },
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.points.len() {
+            0 => 0,
+            len => (len + 1).saturating_sub(self.index),
+        };
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: Copy> ExactSizeIterator for PolylineIter<'a, T> {}
+
+impl<'a, T: Copy> FusedIterator for PolylineIter<'a, T> {}
+
+/// An iterator over the line segments in a [`Polyline`].
+#[derive(Debug, Clone)]
+pub struct PolylineSegments<'a, T: Copy> {
+    /// Pairs of consecutive points.
+    windows: Windows<'a, Point<T>>,
+}
+
+impl<'a, T: Copy> Iterator for PolylineSegments<'a, T> {
+    type Item = LineSegment<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.windows
+            .next()
+            .map(|pair| LineSegment::new(pair[0], pair[1]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.windows.size_hint()
+    }
+}
+
+impl<'a, T: Copy> DoubleEndedIterator for PolylineSegments<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.windows
+            .next_back()
+            .map(|pair| LineSegment::new(pair[0], pair[1]))
+    }
+}
+
+impl<'a, T: Copy> ExactSizeIterator for PolylineSegments<'a, T> {}
+
+impl<'a, T: Copy> FusedIterator for PolylineSegments<'a, T> {}
+
+/// Find the points to keep using the Ramer-Douglas-Peucker algorithm.
+fn ramer_douglas_peucker<T: Real>(points: &[Point<T>], tolerance: T) -> Vec<bool> {
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let (a, b) = (points[start], points[end]);
+
+        // Find the point that is furthest from the line between the ends.
+        let furthest = (start + 1..end)
+            .map(|i| (closest_on_segment(points[i], a, b).distance(points[i]), i))
+            .fold(None, |best: Option<(T, usize)>, (distance, i)| match best {
+                Some((best_distance, _)) if best_distance >= distance => best,
+                _ => Some((distance, i)),
+            });
+
+        if let Some((distance, i)) = furthest {
+            if distance > tolerance {
+                keep[i] = true;
+                stack.push((start, i));
+                stack.push((i, end));
+            }
+        }
+    }
+
+    keep
+}
+
+/// Find the points to keep using the Visvalingam-Whyatt algorithm.
+fn visvalingam_whyatt<T: Real>(points: &[Point<T>], tolerance: T) -> Vec<bool> {
+    let len = points.len();
+    let mut keep = vec![true; len];
+    let mut prev = (0..len).map(|i| i.saturating_sub(1)).collect::<Vec<_>>();
+    let mut next = (1..=len).collect::<Vec<_>>();
+
+    // Stale candidates are skipped by checking their version.
+    let mut versions = vec![0; len];
+    let area = |prev: usize, i: usize, next: usize| {
+        (points[i] - points[prev])
+            .cross(points[next] - points[prev])
+            .abs()
+            / (T::one() + T::one())
+    };
+
+    let mut heap = (1..len - 1)
+        .map(|i| Candidate {
+            area: area(i - 1, i, i + 1),
+            index: i,
+            version: 0,
+        })
+        .collect::<BinaryHeap<_>>();
+
+    while let Some(candidate) = heap.pop() {
+        let i = candidate.index;
+        if !keep[i] || candidate.version != versions[i] {
+            continue;
+        }
+        if candidate.area >= tolerance {
+            break;
+        }
+
+        // Unlink the point and update its neighbors.
+        keep[i] = false;
+        let (before, after) = (prev[i], next[i]);
+        next[before] = after;
+        prev[after] = before;
+
+        for &j in &[before, after] {
+            if j != 0 && j != len - 1 {
+                versions[j] += 1;
+                heap.push(Candidate {
+                    area: area(prev[j], j, next[j]),
+                    index: j,
+                    version: versions[j],
+                });
+            }
+        }
+    }
+
+    keep
+}
+
+/// A point that may be removed by the Visvalingam-Whyatt algorithm.
+///
+/// These are ordered so that the smallest area is popped from the heap first.
+struct Candidate<T> {
+    area: T,
+    index: usize,
+    version: usize,
+}
+
+impl<T: PartialOrd> PartialEq for Candidate<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: PartialOrd> Eq for Candidate<T> {}
+
+impl<T: PartialOrd> PartialOrd for Candidate<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: PartialOrd> Ord for Candidate<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .partial_cmp(&self.area)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+/// Get the point on the segment between `a` and `b` that is closest to `point`.
+fn closest_on_segment<T: Real>(point: Point<T>, a: Point<T>, b: Point<T>) -> Point<T> {
+    let direction = b - a;
+    let length = direction.length_squared();
+    if length <= T::zero() {
+        return a;
+    }
+
+    let t = (point - a).dot(direction) / length;
+    a.lerp(b, t.max(T::zero()).min(T::one()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Triangle;
+
+    fn zigzag() -> Polyline<f64> {
+        Polyline::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(3.0, 4.0),
+            Point::new(6.0, 0.0),
+        ])
+    }
+
+    #[test]
+    fn test_measure() {
+        let line = zigzag();
+        assert_eq!(line.length(), 10.0);
+        assert_eq!(line.point_at_distance(-1.0), Some(Point::new(0.0, 0.0)));
+        assert_eq!(line.point_at_distance(5.0), Some(Point::new(3.0, 4.0)));
+        assert_eq!(line.point_at_distance(7.5), Some(Point::new(4.5, 2.0)));
+        assert_eq!(line.point_at_distance(20.0), Some(Point::new(6.0, 0.0)));
+        assert_eq!(Polyline::<f64>::default().point_at_distance(1.0), None);
+
+        assert_eq!(
+            line.nearest_point(Point::new(3.0, 10.0)),
+            Some(Point::new(3.0, 4.0))
+        );
+        assert_eq!(
+            line.nearest_point(Point::new(-2.0, -1.0)),
+            Some(Point::new(0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        let line = zigzag();
+        assert_eq!(line.line_segments().len(), 2);
+        assert_eq!((&line).path_iter().count(), 4);
+
+        let buffer = line.to_path_buffer().unwrap();
+        assert_eq!(
+            (&buffer).path_iter().collect::<Vec<_>>(),
+            (&line).path_iter().collect::<Vec<_>>()
+        );
+        assert!(Polyline::<f64>::default().to_path_buffer().is_none());
+    }
+
+    #[test]
+    fn test_smooth() {
+        let line = Polyline::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 0.0),
+            Point::new(10.0, 3.0),
+        ]);
+        let smoothed = line.smooth(2.0).unwrap();
+
+        // The second segment is too short to cut two units off of it.
+        assert_eq!(
+            (&smoothed).path_iter().collect::<Vec<_>>(),
+            vec![
+                PathEvent::Begin {
+                    at: Point::new(0.0, 0.0)
+                },
+                PathEvent::Line {
+                    from: Point::new(0.0, 0.0),
+                    to: Point::new(8.5, 0.0)
+                },
+                PathEvent::Quadratic {
+                    from: Point::new(8.5, 0.0),
+                    control: Point::new(10.0, 0.0),
+                    to: Point::new(10.0, 1.5)
+                },
+                PathEvent::Line {
+                    from: Point::new(10.0, 1.5),
+                    to: Point::new(10.0, 3.0)
+                },
+                PathEvent::End {
+                    first: Point::new(0.0, 0.0),
+                    last: Point::new(10.0, 3.0),
+                    close: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chaikin() {
+        let line = Polyline::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(4.0, 4.0),
+        ]);
+
+        assert_eq!(
+            line.chaikin(1).points(),
+            &[
+                Point::new(0.0, 0.0),
+                Point::new(3.0, 0.0),
+                Point::new(4.0, 1.0),
+                Point::new(4.0, 4.0),
+            ]
+        );
+        assert_eq!(line.chaikin(2).len(), 6);
+        assert_eq!(line.chaikin(0), line);
+    }
+
+    #[test]
+    fn test_simplify() {
+        let line = Polyline::new(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.1),
+            Point::new(2.0, -0.1),
+            Point::new(3.0, 5.0),
+            Point::new(4.0, 6.0),
+            Point::new(5.0, 7.0),
+        ]);
+
+        let simplified = line.simplify(SimplifyMethod::RamerDouglasPeucker, 0.5);
+        assert_eq!(
+            simplified.points(),
+            &[
+                Point::new(0.0, 0.0),
+                Point::new(2.0, -0.1),
+                Point::new(3.0, 5.0),
+                Point::new(5.0, 7.0),
+            ]
+        );
+
+        // The bumps near the start and the point on the straight part are small.
+        let simplified = line.simplify(SimplifyMethod::VisvalingamWhyatt, 0.5);
+        assert_eq!(
+            simplified.points(),
+            &[
+                Point::new(0.0, 0.0),
+                Point::new(2.0, -0.1),
+                Point::new(3.0, 5.0),
+                Point::new(5.0, 7.0),
+            ]
+        );
+        assert_eq!(
+            line.simplify(SimplifyMethod::VisvalingamWhyatt, 100.0)
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_from_path() {
+        let triangle = Triangle::new(
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(0.0, 3.0),
+        );
+        let polylines = Polyline::from_path(triangle, 0.1);
+        assert_eq!(polylines.len(), 1);
+        assert_eq!(polylines[0].len(), 4);
+        assert_eq!(polylines[0].length(), 12.0);
+    }
+}
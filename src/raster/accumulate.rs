@@ -0,0 +1,246 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Signed-area coverage accumulation, in the style of font rasterizers.
+
+use crate::path::Path;
+use crate::point::Point;
+use crate::ApproxEq;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// Accumulates the exact area covered by a shape in each pixel.
+///
+/// Each edge adds the signed area it covers to the cells that it passes through. Summing
+/// the cells across a row then gives the coverage of each pixel. Unlike sampling
+/// trapezoids at sub-scanlines, this is exact, which matters for very thin shapes. The
+/// pixel at `(x, y)` covers the area from `(x, y)` to `(x + 1, y + 1)`.
+#[derive(Debug, Clone)]
+pub struct Accumulator {
+    /// The width of the area, in pixels.
+    width: usize,
+
+    /// The height of the area, in pixels.
+    height: usize,
+
+    /// The accumulated area deltas, row by row.
+    ///
+    /// Every row has two extra cells, since edges spill into the cells to their right.
+    cells: Vec<f32>,
+}
+
+impl Accumulator {
+    /// Create a new, empty `Accumulator` of the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        Accumulator {
+            width,
+            height,
+            cells: vec![0.0; (width + 2) * height],
+        }
+    }
+
+    /// Get the width of the area, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the area, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Remove everything that has been accumulated.
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(|cell| *cell = 0.0);
+    }
+
+    /// Add the outline of a path, treating every subpath as closed.
+    pub fn add_path<T, P>(&mut self, path: P, tolerance: T)
+    where
+        T: Real + ApproxEq,
+        P: Path<T>,
+    {
+        let to_f32 = |point: Point<T>| {
+            Point::new(
+                point.x().to_f32().unwrap_or(0.0),
+                point.y().to_f32().unwrap_or(0.0),
+            )
+        };
+
+        for segment in path.segments(tolerance).close_open_subpaths(true) {
+            self.add_line(to_f32(segment.from()), to_f32(segment.to()));
+        }
+    }
+
+    /// Add a single edge of an outline.
+    pub fn add_line(&mut self, from: Point<f32>, to: Point<f32>) {
+        let right = self.width as f32;
+
+        // Split the line where it leaves the area on the left or right, and squash the
+        // parts outside onto the border. Everything to the right of them is still covered.
+        let mut cuts = [0.0, 1.0, 1.0, 1.0];
+        let mut count = 1;
+        for &border in [0.0, right].iter() {
+            let t = (border - from.x()) / (to.x() - from.x());
+            if t > 0.0 && t < 1.0 {
+                cuts[count] = t;
+                count += 1;
+            }
+        }
+        cuts[..=count].sort_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+
+        for window in cuts[..=count].windows(2) {
+            let clamp = |t: f32| {
+                let point = from.lerp(to, t);
+                Point::new(point.x().clamp(0.0, right), point.y())
+            };
+
+            self.add_clamped_line(clamp(window[0]), clamp(window[1]));
+        }
+    }
+
+    /// Add an edge that is within the horizontal bounds of the area.
+    fn add_clamped_line(&mut self, from: Point<f32>, to: Point<f32>) {
+        if (from.y() - to.y()).abs() <= f32::EPSILON || !from.y().is_finite() {
+            return;
+        }
+
+        let (direction, top, bottom) = if from.y() < to.y() {
+            (1.0, from, to)
+        } else {
+            (-1.0, to, from)
+        };
+
+        let stride = self.width + 2;
+        let dxdy = (bottom.x() - top.x()) / (bottom.y() - top.y());
+        let first_row = top.y().max(0.0) as usize;
+        let last_row = (bottom.y().ceil().max(0.0) as usize).min(self.height);
+
+        let mut x = top.x();
+        if top.y() < 0.0 {
+            x -= top.y() * dxdy;
+        }
+
+        for row in first_row..last_row {
+            let start = row * stride;
+            let dy = ((row + 1) as f32).min(bottom.y()) - (row as f32).max(top.y());
+            let x_next = x + dxdy * dy;
+            let delta = dy * direction;
+
+            let (x0, x1) = if x < x_next { (x, x_next) } else { (x_next, x) };
+            let x0_floor = x0.floor();
+            let x0_index = start + x0_floor as usize;
+            let x1_ceil = x1.ceil();
+            let span = x1_ceil as usize - x0_floor as usize;
+
+            if span <= 1 {
+                // The edge stays within a single pixel in this row.
+                let middle = 0.5 * (x + x_next) - x0_floor;
+                self.cells[x0_index] += delta - delta * middle;
+                self.cells[x0_index + 1] += delta * middle;
+            } else {
+                // Spread the area over every pixel the edge crosses.
+                let scale = (x1 - x0).recip();
+                let x0_fract = x0 - x0_floor;
+                let first_area = 0.5 * scale * (1.0 - x0_fract) * (1.0 - x0_fract);
+                let x1_fract = x1 - x1_ceil + 1.0;
+                let last_area = 0.5 * scale * x1_fract * x1_fract;
+
+                self.cells[x0_index] += delta * first_area;
+                if span == 2 {
+                    self.cells[x0_index + 1] += delta * (1.0 - first_area - last_area);
+                } else {
+                    let second_area = scale * (1.5 - x0_fract);
+                    self.cells[x0_index + 1] += delta * (second_area - first_area);
+                    for cell in &mut self.cells[x0_index + 2..x0_index + span - 1] {
+                        *cell += delta * scale;
+                    }
+
+                    let middle_area = second_area + (span - 3) as f32 * scale;
+                    self.cells[x0_index + span - 1] += delta * (1.0 - middle_area - last_area);
+                }
+                self.cells[x0_index + span] += delta * last_area;
+            }
+
+            x = x_next;
+        }
+    }
+
+    /// Get the coverage of the pixel at the given coordinates, clamped to `[0, 1]`.
+    pub fn value(&self, x: usize, y: usize) -> f32 {
+        self.row(y).nth(x).unwrap_or(0.0)
+    }
+
+    /// Iterate over the coverage of each row of pixels, clamped to the `[0, 1]` range.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = f32> + '_> + '_ {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// Iterate over the coverage of a single row of pixels.
+    fn row(&self, y: usize) -> impl Iterator<Item = f32> + '_ {
+        let stride = self.width + 2;
+        let cells = self.cells.get(y * stride..y * stride + self.width);
+
+        cells
+            .into_iter()
+            .flatten()
+            .scan(0.0, |total: &mut f32, cell| {
+                *total += *cell;
+                Some(total.abs().min(1.0))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::box2d::Box;
+
+    #[test]
+    fn test_box() {
+        let mut accumulator = Accumulator::new(4, 4);
+        accumulator.add_path(Box::new(Point::new(1.0f32, 1.0), Point::new(2.5, 3.0)), 0.1);
+
+        assert_eq!(accumulator.value(0, 0), 0.0);
+        assert_eq!(accumulator.value(1, 1), 1.0);
+        assert_eq!(accumulator.value(2, 2), 0.5);
+        assert_eq!(accumulator.value(3, 2), 0.0);
+        assert_eq!(accumulator.value(1, 3), 0.0);
+    }
+
+    #[test]
+    fn test_thin_diagonal() {
+        // A thin sliver that is partially outside of the area.
+        let mut accumulator = Accumulator::new(4, 4);
+        let triangle = crate::Triangle::new(
+            Point::new(-2.0f32, 0.0),
+            Point::new(4.0, 4.0),
+            Point::new(3.9, 4.0),
+        );
+        accumulator.add_path(triangle, 0.1);
+
+        // The total area inside of the accumulator's bounds.
+        let total: f32 = accumulator.rows().flatten().sum();
+        let expected = 0.5 * 0.1 * 4.0 * (4.0 / 6.0) * (4.0 / 6.0) * 6.0 / 4.0;
+        assert!((total - expected).abs() < 0.05, "{} vs {}", total, expected);
+
+        // Everything to the left of the edge is empty.
+        assert_eq!(accumulator.value(0, 3), 0.0);
+    }
+}
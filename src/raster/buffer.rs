@@ -0,0 +1,140 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! A buffer of pixels to rasterize into.
+
+use num_traits::real::Real;
+
+/// The number of bytes in a single pixel.
+const BYTES_PER_PIXEL: usize = 4;
+
+/// A mutable view into a buffer of RGBA8 pixels.
+///
+/// Pixels are stored row by row, with the red, green, blue and alpha components in
+/// that order. The color components are premultiplied by alpha.
+#[derive(Debug)]
+pub struct PixelBuffer<'a> {
+    /// The raw pixel data.
+    data: &'a mut [u8],
+
+    /// The width of the buffer, in pixels.
+    width: usize,
+
+    /// The height of the buffer, in pixels.
+    height: usize,
+
+    /// The number of bytes between the start of two rows.
+    stride: usize,
+}
+
+impl<'a> PixelBuffer<'a> {
+    /// Create a new `PixelBuffer` with tightly packed rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is too small to hold `width * height` pixels.
+    pub fn new(data: &'a mut [u8], width: usize, height: usize) -> Self {
+        Self::with_stride(data, width, height, width * BYTES_PER_PIXEL)
+    }
+
+    /// Create a new `PixelBuffer` with the given number of bytes between rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is smaller than a row of pixels, or if `data` is too small to
+    /// hold `height` rows.
+    pub fn with_stride(data: &'a mut [u8], width: usize, height: usize, stride: usize) -> Self {
+        assert!(
+            stride >= width * BYTES_PER_PIXEL,
+            "stride is too small for the width"
+        );
+
+        let needed = match height {
+            0 => 0,
+            height => stride * (height - 1) + width * BYTES_PER_PIXEL,
+        };
+        assert!(data.len() >= needed, "pixel buffer is too small");
+
+        PixelBuffer {
+            data,
+            width,
+            height,
+            stride,
+        }
+    }
+
+    /// Get the width of the buffer, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the buffer, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the number of bytes between the start of two rows.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Get the pixel at the given coordinates, if it is in bounds.
+    pub fn pixel(&self, x: usize, y: usize) -> Option<[u8; 4]> {
+        self.offset(x, y).map(|offset| {
+            let mut pixel = [0; BYTES_PER_PIXEL];
+            pixel.copy_from_slice(&self.data[offset..offset + BYTES_PER_PIXEL]);
+            pixel
+        })
+    }
+
+    /// Get the underlying pixel data.
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    /// Get the underlying pixel data, mutably.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    /// Composite a non-premultiplied color with the given coverage onto a pixel.
+    pub(super) fn blend(&mut self, x: usize, y: usize, color: [f32; 4], coverage: f32) {
+        let offset = match self.offset(x, y) {
+            Some(offset) => offset,
+            None => return,
+        };
+
+        let alpha = color[3] * coverage.min(1.0);
+        let inverse = 1.0 - alpha;
+        let pixel = &mut self.data[offset..offset + BYTES_PER_PIXEL];
+
+        for (i, dst) in pixel.iter_mut().enumerate() {
+            let src = if i == 3 { alpha } else { color[i] * alpha };
+            let value = src * 255.0 + f32::from(*dst) * inverse;
+            *dst = Real::round(value).clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    /// Get the byte offset of the pixel at the given coordinates.
+    fn offset(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width && y < self.height {
+            Some(y * self.stride + x * BYTES_PER_PIXEL)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Finding the grid cells that a path passes through.
+
+use super::to_i32;
+use crate::line::{CellSteps, LineSegment};
+use crate::path::{LineSegments, Path, PathEvent};
+use crate::point::Point;
+use crate::size::Size;
+use crate::ApproxEq;
+
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+use num_traits::real::Real;
+
+/// Iterate over every grid cell that a path passes through.
+///
+/// The path is flattened with the given `tolerance`, and the grid is made up of cells of
+/// size `cell_size` with the cell at `(0, 0)` starting at the origin. This is conservative:
+/// when the path passes exactly through a corner, every cell touching that corner is
+/// visited. A cell may be visited more than once if the path comes back to it.
+pub fn cells<T, P>(path: P, tolerance: T, cell_size: Size<T>) -> Cells<T, P::Iter>
+where
+    T: Real + ApproxEq,
+    P: Path<T>,
+{
+    Cells {
+        segments: path.segments(tolerance),
+        cell_size,
+        walk: None,
+        last: None,
+    }
+}
+
+/// The iterator returned by [`cells`].
+#[derive(Debug, Clone)]
+pub struct Cells<T: Copy, P> {
+    /// The segments of the path.
+    segments: LineSegments<T, P>,
+
+    /// The size of each cell.
+    cell_size: Size<T>,
+
+    /// The walk along the current segment.
+    walk: Option<GridWalk<T>>,
+
+    /// The last cell that was yielded.
+    last: Option<Point<i32>>,
+}
+
+impl<T: Real + ApproxEq, P: Iterator<Item = PathEvent<T>>> Iterator for Cells<T, P> {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let cell = match self.walk.as_mut().and_then(|walk| walk.next()) {
+                Some(cell) => cell,
+                None => {
+                    let segment = self.segments.next()?;
+                    self.walk = Some(GridWalk::new(segment, self.cell_size));
+                    continue;
+                }
+            };
+
+            // Segments share their end points, so skip repeated cells.
+            if self.last.replace(cell) != Some(cell) {
+                return Some(cell);
+            }
+        }
+    }
+}
+
+impl<T: Real + ApproxEq, P: Iterator<Item = PathEvent<T>>> FusedIterator for Cells<T, P> {}
+
+/// A walk over the cells along a single line segment.
+///
+/// This steps through the cells in the same way as [`Supercover`], except that the
+/// segment's ends can be anywhere inside of their cells.
+///
+/// [`Supercover`]: crate::line::Supercover
+#[derive(Debug, Clone)]
+struct GridWalk<T: Copy> {
+    /// The steps from the first cell to the last.
+    steps: CellSteps,
+
+    /// The parameter along the segment where it crosses the first cell border on each axis.
+    t_first: Point<T>,
+
+    /// The change in the parameter between cell borders on each axis.
+    t_delta: Point<T>,
+}
+
+impl<T: Real> GridWalk<T> {
+    /// Begin a walk along a segment.
+    fn new(segment: LineSegment<T>, cell_size: Size<T>) -> Self {
+        let (from, to) = segment.points();
+        let (width, height) = (cell_size.width(), cell_size.height());
+        let cell_of = |point: Point<T>| {
+            Point::new(
+                to_i32((point.x() / width).floor()),
+                to_i32((point.y() / height).floor()),
+            )
+        };
+
+        let start = cell_of(from);
+
+        // Find the first crossing and the spacing between crossings on one axis.
+        let axis = |from: T, delta: T, cell: i32, size: T| {
+            if delta > T::zero() {
+                let border = T::from(cell + 1).unwrap() * size;
+                ((border - from) / delta, size / delta)
+            } else if delta < T::zero() {
+                let border = T::from(cell).unwrap() * size;
+                ((border - from) / delta, size / -delta)
+            } else {
+                (T::max_value(), T::max_value())
+            }
+        };
+
+        let (tx, dx) = axis(from.x(), to.x() - from.x(), start.x(), width);
+        let (ty, dy) = axis(from.y(), to.y() - from.y(), start.y(), height);
+
+        GridWalk {
+            steps: CellSteps::new(start, cell_of(to)),
+            t_first: Point::new(tx, ty),
+            t_delta: Point::new(dx, dy),
+        }
+    }
+}
+
+impl<T: Real> Iterator for GridWalk<T> {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (first, delta) = (self.t_first, self.t_delta);
+
+        // Step along whichever axis crosses a border first, or both at a corner.
+        self.steps.next_by(|progress| {
+            let tx = first.x() + delta.x() * T::from(progress.x()).unwrap();
+            let ty = first.y() + delta.y() * T::from(progress.y()).unwrap();
+            tx.partial_cmp(&ty).unwrap_or(Ordering::Equal)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::box2d::Box;
+    use alloc::vec::Vec;
+
+    fn unit() -> Size<f32> {
+        Size::new(1.0, 1.0)
+    }
+
+    #[test]
+    fn test_straight() {
+        let segment = LineSegment::new(Point::new(0.5f32, 0.5), Point::new(2.5, 0.5));
+        let found = cells(segment, 0.1, unit()).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [Point::new(0, 0), Point::new(1, 0), Point::new(2, 0)]
+        );
+    }
+
+    #[test]
+    fn test_corner() {
+        let segment = LineSegment::new(Point::new(0.5f32, 0.5), Point::new(1.5, 1.5));
+        let found = cells(segment, 0.1, unit()).collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(1, 1)
+            ]
+        );
+
+        // Going backwards with a larger cell size.
+        let segment = LineSegment::new(Point::new(-1.0f32, 3.0), Point::new(-5.0, 1.0));
+        let found = cells(segment, 0.1, Size::new(2.0, 2.0)).collect::<Vec<_>>();
+        assert_eq!(found.first(), Some(&Point::new(-1, 1)));
+        assert_eq!(found.last(), Some(&Point::new(-3, 0)));
+    }
+
+    #[test]
+    fn test_box_outline() {
+        let shape = Box::new(Point::new(0.5f32, 0.5), Point::new(2.5, 2.5));
+        let mut found = cells(shape, 0.1, unit()).collect::<Vec<_>>();
+        found.sort();
+        found.dedup();
+
+        assert_eq!(found.len(), 8);
+        assert!(!found.contains(&Point::new(1, 1)));
+    }
+}
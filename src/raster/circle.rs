@@ -0,0 +1,218 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Rasterization of circles using the midpoint algorithm.
+
+use crate::point::Point;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+
+/// Iterate over the pixels on the outline of a circle.
+///
+/// This uses the midpoint circle algorithm, which only uses integer arithmetic. Every
+/// pixel is visited exactly once. A negative radius produces no pixels.
+pub fn circle_outline(center: Point<i32>, radius: i32) -> CircleOutline {
+    CircleOutline {
+        center,
+        x: radius,
+        y: 0,
+        decision: 1 - radius,
+        batch: [Point::new(0, 0); 8],
+        len: 0,
+        index: 0,
+    }
+}
+
+/// Iterate over the pixels inside of a circle, row by row.
+///
+/// The filled area matches the pixels produced by [`circle_outline`]. A negative radius
+/// produces no pixels.
+pub fn circle_fill(center: Point<i32>, radius: i32) -> CircleFill {
+    // Find the half-width of each row using the outline.
+    let mut half_widths = vec![-1; (radius.max(-1) + 1) as usize];
+    let mut outline = circle_outline(Point::new(0, 0), radius);
+
+    while let Some((x, y)) = outline.step() {
+        half_widths[y as usize] = half_widths[y as usize].max(x);
+        half_widths[x as usize] = half_widths[x as usize].max(y);
+    }
+
+    CircleFill {
+        center,
+        half_widths,
+        row: -radius,
+        column: None,
+    }
+}
+
+/// The iterator returned by [`circle_outline`].
+#[derive(Debug, Clone)]
+pub struct CircleOutline {
+    /// The center of the circle.
+    center: Point<i32>,
+
+    /// The current X offset in the first octant.
+    x: i32,
+
+    /// The current Y offset in the first octant.
+    y: i32,
+
+    /// The midpoint decision variable.
+    decision: i32,
+
+    /// The pixels mirrored from the current octant point.
+    batch: [Point<i32>; 8],
+
+    /// The number of distinct pixels in `batch`.
+    len: usize,
+
+    /// The index of the next pixel in `batch`.
+    index: usize,
+}
+
+impl CircleOutline {
+    /// Advance to the next point in the first octant, returning the current one.
+    fn step(&mut self) -> Option<(i32, i32)> {
+        if self.x < self.y {
+            return None;
+        }
+
+        let point = (self.x, self.y);
+        self.y += 1;
+
+        if self.decision < 0 {
+            self.decision += 2 * self.y + 1;
+        } else {
+            self.x -= 1;
+            self.decision += 2 * (self.y - self.x) + 1;
+        }
+
+        Some(point)
+    }
+}
+
+impl Iterator for CircleOutline {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            let (x, y) = self.step()?;
+            let mirrored = [
+                (x, y),
+                (y, x),
+                (-y, x),
+                (-x, y),
+                (-x, -y),
+                (-y, -x),
+                (y, -x),
+                (x, -y),
+            ];
+
+            // Points on the axes and diagonals are mirrored onto themselves.
+            self.len = 0;
+            self.index = 0;
+            for &(dx, dy) in mirrored.iter() {
+                let point = Point::new(self.center.x() + dx, self.center.y() + dy);
+
+                if !self.batch[..self.len].contains(&point) {
+                    self.batch[self.len] = point;
+                    self.len += 1;
+                }
+            }
+        }
+
+        let point = self.batch[self.index];
+        self.index += 1;
+        Some(point)
+    }
+}
+
+impl FusedIterator for CircleOutline {}
+
+/// The iterator returned by [`circle_fill`].
+#[derive(Debug, Clone)]
+pub struct CircleFill {
+    /// The center of the circle.
+    center: Point<i32>,
+
+    /// The half-width of each row, indexed by its distance from the center.
+    half_widths: Vec<i32>,
+
+    /// The current row, relative to the center.
+    row: i32,
+
+    /// The next column in the current row, relative to the center.
+    column: Option<i32>,
+}
+
+impl Iterator for CircleFill {
+    type Item = Point<i32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let half_width = *self.half_widths.get(self.row.unsigned_abs() as usize)?;
+            let column = *self.column.get_or_insert(-half_width);
+
+            if column > half_width {
+                self.row += 1;
+                self.column = None;
+                continue;
+            }
+
+            self.column = Some(column + 1);
+            return Some(Point::new(
+                self.center.x() + column,
+                self.center.y() + self.row,
+            ));
+        }
+    }
+}
+
+impl FusedIterator for CircleFill {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outline() {
+        let center = Point::new(5, 5);
+        assert_eq!(circle_outline(center, 0).collect::<Vec<_>>(), vec![center]);
+        assert_eq!(circle_outline(center, 1).count(), 4);
+        assert_eq!(circle_outline(center, 2).count(), 12);
+        assert_eq!(circle_outline(center, -1).count(), 0);
+
+        for point in circle_outline(center, 10) {
+            let (dx, dy) = (point.x() - 5, point.y() - 5);
+            let distance_squared = dx * dx + dy * dy;
+            assert!((90..=110).contains(&distance_squared));
+        }
+    }
+
+    #[test]
+    fn test_fill() {
+        let center = Point::new(-3, 2);
+        assert_eq!(circle_fill(center, 0).collect::<Vec<_>>(), vec![center]);
+        assert_eq!(circle_fill(center, 2).count(), 21);
+        assert_eq!(circle_fill(center, -4).count(), 0);
+
+        let filled = circle_fill(center, 7).collect::<Vec<_>>();
+        assert!(circle_outline(center, 7).all(|point| filled.contains(&point)));
+    }
+}
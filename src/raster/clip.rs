@@ -0,0 +1,212 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Nested clipping for rasterization.
+
+use super::coverage::Coverage;
+use super::Mask;
+use crate::box2d::Box;
+use crate::path::Shape;
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use num_traits::real::Real;
+
+/// A stack of clip shapes, each of which is intersected with the ones below it.
+///
+/// Clips are expressed in pixel coordinates for a target of a fixed size. Boxes are
+/// stored directly, while other shapes are rasterized into a coverage mask. Pushing a
+/// box reuses the mask of the level below it, so masks are only built for shapes.
+#[derive(Debug, Clone)]
+pub struct ClipStack<T: Copy> {
+    /// The width of the clipped area, in pixels.
+    width: usize,
+
+    /// The height of the clipped area, in pixels.
+    height: usize,
+
+    /// The intersection of the clips at every level of the stack.
+    layers: Vec<Layer<T>>,
+
+    /// The coverage masks of the levels that pushed a shape, from the bottom up.
+    masks: Vec<Coverage>,
+}
+
+/// The accumulated clip at one level of the stack.
+#[derive(Debug, Clone)]
+struct Layer<T: Copy> {
+    /// The bounds that everything is clipped to.
+    bounds: Box<T>,
+
+    /// The index of the coverage mask of the non-box clips, if there are any.
+    mask: Option<usize>,
+}
+
+impl<T: Copy> ClipStack<T> {
+    /// Create a new, empty `ClipStack` for a target of the given size.
+    pub fn new(width: usize, height: usize) -> Self {
+        ClipStack {
+            width,
+            height,
+            layers: Vec::new(),
+            masks: Vec::new(),
+        }
+    }
+
+    /// Get the width of the clipped area, in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the height of the clipped area, in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the number of clips on the stack.
+    pub fn depth(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Tell if there are no clips on the stack.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Get the bounds that everything is currently clipped to, if there are any clips.
+    pub fn bounds(&self) -> Option<Box<T>> {
+        self.layers.last().map(|layer| layer.bounds)
+    }
+
+    /// Remove the most recently pushed clip.
+    ///
+    /// Returns `false` if the stack was already empty.
+    pub fn pop(&mut self) -> bool {
+        if self.layers.pop().is_none() {
+            return false;
+        }
+
+        // Drop the mask if it belonged to the popped level.
+        let used = self
+            .layers
+            .last()
+            .and_then(|layer| layer.mask)
+            .map_or(0, |mask| mask + 1);
+        self.masks.truncate(used);
+        true
+    }
+
+    /// Remove all clips from the stack.
+    pub fn clear(&mut self) {
+        self.layers.clear();
+        self.masks.clear();
+    }
+
+    /// Intersect the current clip with a box.
+    pub fn push_box(&mut self, bounds: Box<T>)
+    where
+        T: PartialOrd,
+    {
+        let layer = match self.layers.last() {
+            Some(top) => Layer {
+                bounds: top.bounds.intersection(&bounds),
+                mask: top.mask,
+            },
+            None => Layer { bounds, mask: None },
+        };
+
+        self.layers.push(layer);
+    }
+
+    /// Intersect the current clip with an arbitrary shape.
+    pub fn push_shape<S: Shape<T>>(&mut self, shape: S, tolerance: T)
+    where
+        T: Real + ApproxEq,
+    {
+        let mut coverage = Coverage::new(self.width, self.height);
+        let mut bounds = Box::unbounded_real();
+
+        for trapezoid in shape.trapezoids(tolerance) {
+            coverage.add_trapezoid(&trapezoid);
+            bounds = bounds.union(&crate::BoundingBox::bounding_box(&trapezoid));
+        }
+
+        let bounds = match self.layers.last() {
+            Some(top) => {
+                if let Some(mask) = top.mask {
+                    coverage.multiply(&self.masks[mask]);
+                }
+
+                top.bounds.intersection(&bounds)
+            }
+            None => bounds,
+        };
+
+        self.masks.push(coverage);
+        self.layers.push(Layer {
+            bounds,
+            mask: Some(self.masks.len() - 1),
+        });
+    }
+
+    /// Get the fraction of the pixel at the given coordinates that is not clipped out.
+    pub fn coverage(&self, x: usize, y: usize) -> f32
+    where
+        T: Real,
+    {
+        let layer = match self.layers.last() {
+            Some(layer) => layer,
+            None => return 1.0,
+        };
+
+        let (min, max) = layer.bounds.min_max();
+        let overlap = |lo: T, hi: T, pixel: usize| {
+            let lo = lo.to_f32().unwrap_or(0.0);
+            let hi = hi.to_f32().unwrap_or(0.0);
+            let pixel = pixel as f32;
+            (hi.min(pixel + 1.0) - lo.max(pixel)).clamp(0.0, 1.0)
+        };
+
+        let boxed = overlap(min.x(), max.x(), x) * overlap(min.y(), max.y(), y);
+        match layer.mask {
+            Some(mask) if boxed > 0.0 => boxed * self.masks[mask].value(x, y),
+            _ => boxed,
+        }
+    }
+
+    /// Multiply the coverage of every pixel in a mask by the coverage of the clip.
+    ///
+    /// Pixels outside of the clipped area are cleared, unless the stack is empty.
+    pub(super) fn clip_mask(&self, mask: &mut Mask)
+    where
+        T: Real,
+    {
+        if self.is_empty() {
+            return;
+        }
+
+        for (point, value) in mask.bounds().points().zip(mask.data_mut()) {
+            let clip = match (usize::try_from(point.x()), usize::try_from(point.y())) {
+                (Ok(x), Ok(y)) => self.coverage(x, y),
+                _ => 0.0,
+            };
+
+            *value = (f32::from(*value) * clip).round() as u8;
+        }
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Tracing the outlines of a sampled grid of values.
+
+use crate::path::{OwnedPathBuffer, PathBuffer, Verb};
+use crate::point::Point;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// Trace the outlines where a grid of samples crosses `iso_value`.
+///
+/// `grid` is a row-major list of samples with the given `(width, height)` dimensions,
+/// where the sample at index `y * width + x` is located at `(x, y)`. Samples at or above
+/// `iso_value` are considered to be inside. Crossings are found with marching squares
+/// and positioned by linearly interpolating between samples. Ambiguous cells are
+/// resolved by the average of their corners.
+///
+/// Outlines are oriented so that the inside is on the right, when the Y axis points
+/// down. Outlines that enclose an area are closed by a line back to their first point,
+/// while outlines that run off the edge of the grid are left open.
+///
+/// # Panics
+///
+/// Panics if `grid` has fewer than `width * height` samples.
+pub fn contour<T: Real>(grid: &[T], dims: (usize, usize), iso_value: T) -> Vec<OwnedPathBuffer<T>> {
+    let (width, height) = dims;
+    assert!(
+        grid.len() >= width * height,
+        "grid has {} samples, expected {}",
+        grid.len(),
+        width * height
+    );
+
+    let grid = Grid {
+        samples: grid,
+        width,
+        iso_value,
+    };
+
+    // Map every crossing to the next crossing along the same outline.
+    let mut links = BTreeMap::new();
+    for y in 0..height.saturating_sub(1) {
+        for x in 0..width.saturating_sub(1) {
+            grid.link_cell(x, y, &mut links);
+        }
+    }
+
+    let ends = links.values().copied().collect::<BTreeSet<_>>();
+    let mut contours = Vec::new();
+
+    // Open outlines start at crossings that nothing leads into.
+    let starts = links
+        .keys()
+        .copied()
+        .filter(|key| !ends.contains(key))
+        .collect::<Vec<_>>();
+    for start in starts {
+        contours.push(grid.trace(start, &mut links, false));
+    }
+
+    // Everything left over forms a closed loop.
+    while let Some(&start) = links.keys().next() {
+        contours.push(grid.trace(start, &mut links, true));
+    }
+
+    contours
+}
+
+/// A grid of samples to be contoured.
+struct Grid<'a, T> {
+    /// The samples in the grid, row by row.
+    samples: &'a [T],
+
+    /// The number of samples in each row.
+    width: usize,
+
+    /// The value that outlines are traced along.
+    iso_value: T,
+}
+
+/// The key of a crossing on the horizontal grid line to the right of a sample.
+const HORIZONTAL: usize = 0;
+
+/// The key of a crossing on the vertical grid line below a sample.
+const VERTICAL: usize = 1;
+
+impl<T: Real> Grid<'_, T> {
+    /// Get the sample at the given coordinates.
+    fn sample(&self, x: usize, y: usize) -> T {
+        self.samples[y * self.width + x]
+    }
+
+    /// Get the key for the crossing on the grid line starting at the given coordinates.
+    fn key(&self, x: usize, y: usize, direction: usize) -> usize {
+        (y * self.width + x) * 2 + direction
+    }
+
+    /// Get the position of the crossing with the given key.
+    fn crossing(&self, key: usize) -> Point<T> {
+        let (x, y) = ((key / 2) % self.width, (key / 2) / self.width);
+        let (x2, y2) = if key % 2 == HORIZONTAL {
+            (x + 1, y)
+        } else {
+            (x, y + 1)
+        };
+
+        let (a, b) = (self.sample(x, y), self.sample(x2, y2));
+        let t = if a == b {
+            T::zero()
+        } else {
+            (self.iso_value - a) / (b - a)
+        };
+
+        let cvt = |n: usize| T::from(n).unwrap();
+        Point::new(cvt(x), cvt(y)).lerp(Point::new(cvt(x2), cvt(y2)), t)
+    }
+
+    /// Add the links for the outline segments in the cell at the given coordinates.
+    fn link_cell(&self, x: usize, y: usize, links: &mut BTreeMap<usize, usize>) {
+        // Walk the corners of the cell clockwise, along with the crossing key for the
+        // edge leading away from each corner.
+        let corners = [
+            (self.sample(x, y), self.key(x, y, HORIZONTAL)),
+            (self.sample(x + 1, y), self.key(x + 1, y, VERTICAL)),
+            (self.sample(x + 1, y + 1), self.key(x, y + 1, HORIZONTAL)),
+            (self.sample(x, y + 1), self.key(x, y, VERTICAL)),
+        ];
+        let inside = |value: T| value >= self.iso_value;
+
+        // Find the crossings, and whether the walk is leaving the inside at them.
+        let mut crossings = [(0, false); 4];
+        let mut count = 0;
+        for (i, &(value, key)) in corners.iter().enumerate() {
+            let next = corners[(i + 1) % 4].0;
+            if inside(value) != inside(next) {
+                crossings[count] = (key, inside(value));
+                count += 1;
+            }
+        }
+
+        // Join every exit with the entry either after or before it. Joining with the
+        // entry after it connects the inside corners of an ambiguous cell.
+        let four = T::one() + T::one() + T::one() + T::one();
+        let average = corners
+            .iter()
+            .fold(T::zero(), |sum, &(value, _)| sum + value)
+            / four;
+        let offset = if inside(average) { 1 } else { count - 1 };
+
+        let crossings = &crossings[..count];
+        for (i, &(key, exits)) in crossings.iter().enumerate() {
+            if exits {
+                links.insert(key, crossings[(i + offset) % count].0);
+            }
+        }
+    }
+
+    /// Follow the links from the given crossing to build an outline.
+    fn trace(
+        &self,
+        start: usize,
+        links: &mut BTreeMap<usize, usize>,
+        closed: bool,
+    ) -> OwnedPathBuffer<T> {
+        let mut buffer = Vec::new();
+        let mut current = start;
+
+        while let Some(next) = links.remove(&current) {
+            buffer.push((self.crossing(next), Verb::Line));
+            current = next;
+        }
+
+        debug_assert_eq!(closed, current == start);
+        PathBuffer::new(self.crossing(start), buffer).with_close(closed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::Path;
+    use crate::path::Shape;
+
+    #[test]
+    fn test_closed() {
+        #[rustfmt::skip]
+        let grid = [
+            0.0f32, 0.0, 0.0,
+            0.0, 1.0, 0.0,
+            0.0, 0.0, 0.0,
+        ];
+
+        let contours = contour(&grid, (3, 3), 0.5);
+        assert_eq!(contours.len(), 1);
+
+        let points = (&contours[0])
+            .path_iter()
+            .filter_map(|event| match event {
+                crate::PathEvent::Line { to, .. } => Some(to),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(points.len(), 4);
+        // The loop ends back at its first crossing.
+        assert_eq!(points[3], Point::new(1.0, 0.5));
+        assert_eq!((&contours[0]).area(0.1), 0.5);
+    }
+
+    #[test]
+    fn test_open() {
+        let grid = [0.0f32, 1.0, 0.0, 1.0];
+
+        let contours = contour(&grid, (2, 2), 0.25);
+        assert_eq!(contours.len(), 1);
+
+        let segments = (&contours[0]).segments(0.1).collect::<Vec<_>>();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].from(), Point::new(0.25, 1.0));
+        assert_eq!(segments[0].to(), Point::new(0.25, 0.0));
+    }
+
+    #[test]
+    fn test_saddle() {
+        let grid = [1.0f32, 0.0, 0.0, 1.0];
+
+        // The inside corners are joined when the center is inside, and split otherwise.
+        let first_segment = |iso_value| {
+            let contours = contour(&grid, (2, 2), iso_value);
+            assert_eq!(contours.len(), 2);
+            let segment = (&contours[0]).segments(0.1).next().unwrap();
+            (segment.from(), segment.to())
+        };
+
+        assert_eq!(
+            first_segment(0.5),
+            (Point::new(0.5, 0.0), Point::new(1.0, 0.5))
+        );
+        assert_eq!(
+            first_segment(0.75),
+            (Point::new(0.25, 0.0), Point::new(0.0, 0.25))
+        );
+        assert!(contour(&grid, (1, 1), 0.5).is_empty());
+    }
+}
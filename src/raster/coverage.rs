@@ -0,0 +1,140 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Accumulate the pixel coverage of trapezoids.
+
+use crate::trapezoid::Trapezoid;
+use crate::ApproxEq;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num_traits::real::Real;
+
+/// The number of sub-scanlines sampled in each row of pixels.
+const SUBSAMPLES: usize = 16;
+
+/// The fraction of each pixel covered by a shape.
+#[derive(Debug, Clone)]
+pub(super) struct Coverage {
+    /// The width of the area, in pixels.
+    width: usize,
+
+    /// The height of the area, in pixels.
+    height: usize,
+
+    /// The coverage of each pixel, row by row.
+    data: Vec<f32>,
+}
+
+impl Coverage {
+    /// Create a new, empty `Coverage` of the given size.
+    pub(super) fn new(width: usize, height: usize) -> Self {
+        Coverage {
+            width,
+            height,
+            data: vec![0.0; width * height],
+        }
+    }
+
+    /// Iterate over the rows of coverage, clamped to the `[0, 1]` range.
+    pub(super) fn rows(&mut self) -> impl Iterator<Item = (usize, &[f32])> + '_ {
+        let width = self.width.max(1);
+        self.data.iter_mut().for_each(|c| *c = c.min(1.0));
+        self.data.chunks(width).enumerate()
+    }
+
+    /// Get the coverage of the pixel at the given coordinates, clamped to `[0, 1]`.
+    pub(super) fn value(&self, x: usize, y: usize) -> f32 {
+        if x < self.width && y < self.height {
+            self.data[y * self.width + x].min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Multiply this coverage by another coverage of the same size.
+    pub(super) fn multiply(&mut self, other: &Coverage) {
+        debug_assert_eq!((self.width, self.height), (other.width, other.height));
+
+        for (c, o) in self.data.iter_mut().zip(&other.data) {
+            *c = c.min(1.0) * o.min(1.0);
+        }
+    }
+
+    /// Add the coverage of a trapezoid.
+    pub(super) fn add_trapezoid<T: Real + ApproxEq>(&mut self, trapezoid: &Trapezoid<T>) {
+        self.add_trapezoid_at(trapezoid, (0.0, 0.0));
+    }
+
+    /// Add the coverage of a trapezoid, where the top left pixel is at `origin`.
+    pub(super) fn add_trapezoid_at<T: Real + ApproxEq>(
+        &mut self,
+        trapezoid: &Trapezoid<T>,
+        origin: (f32, f32),
+    ) {
+        let (dx, dy) = origin;
+        let top = trapezoid.top().to_f32().unwrap_or(0.0) - dy;
+        let bottom = trapezoid.bottom().to_f32().unwrap_or(0.0) - dy;
+        if top.partial_cmp(&bottom) != Some(Ordering::Less) || self.width == 0 {
+            return;
+        }
+
+        let first_row = top.floor().max(0.0) as usize;
+        let last_row = (bottom.ceil().max(0.0) as usize).min(self.height);
+        let weight = 1.0 / SUBSAMPLES as f32;
+
+        for row in first_row..last_row {
+            for sample in 0..SUBSAMPLES {
+                let y = row as f32 + (sample as f32 + 0.5) * weight;
+                if y < top || y >= bottom {
+                    continue;
+                }
+
+                let x_at = |line: crate::line::Line<T>| {
+                    T::from(y + dy)
+                        .and_then(|y| line.point_at_y(y))
+                        .and_then(|point| point.x().to_f32())
+                        .map(|x| x - dx)
+                };
+                if let (Some(left), Some(right)) = (x_at(trapezoid.left()), x_at(trapezoid.right()))
+                {
+                    self.add_span(row, left.min(right), left.max(right), weight);
+                }
+            }
+        }
+    }
+
+    /// Add a horizontal span with the given weight to a row.
+    fn add_span(&mut self, row: usize, left: f32, right: f32, weight: f32) {
+        let left = left.max(0.0);
+        let right = right.min(self.width as f32);
+        if left >= right {
+            return;
+        }
+
+        let row = &mut self.data[row * self.width..(row + 1) * self.width];
+        let first = left.floor() as usize;
+        let last = (right.ceil() as usize).min(row.len());
+
+        for (x, cell) in row.iter_mut().enumerate().take(last).skip(first) {
+            let x = x as f32;
+            let covered = right.min(x + 1.0) - left.max(x);
+            *cell += covered * weight;
+        }
+    }
+}
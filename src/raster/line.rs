@@ -0,0 +1,199 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Anti-aliased line drawing.
+
+use super::to_i32;
+use crate::iter::Two;
+use crate::line::LineSegment;
+use crate::point::Point;
+
+use core::iter::FusedIterator;
+use num_traits::real::Real;
+
+/// Iterate over the pixels covered by an anti-aliased, one pixel wide line.
+///
+/// This uses Xiaolin Wu's algorithm, yielding each pixel along with its coverage. The
+/// pixel at `(x, y)` covers the area from `(x, y)` to `(x + 1, y + 1)`, and pixels with no
+/// coverage are skipped.
+pub fn aa_line<T: Real>(segment: LineSegment<T>) -> AaLine<T> {
+    let half = T::one() / (T::one() + T::one());
+    let (from, to) = segment.points();
+
+    // Move the pixel centers onto whole coordinates.
+    let (mut x0, mut y0) = (from.x() - half, from.y() - half);
+    let (mut x1, mut y1) = (to.x() - half, to.y() - half);
+
+    // Always walk along the major axis, from left to right.
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        core::mem::swap(&mut x0, &mut y0);
+        core::mem::swap(&mut x1, &mut y1);
+    }
+    if x0 > x1 {
+        core::mem::swap(&mut x0, &mut x1);
+        core::mem::swap(&mut y0, &mut y1);
+    }
+
+    let dx = x1 - x0;
+    let gradient = if dx.is_zero() {
+        T::one()
+    } else {
+        (y1 - y0) / dx
+    };
+
+    let mut line = AaLine {
+        steep,
+        gradient,
+        intersect_y: T::zero(),
+        x: 0,
+        x_end: 0,
+        first: Two::empty(),
+        last: Two::empty(),
+    };
+
+    // The first end point.
+    let x_start = x0.round();
+    let y_start = y0 + gradient * (x_start - x0);
+    let gap = T::one() - fract(x0 + half);
+    line.first = line.pair(to_i32(x_start), y_start, gap);
+    line.intersect_y = y_start + gradient;
+
+    // The last end point.
+    let x_last = x1.round();
+    let y_last = y1 + gradient * (x_last - x1);
+    let gap = fract(x1 + half);
+    line.last = line.pair(to_i32(x_last), y_last, gap);
+
+    line.x = to_i32(x_start) + 1;
+    line.x_end = to_i32(x_last);
+    line
+}
+
+/// The iterator returned by [`aa_line`].
+#[derive(Debug, Clone)]
+pub struct AaLine<T: Copy> {
+    /// Whether the X and Y axes have been swapped.
+    steep: bool,
+
+    /// The change in the minor axis per pixel along the major axis.
+    gradient: T,
+
+    /// The position of the line on the minor axis at the current pixel.
+    intersect_y: T,
+
+    /// The current pixel along the major axis.
+    x: i32,
+
+    /// The pixel along the major axis where the last end point begins.
+    x_end: i32,
+
+    /// The pixels for the first end point.
+    first: Two<(Point<i32>, u8)>,
+
+    /// The pixels for the last end point.
+    last: Two<(Point<i32>, u8)>,
+}
+
+impl<T: Real> AaLine<T> {
+    /// Get the two pixels straddling the line at the given point.
+    fn pair(&self, x: i32, y: T, scale: T) -> Two<(Point<i32>, u8)> {
+        let below = to_i32(y.floor());
+        let fraction = fract(y);
+
+        Two::from([
+            (self.point(x, below), to_u8((T::one() - fraction) * scale)),
+            (self.point(x, below + 1), to_u8(fraction * scale)),
+        ])
+    }
+
+    /// Convert a position along the major and minor axes into a pixel.
+    fn point(&self, major: i32, minor: i32) -> Point<i32> {
+        if self.steep {
+            Point::new(minor, major)
+        } else {
+            Point::new(major, minor)
+        }
+    }
+}
+
+impl<T: Real> Iterator for AaLine<T> {
+    type Item = (Point<i32>, u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let pixel = if let Some(pixel) = self.first.next() {
+                pixel
+            } else if self.x < self.x_end {
+                self.first = self.pair(self.x, self.intersect_y, T::one());
+                self.intersect_y = self.intersect_y + self.gradient;
+                self.x += 1;
+                continue;
+            } else {
+                self.last.next()?
+            };
+
+            if pixel.1 > 0 {
+                return Some(pixel);
+            }
+        }
+    }
+}
+
+impl<T: Real> FusedIterator for AaLine<T> {}
+
+/// Get the fractional part of a number.
+fn fract<T: Real>(value: T) -> T {
+    value - value.floor()
+}
+
+/// Convert a coverage in the `[0, 1]` range to a byte.
+fn to_u8<T: Real>(coverage: T) -> u8 {
+    let scaled = (coverage * T::from(255).unwrap()).round();
+    scaled.to_f32().unwrap_or(0.0).clamp(0.0, 255.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_horizontal() {
+        // A line through the pixel centers fully covers one row.
+        let segment = LineSegment::new(Point::new(0.5f32, 1.5), Point::new(4.5, 1.5));
+        let mut pixels = aa_line(segment);
+
+        assert_eq!(pixels.next(), Some((Point::new(0, 1), 128)));
+        for x in 1..4 {
+            assert_eq!(pixels.next(), Some((Point::new(x, 1), 255)));
+        }
+        assert_eq!(pixels.next(), Some((Point::new(4, 1), 128)));
+        assert_eq!(pixels.next(), None);
+    }
+
+    #[test]
+    fn test_steep() {
+        // A line between two rows splits its coverage between them.
+        let segment = LineSegment::new(Point::new(2.0f32, 0.5), Point::new(2.0, 3.5));
+
+        for (point, coverage) in aa_line(segment) {
+            assert!(point.x() == 1 || point.x() == 2);
+            assert!(coverage <= 128);
+        }
+        assert_eq!(aa_line(segment).filter(|(p, _)| p.y() == 1).count(), 2);
+    }
+}
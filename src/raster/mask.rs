@@ -0,0 +1,217 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Coverage masks that can be combined with one another.
+
+use super::coverage::Coverage;
+use crate::box2d::Box;
+use crate::path::Shape;
+use crate::point::Point;
+use crate::ApproxEq;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use num_traits::real::Real;
+
+/// An owned buffer holding the coverage of each pixel in an area.
+///
+/// Coverage is stored as a byte per pixel, where `0` is fully uncovered and `255` is fully
+/// covered. Pixels outside of the mask's bounds are always uncovered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mask {
+    /// The area covered by this mask, in pixels.
+    bounds: Box<i32>,
+
+    /// The coverage of each pixel, row by row.
+    data: Vec<u8>,
+}
+
+impl Mask {
+    /// Create a new `Mask` over the given bounds where nothing is covered.
+    pub fn new(bounds: Box<i32>) -> Self {
+        let (width, height) = dimensions(&bounds);
+
+        Mask {
+            bounds,
+            data: vec![0; width * height],
+        }
+    }
+
+    /// Create a new `Mask` over the given bounds that covers a shape.
+    pub fn from_shape<T, S>(shape: S, tolerance: T, bounds: Box<i32>) -> Self
+    where
+        T: Real + ApproxEq,
+        S: Shape<T>,
+    {
+        let (width, height) = dimensions(&bounds);
+        let (min, _) = bounds.min_max();
+        let origin = (min.x() as f32, min.y() as f32);
+
+        let mut coverage = Coverage::new(width, height);
+        for trapezoid in shape.trapezoids(tolerance) {
+            coverage.add_trapezoid_at(&trapezoid, origin);
+        }
+
+        let mut data = Vec::with_capacity(width * height);
+        for (_, row) in coverage.rows() {
+            data.extend(row.iter().map(|&c| (c * 255.0).round() as u8));
+        }
+
+        Mask { bounds, data }
+    }
+
+    /// Get the area covered by this mask.
+    pub fn bounds(&self) -> Box<i32> {
+        self.bounds
+    }
+
+    /// Get the coverage of every pixel in the mask, row by row.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the coverage of every pixel in the mask, mutably.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Get the coverage of the pixel at the given coordinates.
+    pub fn value(&self, point: Point<i32>) -> u8 {
+        self.offset(point).map_or(0, |offset| self.data[offset])
+    }
+
+    /// Only keep the coverage that is also covered by `other`.
+    pub fn intersect(&mut self, other: &Mask) {
+        self.combine(other, mul);
+    }
+
+    /// Add the coverage of `other` to this mask.
+    ///
+    /// Coverage outside of this mask's bounds is lost.
+    pub fn union(&mut self, other: &Mask) {
+        self.combine(other, |a, b| a.saturating_add(b - mul(a, b)));
+    }
+
+    /// Remove the coverage of `other` from this mask.
+    pub fn subtract(&mut self, other: &Mask) {
+        self.combine(other, |a, b| mul(a, 255 - b));
+    }
+
+    /// Multiply a horizontal span of coverage values by this mask.
+    ///
+    /// The first value in `span` is the coverage of the pixel at `start`, and the rest of
+    /// the values continue to the right of it.
+    pub fn apply_to_span(&self, start: Point<i32>, span: &mut [u8]) {
+        for (x, value) in (start.x()..).zip(span) {
+            *value = mul(*value, self.value(Point::new(x, start.y())));
+        }
+    }
+
+    /// Combine every pixel of this mask with the same pixel of another mask.
+    fn combine(&mut self, other: &Mask, op: impl Fn(u8, u8) -> u8) {
+        for (point, value) in self.bounds.points().zip(&mut self.data) {
+            *value = op(*value, other.value(point));
+        }
+    }
+
+    /// Get the index of the pixel at the given coordinates, if it is in bounds.
+    fn offset(&self, point: Point<i32>) -> Option<usize> {
+        let (min, _) = self.bounds.min_max();
+        let (width, height) = dimensions(&self.bounds);
+
+        let x = usize::try_from(i64::from(point.x()) - i64::from(min.x())).ok()?;
+        let y = usize::try_from(i64::from(point.y()) - i64::from(min.y())).ok()?;
+
+        if x < width && y < height {
+            Some(y * width + x)
+        } else {
+            None
+        }
+    }
+}
+
+/// Get the width and height of a pixel area, treating negative sizes as empty.
+fn dimensions(bounds: &Box<i32>) -> (usize, usize) {
+    let (min, max) = bounds.min_max();
+    let length = |lo: i32, hi: i32| usize::try_from(i64::from(hi) - i64::from(lo)).unwrap_or(0);
+
+    (length(min.x(), max.x()), length(min.y(), max.y()))
+}
+
+/// Multiply two coverage values.
+fn mul(a: u8, b: u8) -> u8 {
+    ((u16::from(a) * u16::from(b) + 127) / 255) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(min: i32, max: i32) -> Box<i32> {
+        Box::new(Point::new(min, min), Point::new(max, max))
+    }
+
+    fn float_square(min: f32, max: f32) -> Box<f32> {
+        Box::new(Point::new(min, min), Point::new(max, max))
+    }
+
+    #[test]
+    fn test_from_shape() {
+        let mask = Mask::from_shape(float_square(2.0, 4.0), 0.1, square(1, 5));
+
+        assert_eq!(mask.data().len(), 16);
+        assert_eq!(mask.value(Point::new(1, 1)), 0);
+        assert_eq!(mask.value(Point::new(2, 2)), 255);
+        assert_eq!(mask.value(Point::new(3, 3)), 255);
+        assert_eq!(mask.value(Point::new(4, 4)), 0);
+        assert_eq!(mask.value(Point::new(-10, 3)), 0);
+    }
+
+    #[test]
+    fn test_combine() {
+        let bounds = square(0, 4);
+        let left = Mask::from_shape(float_square(0.0, 2.0), 0.1, bounds);
+        let right = Mask::from_shape(float_square(1.0, 3.0), 0.1, bounds);
+
+        let mut both = left.clone();
+        both.intersect(&right);
+        assert_eq!(both.value(Point::new(0, 0)), 0);
+        assert_eq!(both.value(Point::new(1, 1)), 255);
+        assert_eq!(both.value(Point::new(2, 2)), 0);
+
+        let mut either = left.clone();
+        either.union(&right);
+        assert_eq!(either.value(Point::new(0, 0)), 255);
+        assert_eq!(either.value(Point::new(2, 2)), 255);
+        assert_eq!(either.value(Point::new(3, 3)), 0);
+
+        let mut difference = left;
+        difference.subtract(&right);
+        assert_eq!(difference.value(Point::new(0, 0)), 255);
+        assert_eq!(difference.value(Point::new(1, 1)), 0);
+    }
+
+    #[test]
+    fn test_apply_to_span() {
+        let mask = Mask::from_shape(float_square(1.0, 2.5), 0.1, square(0, 4));
+
+        let mut span = [255, 255, 255, 100, 255];
+        mask.apply_to_span(Point::new(-1, 1), &mut span);
+        assert_eq!(span, [0, 0, 255, 50, 0]);
+    }
+}
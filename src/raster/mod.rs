@@ -0,0 +1,351 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Rasterization of shapes into pixel buffers.
+
+#![cfg(feature = "alloc")]
+
+use crate::color::Color;
+use crate::path::{Path, Shape};
+use crate::ApproxEq;
+
+use core::convert::TryFrom;
+use num_traits::real::Real;
+
+mod accumulate;
+mod buffer;
+mod cells;
+mod circle;
+mod clip;
+mod contour;
+mod coverage;
+mod line;
+mod mask;
+mod spans;
+mod tiles;
+
+pub use accumulate::Accumulator;
+pub use buffer::PixelBuffer;
+pub use cells::{cells, Cells};
+pub use circle::{circle_fill, circle_outline, CircleFill, CircleOutline};
+pub use clip::ClipStack;
+pub use contour::contour;
+pub use line::{aa_line, AaLine};
+pub use mask::Mask;
+pub use spans::{spans, spans_clipped, EdgeSpan, Span, Spans};
+pub use tiles::{tiles, tiles_clipped, Tile, Tiles};
+
+use coverage::Coverage;
+
+/// Fill a shape with a solid color.
+///
+/// The shape is flattened with the given `tolerance`, tesselated into trapezoids, and
+/// then composited onto `target` using the source-over operator. Components of
+/// `color` are expected to be in the `[0, 1]` range and not premultiplied.
+pub fn fill<T, S>(shape: S, tolerance: T, color: Color<T>, target: &mut PixelBuffer<'_>)
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    fill_inner(shape, tolerance, color, None, target)
+}
+
+/// Fill a shape with a solid color, limited to the area of a `ClipStack`.
+///
+/// This is otherwise identical to [`fill`].
+pub fn fill_clipped<T, S>(
+    shape: S,
+    tolerance: T,
+    color: Color<T>,
+    clip: &ClipStack<T>,
+    target: &mut PixelBuffer<'_>,
+) where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    fill_inner(shape, tolerance, color, Some(clip), target)
+}
+
+/// Fill a shape, optionally clipping it.
+fn fill_inner<T, S>(
+    shape: S,
+    tolerance: T,
+    color: Color<T>,
+    clip: Option<&ClipStack<T>>,
+    target: &mut PixelBuffer<'_>,
+) where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    let mut coverage = Coverage::new(target.width(), target.height());
+    for trapezoid in shape.trapezoids(tolerance) {
+        coverage.add_trapezoid(&trapezoid);
+    }
+
+    let color = to_f32(color);
+    for (y, row) in coverage.rows() {
+        for (x, &alpha) in row.iter().enumerate() {
+            let alpha = match clip {
+                Some(clip) if alpha > 0.0 => alpha * clip.coverage(x, y),
+                _ => alpha,
+            };
+
+            if alpha > 0.0 {
+                target.blend(x, y, color, alpha);
+            }
+        }
+    }
+}
+
+/// Fill a shape with a solid color, using exact area coverage for anti-aliasing.
+///
+/// This accumulates the area that the shape covers in each pixel with an
+/// [`Accumulator`] rather than sampling trapezoids, which gives better results for very
+/// thin shapes. It is otherwise identical to [`fill`].
+pub fn fill_analytic<T, S>(shape: S, tolerance: T, color: Color<T>, target: &mut PixelBuffer<'_>)
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    fill_analytic_inner(shape, tolerance, color, None, target)
+}
+
+/// Fill a shape using exact area coverage, limited to the area of a `ClipStack`.
+///
+/// This is otherwise identical to [`fill_analytic`].
+pub fn fill_analytic_clipped<T, S>(
+    shape: S,
+    tolerance: T,
+    color: Color<T>,
+    clip: &ClipStack<T>,
+    target: &mut PixelBuffer<'_>,
+) where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    fill_analytic_inner(shape, tolerance, color, Some(clip), target)
+}
+
+/// Fill a shape using exact area coverage, optionally clipping it.
+fn fill_analytic_inner<T, S>(
+    shape: S,
+    tolerance: T,
+    color: Color<T>,
+    clip: Option<&ClipStack<T>>,
+    target: &mut PixelBuffer<'_>,
+) where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    let mut accumulator = Accumulator::new(target.width(), target.height());
+    accumulator.add_path(shape, tolerance);
+
+    let color = to_f32(color);
+    for (y, row) in accumulator.rows().enumerate() {
+        for (x, alpha) in row.enumerate() {
+            let alpha = match clip {
+                Some(clip) if alpha > 0.0 => alpha * clip.coverage(x, y),
+                _ => alpha,
+            };
+
+            if alpha > 0.0 {
+                target.blend(x, y, color, alpha);
+            }
+        }
+    }
+}
+
+/// Stroke a path with an anti-aliased line one pixel wide.
+///
+/// The path is flattened with the given `tolerance`, and each segment is drawn directly
+/// with [`aa_line`] rather than being converted into a shape and filled. This is much
+/// faster than a full stroke for wireframes and grids, but there are no joins or caps,
+/// and pixels where two segments meet are blended twice.
+pub fn stroke_hairline<T, P>(path: P, tolerance: T, color: Color<T>, target: &mut PixelBuffer<'_>)
+where
+    T: Real + ApproxEq,
+    P: Path<T>,
+{
+    stroke_hairline_inner(path, tolerance, color, None, target)
+}
+
+/// Stroke a path with a one pixel wide line, limited to the area of a `ClipStack`.
+///
+/// This is otherwise identical to [`stroke_hairline`].
+pub fn stroke_hairline_clipped<T, P>(
+    path: P,
+    tolerance: T,
+    color: Color<T>,
+    clip: &ClipStack<T>,
+    target: &mut PixelBuffer<'_>,
+) where
+    T: Real + ApproxEq,
+    P: Path<T>,
+{
+    stroke_hairline_inner(path, tolerance, color, Some(clip), target)
+}
+
+/// Stroke a path with a one pixel wide line, optionally clipping it.
+fn stroke_hairline_inner<T, P>(
+    path: P,
+    tolerance: T,
+    color: Color<T>,
+    clip: Option<&ClipStack<T>>,
+    target: &mut PixelBuffer<'_>,
+) where
+    T: Real + ApproxEq,
+    P: Path<T>,
+{
+    let color = to_f32(color);
+    for segment in path.segments(tolerance).skip_degenerate(true) {
+        for (pixel, coverage) in aa_line(segment) {
+            let (x, y) = match (usize::try_from(pixel.x()), usize::try_from(pixel.y())) {
+                (Ok(x), Ok(y)) => (x, y),
+                _ => continue,
+            };
+
+            let alpha = f32::from(coverage) / 255.0;
+            let alpha = match clip {
+                Some(clip) => alpha * clip.coverage(x, y),
+                None => alpha,
+            };
+
+            if alpha > 0.0 {
+                target.blend(x, y, color, alpha);
+            }
+        }
+    }
+}
+
+/// Convert a color's components to `f32`s, clamped to the `[0, 1]` range.
+fn to_f32<T: Real>(color: Color<T>) -> [f32; 4] {
+    let cvt = |c: T| c.to_f32().unwrap_or(0.0).clamp(0.0, 1.0);
+    [
+        cvt(color.red()),
+        cvt(color.green()),
+        cvt(color.blue()),
+        cvt(color.alpha()),
+    ]
+}
+
+/// Convert a whole number to an `i32`, saturating on overflow.
+fn to_i32<T: Real>(value: T) -> i32 {
+    value.to_i32().unwrap_or(if value < T::zero() {
+        i32::MIN
+    } else {
+        i32::MAX
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::box2d::Box;
+    use crate::point::Point;
+    use alloc::vec;
+
+    #[test]
+    fn test_fill_box() {
+        let mut data = vec![0u8; 4 * 4 * 4];
+        let mut buffer = PixelBuffer::new(&mut data, 4, 4);
+        let shape = Box::new(Point::new(1.0f32, 1.0), Point::new(2.5, 3.0));
+
+        fill(shape, 0.1, Color::new(1.0, 0.0, 0.0, 1.0), &mut buffer);
+
+        assert_eq!(buffer.pixel(0, 0), Some([0, 0, 0, 0]));
+        assert_eq!(buffer.pixel(1, 1), Some([255, 0, 0, 255]));
+        assert_eq!(buffer.pixel(1, 2), Some([255, 0, 0, 255]));
+        assert_eq!(buffer.pixel(2, 2), Some([128, 0, 0, 128]));
+        assert_eq!(buffer.pixel(1, 3), Some([0, 0, 0, 0]));
+        assert_eq!(buffer.pixel(4, 0), None);
+    }
+
+    #[test]
+    fn test_fill_blends() {
+        let mut data = vec![0, 0, 255, 255];
+        let mut buffer = PixelBuffer::new(&mut data, 1, 1);
+        let shape = Box::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0));
+
+        fill(shape, 0.1, Color::new(1.0, 0.0, 0.0, 0.5), &mut buffer);
+
+        assert_eq!(buffer.pixel(0, 0), Some([128, 0, 128, 255]));
+    }
+
+    #[test]
+    fn test_fill_analytic() {
+        let mut data = vec![0u8; 4 * 4 * 4];
+        let mut buffer = PixelBuffer::new(&mut data, 4, 4);
+        let shape = Box::new(Point::new(1.0f32, 1.0), Point::new(2.5, 3.0));
+
+        fill_analytic(shape, 0.1, Color::new(1.0, 0.0, 0.0, 1.0), &mut buffer);
+
+        assert_eq!(buffer.pixel(0, 0), Some([0, 0, 0, 0]));
+        assert_eq!(buffer.pixel(1, 1), Some([255, 0, 0, 255]));
+        assert_eq!(buffer.pixel(2, 2), Some([128, 0, 0, 128]));
+        assert_eq!(buffer.pixel(1, 3), Some([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_stroke_hairline() {
+        let mut data = vec![0u8; 4 * 4 * 4];
+        let mut buffer = PixelBuffer::new(&mut data, 4, 4);
+        let shape = Box::new(Point::new(0.5f32, 0.5), Point::new(3.5, 3.5));
+
+        stroke_hairline(shape, 0.1, Color::new(1.0, 0.0, 0.0, 1.0), &mut buffer);
+
+        assert_eq!(buffer.pixel(1, 0), Some([255, 0, 0, 255]));
+        assert_eq!(buffer.pixel(0, 2), Some([255, 0, 0, 255]));
+        assert_eq!(buffer.pixel(1, 1), Some([0, 0, 0, 0]));
+        assert_eq!(buffer.pixel(2, 2), Some([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_fill_clipped() {
+        let mut data = vec![0u8; 4 * 4 * 4];
+        let mut buffer = PixelBuffer::new(&mut data, 4, 4);
+        let shape = Box::new(Point::new(0.0f32, 0.0), Point::new(4.0, 4.0));
+
+        let mut clip = ClipStack::new(4, 4);
+        clip.push_box(Box::new(Point::new(1.0, 0.0), Point::new(4.0, 4.0)));
+        clip.push_shape(Box::new(Point::new(0.0, 2.0), Point::new(4.0, 4.0)), 0.1);
+
+        fill_clipped(
+            shape,
+            0.1,
+            Color::new(1.0, 1.0, 1.0, 1.0),
+            &clip,
+            &mut buffer,
+        );
+
+        assert_eq!(buffer.pixel(0, 3), Some([0, 0, 0, 0]));
+        assert_eq!(buffer.pixel(1, 1), Some([0, 0, 0, 0]));
+        assert_eq!(buffer.pixel(1, 2), Some([255, 255, 255, 255]));
+
+        // Boxes pushed over a shape keep using its mask.
+        clip.push_box(Box::new(Point::new(0.0, 0.0), Point::new(2.0, 4.0)));
+        assert_eq!(clip.coverage(1, 1), 0.0);
+        assert_eq!(clip.coverage(1, 2), 1.0);
+        assert_eq!(clip.coverage(2, 2), 0.0);
+        assert!(clip.pop());
+
+        assert!(clip.pop());
+        assert_eq!(clip.coverage(1, 1), 1.0);
+        assert_eq!(clip.coverage(0, 1), 0.0);
+        assert!(clip.pop());
+        assert!(!clip.pop());
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Integer spans of coverage, for blitters that fill runs of pixels.
+
+use super::{ClipStack, Mask};
+use crate::box2d::Box;
+use crate::path::Shape;
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// A horizontal run of fully covered pixels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    /// The row that the run is on.
+    y: i32,
+
+    /// The first pixel in the run.
+    x0: i32,
+
+    /// One past the last pixel in the run.
+    x1: i32,
+}
+
+impl Span {
+    /// Create a new `Span` from its row and the range of pixels it covers.
+    pub fn new(y: i32, x0: i32, x1: i32) -> Self {
+        Span { y, x0, x1 }
+    }
+
+    /// Get the row that the run is on.
+    pub fn y(&self) -> i32 {
+        self.y
+    }
+
+    /// Get the first pixel in the run.
+    pub fn x0(&self) -> i32 {
+        self.x0
+    }
+
+    /// Get one past the last pixel in the run.
+    pub fn x1(&self) -> i32 {
+        self.x1
+    }
+}
+
+/// A horizontal run of partially covered pixels, along with their coverage.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdgeSpan {
+    /// The row that the run is on.
+    y: i32,
+
+    /// The first pixel in the run.
+    x: i32,
+
+    /// The coverage of each pixel in the run, from left to right.
+    coverage: Vec<u8>,
+}
+
+impl EdgeSpan {
+    /// Get the row that the run is on.
+    pub fn y(&self) -> i32 {
+        self.y
+    }
+
+    /// Get the first pixel in the run.
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    /// Get the coverage of each pixel in the run, from left to right.
+    ///
+    /// Each value is between `1` and `254`.
+    pub fn coverage(&self) -> &[u8] {
+        &self.coverage
+    }
+}
+
+/// The coverage of a shape, split into fully covered runs and partially covered edges.
+///
+/// Many blitters can fill a run of pixels with a solid color much faster than they can
+/// blend each pixel, so the runs are kept separate from the pixels along the edges of the
+/// shape that need blending. Both lists are sorted by row, then from left to right.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Spans {
+    /// The fully covered runs.
+    full: Vec<Span>,
+
+    /// The partially covered runs.
+    edges: Vec<EdgeSpan>,
+}
+
+/// Rasterize a shape into integer spans within the given bounds.
+pub fn spans<T, S>(shape: S, tolerance: T, bounds: Box<i32>) -> Spans
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    Spans::from_mask(&Mask::from_shape(shape, tolerance, bounds))
+}
+
+/// Rasterize a shape into integer spans within the given bounds, limited to the area
+/// of a `ClipStack`.
+///
+/// This is otherwise identical to [`spans`].
+pub fn spans_clipped<T, S>(shape: S, tolerance: T, bounds: Box<i32>, clip: &ClipStack<T>) -> Spans
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    let mut mask = Mask::from_shape(shape, tolerance, bounds);
+    clip.clip_mask(&mut mask);
+    Spans::from_mask(&mask)
+}
+
+impl Spans {
+    /// Split the coverage of a mask into spans.
+    pub fn from_mask(mask: &Mask) -> Self {
+        let (min, max) = mask.bounds().min_max();
+        let width = (i64::from(max.x()) - i64::from(min.x())).max(0) as usize;
+        let mut spans = Spans::default();
+
+        if width == 0 {
+            return spans;
+        }
+
+        for (y, row) in (min.y()..).zip(mask.data().chunks(width)) {
+            let mut start = 0;
+
+            while start < row.len() {
+                // Find the end of the run of pixels of the same kind.
+                let kind = Kind::of(row[start]);
+                let end = row[start..]
+                    .iter()
+                    .position(|&c| Kind::of(c) != kind)
+                    .map_or(row.len(), |len| start + len);
+                let x = min.x() + start as i32;
+
+                match kind {
+                    Kind::Empty => {}
+                    Kind::Full => spans.full.push(Span::new(y, x, min.x() + end as i32)),
+                    Kind::Edge => spans.edges.push(EdgeSpan {
+                        y,
+                        x,
+                        coverage: row[start..end].to_vec(),
+                    }),
+                }
+
+                start = end;
+            }
+        }
+
+        spans
+    }
+
+    /// Get the fully covered runs.
+    pub fn full(&self) -> &[Span] {
+        &self.full
+    }
+
+    /// Get the partially covered runs.
+    pub fn edges(&self) -> &[EdgeSpan] {
+        &self.edges
+    }
+}
+
+/// How much a pixel is covered.
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    Empty,
+    Full,
+    Edge,
+}
+
+impl Kind {
+    fn of(coverage: u8) -> Self {
+        match coverage {
+            0 => Kind::Empty,
+            255 => Kind::Full,
+            _ => Kind::Edge,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+
+    #[test]
+    fn test_spans() {
+        let shape = Box::new(Point::new(1.5f32, 1.0), Point::new(4.0, 3.0));
+        let spans = spans(shape, 0.1, Box::new(Point::new(0, 0), Point::new(6, 4)));
+
+        assert_eq!(spans.full(), &[Span::new(1, 2, 4), Span::new(2, 2, 4)]);
+        assert_eq!(spans.edges().len(), 2);
+
+        let edge = &spans.edges()[0];
+        assert_eq!((edge.y(), edge.x()), (1, 1));
+        assert_eq!(edge.coverage(), &[128]);
+    }
+
+    #[test]
+    fn test_spans_clipped() {
+        let shape = Box::new(Point::new(1.5f32, 1.0), Point::new(4.0, 3.0));
+        let mut clip = ClipStack::new(6, 4);
+        clip.push_shape(Box::new(Point::new(0.0, 2.0), Point::new(3.0, 4.0)), 0.1);
+
+        let spans = spans_clipped(
+            shape,
+            0.1,
+            Box::new(Point::new(0, 0), Point::new(6, 4)),
+            &clip,
+        );
+        assert_eq!(spans.full(), &[Span::new(2, 2, 3)]);
+        assert_eq!(spans.edges().len(), 1);
+        assert_eq!(spans.edges()[0].coverage(), &[128]);
+    }
+}
@@ -0,0 +1,298 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Rasterize shapes into a grid of tiles.
+
+use super::coverage::Coverage;
+use super::{ClipStack, Mask};
+use crate::box2d::Box;
+use crate::path::Shape;
+use crate::point::Point;
+use crate::trapezoid::Trapezoid;
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+/// The coverage of a single tile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Tile {
+    /// No pixel in the tile is covered.
+    Empty,
+
+    /// Every pixel in the tile is fully covered.
+    Full,
+
+    /// Some pixels in the tile are covered, as described by the mask.
+    Partial(Mask),
+}
+
+/// A shape rasterized into a grid of square tiles.
+///
+/// Empty and fully covered tiles don't store any coverage, so they can be skipped or
+/// filled with a solid color without looking at individual pixels. Tiles are independent
+/// of one another, so they can be composited in parallel.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tiles {
+    /// The width and height of a tile, in pixels.
+    tile_size: usize,
+
+    /// The number of tiles in each row.
+    columns: usize,
+
+    /// The number of rows of tiles.
+    rows: usize,
+
+    /// The tiles, row by row.
+    tiles: Vec<Tile>,
+}
+
+/// Rasterize a shape into tiles covering an area of the given size.
+///
+/// The area is divided into tiles that are `tile_size` pixels on each side. Tiles along
+/// the right and bottom edges are cut short if the area isn't a multiple of the tile size.
+///
+/// # Panics
+///
+/// Panics if `tile_size` is zero.
+pub fn tiles<T, S>(shape: S, tolerance: T, width: usize, height: usize, tile_size: usize) -> Tiles
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    tiles_inner(shape, tolerance, width, height, tile_size, None)
+}
+
+/// Rasterize a shape into tiles, limited to the area of a `ClipStack`.
+///
+/// This is otherwise identical to [`tiles`].
+///
+/// # Panics
+///
+/// Panics if `tile_size` is zero.
+pub fn tiles_clipped<T, S>(
+    shape: S,
+    tolerance: T,
+    width: usize,
+    height: usize,
+    tile_size: usize,
+    clip: &ClipStack<T>,
+) -> Tiles
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    tiles_inner(shape, tolerance, width, height, tile_size, Some(clip))
+}
+
+/// Rasterize a shape into tiles, optionally clipping it.
+fn tiles_inner<T, S>(
+    shape: S,
+    tolerance: T,
+    width: usize,
+    height: usize,
+    tile_size: usize,
+    clip: Option<&ClipStack<T>>,
+) -> Tiles
+where
+    T: Real + ApproxEq,
+    S: Shape<T>,
+{
+    assert!(tile_size > 0, "tile size must be greater than zero");
+
+    let columns = width.div_ceil(tile_size);
+    let rows = height.div_ceil(tile_size);
+    let trapezoids: Vec<Trapezoid<T>> = shape.trapezoids(tolerance).collect();
+    let mut tiles = Vec::with_capacity(columns * rows);
+
+    for row in 0..rows {
+        // Rasterize one band of tiles at a time, using only the trapezoids that touch it.
+        let top = row * tile_size;
+        let band_height = tile_size.min(height - top);
+        let mut coverage = Coverage::new(width, band_height);
+
+        let (band_top, band_bottom) = (T::from(top), T::from(top + band_height));
+        for trapezoid in &trapezoids {
+            let outside = match (band_top, band_bottom) {
+                (Some(band_top), Some(band_bottom)) => {
+                    trapezoid.bottom() <= band_top || trapezoid.top() >= band_bottom
+                }
+                _ => false,
+            };
+
+            if !outside {
+                coverage.add_trapezoid_at(trapezoid, (0.0, top as f32));
+            }
+        }
+
+        let band: Vec<u8> = coverage
+            .rows()
+            .flat_map(|(y, row)| {
+                row.iter().enumerate().map(move |(x, &c)| {
+                    let c = match clip {
+                        Some(clip) if c > 0.0 => c * clip.coverage(x, top + y),
+                        _ => c,
+                    };
+                    (c * 255.0).round() as u8
+                })
+            })
+            .collect();
+
+        for column in 0..columns {
+            let left = column * tile_size;
+            let tile_width = tile_size.min(width - left);
+            let pixels = || {
+                band.chunks(width.max(1))
+                    .flat_map(move |row| &row[left..left + tile_width])
+            };
+
+            let tile = if pixels().all(|&c| c == 0) {
+                Tile::Empty
+            } else if pixels().all(|&c| c == 255) {
+                Tile::Full
+            } else {
+                let min = Point::new(left as i32, top as i32);
+                let max = Point::new((left + tile_width) as i32, (top + band_height) as i32);
+                let mut mask = Mask::new(Box::new(min, max));
+
+                for (value, &c) in mask.data_mut().iter_mut().zip(pixels()) {
+                    *value = c;
+                }
+
+                Tile::Partial(mask)
+            };
+
+            tiles.push(tile);
+        }
+    }
+
+    Tiles {
+        tile_size,
+        columns,
+        rows,
+        tiles,
+    }
+}
+
+impl Tiles {
+    /// Get the width and height of a tile, in pixels.
+    pub fn tile_size(&self) -> usize {
+        self.tile_size
+    }
+
+    /// Get the number of tiles in each row.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Get the number of rows of tiles.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Get the tile at the given column and row, if it exists.
+    pub fn get(&self, column: usize, row: usize) -> Option<&Tile> {
+        if column < self.columns {
+            self.tiles.get(row * self.columns + column)
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over every tile, along with its column and row.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &Tile)> + '_ {
+        let columns = self.columns.max(1);
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(move |(i, tile)| (i % columns, i / columns, tile))
+    }
+
+    /// Iterate over the column and row of every tile with no coverage.
+    pub fn empty(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter().filter_map(|(column, row, tile)| match tile {
+            Tile::Empty => Some((column, row)),
+            _ => None,
+        })
+    }
+
+    /// Iterate over the column and row of every fully covered tile.
+    pub fn full(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter().filter_map(|(column, row, tile)| match tile {
+            Tile::Full => Some((column, row)),
+            _ => None,
+        })
+    }
+
+    /// Iterate over the column, row and mask of every partially covered tile.
+    pub fn partial(&self) -> impl Iterator<Item = (usize, usize, &Mask)> + '_ {
+        self.iter().filter_map(|(column, row, tile)| match tile {
+            Tile::Partial(mask) => Some((column, row, mask)),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tiles() {
+        let shape = Box::new(Point::new(2.0f32, 2.0), Point::new(10.0, 8.5));
+        let tiles = tiles(shape, 0.1, 12, 10, 4);
+
+        assert_eq!((tiles.columns(), tiles.rows()), (3, 3));
+        assert!(matches!(tiles.get(0, 0), Some(Tile::Partial(_))));
+        assert_eq!(tiles.get(1, 1), Some(&Tile::Full));
+        assert_eq!(tiles.get(3, 0), None);
+        assert_eq!(tiles.full().collect::<Vec<_>>(), [(1, 1)]);
+        assert_eq!(tiles.empty().count(), 0);
+
+        // The bottom row is cut short, and half covered at the bottom.
+        let (_, _, mask) = tiles.partial().last().unwrap();
+        assert_eq!(
+            mask.bounds(),
+            Box::new(Point::new(8, 8), Point::new(12, 10))
+        );
+        assert_eq!(mask.value(Point::new(9, 8)), 128);
+        assert_eq!(mask.value(Point::new(9, 9)), 0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let shape = Box::new(Point::new(0.0f32, 0.0), Point::new(4.0, 4.0));
+        let tiles = tiles(shape, 0.1, 8, 4, 4);
+
+        assert_eq!(tiles.full().collect::<Vec<_>>(), [(0, 0)]);
+        assert_eq!(tiles.empty().collect::<Vec<_>>(), [(1, 0)]);
+    }
+
+    #[test]
+    fn test_tiles_clipped() {
+        let shape = Box::new(Point::new(0.0f32, 0.0), Point::new(8.0, 8.0));
+        let mut clip = ClipStack::new(8, 8);
+        clip.push_box(Box::new(Point::new(0.0, 0.0), Point::new(6.0, 8.0)));
+
+        let tiles = tiles_clipped(shape, 0.1, 8, 8, 4, &clip);
+        assert_eq!(tiles.full().collect::<Vec<_>>(), [(0, 0), (0, 1)]);
+        assert_eq!(tiles.partial().count(), 2);
+        let (_, _, mask) = tiles.partial().next().unwrap();
+        assert_eq!(mask.value(Point::new(5, 0)), 255);
+        assert_eq!(mask.value(Point::new(6, 0)), 0);
+    }
+}
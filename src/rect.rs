@@ -15,10 +15,10 @@
 // You should have received a copy of the GNU Affero General Public License 
 // along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
 
-use crate::pair::Quad;
+use crate::pair::{Double, Quad};
 use crate::path::{Path, PathEvent, Shape};
-use crate::{Box, Point, Size, Vector};
-use num_traits::Zero;
+use crate::{Box, Direction, Point, Scale, SideOffsets, Size, Vector};
+use num_traits::{One, Zero};
 
 use core::fmt;
 use core::ops;
@@ -176,6 +176,153 @@ impl<T: Copy> Rect<T> {
         let [_, _, w, h] = self.0.into_inner();
         w * h
     }
+
+    /// Get the center of the rectangle.
+    #[inline]
+    pub fn center(self) -> Point<T>
+    where
+        T: ops::Add<Output = T> + ops::Div<Output = T> + One,
+    {
+        let (origin, size) = self.0.split();
+        let two = T::one() + T::one();
+        Point(origin + size / Double::splat(two))
+    }
+
+    /// Tell if this rectangle contains a point.
+    ///
+    /// Points on the top and left edges are contained, while points on the bottom and
+    /// right edges are not.
+    #[inline]
+    pub fn contains(self, point: Point<T>) -> bool
+    where
+        T: ops::Add<Output = T> + Zero + PartialOrd,
+    {
+        let point_repeated = Quad::from_double(point.0, point.0);
+        let packed_lt = point_repeated.packed_lt(self.min_max());
+        let (min_cmp, max_cmp) = packed_lt.split();
+
+        // The point can't be less than the minimum, and must be less than the maximum.
+        !min_cmp.any() && max_cmp.all()
+    }
+
+    /// Tell if two rectangles intersect.
+    #[inline]
+    pub fn intersects(self, other: Self) -> bool
+    where
+        T: ops::Add<Output = T> + Zero + PartialOrd,
+    {
+        // To intersect, all of the mins have to be less than all of the maxes.
+        let (self_min, self_max) = self.min_max().split();
+        let (other_min, other_max) = other.min_max().split();
+        let mins = Quad::from_double(self_min, other_min);
+        let maxs = Quad::from_double(other_max, self_max);
+
+        mins.packed_lt(maxs).all()
+    }
+
+    /// Get the intersection of two rectangles.
+    ///
+    /// If the rectangles do not intersect, the result has a zero size.
+    #[inline]
+    pub fn intersection(self, other: Self) -> Self
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + Zero + PartialOrd,
+    {
+        let (self_min, self_max) = self.min_max().split();
+        let (other_min, other_max) = other.min_max().split();
+
+        let min = self_min.max(other_min);
+        let max = self_max.min(other_max);
+        let size = (max - min).max(Double::splat(T::zero()));
+        Rect(Quad::from_double(min, size))
+    }
+
+    /// Get the smallest rectangle containing both rectangles.
+    #[inline]
+    pub fn union(self, other: Self) -> Self
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + Zero + PartialOrd,
+    {
+        let (self_min, self_max) = self.min_max().split();
+        let (other_min, other_max) = other.min_max().split();
+
+        let min = self_min.min(other_min);
+        let max = self_max.max(other_max);
+        Rect(Quad::from_double(min, max - min))
+    }
+
+    /// Get a copy of this rectangle with a different origin.
+    #[inline]
+    pub fn with_origin(self, origin: Point<T>) -> Self {
+        Rect::new(origin, self.size())
+    }
+
+    /// Get a copy of this rectangle with a different size.
+    #[inline]
+    pub fn with_size(self, size: Size<T>) -> Self {
+        Rect::new(self.origin(), size)
+    }
+
+    /// Shrink the rectangle by the given offsets from each side.
+    ///
+    /// The size of the result is clamped so that it never becomes negative.
+    #[inline]
+    pub fn inset(self, offsets: SideOffsets<T>) -> Self
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + Zero + PartialOrd,
+    {
+        let [top, right, bottom, left] = offsets.0.into_inner();
+        let (origin, size) = self.0.split();
+
+        let origin = origin + Double::new([left, top]);
+        let size = size - Double::new([left + right, top + bottom]);
+        Rect(Quad::from_double(
+            origin,
+            size.max(Double::splat(T::zero())),
+        ))
+    }
+
+    /// Grow the rectangle by the given offsets from each side.
+    #[inline]
+    pub fn outset(self, offsets: SideOffsets<T>) -> Self
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T>,
+    {
+        let [top, right, bottom, left] = offsets.0.into_inner();
+        let (origin, size) = self.0.split();
+
+        let origin = origin - Double::new([left, top]);
+        let size = size + Double::new([left + right, top + bottom]);
+        Rect(Quad::from_double(origin, size))
+    }
+
+    /// Scale the size of the rectangle, keeping its center in place.
+    #[inline]
+    pub fn scale_from_center(self, factor: T) -> Self
+    where
+        T: ops::Add<Output = T>
+            + ops::Sub<Output = T>
+            + ops::Mul<Output = T>
+            + ops::Div<Output = T>
+            + One,
+    {
+        let (origin, size) = self.0.split();
+        let two = Double::splat(T::one() + T::one());
+
+        let center = origin + size / two;
+        let size = size * Double::splat(factor);
+        Rect(Quad::from_double(center - size / two, size))
+    }
+
+    /// Get the minimum and maximum points of the rectangle, packed together.
+    #[inline]
+    fn min_max(self) -> Quad<T>
+    where
+        T: ops::Add<Output = T> + Zero,
+    {
+        let origin = self.0.lo();
+        self.0 + Quad::from_double(Double::splat(T::zero()), origin)
+    }
 }
 
 impl<T: Copy> From<Rect<T>> for Box<T>
@@ -230,6 +377,54 @@ impl<T: Copy + ops::Div<Output = T>> ops::DivAssign<T> for Rect<T> {
     }
 }
 
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<Vector<T>> for Rect<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Vector<T>) -> Self::Output {
+        Rect(self.0 * Quad::from_double(rhs.0, rhs.0))
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<T> for Rect<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: T) -> Self::Output {
+        Rect(self.0 * Quad::splat(rhs))
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<Scale<T>> for Rect<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Scale<T>) -> Self::Output {
+        self * rhs.vector()
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::MulAssign<Vector<T>> for Rect<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Vector<T>) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::MulAssign<T> for Rect<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::MulAssign<Scale<T>> for Rect<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Scale<T>) {
+        *self = *self * rhs;
+    }
+}
+
 #[cfg(feature = "euclid")]
 impl<T: Copy, U> From<euclid::Rect<T, U>> for Rect<T> {
     #[inline]
@@ -255,6 +450,17 @@ impl<T: ops::Add<Output = T> + Zero + Copy> crate::BoundingBox<T> for Rect<T> {
     }
 }
 
+impl<T: Copy + ops::Add<Output = T> + Zero> Rect<T> {
+    /// Get the path events that make up this rectangle, going around it in the given
+    /// direction.
+    ///
+    /// `Direction::Forwards` gives the same order as `path_iter`, and
+    /// `Direction::Backwards` reverses it.
+    pub fn path_iter_with_direction(self, direction: Direction) -> crate::iter::Five<PathEvent<T>> {
+        self.to_box().path_iter_with_direction(direction)
+    }
+}
+
 impl<T: Copy + ops::Add<Output = T> + Zero> Path<T> for Rect<T> {
     type Iter = crate::iter::Five<PathEvent<T>>;
 
@@ -287,3 +493,61 @@ impl<T: Copy + ops::Add<Output = T> + ops::Mul<Output = T> + Zero> Shape<T> for
         w + w + h + h
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let rect = Rect::new(Point::new(1.0, 1.0), Size::new(2.0, 3.0));
+        assert!(rect.contains(Point::new(1.0, 2.0)));
+        assert!(!rect.contains(Point::new(3.0, 2.0)));
+        assert_eq!(rect.center(), Point::new(2.0, 2.5));
+    }
+
+    #[test]
+    fn test_scale() {
+        let rect = Rect::new(Point::new(1.0, 2.0), Size::new(3.0, 4.0));
+        assert_eq!(
+            rect * Scale::new(Vector::new(2.0, 0.5)),
+            Rect::new(Point::new(2.0, 1.0), Size::new(6.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn test_inset() {
+        let rect = Rect::new(Point::new(0.0, 0.0), Size::new(10.0, 4.0));
+        let offsets = SideOffsets::new(1.0, 2.0, 3.0, 4.0);
+
+        let inset = rect.inset(offsets);
+        assert_eq!(inset, Rect::new(Point::new(4.0, 1.0), Size::new(4.0, 0.0)));
+        assert_eq!(
+            rect.outset(offsets),
+            Rect::new(Point::new(-4.0, -1.0), Size::new(16.0, 8.0))
+        );
+        assert_eq!(
+            rect.scale_from_center(0.5),
+            Rect::new(Point::new(2.5, 1.0), Size::new(5.0, 2.0))
+        );
+    }
+
+    #[test]
+    fn test_intersection() {
+        let rect1 = Rect::new(Point::new(0.0, 0.0), Size::new(2.0, 2.0));
+        let rect2 = Rect::new(Point::new(1.0, 1.0), Size::new(2.0, 2.0));
+        let rect3 = Rect::new(Point::new(5.0, 5.0), Size::new(1.0, 1.0));
+
+        assert!(rect1.intersects(rect2));
+        assert!(!rect1.intersects(rect3));
+        assert_eq!(
+            rect1.intersection(rect2),
+            Rect::new(Point::new(1.0, 1.0), Size::new(1.0, 1.0))
+        );
+        assert_eq!(rect1.intersection(rect3).area(), 0.0);
+        assert_eq!(
+            rect1.union(rect3),
+            Rect::new(Point::new(0.0, 0.0), Size::new(6.0, 6.0))
+        );
+    }
+}
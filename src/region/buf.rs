@@ -0,0 +1,313 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! An owned region made up of bands of boxes.
+
+#![cfg(feature = "alloc")]
+
+use crate::box2d::Box;
+use crate::point::Point;
+
+use alloc::vec::{IntoIter as VecIntoIter, Vec};
+use core::ops;
+use core::slice::Iter as SliceIter;
+use num_traits::Zero;
+
+/// An owned region, stored as a list of boxes that do not overlap.
+///
+/// The boxes are organized into bands. Every box in a band covers the same range of Y
+/// coordinates, the boxes in a band are sorted from left to right without touching, and
+/// the bands are sorted from top to bottom. Neighboring bands that cover the same range of
+/// X coordinates are merged together.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct RegionBuf<T: Copy> {
+    /// The boxes making up the region, in band order.
+    boxes: Vec<Box<T>>,
+}
+
+impl<T: Copy> RegionBuf<T> {
+    /// Create a new, empty region.
+    #[inline]
+    pub fn new() -> Self {
+        RegionBuf { boxes: Vec::new() }
+    }
+
+    /// Get the boxes that make up this region, in band order.
+    #[inline]
+    pub fn boxes(&self) -> &[Box<T>] {
+        &self.boxes
+    }
+
+    /// Get the number of boxes in this region.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// Tell if this region has no boxes in it.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// Remove every box from this region.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.boxes.clear();
+    }
+
+    /// Get the total area covered by this region.
+    pub fn area(&self) -> T
+    where
+        T: Zero + ops::Sub<Output = T> + ops::Mul<Output = T>,
+    {
+        self.boxes
+            .iter()
+            .fold(T::zero(), |area, bx| area + bx.area())
+    }
+
+    /// Get the length of the outline of this region.
+    pub fn perimeter(&self) -> T
+    where
+        T: PartialOrd + Zero + ops::Sub<Output = T>,
+    {
+        // Boxes in a band never touch, so every vertical side is on the outline.
+        let vertical = self.boxes.iter().fold(T::zero(), |sum, bx| {
+            let height = bx.max().y() - bx.min().y();
+            sum + height + height
+        });
+
+        // Horizontal sides are on the outline unless they touch the neighboring band.
+        let mut horizontal = T::zero();
+        let mut previous: &[Box<T>] = &[];
+
+        for band in self.bands() {
+            let width = band
+                .iter()
+                .fold(T::zero(), |sum, bx| sum + (bx.max().x() - bx.min().x()));
+            horizontal = horizontal + width + width;
+
+            if previous.first().map(|bx| bx.max().y()) == band.first().map(|bx| bx.min().y()) {
+                let shared = overlap(previous, band);
+                horizontal = horizontal - shared - shared;
+            }
+
+            previous = band;
+        }
+
+        vertical + horizontal
+    }
+
+    /// Get the smallest box that contains the entire region.
+    ///
+    /// Returns a zero-sized box at the origin if the region is empty.
+    pub fn extents(&self) -> Box<T>
+    where
+        T: PartialOrd + Zero,
+    {
+        match (self.boxes.first(), self.boxes.last()) {
+            (Some(first), Some(last)) => {
+                let (left, right) = self.boxes.iter().skip(1).fold(
+                    (first.min().x(), first.max().x()),
+                    |(left, right), bx| {
+                        (
+                            if bx.min().x() < left {
+                                bx.min().x()
+                            } else {
+                                left
+                            },
+                            if bx.max().x() > right {
+                                bx.max().x()
+                            } else {
+                                right
+                            },
+                        )
+                    },
+                );
+
+                Box::new(
+                    Point::new(left, first.min().y()),
+                    Point::new(right, last.max().y()),
+                )
+            }
+            _ => Box::zero(),
+        }
+    }
+
+    /// Iterate over the bands of this region, from top to bottom.
+    fn bands(&self) -> impl Iterator<Item = &[Box<T>]>
+    where
+        T: PartialEq,
+    {
+        let mut rest = &self.boxes[..];
+
+        core::iter::from_fn(move || {
+            let top = rest.first()?.min().y();
+            let len = rest
+                .iter()
+                .position(|bx| bx.min().y() != top)
+                .unwrap_or(rest.len());
+
+            let (band, remaining) = rest.split_at(len);
+            rest = remaining;
+            Some(band)
+        })
+    }
+
+    /// Append a band to the bottom of the region.
+    ///
+    /// The spans must be sorted from left to right and must not overlap. Empty spans are
+    /// skipped, touching spans are joined and the band is merged with the band above it
+    /// if they line up.
+    pub(super) fn push_band(&mut self, top: T, bottom: T, spans: impl IntoIterator<Item = (T, T)>)
+    where
+        T: PartialOrd,
+    {
+        if !matches!(top.partial_cmp(&bottom), Some(core::cmp::Ordering::Less)) {
+            return;
+        }
+
+        let band_start = self.boxes.len();
+        for (left, right) in spans {
+            if !matches!(left.partial_cmp(&right), Some(core::cmp::Ordering::Less)) {
+                continue;
+            }
+
+            match self.boxes[band_start..].last_mut() {
+                Some(last) if last.max().x() == left => {
+                    *last = Box::new(last.min(), Point::new(right, bottom));
+                }
+                _ => self
+                    .boxes
+                    .push(Box::new(Point::new(left, top), Point::new(right, bottom))),
+            }
+        }
+
+        // Merge the band with the previous one if they have the same spans.
+        let previous_start = self.band_start(band_start);
+        let previous = &self.boxes[previous_start..band_start];
+        let current = &self.boxes[band_start..];
+
+        let mergeable = !previous.is_empty()
+            && previous[0].max().y() == top
+            && previous.len() == current.len()
+            && previous
+                .iter()
+                .zip(current)
+                .all(|(a, b)| a.min().x() == b.min().x() && a.max().x() == b.max().x());
+
+        if mergeable {
+            self.boxes.truncate(band_start);
+            for bx in &mut self.boxes[previous_start..] {
+                *bx = Box::new(bx.min(), Point::new(bx.max().x(), bottom));
+            }
+        }
+    }
+
+    /// Find the index where the band that ends just before `end` begins.
+    fn band_start(&self, end: usize) -> usize
+    where
+        T: PartialEq,
+    {
+        match self.boxes[..end].last() {
+            None => end,
+            Some(last) => self.boxes[..end]
+                .iter()
+                .rposition(|bx| bx.min().y() != last.min().y())
+                .map_or(0, |index| index + 1),
+        }
+    }
+}
+
+/// Get the total length where the spans of two bands overlap.
+fn overlap<T>(a: &[Box<T>], b: &[Box<T>]) -> T
+where
+    T: Copy + PartialOrd + Zero + ops::Sub<Output = T>,
+{
+    let mut total = T::zero();
+    let (mut i, mut j) = (0, 0);
+
+    // Both bands are sorted from left to right, so walk them together.
+    while let (Some(x), Some(y)) = (a.get(i), b.get(j)) {
+        let left = if x.min().x() > y.min().x() {
+            x.min().x()
+        } else {
+            y.min().x()
+        };
+        let right = if x.max().x() < y.max().x() {
+            x.max().x()
+        } else {
+            y.max().x()
+        };
+
+        if left < right {
+            total = total + (right - left);
+        }
+
+        if x.max().x() < y.max().x() {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    total
+}
+
+impl<'a, T: Copy> IntoIterator for &'a RegionBuf<T> {
+    type Item = &'a Box<T>;
+    type IntoIter = SliceIter<'a, Box<T>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.boxes.iter()
+    }
+}
+
+impl<T: Copy> IntoIterator for RegionBuf<T> {
+    type Item = Box<T>;
+    type IntoIter = VecIntoIter<Box<T>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.boxes.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measurements() {
+        let mut region = RegionBuf::new();
+        assert!(region.is_empty());
+        assert_eq!(region.extents(), Box::zero());
+
+        // An L shape.
+        region.push_band(0, 1, Some((0, 1)));
+        region.push_band(1, 2, Some((0, 2)));
+
+        assert_eq!(region.len(), 2);
+        assert_eq!(region.area(), 3);
+        assert_eq!(region.perimeter(), 8);
+        assert_eq!(
+            region.extents(),
+            Box::new(Point::new(0, 0), Point::new(2, 2))
+        );
+    }
+}
@@ -25,6 +25,17 @@ use core::borrow::Borrow;
 use core::iter::{self, FromIterator, FusedIterator};
 use core::marker::PhantomData;
 
+mod buf;
+mod ops;
+mod rectilinear;
+
+#[cfg(feature = "alloc")]
+pub use buf::RegionBuf;
+#[cfg(feature = "alloc")]
+pub use ops::{intersect, subtract, union, xor};
+#[cfg(feature = "alloc")]
+pub use rectilinear::from_rectilinear_path;
+
 /// Represents a structure that can represent a region.
 pub trait Region<T: Copy> {
     /// The iterator type returned by `boxes_iter`.
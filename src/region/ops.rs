@@ -0,0 +1,231 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Boolean operations on regions.
+
+#![cfg(feature = "alloc")]
+
+use super::{Region, RegionBuf};
+use crate::box2d::Box;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// Get the area covered by either region.
+pub fn union<T, A, B>(a: A, b: B) -> RegionBuf<T>
+where
+    T: Copy + PartialOrd,
+    A: Region<T>,
+    B: Region<T>,
+{
+    combine(a, b, |a, b| a || b)
+}
+
+/// Get the area covered by both regions.
+pub fn intersect<T, A, B>(a: A, b: B) -> RegionBuf<T>
+where
+    T: Copy + PartialOrd,
+    A: Region<T>,
+    B: Region<T>,
+{
+    combine(a, b, |a, b| a && b)
+}
+
+/// Get the area covered by the first region but not the second.
+pub fn subtract<T, A, B>(a: A, b: B) -> RegionBuf<T>
+where
+    T: Copy + PartialOrd,
+    A: Region<T>,
+    B: Region<T>,
+{
+    combine(a, b, |a, b| a && !b)
+}
+
+/// Get the area covered by exactly one of the regions.
+pub fn xor<T, A, B>(a: A, b: B) -> RegionBuf<T>
+where
+    T: Copy + PartialOrd,
+    A: Region<T>,
+    B: Region<T>,
+{
+    combine(a, b, |a, b| a != b)
+}
+
+/// Combine two regions, keeping the areas where `keep` returns `true`.
+fn combine<T, A, B>(a: A, b: B, keep: impl Fn(bool, bool) -> bool) -> RegionBuf<T>
+where
+    T: Copy + PartialOrd,
+    A: Region<T>,
+    B: Region<T>,
+{
+    let a = non_empty_boxes(a);
+    let b = non_empty_boxes(b);
+
+    // Every Y coordinate where a box starts or stops begins a new band.
+    let mut ys = Vec::with_capacity((a.len() + b.len()) * 2);
+    for bx in a.iter().chain(&b) {
+        ys.push(bx.min().y());
+        ys.push(bx.max().y());
+    }
+    ys.sort_by(order);
+    ys.dedup_by(|a, b| a == b);
+
+    let mut region = RegionBuf::new();
+    let mut spans = Vec::new();
+    let mut events = Vec::new();
+
+    for band in ys.windows(2) {
+        let (top, bottom) = (band[0], band[1]);
+
+        // Each edge of a span toggles whether we are inside of its region.
+        events.clear();
+        for (is_a, boxes) in [(true, &a), (false, &b)].iter() {
+            for (left, right) in band_spans(boxes, top, bottom) {
+                events.push((left, *is_a, 1));
+                events.push((right, *is_a, -1));
+            }
+        }
+        events.sort_by(|a, b| order(&a.0, &b.0));
+
+        let (mut in_a, mut in_b) = (0, 0);
+        let mut start = None;
+        spans.clear();
+
+        let mut i = 0;
+        while i < events.len() {
+            // Apply every event at this X coordinate at once.
+            let x = events[i].0;
+            while i < events.len() && events[i].0 == x {
+                let (_, is_a, delta) = events[i];
+                if is_a {
+                    in_a += delta;
+                } else {
+                    in_b += delta;
+                }
+                i += 1;
+            }
+
+            let inside = keep(in_a > 0, in_b > 0);
+            match (start, inside) {
+                (None, true) => start = Some(x),
+                (Some(left), false) => {
+                    spans.push((left, x));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        region.push_band(top, bottom, spans.iter().copied());
+    }
+
+    region
+}
+
+/// Collect the boxes of a region, skipping the empty ones.
+fn non_empty_boxes<T: Copy + PartialOrd>(region: impl Region<T>) -> Vec<Box<T>> {
+    region
+        .boxes_iter()
+        .filter(|bx| bx.min().x() < bx.max().x() && bx.min().y() < bx.max().y())
+        .collect()
+}
+
+/// Get the sorted, merged X ranges covered by the boxes within a band.
+fn band_spans<T: Copy + PartialOrd>(boxes: &[Box<T>], top: T, bottom: T) -> Vec<(T, T)> {
+    let mut spans: Vec<(T, T)> = boxes
+        .iter()
+        .filter(|bx| bx.min().y() <= top && bx.max().y() >= bottom)
+        .map(|bx| (bx.min().x(), bx.max().x()))
+        .collect();
+    spans.sort_by(|a, b| order(&a.0, &b.0));
+
+    let mut merged: Vec<(T, T)> = Vec::with_capacity(spans.len());
+    for (left, right) in spans {
+        match merged.last_mut() {
+            Some(last) if left <= last.1 => {
+                if right > last.1 {
+                    last.1 = right;
+                }
+            }
+            _ => merged.push((left, right)),
+        }
+    }
+
+    merged
+}
+
+/// Order two values, treating incomparable values as equal.
+pub(super) fn order<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::region::single;
+    use crate::Point;
+
+    fn boxes() -> (Box<i32>, Box<i32>) {
+        (
+            Box::new(Point::new(0, 0), Point::new(2, 2)),
+            Box::new(Point::new(1, 1), Point::new(3, 3)),
+        )
+    }
+
+    fn bx(x0: i32, y0: i32, x1: i32, y1: i32) -> Box<i32> {
+        Box::new(Point::new(x0, y0), Point::new(x1, y1))
+    }
+
+    #[test]
+    fn test_union() {
+        let (a, b) = boxes();
+        assert_eq!(
+            union(single(a), single(b)).boxes(),
+            &[bx(0, 0, 2, 1), bx(0, 1, 3, 2), bx(1, 2, 3, 3)]
+        );
+    }
+
+    #[test]
+    fn test_intersect() {
+        let (a, b) = boxes();
+        assert_eq!(intersect(single(a), single(b)).boxes(), &[bx(1, 1, 2, 2)]);
+        assert!(intersect(single(a), single(bx(5, 5, 6, 6))).is_empty());
+    }
+
+    #[test]
+    fn test_subtract() {
+        let (a, b) = boxes();
+        assert_eq!(
+            subtract(single(a), single(b)).boxes(),
+            &[bx(0, 0, 2, 1), bx(0, 1, 1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_xor() {
+        let (a, b) = boxes();
+        assert_eq!(
+            xor(single(a), single(b)).boxes(),
+            &[
+                bx(0, 0, 2, 1),
+                bx(0, 1, 1, 2),
+                bx(2, 1, 3, 2),
+                bx(1, 2, 3, 3)
+            ]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Converting rectilinear paths into regions.
+
+#![cfg(feature = "alloc")]
+
+use super::ops::order;
+use super::RegionBuf;
+use crate::path::{Path, PathEvent};
+use crate::ApproxEq;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// A vertical edge of a rectilinear path.
+struct VerticalEdge<T> {
+    /// The X coordinate of the edge.
+    x: T,
+
+    /// The smaller Y coordinate of the edge.
+    top: T,
+
+    /// The larger Y coordinate of the edge.
+    bottom: T,
+
+    /// Whether the edge goes down (`1`) or up (`-1`).
+    winding: i32,
+}
+
+/// Convert a rectilinear path into a region, using the non-zero fill rule.
+///
+/// Every subpath is treated as closed. This scans over the vertical edges of the path
+/// directly, which is much cheaper than tessellating the path into trapezoids. Returns
+/// `None` if the path contains curves or edges that are not axis-aligned.
+///
+/// The edges are sorted by where they start, and each band only looks at the edges that
+/// cross it rather than at every edge in the path.
+pub fn from_rectilinear_path<T, P>(path: P) -> Option<RegionBuf<T>>
+where
+    T: Copy + PartialOrd + ApproxEq,
+    P: Path<T>,
+{
+    let mut edges = Vec::new();
+
+    for event in path.path_iter() {
+        let (from, to) = match event {
+            PathEvent::Begin { .. } => continue,
+            PathEvent::Line { from, to }
+            | PathEvent::End {
+                first: to,
+                last: from,
+                ..
+            } => (from, to),
+            _ => return None,
+        };
+
+        if !from.x().approx_eq(&to.x()) {
+            // Horizontal edges don't affect the scanline.
+            if from.y().approx_eq(&to.y()) {
+                continue;
+            }
+
+            return None;
+        }
+
+        match from.y().partial_cmp(&to.y()) {
+            Some(Ordering::Less) => edges.push(VerticalEdge {
+                x: from.x(),
+                top: from.y(),
+                bottom: to.y(),
+                winding: 1,
+            }),
+            Some(Ordering::Greater) => edges.push(VerticalEdge {
+                x: from.x(),
+                top: to.y(),
+                bottom: from.y(),
+                winding: -1,
+            }),
+            _ => {}
+        }
+    }
+
+    // Every Y coordinate where an edge starts or stops begins a new band.
+    let mut ys = Vec::with_capacity(edges.len() * 2);
+    for edge in &edges {
+        ys.push(edge.top);
+        ys.push(edge.bottom);
+    }
+
+    ys.sort_by(order);
+    ys.dedup_by(|a, b| a == b);
+    edges.sort_by(|a, b| order(&a.top, &b.top));
+
+    let mut region = RegionBuf::new();
+    let mut spans = Vec::new();
+
+    // The edges that cross the current band, sorted by X coordinate.
+    let mut active: Vec<&VerticalEdge<T>> = Vec::new();
+    let mut next = 0;
+
+    for band in ys.windows(2) {
+        let (top, bottom) = (band[0], band[1]);
+        let mut winding = 0;
+        let mut start = None;
+
+        // Every edge starts and stops on a band boundary, so the edges that cross this
+        // band are the ones that started at or above its top and haven't stopped yet.
+        active.retain(|edge| edge.bottom > top);
+        while let Some(edge) = edges.get(next).filter(|edge| edge.top <= top) {
+            let index = active
+                .binary_search_by(|other| match order(&other.x, &edge.x) {
+                    Ordering::Greater => Ordering::Greater,
+                    _ => Ordering::Less,
+                })
+                .unwrap_err();
+            active.insert(index, edge);
+            next += 1;
+        }
+        debug_assert!(active.iter().all(|edge| edge.bottom >= bottom));
+
+        spans.clear();
+        for edge in &active {
+            let was_inside = winding != 0;
+            winding += edge.winding;
+
+            match (was_inside, winding != 0) {
+                (false, true) => start = Some(edge.x),
+                (true, false) => spans.extend(start.take().map(|start| (start, edge.x))),
+                _ => {}
+            }
+        }
+
+        region.push_band(top, bottom, spans.iter().copied());
+    }
+
+    Some(region)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::PathBuffer;
+    use crate::{Box, Point, QuadraticBezier};
+
+    #[test]
+    fn test_overlapping_boxes() {
+        let a = Box::new(Point::new(0.0f32, 0.0), Point::new(2.0, 2.0));
+        let b = Box::new(Point::new(1.0, 1.0), Point::new(3.0, 3.0));
+        let path: PathBuffer<f32, Vec<_>> = a.path_iter().chain(b.path_iter()).collect();
+
+        let region = from_rectilinear_path(&path).unwrap();
+        assert_eq!(
+            region.boxes(),
+            &[
+                Box::new(Point::new(0.0, 0.0), Point::new(2.0, 1.0)),
+                Box::new(Point::new(0.0, 1.0), Point::new(3.0, 2.0)),
+                Box::new(Point::new(1.0, 2.0), Point::new(3.0, 3.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_staggered_boxes() {
+        // A tall box with two short boxes beside it, at different heights.
+        let boxes = [
+            Box::new(Point::new(0, 0), Point::new(1, 6)),
+            Box::new(Point::new(2, 1), Point::new(3, 2)),
+            Box::new(Point::new(4, 3), Point::new(5, 5)),
+        ];
+        let path: PathBuffer<i32, Vec<_>> = boxes.iter().flat_map(|b| b.path_iter()).collect();
+
+        let region = from_rectilinear_path(&path).unwrap();
+        let area: i32 = region
+            .boxes()
+            .iter()
+            .map(|b| (b.max().x() - b.min().x()) * (b.max().y() - b.min().y()))
+            .sum();
+        assert_eq!(area, 9);
+        for b in &boxes {
+            assert!(region
+                .boxes()
+                .iter()
+                .any(|piece| piece.min().x() == b.min().x() && piece.max().x() == b.max().x()));
+        }
+    }
+
+    #[test]
+    fn test_merged_bands() {
+        let a = Box::new(Point::new(0, 0), Point::new(2, 2));
+        let b = Box::new(Point::new(0, 2), Point::new(2, 4));
+        let path: PathBuffer<i32, Vec<_>> = a.path_iter().chain(b.path_iter()).collect();
+
+        let region = from_rectilinear_path(&path).unwrap();
+        assert_eq!(
+            region.boxes(),
+            &[Box::new(Point::new(0, 0), Point::new(2, 4))]
+        );
+    }
+
+    #[test]
+    fn test_not_rectilinear() {
+        let curve = QuadraticBezier::new(
+            Point::new(0.0f32, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 0.0),
+        );
+        assert!(from_rectilinear_path(curve).is_none());
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Mapping numbers between ranges, for plotting.
+//!
+//! These pair with [`Box::x_range`] and [`Box::y_range`] to map data onto the area of a
+//! chart.
+//!
+//! [`Box::x_range`]: crate::Box::x_range
+//! [`Box::y_range`]: crate::Box::y_range
+
+use core::ops::Range;
+use num_traits::real::Real;
+
+/// Round a range of values outwards to "nice" numbers, for the axis of a chart.
+///
+/// Returns the rounded range and the step between its ticks. The step is one, two or
+/// five times a power of ten, chosen so that there are about `target_ticks` ticks, and
+/// both ends of the range are multiples of the step. The ends may be given in either
+/// order. If `min` and `max` are the same, the range is widened around them.
+pub fn nice_range<T: Real>(min: T, max: T, target_ticks: usize) -> (Range<T>, T) {
+    let (min, max) = if min <= max { (min, max) } else { (max, min) };
+    let intervals = T::from(target_ticks.max(2) - 1).unwrap();
+
+    let span = if max > min {
+        max - min
+    } else if min.is_zero() {
+        T::one()
+    } else {
+        min.abs()
+    };
+
+    let step = nice_number(nice_number(span, false) / intervals, true);
+    let range = (min / step).floor() * step..(max / step).ceil() * step;
+
+    if range.start < range.end {
+        (range, step)
+    } else {
+        (range.start..range.start + step, step)
+    }
+}
+
+/// Map a value from one range onto another.
+///
+/// The value isn't clamped, so values outside of `from` end up outside of `to`. Either
+/// range may be reversed, which is handy for flipping the Y axis. If `from` is empty,
+/// this returns the start of `to`.
+pub fn linear_map<T: Real>(value: T, from: Range<T>, to: Range<T>) -> T {
+    let length = from.end - from.start;
+    if length.is_zero() {
+        return to.start;
+    }
+
+    to.start + (value - from.start) * ((to.end - to.start) / length)
+}
+
+/// Find a number that is one, two or five times a power of ten, close to `value`.
+///
+/// If `round` is false, this is the smallest such number that is at least `value`.
+fn nice_number<T: Real>(value: T, round: bool) -> T {
+    let ten = T::from(10).unwrap();
+    let power = ten.powf(value.log10().floor());
+    let fraction = value / power;
+
+    let thresholds: [(f32, f32); 3] = if round {
+        [(1.5, 1.0), (3.0, 2.0), (7.0, 5.0)]
+    } else {
+        [(1.0, 1.0), (2.0, 2.0), (5.0, 5.0)]
+    };
+
+    let nice = thresholds
+        .iter()
+        .find(|&&(threshold, _)| {
+            let threshold = T::from(threshold).unwrap();
+            if round {
+                fraction < threshold
+            } else {
+                fraction <= threshold
+            }
+        })
+        .map_or(ten, |&(_, nice)| T::from(nice).unwrap());
+
+    nice * power
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nice_range() {
+        assert_eq!(nice_range(0.0, 1.0, 5), (0.0..1.0, 0.2));
+        assert_eq!(nice_range(-3.2, 97.1, 6), (-50.0..100.0, 50.0));
+        assert_eq!(nice_range(97.1, -3.2, 6), (-50.0..100.0, 50.0));
+        assert_eq!(nice_range(1234.0, 5678.0, 5), (1000.0..6000.0, 1000.0));
+
+        let (range, step) = nice_range(5.0, 5.0, 5);
+        assert!(range.start <= 5.0 && range.end > 5.0);
+        assert!(step > 0.0);
+        let (range, _) = nice_range(0.0, 0.0, 0);
+        assert!(range.start < range.end);
+    }
+
+    #[test]
+    fn test_linear_map() {
+        assert_eq!(linear_map(5.0, 0.0..10.0, 100.0..200.0), 150.0);
+        assert_eq!(linear_map(15.0, 0.0..10.0, 100.0..200.0), 250.0);
+        assert_eq!(linear_map(2.0, 0.0..10.0, 50.0..0.0), 40.0);
+        assert_eq!(linear_map(2.0, 3.0..3.0, 50.0..0.0), 50.0);
+    }
+}
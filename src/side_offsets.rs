@@ -0,0 +1,347 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Offsets from each side of a rectangle.
+
+use crate::pair::{Double, Quad};
+use crate::{Box, Rect};
+use num_traits::Zero;
+
+use core::fmt;
+use core::ops;
+
+/// Offsets from the top, right, bottom and left sides of a rectangle.
+///
+/// These are typically used to describe margins, padding or borders.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct SideOffsets<T: Copy>(pub(crate) Quad<T>);
+
+impl<T: fmt::Debug + Copy> fmt::Debug for SideOffsets<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SideOffsets")
+            .field("top", &self.top())
+            .field("right", &self.right())
+            .field("bottom", &self.bottom())
+            .field("left", &self.left())
+            .finish()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Copy + arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for SideOffsets<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let value: [T; 4] = arbitrary::Arbitrary::arbitrary(u)?;
+        Ok(SideOffsets(Quad::new(value)))
+    }
+}
+
+/// The logical, serde representation of side offsets.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone)]
+#[serde(rename = "SideOffsets")]
+struct LogicalSideOffsets<T: Copy> {
+    top: T,
+    right: T,
+    bottom: T,
+    left: T,
+}
+
+#[cfg(feature = "serde")]
+impl<T: Copy + serde::Serialize> serde::Serialize for SideOffsets<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let [top, right, bottom, left] = self.0.into_inner();
+
+        LogicalSideOffsets {
+            top,
+            right,
+            bottom,
+            left,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: Copy + serde::Deserialize<'de>> serde::Deserialize<'de> for SideOffsets<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let LogicalSideOffsets {
+            top,
+            right,
+            bottom,
+            left,
+        } = LogicalSideOffsets::deserialize(deserializer)?;
+
+        Ok(Self::new(top, right, bottom, left))
+    }
+}
+
+impl<T: Copy> SideOffsets<T> {
+    /// Create a new set of offsets, in the same order as CSS.
+    #[inline]
+    pub fn new(top: T, right: T, bottom: T, left: T) -> Self {
+        SideOffsets(Quad::new([top, right, bottom, left]))
+    }
+
+    /// Create a new set of offsets that are the same on every side.
+    #[inline]
+    pub fn uniform(value: T) -> Self {
+        SideOffsets(Quad::splat(value))
+    }
+
+    /// Create a new set of offsets with one value for the top and bottom, and another
+    /// for the left and right.
+    #[inline]
+    pub fn symmetric(vertical: T, horizontal: T) -> Self {
+        Self::new(vertical, horizontal, vertical, horizontal)
+    }
+
+    /// Create a new set of offsets that are zero on every side.
+    #[inline]
+    pub fn zero() -> Self
+    where
+        T: Zero,
+    {
+        Self::uniform(T::zero())
+    }
+
+    /// Get the offset from the top side.
+    #[inline]
+    pub fn top(&self) -> T {
+        self.0[0]
+    }
+
+    /// Get the offset from the right side.
+    #[inline]
+    pub fn right(&self) -> T {
+        self.0[1]
+    }
+
+    /// Get the offset from the bottom side.
+    #[inline]
+    pub fn bottom(&self) -> T {
+        self.0[2]
+    }
+
+    /// Get the offset from the left side.
+    #[inline]
+    pub fn left(&self) -> T {
+        self.0[3]
+    }
+
+    /// Get the total of the left and right offsets.
+    #[inline]
+    pub fn horizontal(&self) -> T
+    where
+        T: ops::Add<Output = T>,
+    {
+        self.left() + self.right()
+    }
+
+    /// Get the total of the top and bottom offsets.
+    #[inline]
+    pub fn vertical(&self) -> T
+    where
+        T: ops::Add<Output = T>,
+    {
+        self.top() + self.bottom()
+    }
+
+    /// Get the offsets of the top left corner and the bottom right corner, as X/Y
+    /// pairs.
+    #[inline]
+    fn corners(&self) -> (Double<T>, Double<T>) {
+        let [top, right, bottom, left] = self.0.into_inner();
+        (Double::new([left, top]), Double::new([right, bottom]))
+    }
+
+    /// Shrink a box by these offsets.
+    ///
+    /// If the box would be inverted along an axis, its maximum is moved to its
+    /// minimum along that axis.
+    #[inline]
+    pub fn inset_box(&self, box_: Box<T>) -> Box<T>
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + PartialOrd,
+    {
+        let (min, max) = box_.min_max();
+        let (top_left, bottom_right) = self.corners();
+
+        let min = min.0 + top_left;
+        let max = (max.0 - bottom_right).max(min);
+        Box::new(crate::Point(min), crate::Point(max))
+    }
+
+    /// Grow a box by these offsets.
+    #[inline]
+    pub fn outset_box(&self, box_: Box<T>) -> Box<T>
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T>,
+    {
+        let (min, max) = box_.min_max();
+        let (top_left, bottom_right) = self.corners();
+
+        Box::new(
+            crate::Point(min.0 - top_left),
+            crate::Point(max.0 + bottom_right),
+        )
+    }
+
+    /// Shrink a rectangle by these offsets.
+    ///
+    /// This is equivalent to `Rect::inset`.
+    #[inline]
+    pub fn inset_rect(&self, rect: Rect<T>) -> Rect<T>
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T> + Zero + PartialOrd,
+    {
+        rect.inset(*self)
+    }
+
+    /// Grow a rectangle by these offsets.
+    ///
+    /// This is equivalent to `Rect::outset`.
+    #[inline]
+    pub fn outset_rect(&self, rect: Rect<T>) -> Rect<T>
+    where
+        T: ops::Add<Output = T> + ops::Sub<Output = T>,
+    {
+        rect.outset(*self)
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::Add for SideOffsets<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        SideOffsets(self.0 + rhs.0)
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::AddAssign for SideOffsets<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::Sub for SideOffsets<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        SideOffsets(self.0 - rhs.0)
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::SubAssign for SideOffsets<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<T> for SideOffsets<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: T) -> Self::Output {
+        SideOffsets(self.0 * Quad::splat(rhs))
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::MulAssign<T> for SideOffsets<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Copy + ops::Div<Output = T>> ops::Div<T> for SideOffsets<T> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: T) -> Self::Output {
+        SideOffsets(self.0 / Quad::splat(rhs))
+    }
+}
+
+impl<T: Copy + ops::Div<Output = T>> ops::DivAssign<T> for SideOffsets<T> {
+    #[inline]
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+
+impl<T: Copy + ops::Neg<Output = T>> ops::Neg for SideOffsets<T> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        SideOffsets(-self.0)
+    }
+}
+
+#[cfg(feature = "euclid")]
+impl<T: Copy, U> From<euclid::SideOffsets2D<T, U>> for SideOffsets<T> {
+    #[inline]
+    fn from(offsets: euclid::SideOffsets2D<T, U>) -> Self {
+        SideOffsets::new(offsets.top, offsets.right, offsets.bottom, offsets.left)
+    }
+}
+
+#[cfg(feature = "euclid")]
+impl<T: Copy, U> From<SideOffsets<T>> for euclid::SideOffsets2D<T, U> {
+    #[inline]
+    fn from(offsets: SideOffsets<T>) -> Self {
+        euclid::SideOffsets2D::new(
+            offsets.top(),
+            offsets.right(),
+            offsets.bottom(),
+            offsets.left(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn test_arithmetic() {
+        let offsets = SideOffsets::new(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(offsets + offsets, offsets * 2.0);
+        assert_eq!(offsets - offsets, SideOffsets::zero());
+        assert_eq!(offsets.horizontal(), 6.0);
+        assert_eq!(offsets.vertical(), 4.0);
+    }
+
+    #[test]
+    fn test_box() {
+        let box_ = Box::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+        let offsets = SideOffsets::new(1.0, 2.0, 3.0, 4.0);
+
+        let inset = offsets.inset_box(box_);
+        assert_eq!(inset, Box::new(Point::new(4.0, 1.0), Point::new(8.0, 7.0)));
+        assert_eq!(offsets.outset_box(inset), box_);
+
+        let collapsed = SideOffsets::uniform(6.0).inset_box(box_);
+        assert_eq!(collapsed.size().width(), 0.0);
+    }
+}
@@ -15,8 +15,8 @@
 // You should have received a copy of the GNU Affero General Public License 
 // along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
 
-use crate::{Point, Vector};
 use crate::pair::{Double, Quad};
+use crate::{Point, Scale, Vector};
 use num_traits::real::Real;
 use num_traits::{One, Signed, Zero};
 
@@ -236,6 +236,64 @@ impl<T: Copy + ops::MulAssign> ops::MulAssign<T> for Size<T> {
     }
 }
 
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<T> for Size<T> {
+    type Output = Self;
+
+    fn mul(self, other: T) -> Self {
+        Size(self.0 * Double::splat(other))
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<Vector<T>> for Size<T> {
+    type Output = Size<T>;
+
+    fn mul(self, other: Vector<T>) -> Size<T> {
+        Size(self.0 * other.0)
+    }
+}
+
+impl<T: Copy + ops::MulAssign> ops::MulAssign<Vector<T>> for Size<T> {
+    fn mul_assign(&mut self, other: Vector<T>) {
+        self.0 *= other.0;
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<Scale<T>> for Size<T> {
+    type Output = Size<T>;
+
+    fn mul(self, other: Scale<T>) -> Size<T> {
+        self * other.vector()
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::Add<Vector<T>> for Size<T> {
+    type Output = Size<T>;
+
+    fn add(self, other: Vector<T>) -> Size<T> {
+        Size(self.0 + other.0)
+    }
+}
+
+impl<T: Copy + ops::AddAssign> ops::AddAssign<Vector<T>> for Size<T> {
+    fn add_assign(&mut self, other: Vector<T>) {
+        self.0 += other.0;
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::Sub<Vector<T>> for Size<T> {
+    type Output = Size<T>;
+
+    fn sub(self, other: Vector<T>) -> Size<T> {
+        Size(self.0 - other.0)
+    }
+}
+
+impl<T: Copy + ops::SubAssign> ops::SubAssign<Vector<T>> for Size<T> {
+    fn sub_assign(&mut self, other: Vector<T>) {
+        self.0 -= other.0;
+    }
+}
+
 impl<T: Copy + ops::Div<Output = T>> ops::Div<T> for Size<T> {
     type Output = Self;
 
@@ -258,6 +316,12 @@ impl<T: Copy + ops::DivAssign> ops::DivAssign<T> for Size<T> {
     }
 }
 
+impl<T: Copy + ops::DivAssign> ops::DivAssign<Vector<T>> for Size<T> {
+    fn div_assign(&mut self, other: Vector<T>) {
+        self.0 /= other.0;
+    }
+}
+
 impl<T: Copy + ops::Neg<Output = T>> ops::Neg for Size<T> {
     type Output = Self;
 
@@ -352,4 +416,78 @@ impl<T: Copy> Size<T> {
         let [width, height] = self.0.into_inner();
         width * height
     }
+
+    /// Tell if either dimension of this size is zero or negative.
+    #[inline]
+    pub fn is_empty(self) -> bool
+    where
+        T: PartialOrd + Zero,
+    {
+        let zero = Double::splat(T::zero());
+        self.0.packed_le(zero).any()
+    }
+
+    /// Get the ratio of the width to the height.
+    #[inline]
+    pub fn aspect_ratio(self) -> T
+    where
+        T: ops::Div<Output = T>,
+    {
+        let [width, height] = self.0.into_inner();
+        width / height
+    }
+
+    /// Scale this size uniformly so that it fits entirely inside of `other`.
+    #[inline]
+    pub fn scale_to_fit(self, other: Self) -> Self
+    where
+        T: Real,
+    {
+        let [x, y] = (other.0 / self.0).into_inner();
+        Size(self.0 * Double::splat(x.min(y)))
+    }
+
+    /// Scale this size uniformly so that it entirely covers `other`.
+    #[inline]
+    pub fn scale_to_fill(self, other: Self) -> Self
+    where
+        T: Real,
+    {
+        let [x, y] = (other.0 / self.0).into_inner();
+        Size(self.0 * Double::splat(x.max(y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Size;
+    use crate::{Scale, Vector};
+
+    #[test]
+    fn test_scale_to_fit() {
+        let size = Size::new(4.0, 2.0);
+        let viewport = Size::new(8.0, 8.0);
+
+        assert_eq!(size.aspect_ratio(), 2.0);
+        assert_eq!(size.scale_to_fit(viewport), Size::new(8.0, 4.0));
+        assert_eq!(size.scale_to_fill(viewport), Size::new(16.0, 8.0));
+    }
+
+    #[test]
+    fn test_ops() {
+        let mut size = Size::new(4.0, 2.0) * 2.0;
+        assert_eq!(size, Size::new(8.0, 4.0));
+
+        size *= Vector::new(0.5, 2.0);
+        assert_eq!(size, Size::new(4.0, 8.0));
+        assert_eq!(size + Vector::new(1.0, 1.0), Size::new(5.0, 9.0));
+        assert_eq!(size * Scale::uniform(0.5), Size::new(2.0, 4.0));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        assert!(!Size::new(1.0, 2.0).is_empty());
+        assert!(Size::new(0.0, 2.0).is_empty());
+        assert!(Size::new(3.0, -1.0).is_empty());
+    }
 }
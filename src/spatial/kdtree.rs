@@ -0,0 +1,251 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! A kd-tree for nearest neighbor queries on points.
+
+use crate::point::Point;
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use num_traits::real::Real;
+
+/// A two-dimensional tree of points, for finding the points nearest to another point.
+///
+/// The tree is stored implicitly in a single list: each range of the list is split at its
+/// median point, alternating between the X and Y axes at every level. Queries return the
+/// indices of the points in the list the tree was built from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KdTree<T: Copy> {
+    /// The points, along with their original indices, in tree order.
+    points: Vec<(Point<T>, usize)>,
+}
+
+impl<T: Real> KdTree<T> {
+    /// Build a new `KdTree` from a list of points.
+    pub fn new(points: &[Point<T>]) -> Self {
+        let mut points = points
+            .iter()
+            .copied()
+            .enumerate()
+            .map(|(i, p)| (p, i))
+            .collect::<Vec<_>>();
+        build(&mut points, 0);
+        KdTree { points }
+    }
+
+    /// Get the number of points in the tree.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Tell whether the tree has no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Get the point with the given index.
+    pub fn point(&self, index: usize) -> Option<Point<T>> {
+        self.points
+            .iter()
+            .find(|&&(_, i)| i == index)
+            .map(|&(point, _)| point)
+    }
+
+    /// Find the index of the point closest to the given point.
+    pub fn nearest(&self, point: Point<T>) -> Option<usize> {
+        self.k_nearest(point, 1).pop()
+    }
+
+    /// Find the indices of the `k` points closest to the given point, nearest first.
+    pub fn k_nearest(&self, point: Point<T>, k: usize) -> Vec<usize> {
+        let mut found = Vec::with_capacity(k.min(self.points.len()) + 1);
+        if k > 0 && !self.points.is_empty() {
+            self.search(&self.points, 0, point, &mut |distance, bound| {
+                let (_, index) = bound;
+                let at = found
+                    .iter()
+                    .position(|&(d, _)| distance < d)
+                    .unwrap_or(found.len());
+                found.insert(at, (distance, index));
+                found.truncate(k);
+
+                // Only look at points closer than the farthest one found so far.
+                if found.len() == k {
+                    found[k - 1].0
+                } else {
+                    T::max_value()
+                }
+            });
+        }
+
+        found.into_iter().map(|(_, index)| index).collect()
+    }
+
+    /// Find the indices of every point within `radius` of the given point, in no
+    /// particular order.
+    pub fn within_radius(&self, point: Point<T>, radius: T) -> Vec<usize> {
+        let radius_squared = radius * radius;
+        let mut found = Vec::new();
+        if self.points.is_empty() {
+            return found;
+        }
+
+        self.search(&self.points, 0, point, &mut |distance, (_, index)| {
+            if distance <= radius_squared {
+                found.push(index);
+            }
+            radius_squared
+        });
+
+        found
+    }
+
+    /// Visit the points in a subtree that could be closer than the current limit.
+    ///
+    /// The visitor is called with the squared distance to each point, and returns the
+    /// squared distance past which points are no longer interesting.
+    fn search(
+        &self,
+        points: &[(Point<T>, usize)],
+        depth: usize,
+        target: Point<T>,
+        visit: &mut impl FnMut(T, (Point<T>, usize)) -> T,
+    ) -> T {
+        let mid = points.len() / 2;
+        let (point, index) = points[mid];
+        let mut limit = visit(point.distance_squared(target), (point, index));
+
+        let offset = axis(target, depth) - axis(point, depth);
+        let (near, far) = if offset < T::zero() {
+            (&points[..mid], &points[mid + 1..])
+        } else {
+            (&points[mid + 1..], &points[..mid])
+        };
+
+        if !near.is_empty() {
+            limit = self.search(near, depth + 1, target, visit);
+        }
+
+        // The far side can only have closer points if the splitting line is closer.
+        if !far.is_empty() && offset * offset <= limit {
+            limit = self.search(far, depth + 1, target, visit);
+        }
+
+        limit
+    }
+}
+
+/// Get the coordinate of a point along the axis that splits the given level.
+fn axis<T: Copy>(point: Point<T>, depth: usize) -> T {
+    if depth & 1 == 0 {
+        point.x()
+    } else {
+        point.y()
+    }
+}
+
+/// Arrange a list of points into an implicit kd-tree.
+fn build<T: Real>(points: &mut [(Point<T>, usize)], depth: usize) {
+    if points.len() <= 1 {
+        return;
+    }
+
+    let mid = points.len() / 2;
+    points.select_nth_unstable_by(mid, |a, b| {
+        axis(a.0, depth)
+            .partial_cmp(&axis(b.0, depth))
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let (left, right) = points.split_at_mut(mid);
+    build(left, depth + 1);
+    build(&mut right[1..], depth + 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points() -> Vec<Point<f64>> {
+        (0..200)
+            .map(|i| {
+                let i = i as f64;
+                Point::new((i * 7.3) % 17.0, (i * 3.1) % 11.0)
+            })
+            .collect()
+    }
+
+    fn brute_force(points: &[Point<f64>], target: Point<f64>) -> Vec<usize> {
+        let mut indices = (0..points.len()).collect::<Vec<_>>();
+        indices.sort_by(|&a, &b| {
+            let da = points[a].distance_squared(target);
+            let db = points[b].distance_squared(target);
+            da.partial_cmp(&db).unwrap()
+        });
+        indices
+    }
+
+    #[test]
+    fn test_nearest() {
+        let points = points();
+        let tree = KdTree::new(&points);
+        assert_eq!(tree.len(), 200);
+
+        for &target in &[
+            Point::new(3.0, 4.0),
+            Point::new(-10.0, 5.0),
+            Point::new(16.5, 10.9),
+        ] {
+            let expected = brute_force(&points, target);
+            let nearest = tree.nearest(target).unwrap();
+            assert_eq!(
+                points[nearest].distance_squared(target),
+                points[expected[0]].distance_squared(target)
+            );
+
+            let distances = |indices: &[usize]| {
+                indices
+                    .iter()
+                    .map(|&i| points[i].distance_squared(target))
+                    .collect::<Vec<_>>()
+            };
+            let k = tree.k_nearest(target, 10);
+            assert_eq!(distances(&k), distances(&expected[..10]));
+        }
+
+        assert_eq!(KdTree::<f32>::new(&[]).nearest(Point::new(0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn test_within_radius() {
+        let points = points();
+        let tree = KdTree::new(&points);
+        let target = Point::new(8.0, 5.0);
+
+        let mut found = tree.within_radius(target, 2.5);
+        found.sort_unstable();
+
+        let mut expected = (0..points.len())
+            .filter(|&i| points[i].distance(target) <= 2.5)
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+
+        assert!(!expected.is_empty());
+        assert_eq!(found, expected);
+        assert_eq!(tree.point(found[0]), Some(points[found[0]]));
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Spatial indexes for quickly finding nearby geometry.
+
+#![cfg(feature = "alloc")]
+
+mod kdtree;
+mod order;
+
+pub use kdtree::KdTree;
+pub use order::{
+    hilbert_index, hilbert_sort, hilbert_sort_boxes, morton_code, morton_decode, morton_encode,
+    morton_sort,
+};
@@ -0,0 +1,183 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! Ordering geometry along space-filling curves.
+
+use crate::box2d::Box;
+use crate::point::Point;
+
+use num_traits::real::Real;
+
+/// The number of cells along each side of the grid that the curves are drawn on.
+const GRID_SIZE: u32 = 1 << 16;
+
+/// Interleave the bits of two coordinates into a Morton code, also known as a Z-order code.
+pub fn morton_encode(x: u16, y: u16) -> u32 {
+    spread(x) | (spread(y) << 1)
+}
+
+/// Split a Morton code back into its two coordinates.
+pub fn morton_decode(code: u32) -> (u16, u16) {
+    (compact(code), compact(code >> 1))
+}
+
+/// Get the Morton code of a point, on a grid covering the given bounds.
+pub fn morton_code<T: Real>(point: Point<T>, bounds: &Box<T>) -> u32 {
+    let (x, y) = quantize(point, bounds);
+    morton_encode(x as u16, y as u16)
+}
+
+/// Get the distance along the Hilbert curve of a point, on a grid covering the given
+/// bounds.
+///
+/// Points that are close along the Hilbert curve are also close in space, which makes it
+/// a better ordering than Morton codes for most spatial indexes.
+pub fn hilbert_index<T: Real>(point: Point<T>, bounds: &Box<T>) -> u32 {
+    let (mut x, mut y) = quantize(point, bounds);
+    let mut index = 0;
+    let mut size = GRID_SIZE / 2;
+
+    while size > 0 {
+        let rx = (x & size != 0) as u32;
+        let ry = (y & size != 0) as u32;
+        index += size * size * ((3 * rx) ^ ry);
+
+        // Rotate the quadrant so that the curve inside of it has the right orientation.
+        if ry == 0 {
+            if rx == 1 {
+                x = GRID_SIZE - 1 - x;
+                y = GRID_SIZE - 1 - y;
+            }
+            core::mem::swap(&mut x, &mut y);
+        }
+
+        size /= 2;
+    }
+
+    index
+}
+
+/// Sort points in the order that the Hilbert curve over the given bounds visits them.
+pub fn hilbert_sort<T: Real>(points: &mut [Point<T>], bounds: &Box<T>) {
+    points.sort_unstable_by_key(|&point| hilbert_index(point, bounds));
+}
+
+/// Sort points by their Morton codes over the given bounds.
+pub fn morton_sort<T: Real>(points: &mut [Point<T>], bounds: &Box<T>) {
+    points.sort_unstable_by_key(|&point| morton_code(point, bounds));
+}
+
+/// Sort boxes in the order that the Hilbert curve over the given bounds visits their
+/// centers.
+pub fn hilbert_sort_boxes<T: Real>(boxes: &mut [Box<T>], bounds: &Box<T>) {
+    boxes.sort_unstable_by_key(|b| hilbert_index(b.center(), bounds));
+}
+
+/// Map a point onto the grid covering the bounds, clamping it to the grid.
+fn quantize<T: Real>(point: Point<T>, bounds: &Box<T>) -> (u32, u32) {
+    let scale = T::from(GRID_SIZE - 1).unwrap();
+    let cell = |value: T, min: T, max: T| {
+        let extent = max - min;
+        if extent <= T::zero() {
+            return 0;
+        }
+
+        ((value - min) / extent * scale)
+            .max(T::zero())
+            .min(scale)
+            .to_u32()
+            .unwrap_or(0)
+    };
+
+    (
+        cell(point.x(), bounds.min().x(), bounds.max().x()),
+        cell(point.y(), bounds.min().y(), bounds.max().y()),
+    )
+}
+
+/// Spread the bits of a number out so that there is a zero between each of them.
+fn spread(value: u16) -> u32 {
+    let mut value = value as u32;
+    value = (value | (value << 8)) & 0x00FF_00FF;
+    value = (value | (value << 4)) & 0x0F0F_0F0F;
+    value = (value | (value << 2)) & 0x3333_3333;
+    (value | (value << 1)) & 0x5555_5555
+}
+
+/// Undo `spread`, gathering every other bit back together.
+fn compact(value: u32) -> u16 {
+    let mut value = value & 0x5555_5555;
+    value = (value | (value >> 1)) & 0x3333_3333;
+    value = (value | (value >> 2)) & 0x0F0F_0F0F;
+    value = (value | (value >> 4)) & 0x00FF_00FF;
+    ((value | (value >> 8)) & 0x0000_FFFF) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_morton() {
+        assert_eq!(morton_encode(0, 0), 0);
+        assert_eq!(morton_encode(1, 0), 1);
+        assert_eq!(morton_encode(0, 1), 2);
+        assert_eq!(morton_encode(3, 3), 15);
+        assert_eq!(morton_decode(morton_encode(1234, 65535)), (1234, 65535));
+
+        let bounds = Box::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0));
+        assert_eq!(morton_code(Point::new(1.0, 1.0), &bounds), u32::MAX);
+        assert_eq!(morton_code(Point::new(-5.0, 0.0), &bounds), 0);
+    }
+
+    #[test]
+    fn test_hilbert_sort() {
+        let bounds = Box::new(Point::new(0.0f32, 0.0), Point::new(1.0, 1.0));
+        let mut points = [
+            Point::new(0.75, 0.25),
+            Point::new(0.25, 0.75),
+            Point::new(0.75, 0.75),
+            Point::new(0.25, 0.25),
+        ];
+        hilbert_sort(&mut points, &bounds);
+
+        // The curve visits the quadrants in a "U" shape.
+        assert_eq!(
+            points,
+            [
+                Point::new(0.25, 0.25),
+                Point::new(0.25, 0.75),
+                Point::new(0.75, 0.75),
+                Point::new(0.75, 0.25),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hilbert_adjacent() {
+        // Each step along the curve moves to a neighboring cell.
+        let bounds = Box::new(Point::new(0.0f64, 0.0), Point::new(65535.0, 65535.0));
+        let mut points = (0..16)
+            .flat_map(|x| (0..16).map(move |y| Point::new(x as f64, y as f64)))
+            .collect::<alloc::vec::Vec<_>>();
+        hilbert_sort(&mut points, &bounds);
+
+        for pair in points.windows(2) {
+            assert_eq!(pair[0].distance(pair[1]), 1.0);
+        }
+    }
+}
@@ -17,10 +17,11 @@
 
 //! Affine transformations.
 
-use super::Transform;
+use super::{Rotation, Scale, Skew, Transform, Translation};
 use crate::angle::Angle;
-use crate::point::Point;
 use crate::pair::{Double, Quad};
+use crate::point::{Point, Vector};
+use crate::size::Size;
 use num_traits::{real::Real, One, Zero};
 
 use core::ops;
@@ -106,10 +107,9 @@ impl<T: Copy> Affine<T> {
     where
         T: Zero + Real,
     {
-        let sin = angle.sin();
-        let cos = angle.cos();
+        let (sin, cos) = angle.sin_cos();
 
-        Self::new([cos, -sin, sin, cos, T::zero(), T::zero()])
+        Self::new([cos, sin, -sin, cos, T::zero(), T::zero()])
     }
 
     /// Get an affine transformation that represents a translation.
@@ -121,6 +121,18 @@ impl<T: Copy> Affine<T> {
         Self::new([T::one(), T::zero(), T::zero(), T::one(), x, y])
     }
 
+    /// Get an affine transformation that represents a skew.
+    ///
+    /// `x` shifts points horizontally in proportion to their Y coordinate, and `y`
+    /// shifts points vertically in proportion to their X coordinate.
+    #[inline]
+    pub fn skew(x: T, y: T) -> Self
+    where
+        T: Zero + One,
+    {
+        Self::new([T::one(), y, x, T::one(), T::zero(), T::zero()])
+    }
+
     /// Get the determinant of the affine transformation.
     #[inline]
     pub fn determinant(&self) -> T
@@ -132,7 +144,35 @@ impl<T: Copy> Affine<T> {
         a * d - b * c
     }
 
+    /// Tell whether this affine transformation can be inverted.
+    #[inline]
+    pub fn is_invertible(&self) -> bool
+    where
+        T: Real,
+    {
+        let determinant = self.determinant();
+        crate::is_finite(determinant) && !determinant.is_zero()
+    }
+
+    /// Tell whether this affine transformation collapses the plane onto a line or a point,
+    /// within the given tolerance.
+    #[inline]
+    pub fn is_degenerate(&self, epsilon: T) -> bool
+    where
+        T: Real,
+    {
+        // NaN determinants are also degenerate.
+        let determinant = self.determinant().abs();
+        !matches!(
+            determinant.partial_cmp(&epsilon),
+            Some(core::cmp::Ordering::Greater)
+        )
+    }
+
     /// Get the inverse of the affine transformation.
+    ///
+    /// If the transformation is not invertible, the coefficients of the result will be
+    /// infinite or NaN. Use [`Affine::try_inverse`] to check for this.
     #[inline]
     pub fn inverse(&self) -> Self
     where
@@ -150,6 +190,80 @@ impl<T: Copy> Affine<T> {
             (b * e - a * f) * inverse_det,
         ])
     }
+
+    /// Get the inverse of the affine transformation, or `None` if it cannot be inverted.
+    #[inline]
+    pub fn try_inverse(&self) -> Option<Self>
+    where
+        T: Real,
+    {
+        if !self.is_invertible() {
+            return None;
+        }
+
+        // A tiny determinant can still overflow when inverted.
+        let inverse = self.inverse();
+        if inverse
+            .as_coefficients()
+            .iter()
+            .all(|&c| crate::is_finite(c))
+        {
+            Some(inverse)
+        } else {
+            None
+        }
+    }
+
+    /// Transform a size by the scale factors of this transformation.
+    ///
+    /// The width is scaled by how much the transformation stretches the X axis, and the
+    /// height by how much it stretches the Y axis. Translation is ignored, and the
+    /// result is never negative.
+    #[inline]
+    pub fn transform_size(&self, size: Size<T>) -> Size<T>
+    where
+        T: Real,
+    {
+        let [a, b, c, d] = self.matrix.into_inner();
+
+        Size::new(
+            size.width().abs() * a.hypot(b),
+            size.height().abs() * c.hypot(d),
+        )
+    }
+
+    /// Transform an angle by the rotation component of this transformation.
+    ///
+    /// This is the angle of a direction vector at the given angle after it has been
+    /// transformed. Translation is ignored.
+    #[inline]
+    pub fn transform_angle(&self, angle: Angle<T>) -> Angle<T>
+    where
+        T: Real,
+    {
+        let [a, b, c, d] = self.matrix.into_inner();
+        let direction = Vector::from_angle(angle);
+
+        Vector::new(
+            a * direction.x() + c * direction.y(),
+            b * direction.x() + d * direction.y(),
+        )
+        .angle()
+    }
+
+    /// Linearly interpolate between the coefficients of two affine transformations.
+    #[inline]
+    pub fn lerp(self, other: Self, t: T) -> Self
+    where
+        T: One + ops::Sub<Output = T> + ops::Mul<Output = T> + ops::Add<Output = T>,
+    {
+        let one_t = T::one() - t;
+
+        Affine {
+            matrix: self.matrix * Quad::splat(one_t) + other.matrix * Quad::splat(t),
+            transform: self.transform * Double::splat(one_t) + other.transform * Double::splat(t),
+        }
+    }
 }
 
 impl<T: Copy + Zero + One> Default for Affine<T> {
@@ -168,9 +282,243 @@ impl<T: Copy + Zero + One> Default for Affine<T> {
 
 impl<T: Copy + ops::Mul<Output = T> + ops::Add<Output = T>> Transform<T> for Affine<T> {
     fn transform_point(&self, point: Point<T>) -> Point<T> {
+        // The low half is the first column of the matrix, and the high half the second.
         let (lo, hi) = self.matrix.split();
-        let point_swapped = point.0.swap();
+        let [x, y] = point.0.into_inner();
+
+        Point(((lo * Double::splat(x)) + (hi * Double::splat(y))) + self.transform)
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T> + ops::Add<Output = T>> ops::Mul for Affine<T> {
+    type Output = Self;
+
+    /// Compose two transformations, such that `other` is applied first.
+    #[inline]
+    fn mul(self, other: Self) -> Self::Output {
+        let [a0, a1, a2, a3, a4, a5] = self.as_coefficients();
+        let [b0, b1, b2, b3, b4, b5] = other.as_coefficients();
+
+        Self::new([
+            a0 * b0 + a2 * b1,
+            a1 * b0 + a3 * b1,
+            a0 * b2 + a2 * b3,
+            a1 * b2 + a3 * b3,
+            a0 * b4 + a2 * b5 + a4,
+            a1 * b4 + a3 * b5 + a5,
+        ])
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T> + ops::Add<Output = T>> ops::MulAssign for Affine<T> {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+/// A transformation that can be expressed as an `Affine` transformation.
+pub(super) trait IntoAffine<T: Copy> {
+    /// Get the equivalent affine transformation.
+    fn into_affine(self) -> Affine<T>;
+}
+
+impl<T: Copy> IntoAffine<T> for Affine<T> {
+    #[inline]
+    fn into_affine(self) -> Affine<T> {
+        self
+    }
+}
+
+impl<T: Copy + Zero> From<Scale<T>> for Affine<T> {
+    #[inline]
+    fn from(scale: Scale<T>) -> Self {
+        let [x, y] = scale.vector().0.into_inner();
+        Affine::scale(x, y)
+    }
+}
+
+impl<T: Copy + Zero + One> From<Translation<T>> for Affine<T> {
+    #[inline]
+    fn from(translation: Translation<T>) -> Self {
+        let [x, y] = translation.vector().0.into_inner();
+        Affine::translate(x, y)
+    }
+}
+
+impl<T: Copy + Real> From<Rotation<T>> for Affine<T> {
+    #[inline]
+    fn from(rotation: Rotation<T>) -> Self {
+        Affine::rotate(rotation.angle())
+    }
+}
+
+impl<T: Copy + Zero + One> From<Skew<T>> for Affine<T> {
+    #[inline]
+    fn from(skew: Skew<T>) -> Self {
+        let [x, y] = skew.factors().0.into_inner();
+        Affine::skew(x, y)
+    }
+}
+
+/// Implement `IntoAffine` for types that can be converted into an `Affine`.
+macro_rules! into_affine {
+    ($($name:ident),* $(,)?) => {
+        $(
+            impl<T: Real> IntoAffine<T> for $name<T> {
+                #[inline]
+                fn into_affine(self) -> Affine<T> {
+                    Affine::from(self)
+                }
+            }
+        )*
+    };
+}
+
+into_affine! {
+    Scale,
+    Translation,
+    Rotation,
+    Skew,
+}
+
+/// Implement composition between two different kinds of transformations.
+macro_rules! mixed_composition {
+    ($($lhs:ident * $rhs:ident),* $(,)?) => {
+        $(
+            impl<T: Real> ops::Mul<$rhs<T>> for $lhs<T> {
+                type Output = Affine<T>;
+
+                /// Compose two transformations, such that `other` is applied first.
+                #[inline]
+                fn mul(self, other: $rhs<T>) -> Affine<T> {
+                    self.into_affine() * other.into_affine()
+                }
+            }
+        )*
+    };
+}
+
+mixed_composition! {
+    Affine * Scale,
+    Affine * Translation,
+    Affine * Rotation,
+    Affine * Skew,
+    Scale * Affine,
+    Scale * Translation,
+    Scale * Rotation,
+    Scale * Skew,
+    Translation * Affine,
+    Translation * Scale,
+    Translation * Rotation,
+    Translation * Skew,
+    Rotation * Affine,
+    Rotation * Scale,
+    Rotation * Translation,
+    Rotation * Skew,
+    Skew * Affine,
+    Skew * Scale,
+    Skew * Translation,
+    Skew * Rotation,
+    Skew * Skew,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApproxEq;
+
+    #[test]
+    fn test_transform_point() {
+        let point = Point::new(1.0f32, 2.0);
+
+        let scaled = Affine::scale(2.0, 3.0).transform_point(point);
+        assert_eq!(scaled, Point::new(2.0, 6.0));
+
+        let translated = Affine::translate(1.0, -1.0).transform_point(point);
+        assert_eq!(translated, Point::new(2.0, 1.0));
+
+        let rotated = Affine::rotate(Angle::quarter_turn()).transform_point(point);
+        assert!(rotated.approx_eq(&Point::new(-2.0, 1.0)));
+    }
+
+    #[test]
+    fn test_compose() {
+        let point = Point::new(1.0f32, 2.0);
+        let scale = Scale::uniform(2.0);
+        let translation = Translation::new(crate::Vector::new(1.0, 0.0));
+
+        // The translation is applied first, then the scale.
+        let composed = scale * translation;
+        assert_eq!(composed.transform_point(point), Point::new(4.0, 4.0));
+
+        let skew = Skew::new(crate::Vector::new(1.0, 0.5));
+        let skewed = Affine::from(skew).transform_point(point);
+        assert_eq!(skewed, skew.transform_point(point));
+        assert_eq!(skewed, Point::new(3.0, 2.5));
+        assert_eq!(Affine::from(translation), Affine::translate(1.0, 0.0));
+
+        let inverse = composed.inverse();
+        assert!(inverse
+            .transform_point(Point::new(4.0, 4.0))
+            .approx_eq(&point));
+    }
+
+    #[test]
+    fn test_try_inverse() {
+        let transform = Affine::new([2.0f32, 0.0, 0.0, 4.0, 1.0, 1.0]);
+        assert!(transform.is_invertible());
+        assert!(!transform.is_degenerate(0.001));
+        assert_eq!(transform.try_inverse(), Some(transform.inverse()));
+
+        // Everything is squashed onto the X axis.
+        let squashed = Affine::scale(1.0f32, 0.0);
+        assert!(!squashed.is_invertible());
+        assert!(squashed.is_degenerate(0.001));
+        assert_eq!(squashed.try_inverse(), None);
+
+        let tiny = Affine::scale(1e-20f32, 1e-20);
+        assert!(tiny.is_degenerate(0.001));
+        assert_eq!(tiny.try_inverse(), None);
+
+        let nan = Affine::scale(f32::NAN, 1.0);
+        assert!(nan.is_degenerate(0.001));
+        assert_eq!(nan.try_inverse(), None);
+    }
+
+    #[test]
+    fn test_transform_size_angle() {
+        let transform = Affine::scale(2.0f32, -3.0) * Affine::translate(5.0, 5.0);
+        assert_eq!(
+            transform.transform_size(Size::new(1.0, 2.0)),
+            Size::new(2.0, 6.0)
+        );
+
+        let rotated = Affine::rotate(Angle::quarter_turn());
+        let size = rotated.transform_size(Size::new(1.0f32, 2.0));
+        assert!(size.width().approx_eq(&1.0) && size.height().approx_eq(&2.0));
+        assert!(rotated
+            .transform_angle(Angle::zero())
+            .radians()
+            .approx_eq(&Angle::<f32>::quarter_turn().radians()));
+
+        // Flipping the Y axis mirrors angles.
+        let flipped = Affine::scale(1.0f32, -1.0).transform_angle(Angle::quarter_turn());
+        assert!(flipped
+            .radians()
+            .approx_eq(&-Angle::<f32>::quarter_turn().radians()));
+    }
+
+    #[test]
+    fn test_lerp() {
+        let from = Affine::translate(0.0f32, 0.0);
+        let to = Affine::new([3.0, 0.0, 0.0, 5.0, 4.0, -2.0]);
 
-        Point(((lo * point.0) + (hi * point_swapped)) + self.transform)
+        assert_eq!(from.lerp(to, 0.0), from);
+        assert_eq!(from.lerp(to, 1.0), to);
+        assert_eq!(
+            from.lerp(to, 0.5),
+            Affine::new([2.0, 0.0, 0.0, 3.0, 2.0, -1.0])
+        );
     }
 }
@@ -0,0 +1,249 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! A two-dimensional camera for mapping between world and screen space.
+
+use super::{Affine, Transform};
+use crate::angle::Angle;
+use crate::box2d::Box;
+use crate::point::{Point, Vector};
+use crate::size::Size;
+use num_traits::real::Real;
+
+/// A camera looking at a two-dimensional world.
+///
+/// The camera places its `position` in the world at its `focal` point on the screen,
+/// then zooms and rotates the world around that point.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Camera2D<T: Copy> {
+    /// The point in the world that the camera is looking at.
+    position: Point<T>,
+
+    /// The point on the screen where `position` appears.
+    focal: Point<T>,
+
+    /// The number of screen units per world unit.
+    zoom: T,
+
+    /// The rotation of the world, as seen on the screen.
+    rotation: Angle<T>,
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Copy + arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for Camera2D<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Camera2D {
+            position: arbitrary::Arbitrary::arbitrary(u)?,
+            focal: arbitrary::Arbitrary::arbitrary(u)?,
+            zoom: arbitrary::Arbitrary::arbitrary(u)?,
+            rotation: arbitrary::Arbitrary::arbitrary(u)?,
+        })
+    }
+}
+
+impl<T: Real> Default for Camera2D<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Point::zero(), Point::zero())
+    }
+}
+
+impl<T: Copy> Camera2D<T> {
+    /// Create a new camera that shows `position` at `focal` on the screen, without any
+    /// zoom or rotation.
+    #[inline]
+    pub fn new(position: Point<T>, focal: Point<T>) -> Self
+    where
+        T: Real,
+    {
+        Camera2D {
+            position,
+            focal,
+            zoom: T::one(),
+            rotation: Angle::zero(),
+        }
+    }
+
+    /// Get the point in the world that the camera is looking at.
+    #[inline]
+    pub fn position(&self) -> Point<T> {
+        self.position
+    }
+
+    /// Set the point in the world that the camera is looking at.
+    #[inline]
+    pub fn set_position(&mut self, position: Point<T>) {
+        self.position = position;
+    }
+
+    /// Get the point on the screen that the camera's position appears at.
+    #[inline]
+    pub fn focal(&self) -> Point<T> {
+        self.focal
+    }
+
+    /// Set the point on the screen that the camera's position appears at.
+    #[inline]
+    pub fn set_focal(&mut self, focal: Point<T>) {
+        self.focal = focal;
+    }
+
+    /// Get the number of screen units per world unit.
+    #[inline]
+    pub fn zoom(&self) -> T {
+        self.zoom
+    }
+
+    /// Set the number of screen units per world unit.
+    #[inline]
+    pub fn set_zoom(&mut self, zoom: T) {
+        self.zoom = zoom;
+    }
+
+    /// Get the rotation of the world, as seen on the screen.
+    #[inline]
+    pub fn rotation(&self) -> Angle<T> {
+        self.rotation
+    }
+
+    /// Set the rotation of the world, as seen on the screen.
+    #[inline]
+    pub fn set_rotation(&mut self, rotation: Angle<T>) {
+        self.rotation = rotation;
+    }
+
+    /// Move the camera by a distance measured on the screen.
+    ///
+    /// The delta is mapped through the camera's zoom and rotation, so the world appears
+    /// to move by `-delta` on the screen. To drag the world along with the cursor, pass
+    /// the negated cursor movement.
+    #[inline]
+    pub fn pan_by(&mut self, delta: Vector<T>)
+    where
+        T: Real,
+    {
+        // The default `transform_vector` includes the translation, so cancel it out.
+        let screen_to_world = self.screen_to_world();
+        let delta = screen_to_world.transform_vector(delta)
+            - screen_to_world.transform_vector(Vector::zero());
+        self.position = self.position + delta;
+    }
+
+    /// Multiply the zoom by `factor`, keeping the world point under `anchor` on the
+    /// screen in place.
+    #[inline]
+    pub fn zoom_at(&mut self, anchor: Point<T>, factor: T)
+    where
+        T: Real,
+    {
+        let before = self.screen_to_world().transform_point(anchor);
+        self.zoom = self.zoom * factor;
+        let after = self.screen_to_world().transform_point(anchor);
+
+        self.position = self.position + (before - after);
+    }
+
+    /// Get the transformation from world space to screen space.
+    #[inline]
+    pub fn world_to_screen(&self) -> Affine<T>
+    where
+        T: Real,
+    {
+        Affine::translate(self.focal.x(), self.focal.y())
+            * Affine::rotate(self.rotation)
+            * Affine::scale(self.zoom, self.zoom)
+            * Affine::translate(-self.position.x(), -self.position.y())
+    }
+
+    /// Get the transformation from screen space to world space.
+    #[inline]
+    pub fn screen_to_world(&self) -> Affine<T>
+    where
+        T: Real,
+    {
+        self.world_to_screen().inverse()
+    }
+
+    /// Get the smallest box in world space that contains everything visible in a
+    /// viewport of the given size, placed at the screen's origin.
+    #[inline]
+    pub fn visible_box(&self, viewport: Size<T>) -> Box<T>
+    where
+        T: Real,
+    {
+        let screen_to_world = self.screen_to_world();
+        let corners = Box::from_size(viewport).corners();
+
+        Box::of_points(corners.map(|corner| screen_to_world.transform_point(corner)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_world_to_screen() {
+        let mut camera = Camera2D::new(Point::new(10.0f32, 10.0), Point::new(50.0, 50.0));
+        camera.set_zoom(2.0);
+
+        let transform = camera.world_to_screen();
+        assert_eq!(
+            transform.transform_point(Point::new(10.0, 10.0)),
+            Point::new(50.0, 50.0)
+        );
+        assert_eq!(
+            transform.transform_point(Point::new(11.0, 10.0)),
+            Point::new(52.0, 50.0)
+        );
+
+        let visible = camera.visible_box(Size::new(100.0, 100.0));
+        assert!(visible.min().approx_eq(&Point::new(-15.0, -15.0)));
+        assert!(visible.max().approx_eq(&Point::new(35.0, 35.0)));
+    }
+
+    #[test]
+    fn test_zoom_at() {
+        let mut camera = Camera2D::new(Point::new(0.0f32, 0.0), Point::new(50.0, 50.0));
+        let anchor = Point::new(60.0, 50.0);
+        let world = camera.screen_to_world().transform_point(anchor);
+
+        camera.zoom_at(anchor, 4.0);
+        let screen = camera.world_to_screen().transform_point(world);
+        assert!(screen.approx_eq(&anchor));
+
+        camera.pan_by(Vector::new(8.0, 0.0));
+        assert!(camera.position().approx_eq(&Point::new(9.5, 0.0)));
+    }
+
+    #[test]
+    fn test_pan_by() {
+        let mut camera = Camera2D::new(Point::new(0.0f64, 0.0), Point::new(50.0, 50.0));
+        camera.set_zoom(2.0);
+        camera.set_rotation(Angle::from_radians(core::f64::consts::FRAC_PI_2));
+        let world = Point::new(5.0, 0.0);
+        let before = camera.world_to_screen().transform_point(world);
+        assert!(before.distance(Point::new(50.0, 60.0)) < 1e-9);
+
+        // Moving the camera right on the screen moves the world left.
+        camera.pan_by(Vector::new(4.0, 0.0));
+        let after = camera.world_to_screen().transform_point(world);
+        assert!(after.distance(Point::new(46.0, 60.0)) < 1e-9);
+        assert!(camera.position().distance(Point::new(0.0, -2.0)) < 1e-9);
+    }
+}
@@ -18,14 +18,21 @@
 use crate::{Point, Triangle, Vector};
 
 mod affine;
+mod camera;
 mod rotation;
 mod scale;
+mod skew;
+mod stack;
 mod transformable;
 mod translation;
 
 pub use affine::Affine;
+pub use camera::Camera2D;
 pub use rotation::Rotation;
 pub use scale::Scale;
+pub use skew::Skew;
+#[cfg(feature = "alloc")]
+pub use stack::TransformStack;
 pub use transformable::Transformable;
 pub use translation::Translation;
 
@@ -20,7 +20,9 @@
 use super::Transform;
 use crate::angle::Angle;
 use crate::point::Point;
-use num_traits::real::Real;
+use num_traits::{real::Real, FloatConst};
+
+use core::ops;
 
 /// A rotation around the origin.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -48,6 +50,42 @@ impl<T: Copy> Rotation<T> {
     pub fn angle(&self) -> Angle<T> {
         self.0
     }
+
+    /// Get the rotation that undoes this one.
+    #[inline]
+    pub fn inverse(&self) -> Self
+    where
+        T: ops::Neg<Output = T>,
+    {
+        Rotation(-self.0)
+    }
+
+    /// Interpolate between this rotation and `other`, turning the shortest way around.
+    #[inline]
+    pub fn slerp(self, other: Self, t: T) -> Self
+    where
+        T: Real + FloatConst,
+    {
+        Rotation(self.0.lerp_shortest(other.0, t))
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::Mul for Rotation<T> {
+    type Output = Self;
+
+    /// Compose two rotations by adding their angles.
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: Self) -> Self::Output {
+        Rotation(self.0 + other.0)
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::MulAssign for Rotation<T> {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
 }
 
 impl<T: Copy> From<Angle<T>> for Rotation<T> {
@@ -75,8 +113,7 @@ impl<T: Copy, Src, Dst> From<Rotation<T>> for euclid::Rotation2D<T, Src, Dst> {
 
 impl<T: Copy + Real> Transform<T> for Rotation<T> {
     fn transform_point(&self, point: Point<T>) -> Point<T> {
-        let sin = self.0.sin();
-        let cos = self.0.cos();
+        let (sin, cos) = self.0.sin_cos();
 
         Point::new(
             point.x() * cos - point.y() * sin,
@@ -84,3 +121,21 @@ impl<T: Copy + Real> Transform<T> for Rotation<T> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slerp() {
+        let from = Rotation::new(Angle::from_degrees(350.0f64));
+        let to = Rotation::new(Angle::from_degrees(30.0));
+
+        // The shortest way around crosses zero rather than going backwards.
+        let halfway = from.slerp(to, 0.5).angle().positive();
+        assert!((halfway.degrees() - 10.0).abs() < 1e-9);
+
+        let end = to.slerp(from, 1.0).angle().positive();
+        assert!((end.degrees() - 350.0).abs() < 1e-9);
+    }
+}
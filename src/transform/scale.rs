@@ -21,6 +21,7 @@ use super::Transform;
 use crate::point::{Point, Vector};
 
 use core::ops;
+use num_traits::One;
 
 /// A scaling relative to the origin.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -54,6 +55,32 @@ impl<T: Copy> Scale<T> {
     pub fn vector(&self) -> Vector<T> {
         self.0
     }
+
+    /// Get the scaling that undoes this one.
+    #[inline]
+    pub fn inverse(&self) -> Self
+    where
+        T: One + ops::Div<Output = T>,
+    {
+        Scale(Vector::splat(T::one()) / self.0)
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul for Scale<T> {
+    type Output = Self;
+
+    /// Compose two scalings.
+    #[inline]
+    fn mul(self, other: Self) -> Self::Output {
+        Scale(self.0 * other.0)
+    }
+}
+
+impl<T: Copy + ops::MulAssign> ops::MulAssign for Scale<T> {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        self.0 *= other.0;
+    }
 }
 
 impl<T: Copy> From<Vector<T>> for Scale<T> {
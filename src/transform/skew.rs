@@ -0,0 +1,80 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! A skew, or shear, relative to the origin.
+
+use super::Transform;
+use crate::angle::Angle;
+use crate::point::{Point, Vector};
+use num_traits::real::Real;
+
+use core::ops;
+
+/// A skew relative to the origin.
+///
+/// The X factor shifts points horizontally in proportion to their Y coordinate, and the
+/// Y factor shifts points vertically in proportion to their X coordinate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(transparent)]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Skew<T: Copy>(Vector<T>);
+
+#[cfg(feature = "arbitrary")]
+impl<'a, T: Copy + arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a> for Skew<T> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Skew(Vector::arbitrary(u)?))
+    }
+}
+
+impl<T: Copy> Skew<T> {
+    /// Create a new skew from its X and Y factors.
+    #[inline]
+    pub fn new(factors: Vector<T>) -> Self {
+        Skew(factors)
+    }
+
+    /// Create a new skew from the angles that the Y and X axes are tilted by.
+    #[inline]
+    pub fn from_angles(x: Angle<T>, y: Angle<T>) -> Self
+    where
+        T: Real,
+    {
+        Skew(Vector::new(x.radians().tan(), y.radians().tan()))
+    }
+
+    /// Get the skew factors.
+    #[inline]
+    pub fn factors(&self) -> Vector<T> {
+        self.0
+    }
+}
+
+impl<T: Copy> From<Vector<T>> for Skew<T> {
+    #[inline]
+    fn from(factors: Vector<T>) -> Self {
+        Skew::new(factors)
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T> + ops::Add<Output = T>> Transform<T> for Skew<T> {
+    #[inline]
+    fn transform_point(&self, point: Point<T>) -> Point<T> {
+        let shifted = point.0.swap() * self.0 .0;
+        Point(point.0 + shifted)
+    }
+}
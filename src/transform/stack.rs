@@ -0,0 +1,167 @@
+// Copyright 2023 John Nunley
+//
+// This file is part of blood-geometry.
+// 
+// blood-geometry is free software: you can redistribute it and/or modify it 
+// under the terms of the GNU Affero General Public License as published by 
+// the Free Software Foundation, either version 3 of the License, or (at your 
+// option) any later version.
+// 
+// blood-geometry is distributed in the hope that it will be useful, but 
+// WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY 
+// or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License 
+// for more details.
+// 
+// You should have received a copy of the GNU Affero General Public License 
+// along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
+
+//! A stack of transformations, for save/restore style drawing APIs.
+
+#![cfg(feature = "alloc")]
+
+use super::{Affine, Transform};
+use crate::point::Point;
+use num_traits::{One, Zero};
+
+use alloc::vec::Vec;
+use core::ops;
+
+/// A stack of affine transformations.
+///
+/// The current transformation is composed eagerly, so reading it is always cheap.
+/// Transformations applied to the stack are applied to points before the ones that are
+/// already on it, as with a canvas.
+#[derive(Debug, Clone)]
+pub struct TransformStack<T: Copy> {
+    /// The current transformation.
+    current: Affine<T>,
+
+    /// The saved transformations.
+    saved: Vec<Affine<T>>,
+}
+
+impl<T: Copy + Zero + One> Default for TransformStack<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy> TransformStack<T> {
+    /// Create a new stack whose current transformation is the identity.
+    #[inline]
+    pub fn new() -> Self
+    where
+        T: Zero + One,
+    {
+        Self::with_transform(Affine::default())
+    }
+
+    /// Create a new stack with the given current transformation.
+    #[inline]
+    pub fn with_transform(transform: impl Into<Affine<T>>) -> Self {
+        TransformStack {
+            current: transform.into(),
+            saved: Vec::new(),
+        }
+    }
+
+    /// Get the current transformation.
+    #[inline]
+    pub fn current(&self) -> Affine<T> {
+        self.current
+    }
+
+    /// Get the number of saved transformations.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.saved.len()
+    }
+
+    /// Save the current transformation so that it can be restored later.
+    #[inline]
+    pub fn save(&mut self) {
+        self.saved.push(self.current);
+    }
+
+    /// Restore the most recently saved transformation.
+    ///
+    /// Returns `false` and leaves the current transformation unchanged if nothing was
+    /// saved.
+    #[inline]
+    pub fn restore(&mut self) -> bool {
+        match self.saved.pop() {
+            Some(saved) => {
+                self.current = saved;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Compose a transformation into the current one without saving it.
+    #[inline]
+    pub fn apply(&mut self, transform: impl Into<Affine<T>>)
+    where
+        T: ops::Mul<Output = T> + ops::Add<Output = T>,
+    {
+        self.current *= transform.into();
+    }
+
+    /// Save the current transformation, then compose another one into it.
+    #[inline]
+    pub fn push(&mut self, transform: impl Into<Affine<T>>)
+    where
+        T: ops::Mul<Output = T> + ops::Add<Output = T>,
+    {
+        self.save();
+        self.apply(transform);
+    }
+
+    /// Undo the most recent `push`, restoring the transformation before it.
+    ///
+    /// This is the same as `restore`.
+    #[inline]
+    pub fn pop(&mut self) -> bool {
+        self.restore()
+    }
+
+    /// Replace the current transformation and discard all saved ones.
+    #[inline]
+    pub fn reset(&mut self, transform: impl Into<Affine<T>>) {
+        self.current = transform.into();
+        self.saved.clear();
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T> + ops::Add<Output = T>> Transform<T> for TransformStack<T> {
+    #[inline]
+    fn transform_point(&self, point: Point<T>) -> Point<T> {
+        self.current.transform_point(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::{Scale, Translation};
+    use crate::Vector;
+
+    #[test]
+    fn test_push_pop() {
+        let mut stack = TransformStack::new();
+        let point = Point::new(1.0f32, 1.0);
+
+        stack.push(Translation::new(Vector::new(10.0, 0.0)));
+        stack.push(Scale::uniform(2.0));
+        assert_eq!(stack.depth(), 2);
+        assert_eq!(stack.transform_point(point), Point::new(12.0, 2.0));
+
+        assert!(stack.pop());
+        assert_eq!(stack.transform_point(point), Point::new(11.0, 1.0));
+
+        assert!(stack.pop());
+        assert!(!stack.pop());
+        assert_eq!(stack.current(), Affine::default());
+    }
+}
@@ -48,6 +48,33 @@ impl<T: Copy> Translation<T> {
     pub fn vector(&self) -> Vector<T> {
         self.0
     }
+
+    /// Get the translation that undoes this one.
+    #[inline]
+    pub fn inverse(&self) -> Self
+    where
+        T: ops::Neg<Output = T>,
+    {
+        Translation(-self.0)
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::Mul for Translation<T> {
+    type Output = Self;
+
+    /// Compose two translations.
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: Self) -> Self::Output {
+        Translation(self.0 + other.0)
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::MulAssign for Translation<T> {
+    #[inline]
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
 }
 
 impl<T: Copy> From<Vector<T>> for Translation<T> {
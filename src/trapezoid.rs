@@ -22,7 +22,9 @@ use num_traits::real::Real;
 use crate::box2d::{BoundingBox, Box};
 use crate::line::{Line, LineSegment, NhLineSegment};
 use crate::path::{Path, PathEvent, Shape};
-use crate::ApproxEq;
+use crate::{ApproxEq, Direction, Point, Vector};
+
+use core::ops;
 
 /// A trapezoid with horizontal top and bottom edges.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -150,38 +152,224 @@ impl<T: Copy> Trapezoid<T> {
     }
 }
 
-impl<T: Real + ApproxEq> Path<T> for Trapezoid<T> {
-    type Iter = crate::iter::Five<PathEvent<T>>;
+impl<T: Copy + ops::Add<Output = T>> ops::Add<Vector<T>> for Trapezoid<T> {
+    type Output = Self;
 
-    fn path_iter(self) -> Self::Iter {
+    #[inline]
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        Trapezoid::new(
+            self.top + rhs.y(),
+            self.bottom + rhs.y(),
+            self.left + rhs,
+            self.right + rhs,
+        )
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::AddAssign<Vector<T>> for Trapezoid<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Vector<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::Sub<Vector<T>> for Trapezoid<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        Trapezoid::new(
+            self.top - rhs.y(),
+            self.bottom - rhs.y(),
+            self.left - rhs,
+            self.right - rhs,
+        )
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::SubAssign<Vector<T>> for Trapezoid<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Vector<T>) {
+        *self = *self - rhs;
+    }
+}
+
+/// Scales the trapezoid about the origin.
+///
+/// The scale is expected to be positive, or the top and bottom edges are swapped.
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<T> for Trapezoid<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: T) -> Self::Output {
+        Trapezoid::new(
+            self.top * rhs,
+            self.bottom * rhs,
+            self.left * rhs,
+            self.right * rhs,
+        )
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::MulAssign<T> for Trapezoid<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Real + ApproxEq> Trapezoid<T> {
+    /// Tell whether a point is inside of this trapezoid.
+    ///
+    /// Like [`Box::contains`], the top and left sides are inside and the bottom and right
+    /// sides are not.
+    pub fn contains(&self, point: Point<T>) -> bool {
+        let y = point.y();
+        if y < self.top || y >= self.bottom {
+            return false;
+        }
+
+        match (self.left.point_at_y(y), self.right.point_at_y(y)) {
+            (Some(left), Some(right)) => left.x() <= point.x() && point.x() < right.x(),
+            _ => false,
+        }
+    }
+
+    /// Clip this trapezoid to the inside of a box.
+    ///
+    /// The top and bottom are clamped to the box, and the corners of the left and right
+    /// edges are clamped to its sides. This is exact unless an edge crosses one of the
+    /// box's sides partway down, in which case the clipped edge cuts the corner across
+    /// the crossing and some area is lost. Either way, the result lies entirely inside of
+    /// the box. Use [`Trapezoid::clip_split`] to keep all of the area.
+    ///
+    /// Returns `None` if nothing is left after clipping.
+    pub fn clip(&self, clip: &Box<T>) -> Option<Trapezoid<T>> {
+        let (min, max) = clip.min_max();
+        let top = self.top.max(min.y());
+        let bottom = self.bottom.min(max.y());
+        if top >= bottom {
+            return None;
+        }
+
+        let side = |line: Line<T>| {
+            let clamp = |y: T| {
+                let point = line.point_at_y(y)?;
+                Some(point.with_x(point.x().max(min.x()).min(max.x())))
+            };
+
+            Some((clamp(top)?, clamp(bottom)?))
+        };
+
+        let (left_top, left_bottom) = side(self.left)?;
+        let (right_top, right_bottom) = side(self.right)?;
+        if left_top.x() >= right_top.x() && left_bottom.x() >= right_bottom.x() {
+            return None;
+        }
+
+        Some(Trapezoid::new(
+            top,
+            bottom,
+            Line::between(left_top, left_bottom),
+            Line::between(right_top, right_bottom),
+        ))
+    }
+
+    /// Clip this trapezoid to the inside of a box without losing any area.
+    ///
+    /// Where the left or right edge crosses one of the box's sides partway down, the
+    /// trapezoid is split at that height, which gives up to three trapezoids from top to
+    /// bottom. Pieces with nothing left after clipping are skipped.
+    pub fn clip_split(&self, clip: &Box<T>) -> crate::iter::Three<Trapezoid<T>> {
+        let (min, max) = clip.min_max();
+        let top = self.top.max(min.y());
+        let bottom = self.bottom.min(max.y());
+        if top >= bottom {
+            return crate::iter::Three::empty();
+        }
+
+        // Find the heights where either edge crosses the sides of the box.
+        let mut stops = [top, bottom, top, bottom, top, bottom];
+        for (i, (line, x)) in [
+            (self.left, min.x()),
+            (self.left, max.x()),
+            (self.right, min.x()),
+            (self.right, max.x()),
+        ]
+        .iter()
+        .enumerate()
+        {
+            if let Some(point) = line.point_at_x(*x) {
+                stops[i + 2] = point.y().max(top).min(bottom);
+            }
+        }
+        stops.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(core::cmp::Ordering::Equal));
+
+        // The clamped edges are straight between two stops.
+        let corner = |line: Line<T>, y: T| {
+            let point = line.point_at_y(y)?;
+            Some(point.with_x(point.x().max(min.x()).min(max.x())))
+        };
+        let piece = |top: T, bottom: T| {
+            let (left_top, left_bottom) = (corner(self.left, top)?, corner(self.left, bottom)?);
+            let (right_top, right_bottom) = (corner(self.right, top)?, corner(self.right, bottom)?);
+            if left_top.x() >= right_top.x() && left_bottom.x() >= right_bottom.x() {
+                return None;
+            }
+
+            Some(Trapezoid::new(
+                top,
+                bottom,
+                Line::between(left_top, left_bottom),
+                Line::between(right_top, right_bottom),
+            ))
+        };
+
+        // Only the crossings of the left edge with the box's left side and of the right
+        // edge with the box's right side can fall inside of the clipped area, so there
+        // are never more than three pieces.
+        let mut pieces = [None; 3];
+        let mut count = 0;
+        for pair in stops.windows(2) {
+            if pair[0] < pair[1] {
+                if let (Some(trapezoid), Some(slot)) =
+                    (piece(pair[0], pair[1]), pieces.get_mut(count))
+                {
+                    *slot = Some(trapezoid);
+                    count += 1;
+                }
+            }
+        }
+
+        match pieces {
+            [Some(a), Some(b), Some(c)] => [a, b, c].into(),
+            [Some(a), Some(b), None] => [a, b].into(),
+            [Some(a), None, None] => [a].into(),
+            _ => crate::iter::Three::empty(),
+        }
+    }
+
+    /// Get the path events that make up this trapezoid, going around it in the given
+    /// direction.
+    ///
+    /// `Direction::Forwards` gives the same order as `path_iter`, and
+    /// `Direction::Backwards` reverses it.
+    pub fn path_iter_with_direction(self, direction: Direction) -> crate::iter::Five<PathEvent<T>> {
         let top = self.top_segment();
         let bottom = self.bottom_segment();
 
-        let top_left = top.from();
-        let top_right = top.to();
-        let bottom_left = bottom.from();
-        let bottom_right = bottom.to();
-
-        crate::iter::Five::from([
-            PathEvent::Begin { at: top_left },
-            PathEvent::Line {
-                from: top_left,
-                to: top_right,
-            },
-            PathEvent::Line {
-                from: top_right,
-                to: bottom_right,
-            },
-            PathEvent::Line {
-                from: bottom_right,
-                to: bottom_left,
-            },
-            PathEvent::End {
-                last: bottom_left,
-                first: top_left,
-                close: true,
-            },
-        ])
+        crate::path::closed_quad(
+            [top.from(), top.to(), bottom.to(), bottom.from()],
+            direction,
+        )
+    }
+}
+
+impl<T: Real + ApproxEq> Path<T> for Trapezoid<T> {
+    type Iter = crate::iter::Five<PathEvent<T>>;
+
+    fn path_iter(self) -> Self::Iter {
+        self.path_iter_with_direction(Direction::Forwards)
     }
 
     fn rectilinear(self) -> bool
@@ -248,3 +436,125 @@ impl<T: Real + ApproxEq> BoundingBox<T> for Trapezoid<T> {
         ]))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains() {
+        let trapezoid = Trapezoid::new(
+            0.0f32,
+            4.0,
+            Line::between(Point::new(0.0, 0.0), Point::new(4.0, 4.0)),
+            Line::between(Point::new(2.0, 0.0), Point::new(6.0, 4.0)),
+        );
+
+        assert!(trapezoid.contains(Point::new(1.0, 0.0)));
+        assert!(trapezoid.contains(Point::new(3.0, 2.0)));
+        assert!(trapezoid.contains(Point::new(2.0, 2.0)));
+        assert!(!trapezoid.contains(Point::new(4.0, 2.0)));
+        assert!(!trapezoid.contains(Point::new(1.0, 2.0)));
+        assert!(!trapezoid.contains(Point::new(5.0, 4.0)));
+    }
+
+    #[test]
+    fn test_clip() {
+        // A parallelogram leaning to the right.
+        let trapezoid = Trapezoid::new(
+            0.0f32,
+            4.0,
+            Line::between(Point::new(0.0, 0.0), Point::new(4.0, 4.0)),
+            Line::between(Point::new(2.0, 0.0), Point::new(6.0, 4.0)),
+        );
+
+        let inside = Box::new(Point::new(-10.0, -10.0), Point::new(10.0, 10.0));
+        let clipped = trapezoid.clip(&inside).unwrap();
+        assert_eq!(Trapezoid::area(&clipped), Trapezoid::area(&trapezoid));
+
+        // Cut off the top and bottom.
+        let band = Box::new(Point::new(-10.0, 1.0), Point::new(10.0, 3.0));
+        let clipped = trapezoid.clip(&band).unwrap();
+        assert_eq!((clipped.top(), clipped.bottom()), (1.0, 3.0));
+        assert_eq!(Trapezoid::area(&clipped), 4.0);
+
+        // The right side of the box is past the top and bottom right corners.
+        let right = Box::new(Point::new(-10.0, 0.0), Point::new(3.0, 4.0));
+        let clipped = trapezoid.clip(&right).unwrap();
+        assert_eq!(
+            clipped.bottom_segment(),
+            LineSegment::new(Point::new(3.0, 4.0), Point::new(3.0, 4.0))
+        );
+        assert!(clipped.top_segment().to().x() <= 3.0);
+
+        let outside = Box::new(Point::new(20.0, 0.0), Point::new(30.0, 4.0));
+        assert_eq!(trapezoid.clip(&outside), None);
+        let below = Box::new(Point::new(0.0, 5.0), Point::new(10.0, 10.0));
+        assert_eq!(trapezoid.clip(&below), None);
+    }
+
+    #[test]
+    fn test_clip_split() {
+        // A parallelogram leaning to the right.
+        let trapezoid = Trapezoid::new(
+            0.0f32,
+            4.0,
+            Line::between(Point::new(0.0, 0.0), Point::new(4.0, 4.0)),
+            Line::between(Point::new(2.0, 0.0), Point::new(6.0, 4.0)),
+        );
+        let area = |pieces: crate::iter::Three<Trapezoid<f32>>| {
+            pieces.fold(0.0, |area, piece| area + Trapezoid::area(&piece))
+        };
+
+        let inside = Box::new(Point::new(-10.0, -10.0), Point::new(10.0, 10.0));
+        let clipped = trapezoid
+            .clip_split(&inside)
+            .collect::<alloc::vec::Vec<_>>();
+        assert_eq!(clipped, [trapezoid]);
+
+        // Cut off the top and bottom.
+        let band = Box::new(Point::new(-10.0, 1.0), Point::new(10.0, 3.0));
+        let clipped = trapezoid.clip_split(&band).next().unwrap();
+        assert_eq!((clipped.top(), clipped.bottom()), (1.0, 3.0));
+        assert_eq!(Trapezoid::area(&clipped), 4.0);
+
+        // The right edge crosses the right side of the box partway down.
+        let right = Box::new(Point::new(-10.0, 0.0), Point::new(3.0, 4.0));
+        assert_eq!(trapezoid.clip_split(&right).count(), 2);
+        assert_eq!(area(trapezoid.clip_split(&right)), 4.0);
+
+        let outside = Box::new(Point::new(20.0, 0.0), Point::new(30.0, 4.0));
+        assert_eq!(trapezoid.clip_split(&outside).count(), 0);
+        let below = Box::new(Point::new(0.0, 5.0), Point::new(10.0, 10.0));
+        assert_eq!(trapezoid.clip_split(&below).count(), 0);
+    }
+
+    #[test]
+    fn test_clip_conserves_area() {
+        let trapezoid = Trapezoid::new(
+            0.0f64,
+            4.0,
+            Line::between(Point::new(0.0, 0.0), Point::new(0.0, 4.0)),
+            Line::between(Point::new(0.0, 0.0), Point::new(10.0, 4.0)),
+        );
+
+        let clip = Box::new(Point::new(0.0, 0.0), Point::new(5.0, 4.0));
+        let area = trapezoid
+            .clip_split(&clip)
+            .fold(0.0, |area, piece| area + Trapezoid::area(&piece));
+        assert!(area.approx_eq(&15.0));
+
+        // Both edges cross the box, on opposite sides.
+        let slanted = Trapezoid::new(
+            0.0f64,
+            10.0,
+            Line::between(Point::new(-5.0, 0.0), Point::new(5.0, 10.0)),
+            Line::between(Point::new(5.0, 0.0), Point::new(25.0, 10.0)),
+        );
+        let clip = Box::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+        let pieces = slanted.clip_split(&clip);
+        assert_eq!(pieces.len(), 3);
+        let area = pieces.fold(0.0, |area, piece| area + Trapezoid::area(&piece));
+        assert!(area.approx_eq(&81.25));
+    }
+}
@@ -16,11 +16,12 @@
 // along with blood-geometry. If not, see <https://www.gnu.org/licenses/>. 
 
 use crate::path::{Path, PathEvent, Shape};
-use crate::{ApproxEq, Box, LineSegment, Point};
+use crate::{ApproxEq, Box, Direction, LineSegment, Point, Vector};
 use num_traits::real::Real;
 use num_traits::Zero;
 
 use core::fmt;
+use core::ops;
 
 /// A triangle.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
@@ -215,21 +216,71 @@ impl<T: PartialOrd + Copy> crate::BoundingBox<T> for Triangle<T> {
     }
 }
 
+impl<T: Copy> Triangle<T> {
+    /// Get the path events that make up this triangle, going around it in the given
+    /// direction.
+    ///
+    /// `Direction::Forwards` gives the same order as `path_iter`, and
+    /// `Direction::Backwards` reverses it.
+    pub fn path_iter_with_direction(self, direction: Direction) -> crate::iter::Four<PathEvent<T>> {
+        crate::path::closed_triangle(self.0, direction)
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::Add<Vector<T>> for Triangle<T> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        Triangle(self.0.map(|point| point + rhs))
+    }
+}
+
+impl<T: Copy + ops::Add<Output = T>> ops::AddAssign<Vector<T>> for Triangle<T> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Vector<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::Sub<Vector<T>> for Triangle<T> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        Triangle(self.0.map(|point| point - rhs))
+    }
+}
+
+impl<T: Copy + ops::Sub<Output = T>> ops::SubAssign<Vector<T>> for Triangle<T> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Vector<T>) {
+        *self = *self - rhs;
+    }
+}
+
+/// Scales the triangle about the origin.
+impl<T: Copy + ops::Mul<Output = T>> ops::Mul<T> for Triangle<T> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: T) -> Self::Output {
+        Triangle(self.0.map(|point| point * rhs))
+    }
+}
+
+impl<T: Copy + ops::Mul<Output = T>> ops::MulAssign<T> for Triangle<T> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
 impl<T: Copy> Path<T> for Triangle<T> {
     type Iter = crate::iter::Four<PathEvent<T>>;
 
     fn path_iter(self) -> Self::Iter {
-        let [a, b, c] = self.0;
-        crate::iter::Four::from([
-            PathEvent::Begin { at: a },
-            PathEvent::Line { from: a, to: b },
-            PathEvent::Line { from: b, to: c },
-            PathEvent::End {
-                last: c,
-                first: a,
-                close: true,
-            },
-        ])
+        self.path_iter_with_direction(Direction::Forwards)
     }
 
     fn rectilinear(self) -> bool {